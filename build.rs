@@ -0,0 +1,22 @@
+// Копіює шейдери з src/assets/shaders у assets/shaders поруч з виконуваним файлом
+// (target/<профіль>/), де їх шукає NShaderLibrary, коли програму запущено не з
+// дерева проєкту. Для поставки гри цей каталог кладеться поруч з бінарником.
+
+use std::{env, fs, path::Path};
+
+const SOURCE_DIR: &str = "src/assets/shaders";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", SOURCE_DIR);
+    // OUT_DIR = target/<профіль>/build/<пакет>-<хеш>/out
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let Some(profile_dir) = Path::new(&out_dir).ancestors().nth(3) else { return };
+    let target_dir = profile_dir.join("assets/shaders");
+    fs::create_dir_all(&target_dir).unwrap();
+    for entry in fs::read_dir(SOURCE_DIR).unwrap() {
+        let entry = entry.unwrap();
+        if entry.file_type().unwrap().is_file() {
+            fs::copy(entry.path(), target_dir.join(entry.file_name())).unwrap();
+        }
+    }
+}
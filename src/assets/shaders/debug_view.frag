@@ -0,0 +1,50 @@
+#version 450
+// Відладкові режими вьюпорта, яким потрібна лише геометрія (NViewMode::replaces_material).
+// Працює з вершинним шейдером матеріалу: location 0 — нормаль, 1 — UV.
+// Варіант обирається define-ом VIEW_*; WIREFRAME_BARYCENTRIC — каркас без
+// PolygonMode::Line, з барицентричними координатами від wireframe.vert.
+#if defined(VIEW_NORMALS)
+layout(location = 0) in vec3 v_normal;
+#elif defined(VIEW_UVS)
+layout(location = 1) in vec2 v_uv;
+#elif defined(WIREFRAME_BARYCENTRIC)
+layout(location = 0) in vec3 v_barycentric;
+#endif
+
+layout(push_constant) uniform PushConstants {
+    mat4 view_proj;
+    vec4 camera_position;
+    vec4 environment;   // z, w — near і far камери
+} pc;
+
+layout(location = 0) out vec4 f_color;
+
+const vec3 WIRE_COLOR = vec3(0.85, 0.9, 1.0);
+// Внесок одного шару в тепловій карті: після кількох шарів червоний насичується,
+// далі ростуть зелений і синій (червоний -> жовтий -> білий)
+const vec3 OVERDRAW_STEP = vec3(0.2, 0.06, 0.02);
+
+void main() {
+#if defined(VIEW_NORMALS)
+    f_color = vec4(normalize(v_normal) * 0.5 + 0.5, 1.0);
+#elif defined(VIEW_UVS)
+    f_color = vec4(fract(v_uv), 0.0, 1.0);
+#elif defined(VIEW_DEPTH)
+    // 1 / gl_FragCoord.w — w кліпу, тобто відстань від камери вздовж осі погляду
+    float near = pc.environment.z;
+    float depth = (1.0 / gl_FragCoord.w - near) / (pc.environment.w - near);
+    // Корінь розтягує ближню частину діапазону, де зазвичай знаходиться сцена
+    f_color = vec4(vec3(1.0 - sqrt(clamp(depth, 0.0, 1.0))), 1.0);
+#elif defined(VIEW_OVERDRAW)
+    f_color = vec4(OVERDRAW_STEP, 1.0);
+#else
+#ifdef WIREFRAME_BARYCENTRIC
+    // Лишаємо пікселі ближче одного пікселя до будь-якого ребра трикутника
+    vec3 inside = step(fwidth(v_barycentric), v_barycentric);
+    if (min(min(inside.x, inside.y), inside.z) > 0.5) {
+        discard;
+    }
+#endif
+    f_color = vec4(WIRE_COLOR, 1.0);
+#endif
+}
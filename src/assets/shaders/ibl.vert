@@ -0,0 +1,30 @@
+#version 450
+// Повноекранний трикутник для запікання грані кубмапи (або 2D текстури).
+// v_direction — напрямок з центру кубмапи через піксель грані pc.face.
+layout(push_constant) uniform PushConstants {
+    int face;
+    float roughness;
+} pc;
+
+layout(location = 0) out vec2 v_uv;
+layout(location = 1) out vec3 v_direction;
+
+void main() {
+    v_uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    gl_Position = vec4(v_uv * 2.0 - 1.0, 0.0, 1.0);
+
+    vec2 st = v_uv * 2.0 - 1.0;
+    if (pc.face == 0) {
+        v_direction = vec3(1.0, -st.y, -st.x);
+    } else if (pc.face == 1) {
+        v_direction = vec3(-1.0, -st.y, st.x);
+    } else if (pc.face == 2) {
+        v_direction = vec3(st.x, 1.0, st.y);
+    } else if (pc.face == 3) {
+        v_direction = vec3(st.x, -1.0, -st.y);
+    } else if (pc.face == 4) {
+        v_direction = vec3(st.x, -st.y, 1.0);
+    } else {
+        v_direction = vec3(-st.x, -st.y, -1.0);
+    }
+}
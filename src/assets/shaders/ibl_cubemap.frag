@@ -0,0 +1,29 @@
+#version 450
+// Вихідне зображення оточення -> грань кубмапи: equirectangular HDR панорама
+// або (з SOURCE_CUBE) грані вихідної кубмапи довільного розміру
+layout(location = 1) in vec3 v_direction;
+
+#ifdef SOURCE_CUBE
+layout(set = 0, binding = 0) uniform textureCube faces;
+layout(set = 0, binding = 1) uniform sampler faces_sampler;
+#else
+layout(set = 0, binding = 0) uniform texture2D equirect;
+layout(set = 0, binding = 1) uniform sampler equirect_sampler;
+#endif
+
+layout(location = 0) out vec4 f_color;
+
+const float PI = 3.14159265359;
+
+void main() {
+    vec3 direction = normalize(v_direction);
+#ifdef SOURCE_CUBE
+    f_color = vec4(textureLod(samplerCube(faces, faces_sampler), direction, 0.0).rgb, 1.0);
+#else
+    vec2 uv = vec2(
+        atan(direction.z, direction.x) / (2.0 * PI) + 0.5,
+        acos(clamp(direction.y, -1.0, 1.0)) / PI
+    );
+    f_color = vec4(textureLod(sampler2D(equirect, equirect_sampler), uv, 0.0).rgb, 1.0);
+#endif
+}
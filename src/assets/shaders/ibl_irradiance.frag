@@ -0,0 +1,35 @@
+#version 450
+// Дифузна освітленість: косинусна згортка оточення по півсфері навколо нормалі
+layout(location = 1) in vec3 v_direction;
+
+layout(set = 0, binding = 0) uniform textureCube environment_map;
+layout(set = 0, binding = 1) uniform sampler environment_sampler;
+
+layout(location = 0) out vec4 f_color;
+
+const float PI = 3.14159265359;
+const float SAMPLE_STEP = 0.025;
+// Рівень mip-а оточення, з якого беремо семпли (менше шуму, швидше)
+const float SOURCE_LOD = 4.0;
+
+void main() {
+    vec3 normal = normalize(v_direction);
+    vec3 up = abs(normal.y) < 0.999 ? vec3(0.0, 1.0, 0.0) : vec3(0.0, 0.0, 1.0);
+    vec3 right = normalize(cross(up, normal));
+    up = cross(normal, right);
+
+    vec3 irradiance = vec3(0.0);
+    float sample_count = 0.0;
+    for (float phi = 0.0; phi < 2.0 * PI; phi += SAMPLE_STEP) {
+        for (float theta = 0.0; theta < 0.5 * PI; theta += SAMPLE_STEP) {
+            vec3 local = vec3(sin(theta) * cos(phi), sin(theta) * sin(phi), cos(theta));
+            vec3 direction = local.x * right + local.y * up + local.z * normal;
+            vec3 radiance = textureLod(
+                samplerCube(environment_map, environment_sampler), direction, SOURCE_LOD
+            ).rgb;
+            irradiance += radiance * cos(theta) * sin(theta);
+            sample_count += 1.0;
+        }
+    }
+    f_color = vec4(PI * irradiance / sample_count, 1.0);
+}
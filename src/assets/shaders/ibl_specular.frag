@@ -0,0 +1,75 @@
+#version 450
+// Дзеркальне оточення, префільтроване GGX для шорсткості pc.roughness
+// (split-sum апроксимація, N = V = R)
+layout(location = 1) in vec3 v_direction;
+
+layout(push_constant) uniform PushConstants {
+    int face;
+    float roughness;
+} pc;
+
+layout(set = 0, binding = 0) uniform textureCube environment_map;
+layout(set = 0, binding = 1) uniform sampler environment_sampler;
+
+layout(location = 0) out vec4 f_color;
+
+const float PI = 3.14159265359;
+const uint SAMPLE_COUNT = 512u;
+
+float radical_inverse(uint bits) {
+    bits = (bits << 16u) | (bits >> 16u);
+    bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+    bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+    bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+    bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+    return float(bits) * 2.3283064365386963e-10;
+}
+
+vec3 importance_sample_ggx(vec2 xi, vec3 normal, float roughness) {
+    float a = roughness * roughness;
+    float phi = 2.0 * PI * xi.x;
+    float cos_theta = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+    float sin_theta = sqrt(1.0 - cos_theta * cos_theta);
+    vec3 half_vector = vec3(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+
+    vec3 up = abs(normal.z) < 0.999 ? vec3(0.0, 0.0, 1.0) : vec3(1.0, 0.0, 0.0);
+    vec3 tangent = normalize(cross(up, normal));
+    vec3 bitangent = cross(normal, tangent);
+    return normalize(tangent * half_vector.x + bitangent * half_vector.y + normal * half_vector.z);
+}
+
+float distribution_ggx(float n_dot_h, float roughness) {
+    float a = roughness * roughness;
+    float a2 = a * a;
+    float denom = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / (PI * denom * denom);
+}
+
+void main() {
+    vec3 normal = normalize(v_direction);
+    vec3 view = normal;
+    float source_size = float(textureSize(samplerCube(environment_map, environment_sampler), 0).x);
+    // Тілесний кут одного текселя оточення
+    float texel_solid_angle = 4.0 * PI / (6.0 * source_size * source_size);
+
+    vec3 color = vec3(0.0);
+    float total_weight = 0.0;
+    for (uint i = 0u; i < SAMPLE_COUNT; i++) {
+        vec2 xi = vec2(float(i) / float(SAMPLE_COUNT), radical_inverse(i));
+        vec3 half_vector = importance_sample_ggx(xi, normal, pc.roughness);
+        vec3 light = normalize(2.0 * dot(view, half_vector) * half_vector - view);
+        float n_dot_l = dot(normal, light);
+        if (n_dot_l > 0.0) {
+            // Семпл з mip-а, площа текселя якого відповідає тілесному куту семпла:
+            // прибирає яскраві точки на високій шорсткості
+            float n_dot_h = max(dot(normal, half_vector), 0.0);
+            float pdf = distribution_ggx(n_dot_h, pc.roughness) * 0.25 + 0.0001;
+            float sample_solid_angle = 1.0 / (float(SAMPLE_COUNT) * pdf + 0.0001);
+            float lod = pc.roughness == 0.0 ? 0.0 : 0.5 * log2(sample_solid_angle / texel_solid_angle);
+            color += textureLod(samplerCube(environment_map, environment_sampler), light, lod).rgb
+                * n_dot_l;
+            total_weight += n_dot_l;
+        }
+    }
+    f_color = vec4(color / total_weight, 1.0);
+}
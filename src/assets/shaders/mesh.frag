@@ -0,0 +1,34 @@
+#version 450
+layout(location = 0) in vec3 v_normal;
+layout(location = 1) in vec2 v_uv;
+layout(location = 2) in vec4 v_color;
+
+layout(set = 0, binding = 0) uniform Material {
+    vec4 base_color;
+    float ambient;
+} material;
+layout(set = 0, binding = 1) uniform texture2D base_color_texture;
+layout(set = 0, binding = 2) uniform sampler base_color_sampler;
+
+layout(location = 0) out vec4 f_color;
+
+const vec3 LIGHT_DIRECTION = vec3(0.3114, 0.7785, 0.4671);
+
+void main() {
+    vec4 albedo = texture(sampler2D(base_color_texture, base_color_sampler), v_uv)
+        * material.base_color * v_color;
+    // Варіанти відладкових режимів вьюпорта (NViewMode): у матеріалу немає
+    // metallic/roughness/occlusion/emissive, тож показуються їхні нейтральні значення
+#if defined(VIEW_UNLIT)
+    f_color = albedo;
+#elif defined(VIEW_BASE_COLOR)
+    f_color = vec4(albedo.rgb, 1.0);
+#elif defined(VIEW_METALLIC) || defined(VIEW_EMISSIVE)
+    f_color = vec4(0.0, 0.0, 0.0, 1.0);
+#elif defined(VIEW_ROUGHNESS) || defined(VIEW_OCCLUSION)
+    f_color = vec4(1.0);
+#else
+    float diffuse = max(dot(normalize(v_normal), LIGHT_DIRECTION), 0.0);
+    f_color = vec4(albedo.rgb * (material.ambient + (1.0 - material.ambient) * diffuse), albedo.a);
+#endif
+}
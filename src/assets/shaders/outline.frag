@@ -0,0 +1,44 @@
+#version 450
+// Пікселі всередині маски не змінюються; зовні береться максимум маски
+// в колі радіусом thickness, виділення має пріоритет над курсором
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 0) uniform texture2D u_mask;
+layout(set = 0, binding = 1) uniform sampler u_mask_sampler;
+
+layout(push_constant) uniform PushConstants {
+    vec4 selected_color;
+    vec4 hovered_color;
+    int thickness;
+} pc;
+
+vec2 mask(ivec2 pixel) {
+    return texelFetch(sampler2D(u_mask, u_mask_sampler), pixel, 0).rg;
+}
+
+void main() {
+    ivec2 size = textureSize(sampler2D(u_mask, u_mask_sampler), 0);
+    ivec2 pixel = ivec2(gl_FragCoord.xy);
+    vec2 center = mask(pixel);
+
+    vec2 around = vec2(0.0);
+    int radius2 = pc.thickness * pc.thickness;
+    for (int y = -pc.thickness; y <= pc.thickness; y++) {
+        for (int x = -pc.thickness; x <= pc.thickness; x++) {
+            if (x * x + y * y > radius2) {
+                continue;
+            }
+            ivec2 neighbour = clamp(pixel + ivec2(x, y), ivec2(0), size - 1);
+            around = max(around, mask(neighbour));
+        }
+    }
+
+    vec2 edge = around * (1.0 - step(0.5, center));
+    if (edge.r > 0.5) {
+        f_color = pc.selected_color;
+    } else if (edge.g > 0.5) {
+        f_color = pc.hovered_color;
+    } else {
+        discard;
+    }
+}
@@ -0,0 +1,8 @@
+#version 450
+layout(location = 0) in vec4 v_mask;
+
+layout(location = 0) out vec4 f_mask;
+
+void main() {
+    f_mask = v_mask;
+}
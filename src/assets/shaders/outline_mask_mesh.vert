@@ -0,0 +1,38 @@
+#version 450
+// Меш у маску обведення: колір інстансу — значення маски (NHighlights::mask)
+layout(location = 0) in vec3 position;
+layout(location = 3) in vec4 model_x;
+layout(location = 4) in vec4 model_y;
+layout(location = 5) in vec4 model_z;
+layout(location = 6) in vec4 model_w;
+layout(location = 7) in vec4 color;
+
+#ifdef SKINNED
+layout(location = 8) in uvec4 joints;
+layout(location = 9) in vec4 weights;
+layout(location = 10) in uint joint_offset;
+
+layout(set = 0, binding = 0) readonly buffer JointPalette {
+    mat4 matrices[];
+} palette;
+#endif
+
+layout(push_constant) uniform PushConstants {
+    mat4 view_proj;
+} pc;
+
+layout(location = 0) out vec4 v_mask;
+
+void main() {
+    mat4 model = mat4(model_x, model_y, model_z, model_w);
+#ifdef SKINNED
+    model = model * (
+        weights.x * palette.matrices[joint_offset + joints.x]
+        + weights.y * palette.matrices[joint_offset + joints.y]
+        + weights.z * palette.matrices[joint_offset + joints.z]
+        + weights.w * palette.matrices[joint_offset + joints.w]
+    );
+#endif
+    gl_Position = pc.view_proj * model * vec4(position, 1.0);
+    v_mask = color;
+}
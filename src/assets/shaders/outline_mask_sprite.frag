@@ -0,0 +1,16 @@
+#version 450
+// Прозорі пікселі текстури спрайта не потрапляють у маску
+layout(location = 0) in vec4 v_mask;
+layout(location = 1) in vec2 v_uv;
+
+layout(set = 0, binding = 0) uniform texture2D tex;
+layout(set = 0, binding = 1) uniform sampler tex_sampler;
+
+layout(location = 0) out vec4 f_mask;
+
+void main() {
+    if (texture(sampler2D(tex, tex_sampler), v_uv).a < 0.5) {
+        discard;
+    }
+    f_mask = v_mask;
+}
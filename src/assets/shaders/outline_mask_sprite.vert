@@ -0,0 +1,23 @@
+#version 450
+layout(location = 0) in vec3 position;
+layout(location = 2) in vec2 uv;
+layout(location = 3) in vec4 model_x;
+layout(location = 4) in vec4 model_y;
+layout(location = 5) in vec4 model_z;
+layout(location = 6) in vec4 model_w;
+layout(location = 7) in vec4 color;
+layout(location = 8) in vec4 uv_rect;
+
+layout(push_constant) uniform PushConstants {
+    mat4 view_proj;
+} pc;
+
+layout(location = 0) out vec4 v_mask;
+layout(location = 1) out vec2 v_uv;
+
+void main() {
+    mat4 model = mat4(model_x, model_y, model_z, model_w);
+    gl_Position = pc.view_proj * model * vec4(position, 1.0);
+    v_mask = color;
+    v_uv = uv_rect.xy + uv * uv_rect.zw;
+}
@@ -0,0 +1,12 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 1) in vec4 v_color;
+
+layout(set = 1, binding = 0) uniform texture2D particle_texture;
+layout(set = 1, binding = 1) uniform sampler particle_sampler;
+
+layout(location = 0) out vec4 f_color;
+
+void main() {
+    f_color = texture(sampler2D(particle_texture, particle_sampler), v_uv) * v_color;
+}
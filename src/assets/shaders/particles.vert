@@ -0,0 +1,87 @@
+#version 450
+// Частинки як білборди, повернуті до камери. Інстанс — частинка (у порядку
+// відсортованих ключів для альфа-змішування), 6 вершин — квадрат.
+struct Particle {
+    vec4 position_age;
+    vec4 velocity_lifetime;
+};
+
+struct SortKey {
+    float depth;
+    uint index;
+};
+
+layout(set = 0, binding = 0) readonly buffer Particles {
+    Particle particles[];
+};
+layout(set = 0, binding = 1) readonly buffer SortKeys {
+    SortKey keys[];
+};
+layout(set = 0, binding = 2) uniform Emitter {
+    mat4 emission;
+    vec4 origin;
+    vec4 gravity;
+    vec4 speed_lifetime;
+    vec4 shape;
+    vec4 camera_position;
+    vec4 camera_right;
+    vec4 camera_up;
+    vec4 sheet;                 // Розмір, стовпці, рядки, кількість циклів анімації
+    uvec4 counts;
+    uvec4 seed;
+    vec4 colors[8];
+    vec4 sizes[2];
+} emitter;
+
+layout(push_constant) uniform PushConstants {
+    mat4 view_proj;
+    uvec4 options;              // x — 1, якщо інстанси йдуть у порядку ключів сортування
+} pc;
+
+layout(location = 0) out vec2 v_uv;
+layout(location = 1) out vec4 v_color;
+
+vec4 color_at(float t) {
+    float x = t * 7.0;
+    int i = min(int(x), 6);
+    return mix(emitter.colors[i], emitter.colors[i + 1], x - float(i));
+}
+
+float size_at(float t) {
+    float x = t * 7.0;
+    int i = min(int(x), 6);
+    float a = emitter.sizes[i / 4][i % 4];
+    float b = emitter.sizes[(i + 1) / 4][(i + 1) % 4];
+    return mix(a, b, x - float(i));
+}
+
+void main() {
+    uint index = pc.options.x != 0u ? keys[gl_InstanceIndex].index : uint(gl_InstanceIndex);
+    Particle particle = particles[index];
+    if (particle.velocity_lifetime.w <= 0.0) {
+        // Мертва частинка: вироджений трикутник поза кліп-простором
+        gl_Position = vec4(0.0, 0.0, 2.0, 1.0);
+        v_uv = vec2(0.0);
+        v_color = vec4(0.0);
+        return;
+    }
+    float t = clamp(particle.position_age.w / particle.velocity_lifetime.w, 0.0, 1.0);
+
+    // Кути квадрата: два трикутники (0, 1, 2) та (2, 1, 3)
+    const vec2 corners[4] = vec2[](vec2(-0.5, -0.5), vec2(0.5, -0.5), vec2(-0.5, 0.5), vec2(0.5, 0.5));
+    const int indices[6] = int[](0, 1, 2, 2, 1, 3);
+    vec2 corner = corners[indices[gl_VertexIndex]];
+
+    float size = emitter.sheet.x * size_at(t);
+    vec3 position = particle.position_age.xyz
+        + (emitter.camera_right.xyz * corner.x + emitter.camera_up.xyz * corner.y) * size;
+    gl_Position = pc.view_proj * vec4(position, 1.0);
+
+    // Кадр анімації текстури: сітка columns x rows, рядки зверху вниз
+    vec2 grid = max(emitter.sheet.yz, vec2(1.0));
+    float frames = grid.x * grid.y;
+    float frame = mod(floor(t * emitter.sheet.w * frames), frames);
+    vec2 cell = vec2(mod(frame, grid.x), floor(frame / grid.x));
+    v_uv = (cell + vec2(corner.x + 0.5, 0.5 - corner.y)) / grid;
+    v_color = color_at(t);
+}
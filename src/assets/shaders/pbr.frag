@@ -0,0 +1,113 @@
+#version 450
+// Metallic-roughness PBR (модель glTF 2.0) з освітленням від оточення (IBL):
+// дифузна частина з irradiance кубмапи, дзеркальна — з префільтрованої
+// specular кубмапи та BRDF LUT (split-sum).
+layout(location = 0) in vec3 v_normal;
+layout(location = 1) in vec2 v_uv;
+layout(location = 2) in vec4 v_color;
+layout(location = 3) in vec3 v_position;
+
+layout(push_constant) uniform PushConstants {
+    mat4 view_proj;
+    vec4 camera_position;
+    vec4 environment;   // x — інтенсивність, y — останній mip specular_map
+} pc;
+
+layout(set = 0, binding = 0) uniform Material {
+    vec4 base_color;
+    vec3 emissive;
+    float metallic;
+    float roughness;
+    float normal_scale;         // 0 — карта нормалей не використовується
+    float occlusion_strength;
+} material;
+layout(set = 0, binding = 1) uniform texture2D base_color_texture;         // sRGB
+layout(set = 0, binding = 2) uniform texture2D normal_texture;             // linear, tangent space
+layout(set = 0, binding = 3) uniform texture2D metallic_roughness_texture; // linear: G — roughness, B — metallic
+layout(set = 0, binding = 4) uniform texture2D occlusion_texture;          // linear: R
+layout(set = 0, binding = 5) uniform texture2D emissive_texture;           // sRGB
+layout(set = 0, binding = 6) uniform sampler material_sampler;
+
+layout(set = 1, binding = 0) uniform textureCube irradiance_map;
+layout(set = 1, binding = 1) uniform textureCube specular_map;
+layout(set = 1, binding = 2) uniform texture2D brdf_lut;
+layout(set = 1, binding = 3) uniform sampler environment_sampler;
+
+layout(location = 0) out vec4 f_color;
+
+// Меші не мають дотичних, тому TBN базис будується з похідних позиції та UV
+vec3 perturb_normal(vec3 normal, vec3 mapped) {
+    vec3 dp1 = dFdx(v_position);
+    vec3 dp2 = dFdy(v_position);
+    vec2 duv1 = dFdx(v_uv);
+    vec2 duv2 = dFdy(v_uv);
+    vec3 dp2_perp = cross(dp2, normal);
+    vec3 dp1_perp = cross(normal, dp1);
+    vec3 tangent = dp2_perp * duv1.x + dp1_perp * duv2.x;
+    vec3 bitangent = dp2_perp * duv1.y + dp1_perp * duv2.y;
+    float scale = inversesqrt(max(max(dot(tangent, tangent), dot(bitangent, bitangent)), 1e-12));
+    return normalize(mat3(tangent * scale, bitangent * scale, normal) * mapped);
+}
+
+vec3 fresnel_schlick_roughness(float cos_theta, vec3 f0, float roughness) {
+    return f0 + (max(vec3(1.0 - roughness), f0) - f0) * pow(1.0 - cos_theta, 5.0);
+}
+
+void main() {
+    vec4 base_color = texture(sampler2D(base_color_texture, material_sampler), v_uv)
+        * material.base_color * v_color;
+
+    vec3 normal = normalize(v_normal);
+    if (!gl_FrontFacing) {
+        normal = -normal;
+    }
+    if (material.normal_scale > 0.0) {
+        vec3 mapped = texture(sampler2D(normal_texture, material_sampler), v_uv).xyz * 2.0 - 1.0;
+        mapped.xy *= material.normal_scale;
+        normal = perturb_normal(normal, normalize(mapped));
+    }
+
+    vec4 metallic_roughness = texture(sampler2D(metallic_roughness_texture, material_sampler), v_uv);
+    float metallic = clamp(material.metallic * metallic_roughness.b, 0.0, 1.0);
+    float roughness = clamp(material.roughness * metallic_roughness.g, 0.04, 1.0);
+    float occlusion = mix(
+        1.0,
+        texture(sampler2D(occlusion_texture, material_sampler), v_uv).r,
+        material.occlusion_strength
+    );
+    vec3 emissive = texture(sampler2D(emissive_texture, material_sampler), v_uv).rgb
+        * material.emissive;
+
+    // Варіанти відладкових режимів вьюпорта (NViewMode): канали матеріалу без освітлення
+#if defined(VIEW_UNLIT)
+    f_color = vec4(base_color.rgb + emissive, base_color.a);
+#elif defined(VIEW_BASE_COLOR)
+    f_color = vec4(base_color.rgb, 1.0);
+#elif defined(VIEW_METALLIC)
+    f_color = vec4(vec3(metallic), 1.0);
+#elif defined(VIEW_ROUGHNESS)
+    f_color = vec4(vec3(roughness), 1.0);
+#elif defined(VIEW_OCCLUSION)
+    f_color = vec4(vec3(occlusion), 1.0);
+#elif defined(VIEW_EMISSIVE)
+    f_color = vec4(emissive, 1.0);
+#else
+    vec3 view = normalize(pc.camera_position.xyz - v_position);
+    float n_dot_v = clamp(dot(normal, view), 0.001, 1.0);
+    vec3 f0 = mix(vec3(0.04), base_color.rgb, metallic);
+    vec3 fresnel = fresnel_schlick_roughness(n_dot_v, f0, roughness);
+
+    vec3 irradiance = texture(samplerCube(irradiance_map, environment_sampler), normal).rgb;
+    vec3 diffuse = irradiance * base_color.rgb * (1.0 - fresnel) * (1.0 - metallic);
+
+    vec3 reflected = reflect(-view, normal);
+    vec3 prefiltered = textureLod(
+        samplerCube(specular_map, environment_sampler), reflected, roughness * pc.environment.y
+    ).rgb;
+    vec2 brdf = texture(sampler2D(brdf_lut, environment_sampler), vec2(n_dot_v, roughness)).rg;
+    vec3 specular = prefiltered * (fresnel * brdf.x + brdf.y);
+
+    vec3 color = (diffuse + specular) * occlusion * pc.environment.x + emissive;
+    f_color = vec4(color, base_color.a);
+#endif
+}
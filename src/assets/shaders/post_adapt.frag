@@ -0,0 +1,31 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out float f_luminance;
+
+layout(set = 0, binding = 0) uniform texture2D u_log_luminance;
+layout(set = 0, binding = 1) uniform sampler u_log_luminance_sampler;
+layout(set = 0, binding = 2) uniform texture2D u_previous;
+layout(set = 0, binding = 3) uniform sampler u_previous_sampler;
+
+layout(push_constant) uniform Push {
+    float delta_time;
+    float speed;
+    float max_lod;
+    float min_luminance;
+    float max_luminance;
+    int reset;
+} pc;
+
+void main() {
+    // Найменший mip містить середнє логарифмів яскравості всього кадру
+    float log_average = textureLod(
+        sampler2D(u_log_luminance, u_log_luminance_sampler), vec2(0.5), pc.max_lod).r;
+    float average = exp(log_average);
+    average = clamp(average, pc.min_luminance, pc.max_luminance);
+    if (pc.reset != 0) {
+        f_luminance = average;
+        return;
+    }
+    float previous = texelFetch(sampler2D(u_previous, u_previous_sampler), ivec2(0), 0).r;
+    f_luminance = previous + (average - previous) * (1.0 - exp(-pc.delta_time * pc.speed));
+}
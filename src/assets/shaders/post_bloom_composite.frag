@@ -0,0 +1,18 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 0) uniform texture2D u_source;
+layout(set = 0, binding = 1) uniform sampler u_source_sampler;
+layout(set = 0, binding = 2) uniform texture2D u_bloom;
+layout(set = 0, binding = 3) uniform sampler u_bloom_sampler;
+
+layout(push_constant) uniform Push {
+    float intensity;
+} pc;
+
+void main() {
+    vec4 color = texture(sampler2D(u_source, u_source_sampler), v_uv);
+    vec3 bloom = texture(sampler2D(u_bloom, u_bloom_sampler), v_uv).rgb;
+    f_color = vec4(color.rgb + bloom * pc.intensity, color.a);
+}
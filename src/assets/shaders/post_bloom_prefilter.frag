@@ -0,0 +1,21 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 0) uniform texture2D u_source;
+layout(set = 0, binding = 1) uniform sampler u_source_sampler;
+
+layout(push_constant) uniform Push {
+    float threshold;
+    float knee;
+} pc;
+
+void main() {
+    vec3 color = texture(sampler2D(u_source, u_source_sampler), v_uv).rgb;
+    float brightness = max(color.r, max(color.g, color.b));
+    // М'який поріг (quadratic knee)
+    float soft = clamp(brightness - pc.threshold + pc.knee, 0.0, 2.0 * pc.knee);
+    soft = soft * soft / (4.0 * pc.knee + 0.0001);
+    float contribution = max(soft, brightness - pc.threshold) / max(brightness, 0.0001);
+    f_color = vec4(color * contribution, 1.0);
+}
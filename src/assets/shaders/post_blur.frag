@@ -0,0 +1,26 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 0) uniform texture2D u_source;
+layout(set = 0, binding = 1) uniform sampler u_source_sampler;
+
+layout(push_constant) uniform Push {
+    vec2 direction;
+} pc;
+
+const float WEIGHTS[5] = float[](0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+vec3 source(vec2 uv) {
+    return texture(sampler2D(u_source, u_source_sampler), uv).rgb;
+}
+
+void main() {
+    vec3 result = source(v_uv) * WEIGHTS[0];
+    for (int i = 1; i < 5; i++) {
+        vec2 offset = pc.direction * float(i);
+        result += source(v_uv + offset) * WEIGHTS[i];
+        result += source(v_uv - offset) * WEIGHTS[i];
+    }
+    f_color = vec4(result, 1.0);
+}
@@ -0,0 +1,23 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 0) uniform texture2D u_source;
+layout(set = 0, binding = 1) uniform sampler u_source_sampler;
+layout(set = 0, binding = 2) uniform texture3D u_lut;
+layout(set = 0, binding = 3) uniform sampler u_lut_sampler;
+
+layout(push_constant) uniform Push {
+    float intensity;
+    float lut_size;
+} pc;
+
+void main() {
+    vec4 color = texture(sampler2D(u_source, u_source_sampler), v_uv);
+    vec3 linear = clamp(color.rgb, 0.0, 1.0);
+    // LUT-и зазвичай створюються в гамма-просторі
+    vec3 encoded = pow(linear, vec3(1.0 / 2.2));
+    vec3 uvw = encoded * ((pc.lut_size - 1.0) / pc.lut_size) + 0.5 / pc.lut_size;
+    vec3 graded = pow(texture(sampler3D(u_lut, u_lut_sampler), uvw).rgb, vec3(2.2));
+    f_color = vec4(mix(linear, graded, pc.intensity), color.a);
+}
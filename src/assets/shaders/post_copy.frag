@@ -0,0 +1,10 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 0) uniform texture2D u_source;
+layout(set = 0, binding = 1) uniform sampler u_source_sampler;
+
+void main() {
+    f_color = texture(sampler2D(u_source, u_source_sampler), v_uv);
+}
@@ -0,0 +1,25 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 0) uniform texture2D u_source;
+layout(set = 0, binding = 1) uniform sampler u_source_sampler;
+layout(set = 0, binding = 2) uniform texture2D u_adapted_luminance;
+layout(set = 0, binding = 3) uniform sampler u_adapted_luminance_sampler;
+
+layout(push_constant) uniform Push {
+    float exposure;
+    float key_value;
+    int auto_exposure;
+} pc;
+
+void main() {
+    vec4 color = texture(sampler2D(u_source, u_source_sampler), v_uv);
+    float exposure = pc.exposure;
+    if (pc.auto_exposure != 0) {
+        float average = texelFetch(
+            sampler2D(u_adapted_luminance, u_adapted_luminance_sampler), ivec2(0), 0).r;
+        exposure *= pc.key_value / max(average, 0.0001);
+    }
+    f_color = vec4(color.rgb * exposure, color.a);
+}
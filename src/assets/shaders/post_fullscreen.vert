@@ -0,0 +1,9 @@
+#version 450
+// Повноекранний трикутник для ефектів пост-обробки. Ефекти читають вхідні зображення
+// парами binding-ів: текстура вхідного зображення i на 2i, її сэмплер на 2i + 1.
+layout(location = 0) out vec2 v_uv;
+
+void main() {
+    v_uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    gl_Position = vec4(v_uv * 2.0 - 1.0, 0.0, 1.0);
+}
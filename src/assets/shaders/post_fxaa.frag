@@ -0,0 +1,59 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 0) uniform texture2D u_source;
+layout(set = 0, binding = 1) uniform sampler u_source_sampler;
+
+layout(push_constant) uniform Push {
+    vec2 inverse_size;
+    float edge_threshold;
+    float edge_threshold_min;
+    float span_max;
+} pc;
+
+const float REDUCE_MUL = 1.0 / 8.0;
+const float REDUCE_MIN = 1.0 / 128.0;
+
+vec4 source(vec2 uv) {
+    return texture(sampler2D(u_source, u_source_sampler), uv);
+}
+
+float luma(vec3 color) {
+    return dot(color, vec3(0.299, 0.587, 0.114));
+}
+
+void main() {
+    vec2 px = pc.inverse_size;
+    vec4 center = source(v_uv);
+    float luma_m = luma(center.rgb);
+    float luma_nw = luma(source(v_uv + vec2(-1.0, -1.0) * px).rgb);
+    float luma_ne = luma(source(v_uv + vec2(1.0, -1.0) * px).rgb);
+    float luma_sw = luma(source(v_uv + vec2(-1.0, 1.0) * px).rgb);
+    float luma_se = luma(source(v_uv + vec2(1.0, 1.0) * px).rgb);
+
+    float luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    float luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+    if (luma_max - luma_min < max(pc.edge_threshold_min, luma_max * pc.edge_threshold)) {
+        f_color = center;
+        return;
+    }
+
+    vec2 dir = vec2(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se)
+    );
+    float dir_reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+    float inverse_dir_min = 1.0 / (min(abs(dir.x), abs(dir.y)) + dir_reduce);
+    dir = clamp(dir * inverse_dir_min, vec2(-pc.span_max), vec2(pc.span_max)) * px;
+
+    vec3 rgb_a = 0.5 * (
+        source(v_uv + dir * (1.0 / 3.0 - 0.5)).rgb +
+        source(v_uv + dir * (2.0 / 3.0 - 0.5)).rgb);
+    vec3 rgb_b = rgb_a * 0.5 + 0.25 * (
+        source(v_uv - dir * 0.5).rgb +
+        source(v_uv + dir * 0.5).rgb);
+    float luma_b = luma(rgb_b);
+    vec3 result = (luma_b < luma_min || luma_b > luma_max) ? rgb_a : rgb_b;
+    f_color = vec4(result, center.a);
+}
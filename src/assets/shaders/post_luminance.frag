@@ -0,0 +1,12 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out float f_log_luminance;
+
+layout(set = 0, binding = 0) uniform texture2D u_source;
+layout(set = 0, binding = 1) uniform sampler u_source_sampler;
+
+void main() {
+    vec3 color = texture(sampler2D(u_source, u_source_sampler), v_uv).rgb;
+    float luminance = dot(color, vec3(0.2126, 0.7152, 0.0722));
+    f_log_luminance = log(max(luminance, 0.0001));
+}
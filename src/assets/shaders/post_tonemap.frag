@@ -0,0 +1,71 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 0) uniform texture2D u_source;
+layout(set = 0, binding = 1) uniform sampler u_source_sampler;
+
+layout(push_constant) uniform Push {
+    int tonemapper;
+} pc;
+
+// ACES fit (Stephen Hill)
+const mat3 ACES_INPUT = mat3(
+    0.59719, 0.07600, 0.02840,
+    0.35458, 0.90834, 0.13383,
+    0.04823, 0.01566, 0.83777
+);
+const mat3 ACES_OUTPUT = mat3(
+     1.60475, -0.10208, -0.00327,
+    -0.53108,  1.10813, -0.07276,
+    -0.07367, -0.00605,  1.07602
+);
+
+vec3 rrt_and_odt_fit(vec3 v) {
+    vec3 a = v * (v + 0.0245786) - 0.000090537;
+    vec3 b = v * (0.983729 * v + 0.4329510) + 0.238081;
+    return a / b;
+}
+
+vec3 aces(vec3 color) {
+    color = ACES_INPUT * color;
+    color = rrt_and_odt_fit(color);
+    return ACES_OUTPUT * color;
+}
+
+// Minimal AgX (Benjamin Wrensch)
+vec3 agx_contrast(vec3 x) {
+    vec3 x2 = x * x;
+    vec3 x4 = x2 * x2;
+    return 15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4
+        - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x - 0.00232;
+}
+
+vec3 agx(vec3 color) {
+    const mat3 AGX_INSET = mat3(
+        0.842479062253094, 0.0423282422610123, 0.0423756549057051,
+        0.0784335999999992, 0.878468636469772, 0.0784336,
+        0.0792237451477643, 0.0791661274605434, 0.879142973793104
+    );
+    const mat3 AGX_OUTSET = mat3(
+        1.19687900512017, -0.0528968517574562, -0.0529716355144438,
+        -0.0980208811401368, 1.15190312990417, -0.0980434501171241,
+        -0.0990297440797205, -0.0989611768448433, 1.15107367264116
+    );
+    const float MIN_EV = -12.47393;
+    const float MAX_EV = 4.026069;
+
+    color = AGX_INSET * color;
+    color = clamp(log2(max(color, vec3(1e-10))), MIN_EV, MAX_EV);
+    color = (color - MIN_EV) / (MAX_EV - MIN_EV);
+    color = agx_contrast(color);
+    color = AGX_OUTSET * color;
+    // AgX повертає значення в display-просторі, а ціль лінійна (sRGB кодування робить залізо)
+    return pow(max(color, vec3(0.0)), vec3(2.2));
+}
+
+void main() {
+    vec4 color = texture(sampler2D(u_source, u_source_sampler), v_uv);
+    vec3 mapped = pc.tonemapper == 0 ? aces(color.rgb) : agx(color.rgb);
+    f_color = vec4(clamp(mapped, 0.0, 1.0), color.a);
+}
@@ -0,0 +1,20 @@
+#version 450
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 0) uniform texture2D u_source;
+layout(set = 0, binding = 1) uniform sampler u_source_sampler;
+
+layout(push_constant) uniform Push {
+    vec4 color;
+    float intensity;
+    float smoothness;
+} pc;
+
+void main() {
+    vec4 source = texture(sampler2D(u_source, u_source_sampler), v_uv);
+    // 0 в центрі, 1 в кутах
+    float dist = length(v_uv - 0.5) * 1.41421356;
+    float factor = smoothstep(1.0 - pc.smoothness, 1.0001, dist + pc.intensity * 0.5) * pc.intensity;
+    f_color = vec4(mix(source.rgb, pc.color.rgb, clamp(factor, 0.0, 1.0)), source.a);
+}
@@ -0,0 +1,34 @@
+#version 450
+// Фон камери: кубмапа оточення або (з GRADIENT_SKY) процедурне градієнтне небо
+layout(location = 0) in vec3 v_direction;
+
+layout(push_constant) uniform PushConstants {
+    mat4 inverse_view_proj;
+    vec4 environment;   // x — інтенсивність оточення
+    vec4 zenith;        // Кольори градієнтного неба (GRADIENT_SKY)
+    vec4 horizon;
+    vec4 ground;
+} pc;
+
+#ifndef GRADIENT_SKY
+layout(set = 0, binding = 0) uniform textureCube environment_map;
+layout(set = 0, binding = 1) uniform sampler environment_sampler;
+#endif
+
+layout(location = 0) out vec4 f_color;
+
+void main() {
+    vec3 direction = normalize(v_direction);
+#ifdef GRADIENT_SKY
+    // Ті самі криві, що й у процедурного оточення NEnvironment::sky
+    vec3 color = direction.y >= 0.0
+        ? mix(pc.horizon.rgb, pc.zenith.rgb, sqrt(direction.y))
+        : mix(pc.horizon.rgb, pc.ground.rgb, pow(-direction.y, 0.3));
+    f_color = vec4(color, 1.0);
+#else
+    vec3 radiance = textureLod(
+        samplerCube(environment_map, environment_sampler), direction, 0.0
+    ).rgb;
+    f_color = vec4(radiance * pc.environment.x, 1.0);
+#endif
+}
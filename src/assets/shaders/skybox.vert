@@ -0,0 +1,22 @@
+#version 450
+// Повноекранний трикутник на дальній площині; напрямок погляду відновлюється
+// оберненою матрицею view_proj
+layout(push_constant) uniform PushConstants {
+    mat4 inverse_view_proj;
+    vec4 environment;   // x — інтенсивність оточення
+    vec4 zenith;        // Кольори градієнтного неба (GRADIENT_SKY)
+    vec4 horizon;
+    vec4 ground;
+} pc;
+
+layout(location = 0) out vec3 v_direction;
+
+void main() {
+    vec2 uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    vec2 ndc = uv * 2.0 - 1.0;
+    gl_Position = vec4(ndc, 1.0, 1.0);
+
+    vec4 near = pc.inverse_view_proj * vec4(ndc, 0.0, 1.0);
+    vec4 far = pc.inverse_view_proj * vec4(ndc, 1.0, 1.0);
+    v_direction = far.xyz / far.w - near.xyz / near.w;
+}
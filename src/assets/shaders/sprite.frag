@@ -0,0 +1,29 @@
+#version 450
+// Варіанти режимів перегляду (FLAT_VIEW_MODES): VIEW_WIREFRAME — рамка квадрата
+// спрайта, VIEW_OVERDRAW — внесок шару в теплову карту, як у debug_view.frag.
+layout(location = 0) in vec2 v_uv;
+layout(location = 1) in vec4 v_color;
+layout(location = 2) in vec2 v_corner;
+
+layout(set = 0, binding = 0) uniform texture2D tex;
+layout(set = 0, binding = 1) uniform sampler tex_sampler;
+
+layout(location = 0) out vec4 f_color;
+
+const vec3 WIRE_COLOR = vec3(0.85, 0.9, 1.0);
+const vec3 OVERDRAW_STEP = vec3(0.2, 0.06, 0.02);
+
+void main() {
+#if defined(VIEW_WIREFRAME)
+    // Лишаємо пікселі ближче одного пікселя до краю квадрата
+    vec2 edge = min(v_corner, 1.0 - v_corner) / fwidth(v_corner);
+    if (min(edge.x, edge.y) > 1.0) {
+        discard;
+    }
+    f_color = vec4(WIRE_COLOR, 1.0);
+#elif defined(VIEW_OVERDRAW)
+    f_color = vec4(OVERDRAW_STEP, 1.0);
+#else
+    f_color = texture(sampler2D(tex, tex_sampler), v_uv) * v_color;
+#endif
+}
@@ -0,0 +1,46 @@
+#version 450
+// Текст із SDF атласу: альфа атласу — відстань до контуру гліфа (0.5 — контур,
+// більше — всередині). effects: x — ширина обводки, w — розмиття тіні
+// (в одиницях SDF), yz — зсув тіні в UV атласу.
+layout(location = 0) in vec2 v_uv;
+layout(location = 1) in vec4 v_color;
+layout(location = 2) in vec4 v_outline_color;
+layout(location = 3) in vec4 v_shadow_color;
+layout(location = 4) in vec4 v_effects;
+layout(location = 5) in vec4 v_uv_rect;
+
+layout(set = 0, binding = 0) uniform texture2D atlas;
+layout(set = 0, binding = 1) uniform sampler atlas_sampler;
+
+layout(location = 0) out vec4 f_color;
+
+// Відстань у точці атласу; за межами квадрата гліфа (зсунута тінь) — ззовні контуру
+float distance_at(vec2 uv) {
+    vec2 inside = step(v_uv_rect.xy, uv) * step(uv, v_uv_rect.xy + v_uv_rect.zw);
+    return texture(sampler2D(atlas, atlas_sampler), uv).a * inside.x * inside.y;
+}
+
+void main() {
+    float sdf = distance_at(v_uv);
+    // Ширина згладжування — зміна відстані на піксель екрана
+    float smoothing = max(fwidth(sdf) * 0.75, 1e-4);
+
+    float fill = smoothstep(0.5 - smoothing, 0.5 + smoothing, sdf);
+    float outline_edge = 0.5 - clamp(v_effects.x, 0.0, 0.45);
+    float outlined = smoothstep(outline_edge - smoothing, outline_edge + smoothing, sdf);
+    vec4 body = mix(v_outline_color, v_color, fill);
+    body.a *= outlined;
+
+    float softness = clamp(v_effects.w, 0.0, 0.45) + smoothing;
+    float shadow_distance = distance_at(v_uv - v_effects.yz);
+    float shadow = smoothstep(outline_edge - softness, outline_edge + softness, shadow_distance);
+    vec4 below = vec4(v_shadow_color.rgb, v_shadow_color.a * shadow);
+
+    // Тіло тексту поверх тіні (альфа не премножена)
+    float alpha = body.a + below.a * (1.0 - body.a);
+    if (alpha <= 0.0) {
+        discard;
+    }
+    vec3 color = (body.rgb * body.a + below.rgb * below.a * (1.0 - body.a)) / alpha;
+    f_color = vec4(color, alpha);
+}
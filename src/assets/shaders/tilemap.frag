@@ -0,0 +1,37 @@
+#version 450
+// Варіанти режимів перегляду (FLAT_VIEW_MODES): VIEW_WIREFRAME — рамки клітинок,
+// VIEW_OVERDRAW — внесок шару в теплову карту, як у debug_view.frag.
+layout(location = 0) in vec2 v_uv;
+layout(location = 1) in vec2 v_corner;
+
+layout(push_constant) uniform PushConstants {
+    mat4 model_view_proj;
+    vec4 color;
+    vec4 tile_uv;
+    vec2 origin_uv;
+    uint columns;
+    uint time_ms;
+} pc;
+
+layout(set = 0, binding = 0) uniform texture2D tileset;
+layout(set = 0, binding = 1) uniform sampler tileset_sampler;
+
+layout(location = 0) out vec4 f_color;
+
+const vec3 WIRE_COLOR = vec3(0.85, 0.9, 1.0);
+const vec3 OVERDRAW_STEP = vec3(0.2, 0.06, 0.02);
+
+void main() {
+#if defined(VIEW_WIREFRAME)
+    // Лишаємо пікселі ближче одного пікселя до краю клітинки
+    vec2 edge = min(v_corner, 1.0 - v_corner) / fwidth(v_corner);
+    if (min(edge.x, edge.y) > 1.0) {
+        discard;
+    }
+    f_color = vec4(WIRE_COLOR, 1.0);
+#elif defined(VIEW_OVERDRAW)
+    f_color = vec4(OVERDRAW_STEP, 1.0);
+#else
+    f_color = texture(sampler2D(tileset, tileset_sampler), v_uv) * pc.color;
+#endif
+}
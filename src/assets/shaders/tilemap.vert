@@ -0,0 +1,46 @@
+#version 450
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec2 corner;
+layout(location = 2) in uint tile;
+
+layout(push_constant) uniform PushConstants {
+    mat4 model_view_proj;
+    vec4 color;
+    vec4 tile_uv;       // xy — тайл в UV без полутекселя с краёв, zw — шаг сетки
+    vec2 origin_uv;     // Начало сетки атласа (с полутекселем)
+    uint columns;
+    uint time_ms;
+} pc;
+
+// Перші записи — (початок, кількість кадрів) для кожного тайла,
+// далі кадри (тайл, кінець кадру в мс від початку анімації)
+layout(set = 0, binding = 2) readonly buffer Animations {
+    uvec2 entries[];
+} animations;
+
+layout(location = 0) out vec2 v_uv;
+layout(location = 1) out vec2 v_corner;   // Кут клітинки (0..1) для каркаса
+
+uint animated_tile(uint base) {
+    uvec2 header = animations.entries[base];
+    if (header.y == 0u) {
+        return base;
+    }
+    uint period = animations.entries[header.x + header.y - 1u].y;
+    uint time = pc.time_ms % period;
+    for (uint i = 0u; i < header.y; i++) {
+        uvec2 frame = animations.entries[header.x + i];
+        if (time < frame.y) {
+            return frame.x;
+        }
+    }
+    return base;
+}
+
+void main() {
+    gl_Position = pc.model_view_proj * vec4(position, 0.0, 1.0);
+    uint frame = animated_tile(tile);
+    vec2 cell = vec2(float(frame % pc.columns), float(frame / pc.columns));
+    v_uv = pc.origin_uv + cell * pc.tile_uv.zw + corner * pc.tile_uv.xy;
+    v_corner = corner;
+}
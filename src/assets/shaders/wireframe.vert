@@ -0,0 +1,43 @@
+#version 450
+// Каркас без PolygonMode::Line: трикутники меша розгорнуті в окремі вершини,
+// кожна з барицентричною координатою свого кута (див. debug_view.frag).
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec3 barycentric;
+layout(location = 2) in vec4 model_x;
+layout(location = 3) in vec4 model_y;
+layout(location = 4) in vec4 model_z;
+layout(location = 5) in vec4 model_w;
+
+#ifdef SKINNED
+// Скінінг: до чотирьох суглобів на вершину, палітра суглобів усіх інстансів батча
+// лежить підряд, інстанс бере свою зі зміщення joint_offset
+layout(location = 6) in uvec4 joints;
+layout(location = 7) in vec4 weights;
+layout(location = 8) in uint joint_offset;
+
+layout(set = 2, binding = 0) readonly buffer JointPalette {
+    mat4 matrices[];
+} palette;
+#endif
+
+layout(push_constant) uniform PushConstants {
+    mat4 view_proj;
+    vec4 camera_position;
+    vec4 environment;
+} pc;
+
+layout(location = 0) out vec3 v_barycentric;
+
+void main() {
+    mat4 model = mat4(model_x, model_y, model_z, model_w);
+#ifdef SKINNED
+    model = model * (
+        weights.x * palette.matrices[joint_offset + joints.x]
+        + weights.y * palette.matrices[joint_offset + joints.y]
+        + weights.z * palette.matrices[joint_offset + joints.z]
+        + weights.w * palette.matrices[joint_offset + joints.w]
+    );
+#endif
+    gl_Position = pc.view_proj * model * vec4(position, 1.0);
+    v_barycentric = barycentric;
+}
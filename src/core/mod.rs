@@ -4,6 +4,8 @@ mod time;
 
 use std::sync::Arc;
 
+use cgmath::{Matrix4, Point3, SquareMatrix};
+use egui_winit::egui::FontDefinitions;
 use egui_winit::winit as winit;
 
 use egui_winit_vulkano::{Gui, GuiConfig};
@@ -11,9 +13,12 @@ use vulkano::{
     command_buffer::allocator::{
         StandardCommandBufferAllocator, StandardCommandBufferAllocatorCreateInfo,
     },
+    device::physical::PhysicalDevice,
     format::Format,
-    image::{view::ImageView, Image, ImageCreateInfo, ImageType, ImageUsage},
+    image::{sampler::SamplerCreateInfo, view::ImageView, Image, ImageCreateInfo, ImageType, ImageUsage},
+    instance::Instance,
     memory::allocator::AllocationCreateInfo,
+    VulkanLibrary,
 };
 use vulkano::swapchain::{PresentMode, SwapchainCreateInfo};
 use vulkano_util::{
@@ -26,8 +31,15 @@ use winit::{application::ApplicationHandler, event::WindowEvent};
 use crate::{graphics::pipeline::NRenderPipeline,
             graphics::renderer::NRenderer,
             graphics::pipeline::NAllocators,
+            graphics::outline::{NHighlight, NHighlights},
+            graphics::systems::debug::NDebugStyle,
+            graphics::systems::shape::NShapeStyle,
+            graphics::font::{NFont, NFontSettings},
             ui::gui::GuiSystem,
-            core::time::TimeInfo
+            ui::selection::NSelectMode,
+            ui::windows::viewport::NViewportPick,
+            core::time::TimeInfo,
+            scene::scene::NScene,
         };
 
 // Основная структура приложения
@@ -37,7 +49,8 @@ pub struct App {
     scene_view_size: [u32; 2],     // Размер сцены
     pub scene_image: Arc<ImageView>,    // Изображение для рендеринга сцены
     time: TimeInfo,                 // Информация о времени и FPS
-    renderer: NRenderer,  // Пайплайн рендеринга
+    scene: NScene,                  // Сцена (камера и её пост-обработка)
+    pub renderer: NRenderer,  // Пайплайн рендеринга
     gui_system: Option<GuiSystem>,   // Состояние GUI
     is_minimized: bool,
 }
@@ -45,10 +58,7 @@ pub struct App {
 impl Default for App {
     fn default() -> Self {
         // Vulkano context with explicit Vulkan configuration
-        let mut config = VulkanoConfig::default();
-        // config.device_features.shader_float64 = true; // Enable Vulkan features
-
-        let context = VulkanoContext::new(config);
+        let context = create_context();
 
         // Vulkano windows
         let windows = VulkanoWindows::default();
@@ -56,27 +66,15 @@ impl Default for App {
         // Create renderer for our scene & ui
         let scene_view_size = [256, 256];
         // Create a simple image to which we'll draw the triangle scene
-        let scene_image = ImageView::new_default(
-            Image::new(
-                context.memory_allocator().clone(),
-                ImageCreateInfo {
-                    image_type: ImageType::Dim2d,
-                    format: DEFAULT_IMAGE_FORMAT,
-                    extent: [scene_view_size[0], scene_view_size[1], 1],
-                    array_layers: 1,
-                    usage: ImageUsage::SAMPLED | ImageUsage::COLOR_ATTACHMENT,
-                    ..Default::default()
-                },
-                AllocationCreateInfo::default(),
-            )
-            .unwrap(),
-        )
-        .unwrap();
+        let scene_image = create_scene_image(&context, scene_view_size);
 
         let time = TimeInfo::new();
 
         // Create our render pipeline
         let renderer = NRenderer::new(&context);
+        // Шрифт підписів debug draw і тексту демо-сцени
+        let font = default_font();
+        renderer.render_pipeline.debug_draw().set_font(Some(font.clone()));
 
         Self {
             context,
@@ -84,6 +82,7 @@ impl Default for App {
             scene_view_size,
             scene_image,
             time,
+            scene: NScene::demo(font),
             renderer,
             gui_system: None,
             is_minimized: false,
@@ -91,6 +90,194 @@ impl Default for App {
     }
 }
 
+// Шрифт за замовчуванням — Ubuntu-Light із вбудованих шрифтів egui (латиниця й кирилиця)
+fn default_font() -> Arc<NFont> {
+    let fonts = FontDefinitions::default();
+    let data = &fonts.font_data["Ubuntu-Light"];
+    Arc::new(NFont::from_bytes("Ubuntu-Light", &data.font[..], &NFontSettings::default()).unwrap())
+}
+
+// Контекст Vulkan. Каркас вьюпорта лініями (PolygonMode::Line) потребує
+// fill_mode_non_solid, а можливості пристрою задаються в конфігурації до створення
+// контексту. Тому фізичний пристрій обирається заздалегідь тим самим правилом, що й
+// у VulkanoContext (device_filter_fn, потім найменший device_priority_fn), і фільтр
+// закріплює вибір за device_uuid. Без можливості меш-система малює каркас
+// барицентричним шейдером.
+fn create_context() -> VulkanoContext {
+    let mut config = VulkanoConfig::default();
+    // config.device_features.shader_float64 = true; // Enable Vulkan features
+    let physical_device = select_physical_device(&config);
+    config.device_features.fill_mode_non_solid =
+        physical_device.supported_features().fill_mode_non_solid;
+
+    // Пристрої без Vulkan 1.1 не мають UUID; тоді той самий вибір дає саме правило
+    let selected = physical_device.properties().device_uuid;
+    let default_filter = config.device_filter_fn.clone();
+    config.device_filter_fn = Arc::new(move |device| {
+        default_filter(device)
+            && (selected.is_none() || device.properties().device_uuid == selected)
+    });
+    VulkanoContext::new(config)
+}
+
+// Фізичний пристрій, який обрав би VulkanoContext з цією конфігурацією.
+// Тимчасовий instance лише перелічує пристрої, логічний пристрій не створюється.
+fn select_physical_device(config: &VulkanoConfig) -> Arc<PhysicalDevice> {
+    let library = VulkanLibrary::new().expect("failed to load Vulkan library");
+    let instance = Instance::new(library, config.instance_create_info.clone())
+        .expect("failed to create Vulkan instance");
+    instance
+        .enumerate_physical_devices()
+        .expect("failed to enumerate physical devices")
+        .filter(|device| (config.device_filter_fn)(device))
+        .min_by_key(|device| (config.device_priority_fn)(device))
+        .expect("no suitable physical device")
+}
+
+// Зображення, в яке рендериться сцена і яке показує вьюпорт редактора
+fn create_scene_image(context: &VulkanoContext, size: [u32; 2]) -> Arc<ImageView> {
+    ImageView::new_default(
+        Image::new(
+            context.memory_allocator().clone(),
+            ImageCreateInfo {
+                image_type: ImageType::Dim2d,
+                format: DEFAULT_IMAGE_FORMAT,
+                extent: [size[0], size[1], 1],
+                array_layers: 1,
+                usage: ImageUsage::SAMPLED | ImageUsage::COLOR_ATTACHMENT,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+// Перестворює зображення сцени під розмір панелі вьюпорта
+// і перереєструє його як текстуру egui
+fn resize_scene_image(
+    context: &VulkanoContext,
+    scene_view_size: &mut [u32; 2],
+    scene_image: &mut Arc<ImageView>,
+    gui_system: &mut GuiSystem,
+) {
+    let mut viewport = gui_system.viewport.borrow_mut();
+    if viewport.size == *scene_view_size || viewport.size.contains(&0) {
+        return;
+    }
+    *scene_view_size = viewport.size;
+    *scene_image = create_scene_image(context, *scene_view_size);
+
+    if let Some(texture) = viewport.texture.take() {
+        gui_system.gui.unregister_user_image(texture);
+    }
+    viewport.texture = Some(
+        gui_system.gui.register_user_image_view(scene_image.clone(), SamplerCreateInfo::default()),
+    );
+}
+
+// Копіює компоненти основної виділеної сутності в панель Details
+// та список сутностей у панель Hierarchy. Незастосовані правки панелі не перезаписуються.
+fn update_details(scene: &NScene, gui_system: &GuiSystem) {
+    gui_system.hierarchy.borrow_mut().entities =
+        scene.entities.iter().map(|entity| (entity.id(), entity.name.clone())).collect();
+    gui_system.viewport.borrow_mut().background = scene.camera.background;
+
+    let mut details = gui_system.details.borrow_mut();
+    if details.changed {
+        return;
+    }
+    let entity = gui_system.selection.borrow().primary().and_then(|id| scene.entity(id));
+    details.entity = entity.map(|entity| entity.id());
+    details.name = entity.map(|entity| entity.name.clone()).unwrap_or_default();
+    details.particles = entity.and_then(|entity| entity.particles.clone());
+}
+
+// Повертає в сцену фон камери з вьюпорту та компоненти, змінені в панелі Details
+fn apply_details(scene: &mut NScene, gui_system: &GuiSystem) {
+    scene.camera.background = gui_system.viewport.borrow().background;
+
+    let mut details = gui_system.details.borrow_mut();
+    if !std::mem::take(&mut details.changed) {
+        return;
+    }
+    if let Some(entity) = details.entity.and_then(|id| scene.entity_mut(id)) {
+        entity.particles = details.particles.clone();
+    }
+}
+
+// Виділення кліком або рамкою у вьюпорті: промінь з камери або frustum рамки
+// перевіряються з межами сутностей у BVH сцени (частинки без меж не виділяються).
+// Видалені зі сцени сутності прибираються з виділення.
+fn pick_entities(scene: &NScene, scene_view_size: [u32; 2], gui_system: &GuiSystem) {
+    let mut selection = gui_system.selection.borrow_mut();
+    selection.retain(|id| scene.entity(id).is_some());
+    let Some(pick) = gui_system.viewport.borrow_mut().pick.take() else { return };
+    match pick {
+        NViewportPick::Point { uv, mode } => {
+            let ray = scene.camera.screen_ray(uv, scene_view_size);
+            selection.select(scene.raycast(&ray).map(|(id, _)| id), mode);
+        }
+        NViewportPick::Region { min, max, mode } => {
+            let frustum = scene.camera.region_frustum(min, max, scene_view_size);
+            let entities = scene.visible_entities(&frustum);
+            selection.select(entities.into_iter().map(|index| scene.entities[index].id()), mode);
+        }
+    }
+}
+
+// Що обвести контуром: виділені сутності та сутність під курсором у вьюпорті
+fn editor_highlights(
+    scene: &NScene,
+    scene_view_size: [u32; 2],
+    gui_system: &GuiSystem,
+) -> NHighlights {
+    let hovered = gui_system.viewport.borrow().hover.and_then(|uv| {
+        let ray = scene.camera.screen_ray(uv, scene_view_size);
+        scene.raycast(&ray).map(|(id, _)| id)
+    });
+    NHighlights {
+        selected: gui_system.selection.borrow().entities().to_vec(),
+        hovered,
+    }
+}
+
+// Гізмо редактора: сітка на площині XZ та осі світу. Виділені сутності без
+// геометрії (лише частинки) отримують сферу-маркер, яку обводить контур виділення.
+// Підписи осей text_3d малює рендерер шрифтом debug draw.
+fn draw_editor_gizmos(render_pipeline: &NRenderPipeline, scene: &NScene, gui_system: &GuiSystem) {
+    let debug = render_pipeline.debug_draw();
+    debug.grid(Point3::new(0.0, -0.5, 0.0), 1.0, 32, NDebugStyle::new([0.5, 0.5, 0.5, 0.5]));
+    debug.axes(Matrix4::identity(), 1.0, NDebugStyle::new([1.0; 4]).on_top());
+    for (name, position) in [("X", [1.1, 0.0, 0.0]), ("Y", [0.0, 1.1, 0.0]), ("Z", [0.0, 0.0, 1.1])] {
+        debug.text_3d(Point3::from(position), name, NDebugStyle::new([1.0; 4]).on_top());
+    }
+    let marker = NDebugStyle::new([1.0, 0.6, 0.1, 0.5]).highlight(NHighlight::Selected);
+    for &id in gui_system.selection.borrow().entities() {
+        let Some(entity) = scene.entity(id) else { continue };
+        if entity.particles.is_some() && scene.bvh().bounds(id).is_none() {
+            let position = entity.transform.position;
+            debug.sphere(Point3::new(position.x, position.y, position.z), 0.25, marker);
+        }
+    }
+}
+
+// Підкладка під екранний текст сцени (HUD): напівпрозора панель з рамкою
+fn draw_hud(render_pipeline: &NRenderPipeline, scene: &NScene, scene_view_size: [u32; 2]) {
+    const PADDING: f32 = 10.0;
+    let shapes = render_pipeline.shapes();
+    let viewport = scene_view_size.map(|extent| extent as f32);
+    for entity in &scene.entities {
+        let Some(text) = &entity.text else { continue };
+        let Some((min, max)) = text.screen_rect(viewport) else { continue };
+        let min = min.map(|value| value - PADDING);
+        let max = max.map(|value| value + PADDING);
+        shapes.rounded_rect(min, max, 8.0, NShapeStyle::fill([0.05, 0.05, 0.08, 0.55]));
+        shapes.rounded_rect(min, max, 8.0, NShapeStyle::stroke([1.0, 1.0, 1.0, 0.25], 1.0));
+    }
+}
+
 impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
         let window_descriptor = WindowDescriptor {
@@ -112,6 +299,12 @@ impl ApplicationHandler for App {
         self.gui_system = Some(GuiSystem::new(event_loop, self));
     }
 
+    // Кеш пайплайнів зберігається тут, а не лише в Drop: після exit() цикл подій
+    // може завершити процес, не знищивши App
+    fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        self.renderer.shutdown();
+    }
+
     fn window_event(
         &mut self,
         event_loop: &winit::event_loop::ActiveEventLoop,
@@ -142,24 +335,80 @@ impl ApplicationHandler for App {
                 WindowEvent::CloseRequested => {
                     event_loop.exit();
                 }
+                // Перетягнутий у вікно ассет імпортується в сцену і стає виділеним
+                WindowEvent::DroppedFile(path) => {
+                    let gui_system = self.gui_system.as_mut().unwrap();
+                    match self.scene.import(&path) {
+                        Ok(entity) => {
+                            let mut selection = gui_system.selection.borrow_mut();
+                            selection.select([entity], NSelectMode::Replace);
+                        }
+                        Err(error) => gui_system.import_errors.push(error),
+                    }
+                }
                 WindowEvent::RedrawRequested => {
                     if self.is_minimized {
                         return;
                     }
 
+                    self.time.update();
+                    update_details(&self.scene, self.gui_system.as_ref().unwrap());
                     self.gui_system.as_mut().unwrap().draw();
+                    apply_details(&mut self.scene, self.gui_system.as_ref().unwrap());
+                    self.scene.update_animations(self.time.dt() / 1000.0);
+                    self.scene.update_bounds();
+                    pick_entities(
+                        &self.scene,
+                        self.scene_view_size,
+                        self.gui_system.as_ref().unwrap(),
+                    );
+                    resize_scene_image(
+                        &self.context,
+                        &mut self.scene_view_size,
+                        &mut self.scene_image,
+                        self.gui_system.as_mut().unwrap(),
+                    );
+                    // Застосовуємо налаштування з редактора (перебудова пайплайнів при зміні MSAA)
+                    let render_settings = self.gui_system.as_ref().unwrap().render_settings;
+                    self.renderer.render_pipeline.apply_settings(&render_settings);
+                    let gui_system = self.gui_system.as_mut().unwrap();
+                    if std::mem::take(&mut gui_system.dump_render_graph) {
+                        gui_system.render_graph =
+                            Some(self.renderer.render_pipeline.dump_graph());
+                    }
+                    draw_editor_gizmos(
+                        &self.renderer.render_pipeline,
+                        &self.scene,
+                        self.gui_system.as_ref().unwrap(),
+                    );
+                    draw_hud(&self.renderer.render_pipeline, &self.scene, self.scene_view_size);
+                    let highlights = editor_highlights(
+                        &self.scene,
+                        self.scene_view_size,
+                        self.gui_system.as_ref().unwrap(),
+                    );
                     // Render UI
                     // Acquire swapchain future
                     match renderer.acquire( None , |_| {}) {
                         Ok(future) => {
                             // Draw scene
-                            let after_scene_draw =
-                                self.renderer.render_pipeline.render(future, self.scene_image.clone());
+                            let after_scene_draw = self.renderer.render_pipeline.render(
+                                future,
+                                self.scene_image.clone(),
+                                &self.scene,
+                                &highlights,
+                                self.time.dt() / 1000.0,
+                            );
+                            let gui_system = self.gui_system.as_mut().unwrap();
+                            gui_system.render_stats = self.renderer.render_pipeline.stats().clone();
+                            gui_system.shader_errors = self.renderer.render_pipeline.shader_errors();
                             // Render gui
                             let after_future = self.gui_system.as_mut().unwrap().gui
                                 .draw_on_image(after_scene_draw, renderer.swapchain_image_view());
-                            // Present swapchain
-                            renderer.present(after_future, true);
+                            // Present swapchain без очікування GPU: кадри в польоті
+                            // обмежує NRenderPipeline своїми fence-ами
+                            renderer.present(after_future, false);
+                            self.renderer.update();
                         }
                         Err(vulkano::VulkanError::OutOfDate) => {
                             renderer.resize();
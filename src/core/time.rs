@@ -23,7 +23,6 @@ impl TimeInfo {
         TimeInfo { dt: 0.0, fps: 0.0, frame_sum: 0.0, dt_sum: 0.0, prev_time: Instant::now() }
     }
 
-    #[allow(dead_code)]
     pub fn dt(&self) -> f32 {
         self.dt
     }
@@ -0,0 +1,11 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Унікальний ідентифікатор ассета (меш, текстура, матеріал).
+// За ним draw системи кешують GPU ресурси і групують інстанси.
+pub type NAssetId = u64;
+
+static NEXT_ASSET_ID: AtomicU64 = AtomicU64::new(1);
+
+pub fn next_asset_id() -> NAssetId {
+    NEXT_ASSET_ID.fetch_add(1, Ordering::Relaxed)
+}
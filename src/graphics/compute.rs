@@ -0,0 +1,82 @@
+// Compute проходи кадру.
+// NComputeSystem записує dispatch-і в один з compute проходів графа:
+// - PRE_COMPUTE_PASS виконується до растрових проходів (симуляція, culling),
+// - POST_COMPUTE_PASS — після сцени й до пост-обробки, з HDR зображенням як storage image.
+// Порядок і бар'єри між compute та растровими проходами визначає граф за оголошеними
+// доступами; самі pipeline barrier-и вставляє AutoCommandBufferBuilder.
+// Системи з async_compute() = true записуються в окремий командний буфер на
+// compute черзі (якщо VulkanoContext має окрему). Він відправляється перед графічним
+// буфером кадру в тому ж ланцюжку GpuFuture, і vulkano ставить семафор між чергами.
+// Буфери, які ділять обидві черги, треба створювати з NComputeQueues::buffer_create_info().
+
+use std::sync::Arc;
+
+use vulkano::{
+    buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer},
+    device::Queue,
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    sync::Sharing,
+};
+
+/// Черги для compute роботи: графічна та (якщо є) окрема compute черга
+#[derive(Clone)]
+pub struct NComputeQueues {
+    graphics: Arc<Queue>,           // Графическая очередь (кадр и синхронные compute системы)
+    compute: Option<Arc<Queue>>,    // Отдельная compute очередь
+}
+
+impl NComputeQueues {
+    // compute — черга з VulkanoContext::compute_queue; якщо це та сама графічна
+    // черга, асинхронні системи виконуються синхронно у своєму проході
+    pub fn new(graphics: Arc<Queue>, compute: Arc<Queue>) -> Self {
+        let compute = (!Arc::ptr_eq(&graphics, &compute)).then_some(compute);
+        NComputeQueues { graphics, compute }
+    }
+
+    // Окрема compute черга, якщо вона є
+    #[inline]
+    pub fn async_queue(&self) -> Option<&Arc<Queue>> {
+        self.compute.as_ref()
+    }
+
+    // Опис буфера, доступного обом чергам: якщо compute черга з іншого сімейства,
+    // буфер створюється з Sharing::Concurrent замість передачі володіння
+    pub fn buffer_create_info(&self, usage: BufferUsage) -> BufferCreateInfo {
+        let sharing = match &self.compute {
+            Some(compute)
+                if compute.queue_family_index() != self.graphics.queue_family_index() =>
+            {
+                Sharing::Concurrent(
+                    [self.graphics.queue_family_index(), compute.queue_family_index()]
+                        .into_iter()
+                        .collect(),
+                )
+            }
+            _ => Sharing::Exclusive,
+        };
+        BufferCreateInfo { usage, sharing, ..Default::default() }
+    }
+
+    // Storage буфер у пам'яті GPU, доступний обом чергам.
+    // usage доповнюється STORAGE_BUFFER (наприклад, VERTEX_BUFFER для рендерингу результату).
+    pub fn storage_buffer<T>(
+        &self,
+        memory_allocator: &Arc<StandardMemoryAllocator>,
+        len: u64,
+        usage: BufferUsage,
+    ) -> Subbuffer<[T]>
+    where
+        T: BufferContents,
+    {
+        Buffer::new_slice(
+            memory_allocator.clone(),
+            self.buffer_create_info(usage | BufferUsage::STORAGE_BUFFER),
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                ..Default::default()
+            },
+            len,
+        )
+        .unwrap()
+    }
+}
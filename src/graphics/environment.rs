@@ -0,0 +1,668 @@
+// Оточення для освітлення на основі зображень (IBL) і skybox-а.
+// NEnvironment — HDR зображення на CPU: панорама (equirectangular) або шість граней
+// кубмапи. NEnvironmentCache при першому використанні оточення синхронно запікає
+// його на GPU:
+// - кубмапу оточення з повним ланцюжком mip-ів (skybox і джерело для згорток),
+// - irradiance кубмапу — косинусна згортка, дифузне світло,
+// - specular кубмапу — mip N префільтрований GGX з шорсткістю N / (mips - 1),
+// - BRDF LUT — не залежить від оточення, рахується один раз на кеш.
+// Запікання — повноекранні проходи в кожну грань кубмапи шейдерами з shader_dir().
+
+use std::{
+    collections::HashMap,
+    f32::consts::PI,
+    sync::{Arc, Mutex},
+};
+
+use vulkano::{
+    buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage},
+    command_buffer::{
+        AutoCommandBufferBuilder, BlitImageInfo, CommandBufferUsage, CopyBufferToImageInfo,
+        ImageBlit, PrimaryAutoCommandBuffer, RenderPassBeginInfo, SubpassBeginInfo,
+        SubpassContents,
+    },
+    descriptor_set::{DescriptorSet, WriteDescriptorSet},
+    device::Queue,
+    format::Format,
+    image::{
+        sampler::{
+            Filter, Sampler, SamplerAddressMode, SamplerCreateInfo, SamplerMipmapMode,
+            LOD_CLAMP_NONE,
+        },
+        view::{ImageView, ImageViewCreateInfo, ImageViewType},
+        Image, ImageAspects, ImageCreateFlags, ImageCreateInfo, ImageSubresourceLayers,
+        ImageSubresourceRange, ImageType, ImageUsage,
+    },
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter},
+    pipeline::{graphics::viewport::Viewport, GraphicsPipeline, Pipeline, PipelineBindPoint},
+    render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass},
+    sync::{self, GpuFuture},
+};
+
+use crate::graphics::{
+    asset::{next_asset_id, NAssetId},
+    frame::HDR_IMAGE_FORMAT,
+    pipeline::NAllocators,
+    post::{fullscreen_pipeline, single_attachment_render_pass},
+    shader::NShaderStage,
+};
+
+// Набір дескрипторів оточення в шейдерах з IBL та прив'язки в ньому
+pub const ENVIRONMENT_SET: u32 = 1;
+pub const ENVIRONMENT_IRRADIANCE: u32 = 0;
+pub const ENVIRONMENT_SPECULAR: u32 = 1;
+pub const ENVIRONMENT_BRDF_LUT: u32 = 2;
+pub const ENVIRONMENT_SAMPLER: u32 = 3;
+
+// Розміри граней запечених кубмап
+const ENVIRONMENT_SIZE: u32 = 512;
+const IRRADIANCE_SIZE: u32 = 32;
+const SPECULAR_SIZE: u32 = 128;
+// Рівні шорсткості specular кубмапи: 128, 64, ..., 4
+const SPECULAR_MIPS: u32 = 6;
+const BRDF_LUT_SIZE: u32 = 256;
+const BRDF_LUT_FORMAT: Format = Format::R16G16_SFLOAT;
+
+/// Вихідне зображення оточення (лінійний RGBA32F)
+#[derive(Debug)]
+pub enum NEnvironmentSource {
+    // Панорама: пікселі RGBA построчно, зверху (+Y) вниз
+    Equirectangular { size: [u32; 2], data: Vec<f32> },
+    // Квадратні грані size x size одна за одною в порядку шарів кубмапи Vulkan:
+    // +X, -X, +Y, -Y, +Z, -Z
+    Cube { size: u32, data: Vec<f32> },
+}
+
+/// HDR оточення сцени
+#[derive(Debug)]
+pub struct NEnvironment {
+    id: NAssetId,               // Идентификатор ассета
+    pub name: String,           // Имя окружения
+    pub source: NEnvironmentSource, // Исходное изображение
+    pub intensity: f32,         // Множитель яркости освещения и skybox
+}
+
+impl NEnvironment {
+    // Оточення з equirectangular панорами
+    pub fn new(name: impl Into<String>, size: [u32; 2], data: Vec<f32>) -> Self {
+        assert_eq!(
+            data.len(),
+            (size[0] * size[1] * 4) as usize,
+            "environment data size mismatch"
+        );
+        let source = NEnvironmentSource::Equirectangular { size, data };
+        NEnvironment { id: next_asset_id(), name: name.into(), source, intensity: 1.0 }
+    }
+
+    // Оточення з шести граней кубмапи (+X, -X, +Y, -Y, +Z, -Z), кожна size x size RGBA
+    #[allow(dead_code)]
+    pub fn from_faces(name: impl Into<String>, size: u32, faces: [Vec<f32>; 6]) -> Self {
+        for face in &faces {
+            assert_eq!(face.len(), (size * size * 4) as usize, "cubemap face size mismatch");
+        }
+        let source = NEnvironmentSource::Cube { size, data: faces.concat() };
+        NEnvironment { id: next_asset_id(), name: name.into(), source, intensity: 1.0 }
+    }
+
+    #[inline]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    // Завантажує .hdr/.exr панораму
+    #[cfg(feature = "image")]
+    #[allow(dead_code)]
+    pub fn load_hdr(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let image = image::open(path).map_err(|err| err.to_string())?.into_rgba32f();
+        let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+        Ok(NEnvironment::new(
+            name.unwrap_or_default(),
+            [image.width(), image.height()],
+            image.into_raw(),
+        ))
+    }
+
+    // Завантажує шість граней кубмапи (+X, -X, +Y, -Y, +Z, -Z). HDR формати
+    // (.hdr, .exr) вже лінійні, звичайні зображення (PNG, JPEG) переводяться з sRGB.
+    #[cfg(feature = "image")]
+    #[allow(dead_code)]
+    pub fn load_faces(
+        name: impl Into<String>,
+        paths: [impl AsRef<std::path::Path>; 6],
+    ) -> Result<Self, String> {
+        let mut size = None;
+        let mut faces = Vec::with_capacity(6);
+        for path in &paths {
+            let path = path.as_ref();
+            let image = image::open(path).map_err(|err| err.to_string())?;
+            let hdr = matches!(image.color(), image::ColorType::Rgb32F | image::ColorType::Rgba32F);
+            let image = image.into_rgba32f();
+            if image.width() != image.height() || size.is_some_and(|size| size != image.width()) {
+                return Err(format!("{}: cubemap faces must be equal squares", path.display()));
+            }
+            size = Some(image.width());
+            let mut data = image.into_raw();
+            if !hdr {
+                for pixel in data.chunks_exact_mut(4) {
+                    for channel in &mut pixel[..3] {
+                        *channel = srgb_to_linear(*channel);
+                    }
+                }
+            }
+            faces.push(data);
+        }
+        let faces: [Vec<f32>; 6] = faces.try_into().unwrap();
+        Ok(NEnvironment::from_faces(name, size.unwrap(), faces))
+    }
+
+    // Процедурне небо: градієнт від зеніту до горизонту, земля та сонце
+    // в напрямку основного світла сцени
+    pub fn sky(name: impl Into<String>) -> Self {
+        const WIDTH: u32 = 512;
+        const HEIGHT: u32 = 256;
+        const ZENITH: [f32; 3] = [0.18, 0.36, 0.85];
+        const HORIZON: [f32; 3] = [0.85, 0.9, 1.0];
+        const GROUND: [f32; 3] = [0.22, 0.2, 0.18];
+        const SUN: [f32; 3] = [40.0, 36.0, 30.0];
+        const SUN_DIRECTION: [f32; 3] = [0.3114, 0.7785, 0.4671];
+        const SUN_COS_RADIUS: f32 = 0.9995;
+
+        let mix = |a: [f32; 3], b: [f32; 3], t: f32| [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t);
+        let mut data = Vec::with_capacity((WIDTH * HEIGHT * 4) as usize);
+        for y in 0..HEIGHT {
+            let theta = (y as f32 + 0.5) / HEIGHT as f32 * PI;
+            for x in 0..WIDTH {
+                let phi = ((x as f32 + 0.5) / WIDTH as f32 - 0.5) * 2.0 * PI;
+                let direction = [theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin()];
+                let mut color = if direction[1] >= 0.0 {
+                    mix(HORIZON, ZENITH, direction[1].sqrt())
+                } else {
+                    mix(HORIZON, GROUND, (-direction[1]).powf(0.3))
+                };
+                let sun_cos = (0..3).map(|i| direction[i] * SUN_DIRECTION[i]).sum::<f32>();
+                if sun_cos > SUN_COS_RADIUS {
+                    color = SUN;
+                }
+                data.extend_from_slice(&[color[0], color[1], color[2], 1.0]);
+            }
+        }
+        NEnvironment::new(name, [WIDTH, HEIGHT], data)
+    }
+}
+
+/// Запечені на GPU карти оточення
+pub struct NEnvironmentMaps {
+    pub environment: Arc<ImageView>,    // Кубмапа окружения с mip-ами (skybox)
+    pub irradiance: Arc<ImageView>,     // Диффузное освещение
+    pub specular: Arc<ImageView>,       // Префильтрованное отражение по шероховатости
+    pub brdf_lut: Arc<ImageView>,       // BRDF LUT (N·V, шероховатость)
+    pub sampler: Arc<Sampler>,          // Линейный сэмплер с mip-ами
+    pub specular_mips: u32,             // Количество mip-ов specular
+}
+
+#[repr(C)]
+#[derive(BufferContents)]
+struct NIblPushConstants {
+    face: i32,                  // Грань кубмапы
+    roughness: f32,             // Шероховатость (префильтр specular)
+}
+
+// Пайплайни запікання
+struct NIblBaker {
+    cube_render_pass: Arc<RenderPass>,  // Проход в грань HDR кубмапы
+    lut_render_pass: Arc<RenderPass>,   // Проход в BRDF LUT
+    cubemap: Arc<GraphicsPipeline>,     // Панорама -> кубмапа
+    cube_faces: Arc<GraphicsPipeline>,  // Грани исходной кубмапы -> кубмапа (SOURCE_CUBE)
+    irradiance: Arc<GraphicsPipeline>,  // Косинусная свёртка
+    specular: Arc<GraphicsPipeline>,    // GGX префильтр
+    brdf_lut: Arc<GraphicsPipeline>,    // Интеграл BRDF
+    panorama_sampler: Arc<Sampler>,     // Ближайший сэмплер для RGBA32F исходника
+}
+
+struct NEnvironmentCacheState {
+    gfx_queue: Arc<Queue>,                              // Очередь для запекания
+    allocators: NAllocators,                            // Аллокаторы и шейдеры
+    sampler: Arc<Sampler>,                              // Сэмплер карт окружения
+    baker: Option<NIblBaker>,                           // Создаётся при первом запекании
+    brdf_lut: Option<Arc<ImageView>>,                   // Общий BRDF LUT
+    maps: HashMap<NAssetId, Arc<NEnvironmentMaps>>,     // Запечённые окружения
+    fallback: Arc<NEnvironment>,                        // Окружение для сцен без своего
+}
+
+/// Кеш запечених оточень. Клони ділять один стан.
+#[derive(Clone)]
+pub struct NEnvironmentCache {
+    state: Arc<Mutex<NEnvironmentCacheState>>,
+}
+
+impl NEnvironmentCache {
+    pub fn new(gfx_queue: Arc<Queue>, allocators: &NAllocators) -> Self {
+        let sampler = Sampler::new(gfx_queue.device().clone(), SamplerCreateInfo {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            mipmap_mode: SamplerMipmapMode::Linear,
+            address_mode: [SamplerAddressMode::ClampToEdge; 3],
+            lod: 0.0..=LOD_CLAMP_NONE,
+            ..Default::default()
+        })
+        .unwrap();
+        NEnvironmentCache {
+            state: Arc::new(Mutex::new(NEnvironmentCacheState {
+                gfx_queue,
+                allocators: allocators.clone(),
+                sampler,
+                baker: None,
+                brdf_lut: None,
+                maps: HashMap::new(),
+                fallback: Arc::new(NEnvironment::sky("Default sky")),
+            })),
+        }
+    }
+
+    // Карти оточення (None — оточення за замовчуванням). Перший виклик
+    // для оточення запікає його і чекає на GPU.
+    pub fn get(&self, environment: Option<&NEnvironment>) -> Arc<NEnvironmentMaps> {
+        let mut state = self.state.lock().unwrap();
+        let fallback = state.fallback.clone();
+        let environment = environment.unwrap_or(&fallback);
+        if let Some(maps) = state.maps.get(&environment.id()) {
+            return maps.clone();
+        }
+        let maps = Arc::new(state.bake(environment));
+        state.maps.insert(environment.id(), maps.clone());
+        maps
+    }
+
+    // Інтенсивність оточення (None — оточення за замовчуванням)
+    pub fn intensity(&self, environment: Option<&NEnvironment>) -> f32 {
+        match environment {
+            Some(environment) => environment.intensity,
+            None => self.state.lock().unwrap().fallback.intensity,
+        }
+    }
+}
+
+impl NEnvironmentCacheState {
+    fn baker(&mut self) -> &NIblBaker {
+        if self.baker.is_none() {
+            self.baker = Some(NIblBaker::new(&self.gfx_queue, &self.allocators));
+        }
+        self.baker.as_ref().unwrap()
+    }
+
+    // bake:
+    // - завантажує панораму або грані в RGBA32F зображення,
+    // - малює його в 6 граней кубмапи оточення (з перемасштабуванням до
+    //   ENVIRONMENT_SIZE і HDR формату) і будує mip-и blit-ами,
+    // - згортає кубмапу в irradiance та specular (кожен mip — своя шорсткість),
+    // - за потреби рахує BRDF LUT,
+    // - відправляє все одним командним буфером і чекає завершення.
+    fn bake(&mut self, environment: &NEnvironment) -> NEnvironmentMaps {
+        let gfx_queue = self.gfx_queue.clone();
+        let allocators = self.allocators.clone();
+        let sampler = self.sampler.clone();
+        let brdf_lut = self.brdf_lut.clone();
+        let baker = self.baker();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            allocators.command_buffers.clone(),
+            gfx_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        let source = upload_source(&mut builder, &allocators, &environment.source);
+        let source_pipeline = match environment.source {
+            NEnvironmentSource::Equirectangular { .. } => &baker.cubemap,
+            NEnvironmentSource::Cube { .. } => &baker.cube_faces,
+        };
+
+        let environment_mips = ENVIRONMENT_SIZE.ilog2() + 1;
+        let environment_image = create_cube_image(&allocators, ENVIRONMENT_SIZE, environment_mips);
+        for face in 0..6 {
+            baker.draw(
+                &mut builder,
+                &allocators,
+                source_pipeline,
+                face_view(&environment_image, face, 0),
+                ENVIRONMENT_SIZE,
+                vec![
+                    WriteDescriptorSet::image_view(0, source.clone()),
+                    WriteDescriptorSet::sampler(1, baker.panorama_sampler.clone()),
+                ],
+                NIblPushConstants { face: face as i32, roughness: 0.0 },
+            );
+        }
+        generate_cube_mips(&mut builder, &environment_image, ENVIRONMENT_SIZE, environment_mips);
+        let environment_view = cube_view(&environment_image);
+        let environment_writes = || {
+            vec![
+                WriteDescriptorSet::image_view(0, environment_view.clone()),
+                WriteDescriptorSet::sampler(1, sampler.clone()),
+            ]
+        };
+
+        let irradiance_image = create_cube_image(&allocators, IRRADIANCE_SIZE, 1);
+        for face in 0..6 {
+            baker.draw(
+                &mut builder,
+                &allocators,
+                &baker.irradiance,
+                face_view(&irradiance_image, face, 0),
+                IRRADIANCE_SIZE,
+                environment_writes(),
+                NIblPushConstants { face: face as i32, roughness: 0.0 },
+            );
+        }
+
+        let specular_image = create_cube_image(&allocators, SPECULAR_SIZE, SPECULAR_MIPS);
+        for mip in 0..SPECULAR_MIPS {
+            let roughness = mip as f32 / (SPECULAR_MIPS - 1) as f32;
+            for face in 0..6 {
+                baker.draw(
+                    &mut builder,
+                    &allocators,
+                    &baker.specular,
+                    face_view(&specular_image, face, mip),
+                    SPECULAR_SIZE >> mip,
+                    environment_writes(),
+                    NIblPushConstants { face: face as i32, roughness },
+                );
+            }
+        }
+
+        let brdf_lut = match brdf_lut {
+            Some(brdf_lut) => brdf_lut,
+            None => {
+                let image = Image::new(
+                    allocators.memory.clone(),
+                    ImageCreateInfo {
+                        image_type: ImageType::Dim2d,
+                        format: BRDF_LUT_FORMAT,
+                        extent: [BRDF_LUT_SIZE, BRDF_LUT_SIZE, 1],
+                        usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED,
+                        ..Default::default()
+                    },
+                    AllocationCreateInfo::default(),
+                )
+                .unwrap();
+                let view = ImageView::new_default(image).unwrap();
+                baker.draw(
+                    &mut builder,
+                    &allocators,
+                    &baker.brdf_lut,
+                    view.clone(),
+                    BRDF_LUT_SIZE,
+                    Vec::new(),
+                    NIblPushConstants { face: 0, roughness: 0.0 },
+                );
+                view
+            }
+        };
+
+        let command_buffer = builder.build().unwrap();
+        sync::now(gfx_queue.device().clone())
+            .then_execute(gfx_queue, command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        self.brdf_lut = Some(brdf_lut.clone());
+        NEnvironmentMaps {
+            environment: environment_view,
+            irradiance: cube_view(&irradiance_image),
+            specular: cube_view(&specular_image),
+            brdf_lut,
+            sampler,
+            specular_mips: SPECULAR_MIPS,
+        }
+    }
+}
+
+impl NIblBaker {
+    // Шейдери запікання беруться з бібліотеки шейдерів; без них IBL неможливе
+    fn new(gfx_queue: &Arc<Queue>, allocators: &NAllocators) -> Self {
+        let shaders = &allocators.shaders;
+        let entry_point = |path: &str, stage: NShaderStage, defines: &[&str]| {
+            shaders
+                .entry_point(shaders.load_variant(path, stage, defines))
+                .unwrap_or_else(|| panic!("failed to compile IBL shader {}", path))
+        };
+        let vs = entry_point("ibl.vert", NShaderStage::Vertex, &[]);
+
+        let cube_render_pass = single_attachment_render_pass(gfx_queue, HDR_IMAGE_FORMAT);
+        let lut_render_pass = single_attachment_render_pass(gfx_queue, BRDF_LUT_FORMAT);
+        let pipeline = |render_pass: &Arc<RenderPass>, fs: &str, defines: &[&str]| {
+            fullscreen_pipeline(
+                gfx_queue,
+                &allocators.pipeline_cache,
+                vs.clone(),
+                entry_point(fs, NShaderStage::Fragment, defines),
+                render_pass,
+            )
+            .unwrap_or_else(|err| panic!("failed to create IBL pipeline {}: {}", fs, err))
+        };
+
+        NIblBaker {
+            cubemap: pipeline(&cube_render_pass, "ibl_cubemap.frag", &[]),
+            cube_faces: pipeline(&cube_render_pass, "ibl_cubemap.frag", &["SOURCE_CUBE"]),
+            irradiance: pipeline(&cube_render_pass, "ibl_irradiance.frag", &[]),
+            specular: pipeline(&cube_render_pass, "ibl_specular.frag", &[]),
+            brdf_lut: pipeline(&lut_render_pass, "brdf_lut.frag", &[]),
+            cube_render_pass,
+            lut_render_pass,
+            // Лінійна фільтрація RGBA32F не гарантована стандартом
+            panorama_sampler: Sampler::new(gfx_queue.device().clone(), SamplerCreateInfo {
+                address_mode: [
+                    SamplerAddressMode::Repeat,
+                    SamplerAddressMode::ClampToEdge,
+                    SamplerAddressMode::ClampToEdge,
+                ],
+                ..Default::default()
+            })
+            .unwrap(),
+        }
+    }
+
+    // Малює повноекранний трикутник у target розміром size x size
+    #[allow(clippy::too_many_arguments)]
+    fn draw(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        allocators: &NAllocators,
+        pipeline: &Arc<GraphicsPipeline>,
+        target: Arc<ImageView>,
+        size: u32,
+        writes: Vec<WriteDescriptorSet>,
+        push_constants: NIblPushConstants,
+    ) {
+        let render_pass = if target.format() == HDR_IMAGE_FORMAT {
+            &self.cube_render_pass
+        } else {
+            &self.lut_render_pass
+        };
+        let framebuffer = Framebuffer::new(render_pass.clone(), FramebufferCreateInfo {
+            attachments: vec![target],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let layout = pipeline.layout().clone();
+        builder
+            .begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: vec![None],
+                    ..RenderPassBeginInfo::framebuffer(framebuffer)
+                },
+                SubpassBeginInfo { contents: SubpassContents::Inline, ..Default::default() },
+            )
+            .unwrap()
+            .bind_pipeline_graphics(pipeline.clone())
+            .unwrap()
+            .set_viewport(
+                0,
+                [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [size as f32, size as f32],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap()
+            .push_constants(layout.clone(), 0, push_constants)
+            .unwrap();
+        if !writes.is_empty() {
+            let descriptor_set = DescriptorSet::new(
+                allocators.descriptor_sets.clone(),
+                layout.set_layouts()[0].clone(),
+                writes,
+                [],
+            )
+            .unwrap();
+            builder
+                .bind_descriptor_sets(PipelineBindPoint::Graphics, layout, 0, descriptor_set)
+                .unwrap();
+        }
+        unsafe {
+            builder.draw(3, 1, 0, 0).unwrap();
+        }
+        builder.end_render_pass(Default::default()).unwrap();
+    }
+}
+
+// Завантажує вихідне зображення оточення в RGBA32F (копіювання записується в builder):
+// панораму — у 2D зображення, грані — у кубмапу з 6 шарів
+fn upload_source(
+    builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+    allocators: &NAllocators,
+    source: &NEnvironmentSource,
+) -> Arc<ImageView> {
+    let (data, extent, array_layers, flags) = match source {
+        NEnvironmentSource::Equirectangular { size, data } => {
+            (data, [size[0], size[1], 1], 1, ImageCreateFlags::empty())
+        }
+        NEnvironmentSource::Cube { size, data } => {
+            (data, [*size, *size, 1], 6, ImageCreateFlags::CUBE_COMPATIBLE)
+        }
+    };
+    let staging = Buffer::from_iter(
+        allocators.memory.clone(),
+        BufferCreateInfo { usage: BufferUsage::TRANSFER_SRC, ..Default::default() },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        },
+        data.iter().copied(),
+    )
+    .unwrap();
+    let image = Image::new(
+        allocators.memory.clone(),
+        ImageCreateInfo {
+            flags,
+            image_type: ImageType::Dim2d,
+            format: Format::R32G32B32A32_SFLOAT,
+            extent,
+            array_layers,
+            usage: ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
+            ..Default::default()
+        },
+        AllocationCreateInfo::default(),
+    )
+    .unwrap();
+    // Регіон копіювання охоплює всі шари: грані йдуть у буфері одна за одною
+    builder
+        .copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(staging, image.clone()))
+        .unwrap();
+    match source {
+        NEnvironmentSource::Equirectangular { .. } => ImageView::new_default(image).unwrap(),
+        NEnvironmentSource::Cube { .. } => cube_view(&image),
+    }
+}
+
+// sRGB -> лінійний колір (для граней кубмапи з LDR зображень)
+#[cfg(feature = "image")]
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+}
+
+fn create_cube_image(allocators: &NAllocators, size: u32, mip_levels: u32) -> Arc<Image> {
+    Image::new(
+        allocators.memory.clone(),
+        ImageCreateInfo {
+            flags: ImageCreateFlags::CUBE_COMPATIBLE,
+            image_type: ImageType::Dim2d,
+            format: HDR_IMAGE_FORMAT,
+            extent: [size, size, 1],
+            array_layers: 6,
+            mip_levels,
+            usage: ImageUsage::COLOR_ATTACHMENT
+                | ImageUsage::SAMPLED
+                | ImageUsage::TRANSFER_SRC
+                | ImageUsage::TRANSFER_DST,
+            ..Default::default()
+        },
+        AllocationCreateInfo::default(),
+    )
+    .unwrap()
+}
+
+// Вид на всю кубмапу для семплювання
+fn cube_view(image: &Arc<Image>) -> Arc<ImageView> {
+    ImageView::new(image.clone(), ImageViewCreateInfo {
+        view_type: ImageViewType::Cube,
+        ..ImageViewCreateInfo::from_image(image)
+    })
+    .unwrap()
+}
+
+// Вид на одну грань одного mip-а для рендерингу
+fn face_view(image: &Arc<Image>, face: u32, mip: u32) -> Arc<ImageView> {
+    ImageView::new(image.clone(), ImageViewCreateInfo {
+        view_type: ImageViewType::Dim2d,
+        subresource_range: ImageSubresourceRange {
+            aspects: ImageAspects::COLOR,
+            mip_levels: mip..mip + 1,
+            array_layers: face..face + 1,
+        },
+        ..ImageViewCreateInfo::from_image(image)
+    })
+    .unwrap()
+}
+
+// Будує mip-и всіх граней послідовними blit-ами з попереднього рівня
+fn generate_cube_mips(
+    builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+    image: &Arc<Image>,
+    size: u32,
+    mip_levels: u32,
+) {
+    for level in 1..mip_levels {
+        let src_size = (size >> (level - 1)).max(1);
+        let dst_size = (size >> level).max(1);
+        builder
+            .blit_image(BlitImageInfo {
+                regions: [ImageBlit {
+                    src_subresource: ImageSubresourceLayers {
+                        mip_level: level - 1,
+                        ..image.subresource_layers()
+                    },
+                    src_offsets: [[0, 0, 0], [src_size, src_size, 1]],
+                    dst_subresource: ImageSubresourceLayers {
+                        mip_level: level,
+                        ..image.subresource_layers()
+                    },
+                    dst_offsets: [[0, 0, 0], [dst_size, dst_size, 1]],
+                    ..Default::default()
+                }]
+                .into(),
+                filter: Filter::Linear,
+                ..BlitImageInfo::images(image.clone(), image.clone())
+            })
+            .unwrap();
+    }
+}
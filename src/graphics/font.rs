@@ -0,0 +1,387 @@
+// Шрифти для тексту в сцені та в інтерфейсі гри.
+// NFont читає TTF/OTF (ttf-parser розбирає і TrueType, і CFF контури) і генерує
+// атлас гліфів із signed distance field (SDF): піксель атласу зберігає відстань
+// до контуру гліфа, тому текст лишається чітким за будь-якого масштабу, а обводку
+// й тінь шейдер будує з тієї ж відстані. Контури розбиваються на відрізки,
+// відстані рахуються на CPU паралельно по гліфах (rayon), гліфи пакуються
+// в атлас полицями. Кернінг береться з таблиці kern (GPOS не читається).
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use rayon::prelude::*;
+use ttf_parser::{Face, OutlineBuilder};
+
+use crate::graphics::{
+    asset::{next_asset_id, NAssetId},
+    texture::NTexture,
+};
+
+// Проміжок між гліфами в атласі (пікселі), щоб білінійна фільтрація не брала сусідів
+const ATLAS_GAP: u32 = 1;
+// Довжина відрізка (пікселі атласу), якою апроксимуються криві контуру
+const CURVE_TOLERANCE: f32 = 1.5;
+
+/// Параметри генерації SDF атласу
+#[derive(Clone, Debug)]
+pub struct NFontSettings {
+    pub size: f32,          // Размер em в пикселях атласа
+    pub spread: f32,        // Диапазон расстояния в пикселях атласа (по обе стороны контура)
+    pub chars: Vec<char>,   // Символы атласа
+}
+
+impl Default for NFontSettings {
+    fn default() -> Self {
+        // ASCII, кирилиця (з українськими літерами) та кілька типографських символів
+        let chars = ('\u{20}'..='\u{7e}')
+            .chain('\u{400}'..='\u{45f}')
+            .chain(['\u{490}', '\u{491}', '\u{ab}', '\u{b0}', '\u{bb}', '\u{2014}', '\u{2026}'])
+            .collect();
+        NFontSettings { size: 32.0, spread: 4.0, chars }
+    }
+}
+
+/// Гліф атласу. Метрики в em відносно пера на базовій лінії, y вгору.
+#[derive(Clone, Copy, Debug)]
+pub struct NGlyph {
+    pub rect: [f32; 4],     // Квадрат глифа (x, y, ширина, высота); пустой у пробелов
+    pub uv_rect: [f32; 4],  // Область атласа (x, y, ширина, высота) в UV
+    pub advance: f32,       // Сдвиг пера после глифа
+}
+
+/// Шрифт з SDF атласом гліфів
+#[derive(Debug)]
+pub struct NFont {
+    id: NAssetId,                           // Идентификатор ассета
+    pub name: String,                       // Имя шрифта
+    pub atlas: Arc<NTexture>,               // SDF атлас (расстояние в альфе, 0.5 — контур)
+    pub ascender: f32,                      // Высота над базовой линией (em)
+    pub descender: f32,                     // Глубина под базовой линией (em, отрицательная)
+    pub line_gap: f32,                      // Дополнительный интервал между строками (em)
+    distance_scale: f32,                    // Изменение значения SDF на 1 em расстояния
+    em_uv: [f32; 2],                        // Размер em в UV атласа
+    glyphs: HashMap<char, NGlyph>,          // Глифы по символам
+    kerning: HashMap<(char, char), f32>,    // Кернинг пар символов (em)
+}
+
+impl NFont {
+    // Розбирає шрифт з байтів TTF/OTF і генерує атлас для settings.chars.
+    // Символи, яких немає у шрифті, пропускаються (розкладка замінює їх на '?').
+    pub fn from_bytes(
+        name: impl Into<String>,
+        data: &[u8],
+        settings: &NFontSettings,
+    ) -> Result<Self, String> {
+        let name = name.into();
+        let face = Face::parse(data, 0).map_err(|err| format!("{}: {}", name, err))?;
+        let units = face.units_per_em() as f32;
+
+        // Контури читаються послідовно, відстані рахуються паралельно
+        let mut ids = Vec::new();
+        let mut outlines = Vec::new();
+        for &c in &settings.chars {
+            let Some(id) = face.glyph_index(c) else { continue };
+            let advance = face.glyph_hor_advance(id).unwrap_or(0) as f32 / units;
+            let mut outline = NOutline::new(settings.size / units);
+            let filled =
+                face.outline_glyph(id, &mut outline).is_some() && !outline.segments.is_empty();
+            ids.push((c, id));
+            outlines.push((c, advance, filled.then_some(outline)));
+        }
+        let bitmaps: Vec<(char, f32, Option<NGlyphBitmap>)> = outlines
+            .into_par_iter()
+            .map(|(c, advance, outline)| {
+                (c, advance, outline.map(|outline| outline.rasterize(settings.spread)))
+            })
+            .collect();
+
+        let sizes: Vec<[u32; 2]> = bitmaps
+            .iter()
+            .filter_map(|(_, _, bitmap)| bitmap.as_ref().map(|bitmap| bitmap.size))
+            .collect();
+        let (positions, atlas_size) = pack(&sizes);
+
+        // Відстань лежить в альфі, колір білий: шейдер множить його на колір тексту
+        let mut data = [255, 255, 255, 0].repeat((atlas_size[0] * atlas_size[1]) as usize);
+        let mut glyphs = HashMap::new();
+        let mut positions = positions.into_iter();
+        for (c, advance, bitmap) in bitmaps {
+            let Some(bitmap) = bitmap else {
+                glyphs.insert(c, NGlyph { rect: [0.0; 4], uv_rect: [0.0; 4], advance });
+                continue;
+            };
+            let [x, y] = positions.next().unwrap();
+            let [width, height] = bitmap.size;
+            for row in 0..height {
+                for column in 0..width {
+                    let pixel = ((y + row) * atlas_size[0] + x + column) as usize;
+                    data[pixel * 4 + 3] = bitmap.data[(row * width + column) as usize];
+                }
+            }
+            let em = |pixels: f32| pixels / settings.size;
+            glyphs.insert(c, NGlyph {
+                rect: [
+                    em(bitmap.origin[0]),
+                    em(bitmap.origin[1]),
+                    em(width as f32),
+                    em(height as f32),
+                ],
+                uv_rect: [
+                    x as f32 / atlas_size[0] as f32,
+                    y as f32 / atlas_size[1] as f32,
+                    width as f32 / atlas_size[0] as f32,
+                    height as f32 / atlas_size[1] as f32,
+                ],
+                advance,
+            });
+        }
+
+        let mut kerning = HashMap::new();
+        if let Some(kern) = face.tables().kern {
+            for subtable in kern.subtables.into_iter().filter(|table| table.horizontal) {
+                for &(left, left_id) in &ids {
+                    for &(right, right_id) in &ids {
+                        let Some(value) = subtable.glyphs_kerning(left_id, right_id) else {
+                            continue;
+                        };
+                        *kerning.entry((left, right)).or_insert(0.0) += value as f32 / units;
+                    }
+                }
+            }
+        }
+
+        let atlas = NTexture::linear(format!("{} SDF", name), atlas_size, data);
+        Ok(NFont {
+            id: next_asset_id(),
+            name,
+            atlas: Arc::new(atlas),
+            ascender: face.ascender() as f32 / units,
+            descender: face.descender() as f32 / units,
+            line_gap: face.line_gap() as f32 / units,
+            distance_scale: settings.size / (2.0 * settings.spread),
+            em_uv: [
+                settings.size / atlas_size[0] as f32,
+                settings.size / atlas_size[1] as f32,
+            ],
+            glyphs,
+            kerning,
+        })
+    }
+
+    // Завантажує .ttf/.otf файл
+    #[allow(dead_code)]
+    pub fn load(path: impl AsRef<Path>, settings: &NFontSettings) -> Result<Self, String> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+        NFont::from_bytes(name.unwrap_or_default(), &data, settings)
+    }
+
+    #[inline]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    // Гліф символу; відсутні в атласі символи замінюються на '?'
+    pub fn glyph(&self, c: char) -> Option<&NGlyph> {
+        self.glyphs.get(&c).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    // Корекція відстані між парою символів (em)
+    pub fn kerning(&self, left: char, right: char) -> f32 {
+        self.kerning.get(&(left, right)).copied().unwrap_or(0.0)
+    }
+
+    // Відстань між базовими лініями сусідніх рядків (em)
+    pub fn line_height(&self) -> f32 {
+        self.ascender - self.descender + self.line_gap
+    }
+
+    // Ширина рядка (em) з кернінгом
+    pub fn measure(&self, line: &str) -> f32 {
+        let mut width = 0.0;
+        let mut previous = None;
+        for c in line.chars() {
+            let Some(glyph) = self.glyph(c) else { continue };
+            width += previous.map_or(0.0, |previous| self.kerning(previous, c)) + glyph.advance;
+            previous = Some(c);
+        }
+        width
+    }
+
+    // Відстань у em -> зміна значення SDF (ширина обводки, розмиття тіні)
+    #[inline]
+    pub fn distance_scale(&self) -> f32 {
+        self.distance_scale
+    }
+
+    // Розмір em в UV атласу (зсув тіні)
+    #[inline]
+    pub fn em_uv(&self) -> [f32; 2] {
+        self.em_uv
+    }
+}
+
+// SDF гліфа до пакування в атлас
+struct NGlyphBitmap {
+    size: [u32; 2],         // Размер в пикселях атласа
+    origin: [f32; 2],       // Левый нижний угол относительно пера (пиксели атласа)
+    data: Vec<u8>,          // Значения SDF по строкам сверху вниз
+}
+
+// Контур гліфа у пікселях атласу, розбитий на відрізки
+struct NOutline {
+    scale: f32,                             // Единицы шрифта -> пиксели атласа
+    segments: Vec<([f32; 2], [f32; 2])>,    // Отрезки всех контуров
+    start: [f32; 2],                        // Начало текущего контура
+    pen: [f32; 2],                          // Текущая точка
+}
+
+impl NOutline {
+    fn new(scale: f32) -> Self {
+        NOutline { scale, segments: Vec::new(), start: [0.0; 2], pen: [0.0; 2] }
+    }
+
+    fn point(&self, x: f32, y: f32) -> [f32; 2] {
+        [x * self.scale, y * self.scale]
+    }
+
+    fn segment_to(&mut self, to: [f32; 2]) {
+        if to != self.pen {
+            self.segments.push((self.pen, to));
+        }
+        self.pen = to;
+    }
+
+    // Ламана по кривій Безьє з поточної точки через контрольні точки
+    fn bezier_to(&mut self, points: &[[f32; 2]]) {
+        let mut control = vec![self.pen];
+        control.extend_from_slice(points);
+        let length: f32 = control.windows(2).map(|pair| distance(pair[0], pair[1])).sum();
+        let steps = ((length / CURVE_TOLERANCE).ceil() as usize).clamp(1, 32);
+        for step in 1..=steps {
+            self.segment_to(bezier(&control, step as f32 / steps as f32));
+        }
+    }
+
+    // Знакова відстань від точки до контуру: додатна всередині.
+    // Внутрішність — за правилом ненульового обходу (напрям контурів TrueType
+    // і CFF протилежний, тож важливий лише нуль).
+    fn signed_distance(&self, point: [f32; 2]) -> f32 {
+        let mut nearest = f32::MAX;
+        let mut winding = 0;
+        for &(a, b) in &self.segments {
+            nearest = nearest.min(segment_distance(point, a, b));
+            let side = (b[0] - a[0]) * (point[1] - a[1]) - (point[0] - a[0]) * (b[1] - a[1]);
+            if a[1] <= point[1] && b[1] > point[1] && side > 0.0 {
+                winding += 1;
+            } else if b[1] <= point[1] && a[1] > point[1] && side < 0.0 {
+                winding -= 1;
+            }
+        }
+        if winding != 0 { nearest } else { -nearest }
+    }
+
+    // SDF у прямокутнику контуру, розширеному на spread з кожного боку.
+    // Значення 0.5 — контур, 0 і 1 — spread пікселів зовні та всередині.
+    fn rasterize(&self, spread: f32) -> NGlyphBitmap {
+        let (mut min, mut max) = ([f32::MAX; 2], [f32::MIN; 2]);
+        for &(a, _) in &self.segments {
+            min = [min[0].min(a[0]), min[1].min(a[1])];
+            max = [max[0].max(a[0]), max[1].max(a[1])];
+        }
+        let padding = spread.ceil();
+        let origin = [min[0].floor() - padding, min[1].floor() - padding];
+        let size = [
+            (max[0].ceil() + padding - origin[0]) as u32,
+            (max[1].ceil() + padding - origin[1]) as u32,
+        ];
+        let mut data = Vec::with_capacity((size[0] * size[1]) as usize);
+        for row in 0..size[1] {
+            let y = origin[1] + (size[1] - row) as f32 - 0.5;
+            for column in 0..size[0] {
+                let x = origin[0] + column as f32 + 0.5;
+                let value = 0.5 + self.signed_distance([x, y]) / (2.0 * spread);
+                data.push((value.clamp(0.0, 1.0) * 255.0).round() as u8);
+            }
+        }
+        NGlyphBitmap { size, origin, data }
+    }
+}
+
+impl OutlineBuilder for NOutline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = self.point(x, y);
+        self.pen = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.segment_to(to);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let points = [self.point(x1, y1), self.point(x, y)];
+        self.bezier_to(&points);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let points = [self.point(x1, y1), self.point(x2, y2), self.point(x, y)];
+        self.bezier_to(&points);
+    }
+
+    fn close(&mut self) {
+        self.segment_to(self.start);
+    }
+}
+
+fn distance(a: [f32; 2], b: [f32; 2]) -> f32 {
+    (b[0] - a[0]).hypot(b[1] - a[1])
+}
+
+// Відстань від точки до відрізка ab (a != b)
+fn segment_distance(point: [f32; 2], a: [f32; 2], b: [f32; 2]) -> f32 {
+    let ab = [b[0] - a[0], b[1] - a[1]];
+    let ap = [point[0] - a[0], point[1] - a[1]];
+    let t = ((ap[0] * ab[0] + ap[1] * ab[1]) / (ab[0] * ab[0] + ab[1] * ab[1])).clamp(0.0, 1.0);
+    distance(point, [a[0] + ab[0] * t, a[1] + ab[1] * t])
+}
+
+// Точка кривої Безьє (алгоритм де Кастельжо)
+fn bezier(points: &[[f32; 2]], t: f32) -> [f32; 2] {
+    let mut points = points.to_vec();
+    while points.len() > 1 {
+        points = points
+            .windows(2)
+            .map(|pair| {
+                [
+                    pair[0][0] + (pair[1][0] - pair[0][0]) * t,
+                    pair[0][1] + (pair[1][1] - pair[0][1]) * t,
+                ]
+            })
+            .collect();
+    }
+    points[0]
+}
+
+// Пакує прямокутники полицями, від найвищих до найнижчих. Ширина атласу —
+// степінь двійки за сумарною площею; повертає позиції в порядку sizes і розмір атласу.
+fn pack(sizes: &[[u32; 2]]) -> (Vec<[u32; 2]>, [u32; 2]) {
+    let area: u32 = sizes.iter().map(|[w, h]| (w + ATLAS_GAP) * (h + ATLAS_GAP)).sum();
+    let widest = sizes.iter().map(|[w, _]| w + ATLAS_GAP).max().unwrap_or(1);
+    let width = ((area as f32).sqrt().ceil() as u32).max(widest).next_power_of_two();
+
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&index| std::cmp::Reverse(sizes[index][1]));
+    let mut positions = vec![[0, 0]; sizes.len()];
+    let (mut x, mut y, mut shelf) = (0, 0, 0);
+    for index in order {
+        let [w, h] = sizes[index];
+        if x + w + ATLAS_GAP > width {
+            (x, y, shelf) = (0, y + shelf, 0);
+        }
+        positions[index] = [x, y];
+        x += w + ATLAS_GAP;
+        shelf = shelf.max(h + ATLAS_GAP);
+    }
+    (positions, [width, (y + shelf).max(1)])
+}
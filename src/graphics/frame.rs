@@ -13,135 +13,375 @@
 
 use std::sync::Arc;
 
-use cgmath::Matrix4;
+use cgmath::{Matrix4, Vector3};
 use vulkano::{
     command_buffer::{
-        AutoCommandBufferBuilder, CommandBufferUsage, PrimaryAutoCommandBuffer,
-        RenderPassBeginInfo, SecondaryAutoCommandBuffer, SubpassBeginInfo, SubpassContents,
+        AutoCommandBufferBuilder, CommandBufferUsage, RenderPassBeginInfo, SubpassBeginInfo,
+        SubpassContents,
     },
     device::Queue,
-    format::Format,
-    image::{view::ImageView, Image, ImageCreateInfo, ImageType, ImageUsage},
-    memory::allocator::AllocationCreateInfo,
+    format::{ClearValue, Format},
+    image::{view::ImageView, ImageAspects, SampleCount},
     render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
     sync::GpuFuture,
 };
 
-use crate::graphics::pipeline::NAllocators;
+use crate::graphics::{
+    graph::{
+        NAccess, NComputeSystem, NDrawSystem, NFrameContext, NImageDesc, NPassContext, NPassDesc,
+        NRenderGraph, NRenderNode, NResourceId,
+    },
+    outline::{NOutlineMaskPass, NOutlineNode, OUTLINE_MASK_FORMAT},
+    pipeline::NAllocators,
+    post::{NPostProcessNode, NPostProcessSystem},
+    profiler::{NGpuProfiler, NSharedTimings},
+    systems::sanitize::NHdrSanitizeSystem,
+};
+
+// Формат HDR зображення, в яке рендериться сцена до пост-обробки
+pub const HDR_IMAGE_FORMAT: Format = Format::R16G16B16A16_SFLOAT;
+
+// Імена проходів графа, в які реєструються draw та compute системи
+pub const PRE_COMPUTE_PASS: &str = "pre_compute";
+pub const SCENE_PASS: &str = "scene";
+pub const POST_COMPUTE_PASS: &str = "post_compute";
+pub const POST_PROCESS_PASS: &str = "post_process";
+pub const OUTLINE_MASK_PASS: &str = "outline_mask";
+pub const OUTLINE_PASS: &str = "outline";
+pub const OVERLAY_PASS: &str = "overlay";
+
+/// Шар 2D вмісту (текст, фігури): світ малюється в проході scene з перевіркою глибини,
+/// екран (пікселі кадру, 0,0 — лівий верхній кут) — у проході overlay поверх кадру
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NLayer {
+    World,      // Мир (проход scene)
+    Screen,     // Пиксели кадра (проход overlay)
+}
+
+impl NLayer {
+    // Прохід, у який реєструється draw система шару
+    pub fn pass(self) -> &'static str {
+        match self {
+            NLayer::World => SCENE_PASS,
+            NLayer::Screen => OVERLAY_PASS,
+        }
+    }
+
+    // Матриця простору шару -> clip space
+    pub fn view_projection(self, frame: &NFrameContext) -> Matrix4<f32> {
+        match self {
+            NLayer::World => frame.world_to_framebuffer,
+            NLayer::Screen => {
+                let [width, height] = frame.viewport_dimensions.map(|extent| extent as f32);
+                Matrix4::from_translation(Vector3::new(-1.0, -1.0, 0.0))
+                    * Matrix4::from_nonuniform_scale(2.0 / width, 2.0 / height, 1.0)
+            }
+        }
+    }
+}
 
 /// Система для рендеринга одного кадра
 pub struct NFrameSystem {
     gfx_queue: Arc<Queue>,          // Очередь графических команд
-    render_pass: Arc<RenderPass>,   // Проход рендеринга
-    depth_buffer: Arc<ImageView>,   // Буфер глубины
+    graph: NRenderGraph,            // Граф проходов кадра
+    output: NResourceId,            // Импортированное итоговое изображение
+    samples: SampleCount,           // Количество семплов MSAA
+    depth_format: Format,           // Формат буфера глубины
     allocators: NAllocators,         // Аллокаторы памяти и команд
+    profiler: NGpuProfiler,         // Таймінги проходов и draw систем
 }
 
 impl NFrameSystem {
+    // Будує граф кадру:
+    // - pre_compute: compute системи до растеризації (симуляція, culling),
+    // - scene: draw системи малюють у HDR зображення (з MSAA — у мультисемплове
+    //   зображення з resolve в HDR) з буфером глибини,
+    // - post_compute: compute системи над HDR зображенням як storage image
+    //   (NHdrSanitizeSystem прибирає NaN/Inf перед пост-обробкою),
+    // - post_process: стек пост-обробки камери з HDR у вихідне зображення,
+    // - outline_mask: draw системи малюють підсвічені об'єкти в маску,
+    // - outline: обведення з маски накладається на вихідне зображення,
+    // - overlay: draw системи малюють поверх вихідного зображення без глибини (HUD).
     pub fn new(
         gfx_queue: Arc<Queue>,
-        final_output_format: Format,
+        output_format: Format,
+        samples: SampleCount,
+        depth_format: Format,
         allocators: NAllocators,
+        timings: NSharedTimings,
     ) -> NFrameSystem {
-        let render_pass = vulkano::ordered_passes_renderpass!(gfx_queue.device().clone(),
-            attachments: {
-                final_color: {
-                    format: final_output_format,
-                    samples: 1,
-                    load_op: Clear,
-                    store_op: Store,
-                },
-                depth: {
-                    format: Format::D16_UNORM,
-                    samples: 1,
-                    load_op: Clear,
-                    store_op: DontCare,
-                }
-            },
-            passes: [
-                {
-                    color: [final_color],
-                    depth_stencil: {depth},
-                    input: []
-                }
-            ]
-        )
-        .unwrap();
+        let mut graph = NRenderGraph::new(allocators.memory.clone());
 
-        let depth_buffer = Image::new(
-            allocators.memory.clone(),
-            ImageCreateInfo {
-                image_type: ImageType::Dim2d,
-                format: Format::D16_UNORM,
-                extent: [1, 1, 1],
-                array_layers: 1,
-                usage: ImageUsage::SAMPLED | ImageUsage::DEPTH_STENCIL_ATTACHMENT,
-                ..Default::default()
-            },
-            AllocationCreateInfo::default(),
-        )
-        .unwrap();
-        let depth_buffer = ImageView::new_default(depth_buffer.clone()).unwrap();
-        NFrameSystem { gfx_queue, render_pass, depth_buffer, allocators }
+        let output = graph.import_image("output");
+        let hdr = graph.create_image("hdr", NImageDesc::new(HDR_IMAGE_FORMAT));
+        let depth = graph.create_image("depth", NImageDesc::new(depth_format).samples(samples));
+        let msaa_color = (samples != SampleCount::Sample1).then(|| {
+            graph.create_image("hdr_msaa", NImageDesc::new(HDR_IMAGE_FORMAT).samples(samples))
+        });
+
+        // Без ресурсів граф не впорядковує pre_compute відносно scene,
+        // тому він додається першим і лишається першим після сортування
+        graph.add_pass(NPassDesc::new(PRE_COMPUTE_PASS), NComputePass);
+
+        let mut scene_desc = NPassDesc::new(SCENE_PASS)
+            .write(hdr, NAccess::ColorAttachment)
+            .write(depth, NAccess::DepthStencilAttachment);
+        if let Some(msaa_color) = msaa_color {
+            scene_desc = scene_desc.write(msaa_color, NAccess::ColorAttachment);
+        }
+        graph.add_pass(
+            scene_desc,
+            NScenePass::new(&gfx_queue, samples, depth_format, hdr, depth, msaa_color),
+        );
+        graph.add_pass(
+            NPassDesc::new(POST_COMPUTE_PASS).write(hdr, NAccess::Storage),
+            NComputePass,
+        );
+        graph.register_compute_system(
+            POST_COMPUTE_PASS,
+            NHdrSanitizeSystem::new(gfx_queue.clone(), &allocators, hdr),
+        );
+
+        graph.add_pass(
+            NPassDesc::new(POST_PROCESS_PASS)
+                .read(hdr, NAccess::Sampled)
+                .write(output, NAccess::ColorAttachment),
+            NPostProcessNode::new(
+                NPostProcessSystem::new(gfx_queue.clone(), output_format, &allocators),
+                hdr,
+                output,
+            ),
+        );
+
+        let outline_mask = graph.create_image("outline_mask", NImageDesc::new(OUTLINE_MASK_FORMAT));
+        graph.add_pass(
+            NPassDesc::new(OUTLINE_MASK_PASS).write(outline_mask, NAccess::ColorAttachment),
+            NOutlineMaskPass::new(&gfx_queue, outline_mask),
+        );
+        graph.add_pass(
+            NPassDesc::new(OUTLINE_PASS)
+                .read(outline_mask, NAccess::Sampled)
+                .write(output, NAccess::ColorAttachment),
+            NOutlineNode::new(&gfx_queue, output_format, &allocators, outline_mask, output),
+        );
+        graph.add_pass(
+            NPassDesc::new(OVERLAY_PASS).write(output, NAccess::ColorAttachment),
+            NOverlayPass::new(&gfx_queue, output_format, output),
+        );
+        graph.set_async_compute(allocators.compute.async_queue().is_some());
+        graph.compile();
+
+        let profiler =
+            NGpuProfiler::new(gfx_queue.clone(), allocators.command_buffers.clone(), timings);
+
+        NFrameSystem { gfx_queue, graph, output, samples, depth_format, allocators, profiler }
     }
 
     #[inline]
-    pub fn deferred_subpass(&self) -> Subpass {
-        Subpass::from(self.render_pass.clone(), 0).unwrap()
+    pub fn samples(&self) -> SampleCount {
+        self.samples
+    }
+
+    #[inline]
+    pub fn depth_format(&self) -> Format {
+        self.depth_format
+    }
+
+    #[inline]
+    pub fn subpass(&self, pass: &str) -> Option<Subpass> {
+        self.graph.subpass(pass)
+    }
+
+    #[inline]
+    pub fn register_draw_system(&mut self, pass: &str, system: impl NDrawSystem + 'static) {
+        self.graph.register_draw_system(pass, system);
+    }
+
+    #[inline]
+    pub fn register_compute_system(&mut self, pass: &str, system: impl NComputeSystem + 'static) {
+        self.graph.register_compute_system(pass, system);
+    }
+
+    #[allow(dead_code)]
+    #[inline]
+    pub fn graph_mut(&mut self) -> &mut NRenderGraph {
+        &mut self.graph
     }
 
+    #[inline]
+    pub fn dump_graph(&self) -> String {
+        self.graph.dump()
+    }
+
+    // frame:
+    // - імпортує final_image як вихідний ресурс графа,
+    // - асинхронні compute системи (якщо є окрема черга) записує в буфер compute черги
+    //   і виконує його першим,
+    // - записує всі проходи графа в один primary command buffer,
+    // - виконує його після before_future і повертає future завершення.
     pub fn frame<F>(
         &mut self,
         before_future: F,
         final_image: Arc<ImageView>,
-        world_to_framebuffer: Matrix4<f32>,
-    ) -> Frame <'_>
+        frame: &NFrameContext,
+    ) -> Box<dyn GpuFuture>
     where
         F: GpuFuture + 'static,
     {
-        // Пояснення:
-        // - Оновлюємо depth_buffer під розміри final_image (якщо потрібно).
-        // - Створюємо Framebuffer з final_image + depth_buffer.
-        // - Починаємо primary AutoCommandBufferBuilder з begin_render_pass(..., SubpassContents::SecondaryCommandBuffers),
-        //   що дозволяє виконувати всередині вторинні командні буфери (згенеровані draw системою).
-        // - Повертаємо Frame, яка дає next_pass() для послідовного виконання пасів.
-
-        let img_dims = final_image.image().extent();
-        if self.depth_buffer.image().extent() != img_dims {
-            self.depth_buffer = ImageView::new_default(
-                Image::new(
-                    self.allocators.memory.clone(),
-                    ImageCreateInfo {
-                        image_type: ImageType::Dim2d,
-                        format: Format::D16_UNORM,
-                        extent: final_image.image().extent(),
-                        array_layers: 1,
-                        usage: ImageUsage::DEPTH_STENCIL_ATTACHMENT
-                            | ImageUsage::TRANSIENT_ATTACHMENT,
-                        ..Default::default()
-                    },
-                    AllocationCreateInfo::default(),
+        self.graph.set_imported(self.output, final_image);
+
+        let before_future: Box<dyn GpuFuture> = match self.allocators.compute.async_queue() {
+            Some(compute_queue) if self.graph.has_async_compute() => {
+                let mut compute_builder = AutoCommandBufferBuilder::primary(
+                    self.allocators.command_buffers.clone(),
+                    compute_queue.queue_family_index(),
+                    CommandBufferUsage::OneTimeSubmit,
                 )
-                .unwrap(),
-            )
-            .unwrap();
-        }
-        let framebuffer = Framebuffer::new(self.render_pass.clone(), FramebufferCreateInfo {
-            attachments: vec![final_image, self.depth_buffer.clone()],
-            ..Default::default()
-        })
-        .unwrap();
+                .unwrap();
+                self.graph.dispatch_async_compute(&mut compute_builder, frame);
+                // Семафор передає результати compute черги графічному буферу кадру
+                before_future
+                    .then_execute(compute_queue.clone(), compute_builder.build().unwrap())
+                    .unwrap()
+                    .then_signal_semaphore()
+                    .boxed()
+            }
+            _ => before_future.boxed(),
+        };
+
         let mut command_buffer_builder = AutoCommandBufferBuilder::primary(
             self.allocators.command_buffers.clone(),
             self.gfx_queue.queue_family_index(),
             CommandBufferUsage::OneTimeSubmit,
         )
         .unwrap();
-        command_buffer_builder
+        self.profiler.begin_frame(&mut command_buffer_builder, frame.delta_time);
+        self.graph.execute(&mut command_buffer_builder, frame, &mut self.profiler);
+        self.profiler.end_frame(&mut command_buffer_builder);
+
+        let command_buffer = command_buffer_builder.build().unwrap();
+        before_future.then_execute(self.gfx_queue.clone(), command_buffer).unwrap().boxed()
+    }
+}
+
+// NScenePass: растровий прохід сцени. Attachments беруться з ресурсів графа,
+// а вміст малюють зареєстровані draw системи вторинними командними буферами.
+struct NScenePass {
+    render_pass: Arc<RenderPass>,       // Проход рендеринга
+    hdr: NResourceId,                   // HDR изображение (resolve при MSAA)
+    depth: NResourceId,                 // Буфер глубины
+    msaa_color: Option<NResourceId>,    // Мультисемпловый цветовой буфер (только при MSAA)
+    depth_clear: ClearValue,            // Очистка глубины (и трафарета, если он есть)
+}
+
+impl NScenePass {
+    fn new(
+        gfx_queue: &Arc<Queue>,
+        samples: SampleCount,
+        depth_format: Format,
+        hdr: NResourceId,
+        depth: NResourceId,
+        msaa_color: Option<NResourceId>,
+    ) -> NScenePass {
+        // Без MSAA малюємо одразу в hdr_color.
+        // З MSAA малюємо в мультисемпловий msaa_color, який в кінці сабпасу
+        // резолвиться (color_resolve) в hdr_color з одним семплом.
+        let render_pass = if samples == SampleCount::Sample1 {
+            vulkano::ordered_passes_renderpass!(gfx_queue.device().clone(),
+                attachments: {
+                    hdr_color: {
+                        format: HDR_IMAGE_FORMAT,
+                        samples: 1,
+                        load_op: Clear,
+                        store_op: Store,
+                    },
+                    depth: {
+                        format: depth_format,
+                        samples: 1,
+                        load_op: Clear,
+                        store_op: DontCare,
+                    }
+                },
+                passes: [
+                    {
+                        color: [hdr_color],
+                        depth_stencil: {depth},
+                        input: []
+                    }
+                ]
+            )
+            .unwrap()
+        } else {
+            vulkano::ordered_passes_renderpass!(gfx_queue.device().clone(),
+                attachments: {
+                    msaa_color: {
+                        format: HDR_IMAGE_FORMAT,
+                        samples: samples as u32,
+                        load_op: Clear,
+                        store_op: DontCare,
+                    },
+                    hdr_color: {
+                        format: HDR_IMAGE_FORMAT,
+                        samples: 1,
+                        load_op: DontCare,
+                        store_op: Store,
+                    },
+                    depth: {
+                        format: depth_format,
+                        samples: samples as u32,
+                        load_op: Clear,
+                        store_op: DontCare,
+                    }
+                },
+                passes: [
+                    {
+                        color: [msaa_color],
+                        color_resolve: [hdr_color],
+                        depth_stencil: {depth},
+                        input: []
+                    }
+                ]
+            )
+            .unwrap()
+        };
+
+        // Значення очищення має відповідати аспектам формату
+        let depth_clear = if depth_format.aspects().intersects(ImageAspects::STENCIL) {
+            ClearValue::DepthStencil((1.0, 0))
+        } else {
+            ClearValue::Depth(1.0)
+        };
+
+        NScenePass { render_pass, hdr, depth, msaa_color, depth_clear }
+    }
+}
+
+impl NRenderNode for NScenePass {
+    fn execute(&mut self, ctx: &mut NPassContext) {
+        // Порядок attachments та clear_values відповідає оголошенню в render pass-і.
+        // Колір очищення задає фон камери (skybox і градієнт малюються поверх).
+        let clear_color = Some(ctx.frame.scene.camera.background.clear_color().into());
+        let (attachments, clear_values) = match self.msaa_color {
+            Some(msaa_color) => (
+                vec![ctx.image(msaa_color), ctx.image(self.hdr), ctx.image(self.depth)],
+                vec![clear_color, None, Some(self.depth_clear)],
+            ),
+            None => (
+                vec![ctx.image(self.hdr), ctx.image(self.depth)],
+                vec![clear_color, Some(self.depth_clear)],
+            ),
+        };
+        let framebuffer = Framebuffer::new(self.render_pass.clone(), FramebufferCreateInfo {
+            attachments,
+            ..Default::default()
+        })
+        .unwrap();
+
+        ctx.builder
             .begin_render_pass(
                 RenderPassBeginInfo {
-                    clear_values: vec![Some([0.0, 0.0, 0.0, 0.0].into()), Some(1.0f32.into())],
-                    ..RenderPassBeginInfo::framebuffer(framebuffer.clone())
+                    clear_values,
+                    ..RenderPassBeginInfo::framebuffer(framebuffer)
                 },
                 SubpassBeginInfo {
                     contents: SubpassContents::SecondaryCommandBuffers,
@@ -149,97 +389,87 @@ impl NFrameSystem {
                 },
             )
             .unwrap();
+        ctx.execute_draw_systems();
+        ctx.builder.end_render_pass(Default::default()).unwrap();
+    }
 
-        Frame {
-            system: self,
-            num_pass: 0,
-            before_main_cb_future: Some(Box::new(before_future)),
-            framebuffer,
-            recording_command_buffer: Some(command_buffer_builder),
-            world_to_framebuffer,
-        }
+    fn subpass(&self) -> Option<Subpass> {
+        Some(Subpass::from(self.render_pass.clone(), 0).unwrap())
     }
 }
 
-// Frame/Pass/DrawPass пояснення:
-// - Frame::next_pass повертає або DrawPass (для малювання сцени) або Finished (коли все побудовано).
-// - DrawPass::execute отримує SecondaryAutoCommandBuffer і вставляє його в primary builder.
-// - Коли всі паси завершені, primary command buffer будується та виконуються залежності (before_future -> primary CB).
-pub struct Frame<'a> {
-    system: &'a mut NFrameSystem,    // Ссылка на систему кадров
-    num_pass: u8,                   // Номер текущего прохода
-    before_main_cb_future: Option<Box<dyn GpuFuture>>,  // Future для синхронизации
-    framebuffer: Arc<Framebuffer>,  // Фреймбуфер для рендеринга
-    recording_command_buffer: Option<AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>>, // Строитель команд
-    world_to_framebuffer: Matrix4<f32>, // Матрица преобразования мировых координат
+// NOverlayPass: растровий прохід поверх готового вихідного зображення кадру
+// (після пост-обробки й обведення), тому його вміст не проходить тонмапінг.
+// Глибини немає: draw системи малюють у порядку реєстрації.
+struct NOverlayPass {
+    render_pass: Arc<RenderPass>,   // Проход с выходным изображением (load)
+    output: NResourceId,            // Выходное изображение
 }
 
-impl<'a> Frame<'a> {
-    pub fn next_pass<'f>(&'f mut self) -> Option<Pass<'f, 'a>> {
-        let current_pass = {
-            let current_pass = self.num_pass;
-            self.num_pass += 1;
-            current_pass
-        };
-        match current_pass {
-            0 => Some(Pass::Deferred(DrawPass { frame: self })),
-            1 => {
-                self.recording_command_buffer
-                    .as_mut()
-                    .unwrap()
-                    .end_render_pass(Default::default())
-                    .unwrap();
-                let command_buffer = self.recording_command_buffer.take().unwrap().build().unwrap();
-                let after_main_cb = self
-                    .before_main_cb_future
-                    .take()
-                    .unwrap()
-                    .then_execute(self.system.gfx_queue.clone(), command_buffer)
-                    .unwrap();
-                Some(Pass::Finished(Box::new(after_main_cb)))
+impl NOverlayPass {
+    fn new(gfx_queue: &Arc<Queue>, output_format: Format, output: NResourceId) -> NOverlayPass {
+        let render_pass = vulkano::single_pass_renderpass!(gfx_queue.device().clone(),
+            attachments: {
+                color: {
+                    format: output_format,
+                    samples: 1,
+                    load_op: Load,
+                    store_op: Store,
+                }
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {}
             }
-            _ => None,
-        }
+        )
+        .unwrap();
+        NOverlayPass { render_pass, output }
     }
 }
 
-pub enum Pass<'f, 's: 'f> {
-    Deferred(DrawPass<'f, 's>),
-    Finished(Box<dyn GpuFuture>),
-}
-
-pub struct DrawPass<'f, 's: 'f> {
-    frame: &'f mut Frame<'s>,
-}
-
-impl<'f, 's: 'f> DrawPass<'f, 's> {
-    #[inline]
-    pub fn execute(&mut self, command_buffer: Arc<SecondaryAutoCommandBuffer>) {
-        self.frame
-            .recording_command_buffer
-            .as_mut()
-            .unwrap()
-            .execute_commands(command_buffer)
+impl NRenderNode for NOverlayPass {
+    fn execute(&mut self, ctx: &mut NPassContext) {
+        let framebuffer = Framebuffer::new(self.render_pass.clone(), FramebufferCreateInfo {
+            attachments: vec![ctx.image(self.output)],
+            ..Default::default()
+        })
+        .unwrap();
+        ctx.builder
+            .begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: vec![None],
+                    ..RenderPassBeginInfo::framebuffer(framebuffer)
+                },
+                SubpassBeginInfo {
+                    contents: SubpassContents::SecondaryCommandBuffers,
+                    ..Default::default()
+                },
+            )
             .unwrap();
+        ctx.execute_draw_systems();
+        ctx.builder.end_render_pass(Default::default()).unwrap();
     }
 
-    #[allow(dead_code)]
-    #[inline]
-    pub fn viewport_dimensions(&self) -> [u32; 2] {
-        self.frame.framebuffer.extent()
+    fn subpass(&self) -> Option<Subpass> {
+        Some(Subpass::from(self.render_pass.clone(), 0).unwrap())
     }
+}
 
-    #[allow(dead_code)]
-    #[inline]
-    pub fn world_to_framebuffer_matrix(&self) -> Matrix4<f32> {
-        self.frame.world_to_framebuffer
+// NComputePass: compute прохід графа без власних команд, лише dispatch-і
+// зареєстрованих compute систем
+struct NComputePass;
+
+impl NRenderNode for NComputePass {
+    fn execute(&mut self, ctx: &mut NPassContext) {
+        ctx.execute_compute_systems();
     }
 }
 
-// FrameSystem: обгортка над render pass-ом та буферами, яка відповідає за підготовку кадру.
+// FrameSystem: обгортка над render graph-ом, яка відповідає за підготовку кадру.
 // Ключові кроки:
-// - створює RenderPass з color + depth attachments,
-// - містить depth_buffer який підлаштовується під розмір фінального зображення,
-// - frame(...) повертає структуру Frame яка дозволяє виконати послідовність пасів:
-//     * Deferred: місце для малювання сцени (Secondary command buffers),
-//     * Finished: коли завершено запис, будується primary command buffer і виконується на черзі.
+// - будує граф з проходами pre_compute, scene, post_compute, post_process,
+//   outline_mask, outline та overlay (MSAA і формат глибини визначають ресурси
+//   проходу scene),
+// - тимчасові зображення (HDR, глибина, MSAA, маска обведення) виділяє та аліасить сам граф,
+// - frame(...) записує граф у primary command buffer і виконує його на черзі,
+//   обгортаючи кадр, проходи та draw системи в scope-и профайлера.
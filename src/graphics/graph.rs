@@ -0,0 +1,838 @@
+// Декларативний render graph.
+// Проходи оголошують, які зображення вони читають і пишуть (NPassDesc),
+// а граф сам визначає:
+// - порядок виконання (топологічне сортування залежностей read-after-write,
+//   write-after-read та write-after-write),
+// - бар'єри між проходами (для дампу; самі pipeline barrier-и вставляє
+//   AutoCommandBufferBuilder vulkano під час запису),
+// - виділення тимчасових (transient) зображень під розмір кадру та
+//   аліасинг: ресурси з однаковим описом і непересічним часом життя
+//   ділять одне фізичне зображення.
+// Draw системи реєструються в прохід за його ім'ям і отримують Subpass
+// через NRenderGraph::subpass. Draw системи проходу записуються паралельно
+// на пулі воркерів, а їхні буфери виконуються в порядку реєстрації.
+// Compute системи так само реєструються в compute проходи й записують dispatch-і прямо в primary буфер кадру.
+
+use std::{
+    fmt::Write,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use cgmath::Matrix4;
+use vulkano::{
+    command_buffer::{
+        AutoCommandBufferBuilder, PrimaryAutoCommandBuffer, SecondaryAutoCommandBuffer,
+    },
+    format::Format,
+    image::{view::ImageView, Image, ImageCreateInfo, ImageType, ImageUsage, SampleCount},
+    memory::allocator::{AllocationCreateInfo, StandardMemoryAllocator},
+    render_pass::Subpass,
+};
+
+use crate::{
+    graphics::{
+        outline::NHighlights, profiler::NGpuProfiler, ring::NFrameRing,
+        settings::NRenderSettings, stats::NRenderStats, workers::NWorkerPool,
+    },
+    scene::{entity::NEntity, scene::NScene},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NResourceId(usize);
+
+/// Розмір тимчасового зображення відносно вихідного зображення кадру
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NImageSize {
+    Output,                 // Размер выходного изображения
+    Relative(f32),          // Доля от размера выходного изображения
+    Absolute([u32; 2]),     // Фиксированный размер
+}
+
+impl NImageSize {
+    fn resolve(&self, output: [u32; 2]) -> [u32; 2] {
+        match *self {
+            NImageSize::Output => output,
+            NImageSize::Relative(scale) => [
+                ((output[0] as f32 * scale) as u32).max(1),
+                ((output[1] as f32 * scale) as u32).max(1),
+            ],
+            NImageSize::Absolute(size) => size,
+        }
+    }
+}
+
+/// Опис тимчасового зображення графа
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NImageDesc {
+    pub format: Format,         // Формат изображения
+    pub size: NImageSize,       // Размер
+    pub samples: SampleCount,   // Количество семплов
+}
+
+impl NImageDesc {
+    pub fn new(format: Format) -> Self {
+        NImageDesc { format, size: NImageSize::Output, samples: SampleCount::Sample1 }
+    }
+
+    pub fn size(mut self, size: NImageSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn samples(mut self, samples: SampleCount) -> Self {
+        self.samples = samples;
+        self
+    }
+}
+
+/// Як прохід використовує ресурс
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NAccess {
+    ColorAttachment,
+    DepthStencilAttachment,
+    Sampled,
+    Storage,
+    TransferSrc,
+    TransferDst,
+}
+
+impl NAccess {
+    fn usage(self) -> ImageUsage {
+        match self {
+            NAccess::ColorAttachment => ImageUsage::COLOR_ATTACHMENT,
+            NAccess::DepthStencilAttachment => ImageUsage::DEPTH_STENCIL_ATTACHMENT,
+            NAccess::Sampled => ImageUsage::SAMPLED,
+            NAccess::Storage => ImageUsage::STORAGE,
+            NAccess::TransferSrc => ImageUsage::TRANSFER_SRC,
+            NAccess::TransferDst => ImageUsage::TRANSFER_DST,
+        }
+    }
+
+    fn is_attachment(self) -> bool {
+        matches!(self, NAccess::ColorAttachment | NAccess::DepthStencilAttachment)
+    }
+
+    // Layout, в якому ресурс перебуває під час доступу (для дампу)
+    fn layout_name(self) -> &'static str {
+        match self {
+            NAccess::ColorAttachment => "ColorAttachmentOptimal",
+            NAccess::DepthStencilAttachment => "DepthStencilAttachmentOptimal",
+            NAccess::Sampled => "ShaderReadOnlyOptimal",
+            NAccess::Storage => "General",
+            NAccess::TransferSrc => "TransferSrcOptimal",
+            NAccess::TransferDst => "TransferDstOptimal",
+        }
+    }
+}
+
+/// Оголошення проходу: ім'я та ресурси, які він читає і пише
+#[derive(Clone, Debug)]
+pub struct NPassDesc {
+    pub name: String,
+    pub reads: Vec<(NResourceId, NAccess)>,
+    pub writes: Vec<(NResourceId, NAccess)>,
+}
+
+impl NPassDesc {
+    pub fn new(name: impl Into<String>) -> Self {
+        NPassDesc { name: name.into(), reads: Vec::new(), writes: Vec::new() }
+    }
+
+    pub fn read(mut self, resource: NResourceId, access: NAccess) -> Self {
+        self.reads.push((resource, access));
+        self
+    }
+
+    pub fn write(mut self, resource: NResourceId, access: NAccess) -> Self {
+        self.writes.push((resource, access));
+        self
+    }
+
+    fn accesses(&self) -> impl Iterator<Item = (NResourceId, NAccess, bool)> + '_ {
+        self.reads
+            .iter()
+            .map(|&(resource, access)| (resource, access, false))
+            .chain(self.writes.iter().map(|&(resource, access)| (resource, access, true)))
+    }
+}
+
+/// Дані кадру, доступні всім проходам і draw системам (з будь-якого воркера)
+#[derive(Clone, Copy)]
+pub struct NFrameContext<'a> {
+    pub scene: &'a NScene,                  // Сцена
+    pub viewport_dimensions: [u32; 2],      // Размер выходного изображения
+    pub world_to_framebuffer: Matrix4<f32>, // Матрица мир -> clip space камеры
+    pub delta_time: f32,                    // Время кадра в секундах
+    pub stats: &'a Mutex<NRenderStats>,    // Статистика кадра (заполняют draw системы)
+    pub ring: &'a NFrameRing,               // Буферы данных кадра (uniform, инстансы)
+    pub workers: &'a NWorkerPool,           // Пул потоков для записи команд
+    pub visible: &'a [usize],               // Индексы видимых сущностей в scene.entities
+    pub highlights: &'a NHighlights,        // Подсвеченные сущности (обводка)
+    pub settings: &'a NRenderSettings,      // Настройки рендеринга
+}
+
+impl<'a> NFrameContext<'a> {
+    // Сутності, що пройшли frustum culling, у порядку scene.entities
+    pub fn visible_entities(&self) -> impl Iterator<Item = &'a NEntity> + 'a {
+        let scene = self.scene;
+        self.visible.iter().map(move |&index| &scene.entities[index])
+    }
+}
+
+/// Draw система малює в растровий прохід графа вторинними командними буферами.
+/// draw викликається на воркері паралельно з іншими системами проходу; система
+/// може сама поділити роботу на чанки (NWorkerPool::map_chunks) і повернути
+/// кілька буферів — вони виконуються в порядку повернення.
+pub trait NDrawSystem: Send {
+    fn draw(&mut self, frame: &NFrameContext) -> Vec<Arc<SecondaryAutoCommandBuffer>>;
+
+    // Ім'я для профайлера (за замовчуванням — ім'я типу без шляху)
+    fn name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+}
+
+/// Вторинний буфер draw системи, який перезаписується лише при зміні ключа
+/// (пайплайн, розмір кадру, push constant-и). Буфер створюється з SimultaneousUse,
+/// бо може виконуватися в кількох кадрах у польоті одночасно.
+/// Підходить лише системам, чиї команди не залежать від даних кадру (skybox).
+/// Системи з інстансами в кільцевих буферах записують буфери щокадру з
+/// OneTimeSubmit: їхні вершинні буфери змінюються разом зі слотом кадру.
+pub struct NCachedCommandBuffer<K> {
+    cached: Option<(K, Arc<SecondaryAutoCommandBuffer>)>, // Ключ и записанный буфер
+}
+
+impl<K> Default for NCachedCommandBuffer<K> {
+    fn default() -> Self {
+        NCachedCommandBuffer { cached: None }
+    }
+}
+
+impl<K: PartialEq> NCachedCommandBuffer<K> {
+    pub fn get_or_record(
+        &mut self,
+        key: K,
+        record: impl FnOnce() -> Arc<SecondaryAutoCommandBuffer>,
+    ) -> Arc<SecondaryAutoCommandBuffer> {
+        match &self.cached {
+            Some((cached_key, buffer)) if *cached_key == key => buffer.clone(),
+            _ => {
+                let buffer = record();
+                self.cached = Some((key, buffer.clone()));
+                buffer
+            }
+        }
+    }
+}
+
+/// Compute система записує dispatch-і в compute прохід графа
+pub trait NComputeSystem {
+    fn dispatch(&mut self, ctx: &mut NComputeContext);
+
+    // Виконувати на окремій compute черзі (якщо вона є). Такі системи відправляються
+    // перед графічним буфером кадру і не мають доступу до зображень графа:
+    // compile() відхиляє асинхронні системи з непорожнім images().
+    fn async_compute(&self) -> bool {
+        false
+    }
+
+    // Ресурси графа, які система читає через NComputeContext::image.
+    // Мають бути оголошені в NPassDesc її проходу (звідти беруться бар'єри).
+    fn images(&self) -> Vec<NResourceId> {
+        Vec::new()
+    }
+
+    // Ім'я для профайлера (за замовчуванням — ім'я типу без шляху)
+    fn name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+}
+
+pub struct NComputeContext<'a> {
+    pub builder: &'a mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, // Буфер dispatch-ей
+    pub frame: &'a NFrameContext<'a>,               // Данные кадра
+    images: &'a [Option<Arc<ImageView>>],           // Изображения ресурсов по NResourceId
+    declared: &'a [NResourceId],                    // Ресурсы из NComputeSystem::images
+}
+
+impl NComputeContext<'_> {
+    pub fn image(&self, resource: NResourceId) -> Arc<ImageView> {
+        assert!(
+            self.declared.contains(&resource),
+            "compute system has no access to this render graph resource"
+        );
+        self.images[resource.0].clone().expect("render graph resource has no image")
+    }
+}
+
+/// Вузол графа: записує команди свого проходу
+pub trait NRenderNode {
+    fn execute(&mut self, ctx: &mut NPassContext);
+
+    // Сабпас, в який можуть реєструватися draw системи (лише для растрових проходів)
+    fn subpass(&self) -> Option<Subpass> {
+        None
+    }
+}
+
+pub struct NPassContext<'a> {
+    pub builder: &'a mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, // Primary буфер кадра
+    pub frame: &'a NFrameContext<'a>,               // Данные кадра
+    pub profiler: &'a mut NGpuProfiler,             // Профайлер CPU/GPU времени
+    images: &'a [Option<Arc<ImageView>>],           // Изображения ресурсов по NResourceId
+    subpass: Option<Subpass>,                       // Сабпасс прохода (для draw систем)
+    draw_systems: &'a mut [Box<dyn NDrawSystem>],   // Draw системы этого прохода
+    compute_systems: &'a mut [Box<dyn NComputeSystem>], // Compute системы этого прохода
+    async_compute: bool,                            // Async системы идут на отдельной очереди
+}
+
+impl NPassContext<'_> {
+    pub fn image(&self, resource: NResourceId) -> Arc<ImageView> {
+        self.images[resource.0].clone().expect("render graph resource has no image")
+    }
+
+    // Записує draw системи проходу паралельно на воркерах, потім виконує їхні
+    // вторинні буфери в порядку реєстрації. Кожна система пише статистику в
+    // свою копію, тож батчі в NRenderStats теж ідуть у детермінованому порядку.
+    // Має викликатися всередині render pass-у з SubpassContents::SecondaryCommandBuffers,
+    // тому timestamp-и профайлера теж пишуться окремими вторинними буферами.
+    pub fn execute_draw_systems(&mut self) {
+        if self.draw_systems.is_empty() {
+            return;
+        }
+        let subpass = self.subpass.clone().expect("draw systems require a raster pass");
+        let frame = self.frame;
+        let recorded = frame.workers.map(&mut *self.draw_systems, |system| {
+            let start = Instant::now();
+            let stats = Mutex::new(NRenderStats::default());
+            let command_buffers = system.draw(&NFrameContext { stats: &stats, ..*frame });
+            let cpu_ms = start.elapsed().as_secs_f32() * 1000.0;
+            (command_buffers, stats.into_inner().unwrap(), cpu_ms)
+        });
+
+        for (system, (command_buffers, stats, cpu_ms)) in self.draw_systems.iter().zip(recorded) {
+            let mut begin = self.profiler.secondary_builder(&subpass);
+            self.profiler.begin_scope(&mut begin, system.name());
+            self.builder.execute_commands(begin.build().unwrap()).unwrap();
+
+            for command_buffer in command_buffers {
+                self.builder.execute_commands(command_buffer).unwrap();
+            }
+
+            let mut end = self.profiler.secondary_builder(&subpass);
+            self.profiler.end_scope_with_cpu(&mut end, cpu_ms);
+            self.builder.execute_commands(end.build().unwrap()).unwrap();
+            frame.stats.lock().unwrap().batches.extend(stats.batches);
+        }
+    }
+
+    // Записує dispatch-і compute систем проходу в порядку реєстрації.
+    // Асинхронні системи пропускаються, якщо вони вже пішли на compute чергу.
+    pub fn execute_compute_systems(&mut self) {
+        for system in self.compute_systems.iter_mut() {
+            if self.async_compute && system.async_compute() {
+                continue;
+            }
+            let declared = system.images();
+            self.profiler.begin_scope(self.builder, system.name());
+            system.dispatch(&mut NComputeContext {
+                builder: &mut *self.builder,
+                frame: self.frame,
+                images: self.images,
+                declared: &declared,
+            });
+            self.profiler.end_scope(self.builder);
+        }
+    }
+}
+
+enum NResource {
+    Transient { name: String, desc: NImageDesc },
+    Imported { name: String },
+}
+
+impl NResource {
+    fn name(&self) -> &str {
+        match self {
+            NResource::Transient { name, .. } | NResource::Imported { name } => name,
+        }
+    }
+}
+
+struct NGraphPass {
+    desc: NPassDesc,
+    node: Box<dyn NRenderNode>,
+    draw_systems: Vec<Box<dyn NDrawSystem>>,
+    compute_systems: Vec<Box<dyn NComputeSystem>>,
+}
+
+#[derive(Debug)]
+struct NBarrier {
+    resource: NResourceId,
+    from_pass: usize,
+    from: NAccess,
+    to: NAccess,
+}
+
+// Фізичне зображення, яке ділять аліасовані тимчасові ресурси
+struct NPhysicalImage {
+    desc: NImageDesc,
+    usage: ImageUsage,
+    last_use: usize,                    // Последний проход (индекс в order), где оно занято
+    image: Option<Arc<ImageView>>,
+}
+
+struct NCompiledGraph {
+    order: Vec<usize>,                  // Индексы проходов в порядке выполнения
+    barriers: Vec<Vec<NBarrier>>,       // Барьеры перед каждым проходом order
+    physical_of: Vec<Option<usize>>,    // Физическое изображение каждого ресурса
+    lifetimes: Vec<Option<(usize, usize)>>, // Первое/последнее использование ресурса
+    physical: Vec<NPhysicalImage>,
+}
+
+pub struct NRenderGraph {
+    memory_allocator: Arc<StandardMemoryAllocator>, // Аллокатор памяти для ресурсов
+    resources: Vec<NResource>,          // Ресурсы графа
+    imported: Vec<Option<Arc<ImageView>>>, // Изображения импортированных ресурсов
+    passes: Vec<NGraphPass>,            // Проходы в порядке объявления
+    compiled: Option<NCompiledGraph>,   // Результат компиляции
+    allocated_extent: Option<[u32; 2]>, // Размер, под который выделены изображения
+    async_compute: bool,                // Есть отдельная compute очередь
+}
+
+impl NRenderGraph {
+    pub fn new(memory_allocator: Arc<StandardMemoryAllocator>) -> Self {
+        NRenderGraph {
+            memory_allocator,
+            resources: Vec::new(),
+            imported: Vec::new(),
+            passes: Vec::new(),
+            compiled: None,
+            allocated_extent: None,
+            async_compute: false,
+        }
+    }
+
+    // Вмикає відправку асинхронних compute систем на окрему чергу
+    // (без неї вони виконуються у своєму проході разом з іншими)
+    pub fn set_async_compute(&mut self, enabled: bool) {
+        self.async_compute = enabled;
+    }
+
+    // Тимчасове зображення, яке граф виділяє сам
+    pub fn create_image(&mut self, name: impl Into<String>, desc: NImageDesc) -> NResourceId {
+        self.resources.push(NResource::Transient { name: name.into(), desc });
+        self.imported.push(None);
+        self.compiled = None;
+        NResourceId(self.resources.len() - 1)
+    }
+
+    // Зовнішнє зображення, яке задається кожен кадр через set_imported
+    pub fn import_image(&mut self, name: impl Into<String>) -> NResourceId {
+        self.resources.push(NResource::Imported { name: name.into() });
+        self.imported.push(None);
+        self.compiled = None;
+        NResourceId(self.resources.len() - 1)
+    }
+
+    pub fn set_imported(&mut self, resource: NResourceId, image: Arc<ImageView>) {
+        assert!(
+            matches!(self.resources[resource.0], NResource::Imported { .. }),
+            "render graph resource `{}` is not imported",
+            self.resources[resource.0].name()
+        );
+        self.imported[resource.0] = Some(image);
+    }
+
+    pub fn add_pass(&mut self, desc: NPassDesc, node: impl NRenderNode + 'static) {
+        assert!(
+            self.pass_index(&desc.name).is_none(),
+            "render graph already has a pass named `{}`",
+            desc.name
+        );
+        self.passes.push(NGraphPass {
+            desc,
+            node: Box::new(node),
+            draw_systems: Vec::new(),
+            compute_systems: Vec::new(),
+        });
+        self.compiled = None;
+    }
+
+    pub fn subpass(&self, pass: &str) -> Option<Subpass> {
+        self.pass_index(pass).and_then(|index| self.passes[index].node.subpass())
+    }
+
+    pub fn register_draw_system(&mut self, pass: &str, system: impl NDrawSystem + 'static) {
+        let index = self
+            .pass_index(pass)
+            .unwrap_or_else(|| panic!("render graph has no pass named `{}`", pass));
+        self.passes[index].draw_systems.push(Box::new(system));
+    }
+
+    pub fn register_compute_system(&mut self, pass: &str, system: impl NComputeSystem + 'static) {
+        let index = self
+            .pass_index(pass)
+            .unwrap_or_else(|| panic!("render graph has no pass named `{}`", pass));
+        self.passes[index].compute_systems.push(Box::new(system));
+        // Доступ системи до зображень перевіряється при наступній компіляції
+        self.compiled = None;
+    }
+
+    // Чи є системи для окремої compute черги
+    pub fn has_async_compute(&self) -> bool {
+        self.async_compute
+            && self
+                .passes
+                .iter()
+                .any(|pass| pass.compute_systems.iter().any(|system| system.async_compute()))
+    }
+
+    // Записує асинхронні compute системи всіх проходів (в порядку виконання графа)
+    // в командний буфер compute черги
+    pub fn dispatch_async_compute(
+        &mut self,
+        builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        frame: &NFrameContext,
+    ) {
+        if self.compiled.is_none() {
+            self.compile();
+        }
+        let order = self.compiled.as_ref().unwrap().order.clone();
+        for pass in order {
+            for system in &mut self.passes[pass].compute_systems {
+                if system.async_compute() {
+                    system.dispatch(&mut NComputeContext {
+                        builder: &mut *builder,
+                        frame,
+                        images: &[],
+                        declared: &[],
+                    });
+                }
+            }
+        }
+    }
+
+    // Асинхронні системи виконуються на іншій черзі до графічного буфера кадру,
+    // тож зображення графа їм недоступні; решта систем може брати лише ресурси,
+    // оголошені в описі свого проходу.
+    fn validate_compute_images(&self) {
+        for pass in &self.passes {
+            for system in &pass.compute_systems {
+                let images = system.images();
+                if system.async_compute() && !images.is_empty() {
+                    panic!(
+                        "async compute system `{}` in pass `{}` cannot access render graph images",
+                        system.name(),
+                        pass.desc.name
+                    );
+                }
+                for resource in images {
+                    if !pass.desc.accesses().any(|(id, _, _)| id == resource) {
+                        panic!(
+                            "compute system `{}` uses `{}`, which pass `{}` does not declare",
+                            system.name(),
+                            self.resources[resource.0].name(),
+                            pass.desc.name
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn pass_index(&self, name: &str) -> Option<usize> {
+        self.passes.iter().position(|pass| pass.desc.name == name)
+    }
+
+    // compile:
+    // 1. будує ребра залежностей між проходами за оголошеними доступами,
+    // 2. топологічно сортує (при рівних умовах зберігається порядок оголошення),
+    // 3. рахує час життя ресурсів та бар'єри між доступами,
+    // 4. призначає тимчасовим ресурсам фізичні зображення з аліасингом.
+    pub fn compile(&mut self) {
+        self.validate_compute_images();
+        let pass_count = self.passes.len();
+        let mut edges = vec![Vec::new(); pass_count];
+        let mut in_degree = vec![0usize; pass_count];
+        let mut add_edge = |from: usize, to: usize| {
+            if from != to && !edges[from].contains(&to) {
+                edges[from].push(to);
+                in_degree[to] += 1;
+            }
+        };
+
+        for resource in 0..self.resources.len() {
+            let resource = NResourceId(resource);
+            let writers: Vec<usize> = (0..pass_count)
+                .filter(|&pass| self.passes[pass].desc.writes.iter().any(|(id, _)| *id == resource))
+                .collect();
+            let readers: Vec<usize> = (0..pass_count)
+                .filter(|&pass| self.passes[pass].desc.reads.iter().any(|(id, _)| *id == resource))
+                .collect();
+
+            // Записи в один ресурс виконуються в порядку оголошення
+            for pair in writers.windows(2) {
+                add_edge(pair[0], pair[1]);
+            }
+            for &reader in &readers {
+                let earlier: Vec<usize> =
+                    writers.iter().copied().filter(|&writer| writer < reader).collect();
+                if earlier.is_empty() {
+                    // Читач оголошений раніше за всіх записувачів: чекає на всіх
+                    writers.iter().for_each(|&writer| add_edge(writer, reader));
+                } else {
+                    earlier.iter().for_each(|&writer| add_edge(writer, reader));
+                    // Пізніші записи не можуть перезаписати ресурс до читання (WAR)
+                    writers
+                        .iter()
+                        .filter(|&&writer| writer > reader)
+                        .for_each(|&writer| add_edge(reader, writer));
+                }
+            }
+        }
+
+        let mut order = Vec::with_capacity(pass_count);
+        let mut ready: Vec<usize> = (0..pass_count).filter(|&pass| in_degree[pass] == 0).collect();
+        while let Some(position) =
+            ready.iter().enumerate().min_by_key(|(_, pass)| **pass).map(|(position, _)| position)
+        {
+            let pass = ready.remove(position);
+            order.push(pass);
+            for &next in &edges[pass] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+        assert_eq!(order.len(), pass_count, "render graph contains a dependency cycle");
+
+        let resource_count = self.resources.len();
+        let mut lifetimes: Vec<Option<(usize, usize)>> = vec![None; resource_count];
+        let mut usages = vec![ImageUsage::empty(); resource_count];
+        let mut attachment_only = vec![true; resource_count];
+        let mut last_access: Vec<Option<(usize, NAccess, bool)>> = vec![None; resource_count];
+        let mut barriers = Vec::with_capacity(pass_count);
+
+        for (position, &pass) in order.iter().enumerate() {
+            let mut pass_barriers = Vec::new();
+            for (resource, access, is_write) in self.passes[pass].desc.accesses() {
+                let index = resource.0;
+                lifetimes[index] = Some(match lifetimes[index] {
+                    Some((first, _)) => (first, position),
+                    None => (position, position),
+                });
+                usages[index] |= access.usage();
+                attachment_only[index] &= access.is_attachment();
+
+                if let Some((from_pass, from, was_write)) = last_access[index] {
+                    if from_pass != pass && (was_write || is_write) {
+                        pass_barriers.push(NBarrier { resource, from_pass, from, to: access });
+                    }
+                }
+                last_access[index] = Some((pass, access, is_write));
+            }
+            barriers.push(pass_barriers);
+        }
+
+        // Аліасинг: ресурси в порядку першого використання займають перше
+        // фізичне зображення з тим самим описом, яке вже звільнилося
+        let mut transient: Vec<usize> = (0..resource_count)
+            .filter(|&index| {
+                matches!(self.resources[index], NResource::Transient { .. })
+                    && lifetimes[index].is_some()
+            })
+            .collect();
+        transient.sort_by_key(|&index| lifetimes[index].unwrap().0);
+
+        let mut physical: Vec<NPhysicalImage> = Vec::new();
+        let mut physical_of = vec![None; resource_count];
+        for index in transient {
+            let NResource::Transient { desc, .. } = &self.resources[index] else { unreachable!() };
+            let (first, last) = lifetimes[index].unwrap();
+            let slot = physical
+                .iter()
+                .position(|image| image.desc == *desc && image.last_use < first)
+                .unwrap_or_else(|| {
+                    physical.push(NPhysicalImage {
+                        desc: *desc,
+                        usage: ImageUsage::empty(),
+                        last_use: 0,
+                        image: None,
+                    });
+                    physical.len() - 1
+                });
+            physical[slot].last_use = last;
+            physical[slot].usage |= usages[index];
+            // Attachment-и, що живуть в одному проході, не потребують пам'яті поза render pass-ом
+            if attachment_only[index] && first == last {
+                physical[slot].usage |= ImageUsage::TRANSIENT_ATTACHMENT;
+            }
+            physical_of[index] = Some(slot);
+        }
+        // TRANSIENT_ATTACHMENT сумісний лише з attachment usage
+        for image in &mut physical {
+            let attachment_usage = ImageUsage::COLOR_ATTACHMENT
+                | ImageUsage::DEPTH_STENCIL_ATTACHMENT
+                | ImageUsage::TRANSIENT_ATTACHMENT;
+            if !attachment_usage.contains(image.usage) {
+                image.usage = image.usage.difference(ImageUsage::TRANSIENT_ATTACHMENT);
+            }
+        }
+
+        self.compiled = Some(NCompiledGraph { order, barriers, physical_of, lifetimes, physical });
+        self.allocated_extent = None;
+    }
+
+    // Виділяє фізичні зображення під розмір вихідного зображення
+    fn allocate(&mut self, output_extent: [u32; 2]) {
+        if self.allocated_extent == Some(output_extent) {
+            return;
+        }
+        let compiled = self.compiled.as_mut().unwrap();
+        for physical in &mut compiled.physical {
+            let extent = physical.desc.size.resolve(output_extent);
+            physical.image = Some(
+                ImageView::new_default(
+                    Image::new(
+                        self.memory_allocator.clone(),
+                        ImageCreateInfo {
+                            image_type: ImageType::Dim2d,
+                            format: physical.desc.format,
+                            extent: [extent[0], extent[1], 1],
+                            samples: physical.desc.samples,
+                            usage: physical.usage,
+                            ..Default::default()
+                        },
+                        AllocationCreateInfo::default(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap(),
+            );
+        }
+        self.allocated_extent = Some(output_extent);
+    }
+
+    // Виконує всі проходи в порядку компіляції. Імпортовані ресурси мають
+    // бути задані через set_imported до виклику. Кожен прохід — окремий scope профайлера.
+    pub fn execute(
+        &mut self,
+        builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        frame: &NFrameContext,
+        profiler: &mut NGpuProfiler,
+    ) {
+        if self.compiled.is_none() {
+            self.compile();
+        }
+        self.allocate(frame.viewport_dimensions);
+
+        let compiled = self.compiled.as_ref().unwrap();
+        let images: Vec<Option<Arc<ImageView>>> = (0..self.resources.len())
+            .map(|index| match compiled.physical_of[index] {
+                Some(slot) => compiled.physical[slot].image.clone(),
+                None => self.imported[index].clone(),
+            })
+            .collect();
+        let order = compiled.order.clone();
+
+        for pass in order {
+            let pass = &mut self.passes[pass];
+            profiler.begin_scope(builder, &pass.desc.name);
+            pass.node.execute(&mut NPassContext {
+                builder: &mut *builder,
+                frame,
+                profiler: &mut *profiler,
+                images: &images,
+                subpass: pass.node.subpass(),
+                draw_systems: &mut pass.draw_systems,
+                compute_systems: &mut pass.compute_systems,
+                async_compute: self.async_compute,
+            });
+            profiler.end_scope(builder);
+        }
+    }
+
+    // Текстовий дамп графа: ресурси, фізичні зображення, порядок проходів і бар'єри
+    pub fn dump(&self) -> String {
+        let mut out = String::new();
+        let Some(compiled) = &self.compiled else {
+            return "render graph is not compiled\n".to_owned();
+        };
+
+        writeln!(out, "Resources:").unwrap();
+        for (index, resource) in self.resources.iter().enumerate() {
+            match resource {
+                NResource::Transient { name, desc } => {
+                    let physical = compiled.physical_of[index]
+                        .map(|slot| format!("physical #{}", slot))
+                        .unwrap_or_else(|| "unused".to_owned());
+                    let lifetime = compiled.lifetimes[index]
+                        .map(|(first, last)| format!("passes {}..={}", first, last))
+                        .unwrap_or_default();
+                    writeln!(
+                        out,
+                        "  [{}] {} transient {:?} {:?} x{} -> {} {}",
+                        index, name, desc.format, desc.size, desc.samples as u32, physical, lifetime
+                    )
+                    .unwrap();
+                }
+                NResource::Imported { name } => {
+                    writeln!(out, "  [{}] {} imported", index, name).unwrap();
+                }
+            }
+        }
+
+        writeln!(out, "Physical images:").unwrap();
+        for (slot, physical) in compiled.physical.iter().enumerate() {
+            let aliases: Vec<&str> = (0..self.resources.len())
+                .filter(|&index| compiled.physical_of[index] == Some(slot))
+                .map(|index| self.resources[index].name())
+                .collect();
+            writeln!(
+                out,
+                "  #{} {:?} usage {:?} <- {}",
+                slot,
+                physical.desc.format,
+                physical.usage,
+                aliases.join(", ")
+            )
+            .unwrap();
+        }
+
+        writeln!(out, "Passes:").unwrap();
+        for (position, &pass) in compiled.order.iter().enumerate() {
+            let pass_data = &self.passes[pass];
+            writeln!(
+                out,
+                "  {}. {} ({} draw systems, {} compute systems)",
+                position,
+                pass_data.desc.name,
+                pass_data.draw_systems.len(),
+                pass_data.compute_systems.len()
+            )
+            .unwrap();
+            for barrier in &compiled.barriers[position] {
+                writeln!(
+                    out,
+                    "       barrier {}: {} {} -> {}",
+                    self.resources[barrier.resource.0].name(),
+                    self.passes[barrier.from_pass].desc.name,
+                    barrier.from.layout_name(),
+                    barrier.to.layout_name()
+                )
+                .unwrap();
+            }
+            for (resource, access, is_write) in pass_data.desc.accesses() {
+                let kind = if is_write { "write" } else { "read " };
+                let name = self.resources[resource.0].name();
+                writeln!(out, "       {} {} as {:?}", kind, name, access).unwrap();
+            }
+        }
+        out
+    }
+}
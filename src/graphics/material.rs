@@ -0,0 +1,122 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::graphics::{
+    asset::{next_asset_id, NAssetId},
+    shader::{NBindingKind, NParamType},
+    texture::NTexture,
+};
+
+// Набір дескрипторів, зарезервований під параметри матеріалу
+pub const MATERIAL_SET: u32 = 0;
+
+/// Значення параметра матеріалу
+#[derive(Clone, Debug)]
+pub enum NMaterialValue {
+    Float(f32),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+    Color([f32; 4]),            // Линейный RGBA, пишется в vec3/vec4
+    Texture(Arc<NTexture>),
+}
+
+/// Шейдери матеріалу (шляхи відносно каталогу шейдерів)
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NMaterialShader {
+    pub vertex: String,         // Вершинный шейдер
+    pub fragment: String,       // Фрагментный шейдер
+}
+
+/// Матеріал: шейдер та іменовані параметри. Параметри зіставляються з полями
+/// uniform блоків і текстурами шейдера за іменами з рефлексії.
+#[derive(Debug)]
+pub struct NMaterial {
+    id: NAssetId,                               // Идентификатор ассета
+    pub name: String,                           // Имя материала
+    pub shader: NMaterialShader,                // Шейдеры
+    params: BTreeMap<String, NMaterialValue>,   // Параметры по имени
+}
+
+impl NMaterial {
+    pub fn new(name: impl Into<String>, shader: NMaterialShader) -> Self {
+        NMaterial { id: next_asset_id(), name: name.into(), shader, params: BTreeMap::new() }
+    }
+
+    // Стандартний матеріал мешів: колір та текстура з простим освітленням
+    #[allow(dead_code)]
+    pub fn standard(name: impl Into<String>) -> Self {
+        NMaterial::new(name, NMaterialShader {
+            vertex: "mesh.vert".to_owned(),
+            fragment: "mesh.frag".to_owned(),
+        })
+        .with_param("base_color", NMaterialValue::Color([1.0; 4]))
+        .with_param("ambient", NMaterialValue::Float(0.2))
+    }
+
+    // Metallic-roughness PBR матеріал (модель glTF 2.0) з освітленням від оточення.
+    // Текстури: base_color_texture, emissive_texture (sRGB), normal_texture,
+    // metallic_roughness_texture (G — roughness, B — metallic), occlusion_texture (R).
+    // Незадані текстури білі; normal_scale = 0 вимикає карту нормалей.
+    pub fn pbr(name: impl Into<String>) -> Self {
+        NMaterial::new(name, NMaterialShader {
+            vertex: "pbr.vert".to_owned(),
+            fragment: "pbr.frag".to_owned(),
+        })
+        .with_param("base_color", NMaterialValue::Color([1.0; 4]))
+        .with_param("emissive", NMaterialValue::Color([0.0, 0.0, 0.0, 1.0]))
+        .with_param("metallic", NMaterialValue::Float(1.0))
+        .with_param("roughness", NMaterialValue::Float(1.0))
+        .with_param("normal_scale", NMaterialValue::Float(0.0))
+        .with_param("occlusion_strength", NMaterialValue::Float(1.0))
+    }
+
+    pub fn with_param(mut self, name: impl Into<String>, value: NMaterialValue) -> Self {
+        self.params.insert(name.into(), value);
+        self
+    }
+
+    #[inline]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    pub fn param(&self, name: &str) -> Option<&NMaterialValue> {
+        self.params.get(name)
+    }
+
+    // Вміст uniform блоку за рефлексією: задані параметри пишуться за зміщеннями полів,
+    // решта полів лишаються нулями
+    pub fn uniform_data(&self, kind: &NBindingKind) -> Vec<u8> {
+        let NBindingKind::Uniform { size, members } = kind else { return Vec::new() };
+        let mut data = vec![0u8; *size as usize];
+        for member in members {
+            let Some(value) = self.param(&member.name) else { continue };
+            let components: &[f32] = match value {
+                NMaterialValue::Float(value) => std::slice::from_ref(value),
+                NMaterialValue::Vec2(value) => value,
+                NMaterialValue::Vec3(value) => value,
+                NMaterialValue::Vec4(value) | NMaterialValue::Color(value) => value,
+                NMaterialValue::Texture(_) => continue,
+            };
+            let count = match member.ty {
+                NParamType::Float => 1,
+                NParamType::Vec2 => 2,
+                NParamType::Vec3 => 3,
+                NParamType::Vec4 => 4,
+            };
+            for (index, component) in components.iter().take(count).enumerate() {
+                let offset = member.offset as usize + index * 4;
+                data[offset..offset + 4].copy_from_slice(&component.to_le_bytes());
+            }
+        }
+        data
+    }
+
+    // Текстура для ресурсу шейдера з іменем `name`
+    pub fn texture(&self, name: &str) -> Option<&Arc<NTexture>> {
+        match self.param(name) {
+            Some(NMaterialValue::Texture(texture)) => Some(texture),
+            _ => None,
+        }
+    }
+}
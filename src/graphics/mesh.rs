@@ -0,0 +1,177 @@
+use cgmath::{Matrix4, Point3};
+use vulkano::{buffer::BufferContents, pipeline::graphics::vertex_input::Vertex};
+
+use crate::{
+    graphics::asset::{next_asset_id, NAssetId},
+    scene::bounds::NAabb,
+};
+
+// Вершина меша
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Copy, Debug)]
+pub struct NMeshVertex {
+    #[format(R32G32B32_SFLOAT)]
+    pub position: [f32; 3],     // Позиція вершини
+    #[format(R32G32B32_SFLOAT)]
+    pub normal: [f32; 3],       // Нормаль
+    #[format(R32G32_SFLOAT)]
+    pub uv: [f32; 2],           // Текстурні координати
+}
+
+// Набір дескрипторів палітри суглобів у SKINNED варіантах вершинних шейдерів мешів
+pub const SKIN_SET: u32 = 2;
+
+// Прив'язка вершини до скелета: до чотирьох суглобів з вагами (сума ваг — 1)
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Copy, Debug, Default)]
+pub struct NSkinVertex {
+    #[format(R32G32B32A32_UINT)]
+    pub joints: [u32; 4],       // Индексы суставов в палитре
+    #[format(R32G32B32A32_SFLOAT)]
+    pub weights: [f32; 4],      // Веса суставов
+}
+
+/// Меш на CPU. GPU буфери створює та кешує NMeshDrawSystem за `id`.
+/// Меш зі `skin` малюється зі скінінгом, якщо сутність має NAnimationStateMachine.
+#[derive(Debug)]
+pub struct NMesh {
+    id: NAssetId,                   // Идентификатор ассета
+    pub name: String,               // Имя меша
+    pub vertices: Vec<NMeshVertex>, // Вершины
+    pub indices: Vec<u32>,          // Индексы треугольников
+    pub skin: Vec<NSkinVertex>,     // Привязка вершин к суставам (пусто — без скиннинга)
+    bounds: NAabb,                  // Границы вершин в локальных координатах
+    joint_bounds: Vec<Option<NAabb>>, // Границы вершин каждого сустава в позе привязки
+}
+
+impl NMesh {
+    pub fn new(name: impl Into<String>, vertices: Vec<NMeshVertex>, indices: Vec<u32>) -> Self {
+        let bounds = NAabb::from_points(vertices.iter().map(|vertex| vertex.position.into()))
+            .unwrap_or(NAabb::new(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 0.0, 0.0)));
+        let skin = Vec::new();
+        NMesh {
+            id: next_asset_id(),
+            name: name.into(),
+            vertices,
+            indices,
+            skin,
+            bounds,
+            joint_bounds: Vec::new(),
+        }
+    }
+
+    // Додає прив'язку вершин до суглобів (по одній на вершину) і рахує межі
+    // вершин кожного суглоба для skinned_bounds
+    pub fn with_skin(mut self, skin: Vec<NSkinVertex>) -> Self {
+        assert_eq!(skin.len(), self.vertices.len(), "skin size mismatch");
+        let mut joint_bounds: Vec<Option<NAabb>> = Vec::new();
+        for (vertex, binding) in self.vertices.iter().zip(&skin) {
+            let point = NAabb::new(vertex.position.into(), vertex.position.into());
+            for (&joint, &weight) in binding.joints.iter().zip(&binding.weights) {
+                if weight <= 0.0 {
+                    continue;
+                }
+                let joint = joint as usize;
+                if joint_bounds.len() <= joint {
+                    joint_bounds.resize(joint + 1, None);
+                }
+                let bounds = &mut joint_bounds[joint];
+                *bounds = Some(bounds.map_or(point, |bounds| bounds.union(&point)));
+            }
+        }
+        self.skin = skin;
+        self.joint_bounds = joint_bounds;
+        self
+    }
+
+    // Межі меша в позі з палітрою суглобів `palette` (як у шейдері скінінгу).
+    // Вершина — опукла комбінація своїх положень під кожним суглобом, тож лежить
+    // в об'єднанні меж вершин суглобів, перетворених їхніми матрицями.
+    pub fn skinned_bounds(&self, palette: &[[[f32; 4]; 4]]) -> NAabb {
+        self.joint_bounds
+            .iter()
+            .enumerate()
+            .filter_map(|(joint, bounds)| {
+                let matrix = Matrix4::from(*palette.get(joint)?);
+                Some(bounds.as_ref()?.transformed(&matrix))
+            })
+            .reduce(|bounds, other| bounds.union(&other))
+            .unwrap_or(self.bounds)
+    }
+
+    #[inline]
+    pub fn is_skinned(&self) -> bool {
+        !self.skin.is_empty()
+    }
+
+    #[inline]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    // Межі меша в локальних координатах (рахуються при створенні)
+    #[inline]
+    pub fn bounds(&self) -> NAabb {
+        self.bounds
+    }
+
+    // Куб з ребром 1 та центром у початку координат
+    pub fn cube() -> Self {
+        let faces: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+            ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+            ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+            ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+            ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+            ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+        ];
+        let mut vertices = Vec::with_capacity(24);
+        let mut indices = Vec::with_capacity(36);
+        for (normal, right, up) in faces {
+            let base = vertices.len() as u32;
+            for (u, v) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+                let position = [0, 1, 2].map(|axis| {
+                    normal[axis] * 0.5 + right[axis] * (u - 0.5) + up[axis] * (v - 0.5)
+                });
+                vertices.push(NMeshVertex { position, normal, uv: [u, 1.0 - v] });
+            }
+            indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+        NMesh::new("Cube", vertices, indices)
+    }
+
+    // Бічна поверхня циліндра радіуса `radius` від y = 0 до y = `height`:
+    // `sides` граней по колу і `rings` кілець по висоті (для згинання скелетом)
+    pub fn cylinder(radius: f32, height: f32, sides: u32, rings: u32) -> Self {
+        let mut vertices = Vec::with_capacity(((sides + 1) * (rings + 1)) as usize);
+        for ring in 0..=rings {
+            let v = ring as f32 / rings as f32;
+            for side in 0..=sides {
+                let u = side as f32 / sides as f32;
+                let angle = u * std::f32::consts::TAU;
+                let normal = [angle.cos(), 0.0, -angle.sin()];
+                vertices.push(NMeshVertex {
+                    position: [normal[0] * radius, v * height, normal[2] * radius],
+                    normal,
+                    uv: [u, 1.0 - v],
+                });
+            }
+        }
+        let mut indices = Vec::with_capacity((sides * rings * 6) as usize);
+        for ring in 0..rings {
+            for side in 0..sides {
+                let base = ring * (sides + 1) + side;
+                let above = base + sides + 1;
+                indices.extend_from_slice(&[base, base + 1, above + 1, base, above + 1, above]);
+            }
+        }
+        NMesh::new("Cylinder", vertices, indices)
+    }
+
+    // Квадрат 1x1 у площині XY (нормаль +Z)
+    pub fn quad() -> Self {
+        let vertices = [(-0.5, -0.5, 0.0, 1.0), (0.5, -0.5, 1.0, 1.0), (0.5, 0.5, 1.0, 0.0), (-0.5, 0.5, 0.0, 0.0)]
+            .map(|(x, y, u, v)| NMeshVertex { position: [x, y, 0.0], normal: [0.0, 0.0, 1.0], uv: [u, v] });
+        NMesh::new("Quad", vertices.to_vec(), vec![0, 1, 2, 0, 2, 3])
+    }
+}
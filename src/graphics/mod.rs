@@ -2,4 +2,21 @@ pub mod systems;
 
 pub mod renderer;
 pub mod pipeline;
-pub mod frame;
\ No newline at end of file
+pub mod frame;
+pub mod graph;
+pub mod compute;
+pub mod ring;
+pub mod workers;
+pub mod post;
+pub mod outline;
+pub mod settings;
+pub mod asset;
+pub mod mesh;
+pub mod material;
+pub mod environment;
+pub mod texture;
+pub mod font;
+pub mod stats;
+pub mod profiler;
+pub mod pipeline_cache;
+pub mod shader;
@@ -0,0 +1,358 @@
+// Обведення виділених сутностей і сутності під курсором.
+// Прохід outline_mask малює підсвічені об'єкти (меші, спрайти та debug фігури зі
+// стилем highlight) пласким кольором у маску R8G8: r — виділені, g — під курсором.
+// Маска не перевіряє глибину, тож обведення видно і крізь інші об'єкти.
+// Прохід outline після пост-обробки шукає для кожного пікселя поза маскою
+// замасковані пікселі в радіусі товщини й накладає колір обведення поверх
+// вихідного зображення — колір не проходить через tonemapping.
+
+use std::sync::Arc;
+
+use vulkano::{
+    buffer::BufferContents,
+    command_buffer::{RenderPassBeginInfo, SubpassBeginInfo, SubpassContents},
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::Queue,
+    format::Format,
+    image::sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
+    pipeline::{
+        cache::PipelineCache,
+        graphics::{
+            color_blend::{AttachmentBlend, ColorBlendAttachmentState, ColorBlendState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::VertexInputState,
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
+    shader::EntryPoint,
+};
+
+use crate::{
+    graphics::{
+        graph::{NPassContext, NRenderNode, NResourceId},
+        pipeline::NAllocators,
+        settings::MAX_OUTLINE_THICKNESS,
+        shader::{NShaderHandle, NShaderLibrary, NShaderStage},
+    },
+    scene::entity::NEntityId,
+};
+
+// Формат маски підсвічених об'єктів
+pub const OUTLINE_MASK_FORMAT: Format = Format::R8G8_UNORM;
+// Ім'я вузла в помилках пайплайнів редактора
+const ERROR_SOURCE: &str = "Outline";
+
+/// Вид підсвічування об'єкта
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NHighlight {
+    Selected,   // Выделен
+    Hovered,    // Под курсором
+}
+
+impl NHighlight {
+    // Значення в масці: кожен вид пише свій канал
+    pub fn mask(self) -> [f32; 4] {
+        match self {
+            NHighlight::Selected => [1.0, 0.0, 0.0, 1.0],
+            NHighlight::Hovered => [0.0, 1.0, 0.0, 1.0],
+        }
+    }
+}
+
+/// Підсвічені сутності кадру (задає редактор)
+#[derive(Clone, Debug, Default)]
+pub struct NHighlights {
+    pub selected: Vec<NEntityId>,       // Выделенные сущности
+    pub hovered: Option<NEntityId>,     // Сущность под курсором
+}
+
+impl NHighlights {
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty() && self.hovered.is_none()
+    }
+
+    // Значення маски сутності (None — не підсвічена)
+    pub fn mask(&self, entity: NEntityId) -> Option<[f32; 4]> {
+        let selected = self.selected.contains(&entity);
+        let hovered = self.hovered == Some(entity);
+        (selected || hovered).then(|| {
+            [if selected { 1.0 } else { 0.0 }, if hovered { 1.0 } else { 0.0 }, 0.0, 1.0]
+        })
+    }
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+struct NOutlinePushConstants {
+    selected_color: [f32; 4],   // Цвет обводки выделенных
+    hovered_color: [f32; 4],    // Цвет обводки под курсором
+    thickness: i32,             // Толщина в пикселях
+}
+
+/// Растровий прохід маски: draw системи малюють підсвічені об'єкти в маску.
+/// Без підсвічених сутностей прохід не записує нічого.
+pub struct NOutlineMaskPass {
+    render_pass: Arc<RenderPass>,   // Проход с одним вложением маски
+    mask: NResourceId,              // Маска подсвеченных объектов
+}
+
+impl NOutlineMaskPass {
+    pub fn new(gfx_queue: &Arc<Queue>, mask: NResourceId) -> Self {
+        let render_pass = vulkano::single_pass_renderpass!(gfx_queue.device().clone(),
+            attachments: {
+                mask: {
+                    format: OUTLINE_MASK_FORMAT,
+                    samples: 1,
+                    load_op: Clear,
+                    store_op: Store,
+                }
+            },
+            pass: {
+                color: [mask],
+                depth_stencil: {}
+            }
+        )
+        .unwrap();
+        NOutlineMaskPass { render_pass, mask }
+    }
+}
+
+impl NRenderNode for NOutlineMaskPass {
+    fn execute(&mut self, ctx: &mut NPassContext) {
+        if ctx.frame.highlights.is_empty() {
+            return;
+        }
+        let framebuffer = Framebuffer::new(self.render_pass.clone(), FramebufferCreateInfo {
+            attachments: vec![ctx.image(self.mask)],
+            ..Default::default()
+        })
+        .unwrap();
+        ctx.builder
+            .begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: vec![Some([0.0, 0.0, 0.0, 0.0].into())],
+                    ..RenderPassBeginInfo::framebuffer(framebuffer)
+                },
+                SubpassBeginInfo {
+                    contents: SubpassContents::SecondaryCommandBuffers,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        ctx.execute_draw_systems();
+        ctx.builder.end_render_pass(Default::default()).unwrap();
+    }
+
+    fn subpass(&self) -> Option<Subpass> {
+        Some(Subpass::from(self.render_pass.clone(), 0).unwrap())
+    }
+}
+
+/// Накладає обведення з маски на вихідне зображення кадру
+pub struct NOutlineNode {
+    gfx_queue: Arc<Queue>,          // Очередь графических команд
+    pipeline_cache: Arc<PipelineCache>, // Кэш пайплайнов
+    render_pass: Arc<RenderPass>,   // Проход поверх выходного изображения (load)
+    shaders: NShaderLibrary,        // Библиотека шейдеров (горячая перезагрузка)
+    vs: NShaderHandle,              // Полноэкранный вершинный шейдер
+    fs: NShaderHandle,              // Фрагментный шейдер обводки
+    shader_versions: (u64, u64),    // Версии шейдеров, из которых собран пайплайн
+    pipeline: Option<Arc<GraphicsPipeline>>, // Последний удачно собранный пайплайн
+    sampler: Arc<Sampler>,          // Nearest сэмплер маски
+    descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>, // Аллокатор дескрипторов
+    mask: NResourceId,              // Маска подсвеченных объектов
+    output: NResourceId,            // Выходное изображение
+}
+
+impl NOutlineNode {
+    pub fn new(
+        gfx_queue: &Arc<Queue>,
+        output_format: Format,
+        allocators: &NAllocators,
+        mask: NResourceId,
+        output: NResourceId,
+    ) -> Self {
+        let render_pass = vulkano::single_pass_renderpass!(gfx_queue.device().clone(),
+            attachments: {
+                color: {
+                    format: output_format,
+                    samples: 1,
+                    load_op: Load,
+                    store_op: Store,
+                }
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {}
+            }
+        )
+        .unwrap();
+        let shaders = allocators.shaders.clone();
+        let vs = shaders.load("outline.vert", NShaderStage::Vertex);
+        let fs = shaders.load("outline.frag", NShaderStage::Fragment);
+        let sampler = Sampler::new(gfx_queue.device().clone(), SamplerCreateInfo {
+            mag_filter: Filter::Nearest,
+            min_filter: Filter::Nearest,
+            address_mode: [SamplerAddressMode::ClampToEdge; 3],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut node = NOutlineNode {
+            gfx_queue: gfx_queue.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            render_pass,
+            shaders,
+            vs,
+            fs,
+            shader_versions: (0, 0),
+            pipeline: None,
+            sampler,
+            descriptor_set_allocator: allocators.descriptor_sets.clone(),
+            mask,
+            output,
+        };
+        node.rebuild_pipeline_if_changed();
+        node
+    }
+
+    // Перебудовує пайплайн, якщо змінилась версія хоча б одного шейдера.
+    // При помилці лишається попередній пайплайн, а помилка йде в редактор.
+    fn rebuild_pipeline_if_changed(&mut self) {
+        let versions = (self.shaders.version(self.vs), self.shaders.version(self.fs));
+        if versions == self.shader_versions {
+            return;
+        }
+        self.shader_versions = versions;
+
+        let (Some(vs), Some(fs)) =
+            (self.shaders.entry_point(self.vs), self.shaders.entry_point(self.fs))
+        else {
+            return;
+        };
+        match create_pipeline(&self.gfx_queue, &self.pipeline_cache, &self.render_pass, vs, fs) {
+            Ok(pipeline) => {
+                self.pipeline = Some(pipeline);
+                self.shaders.set_pipeline_error(ERROR_SOURCE, None);
+            }
+            Err(error) => self.shaders.set_pipeline_error(ERROR_SOURCE, Some(error)),
+        }
+    }
+}
+
+impl NRenderNode for NOutlineNode {
+    fn execute(&mut self, ctx: &mut NPassContext) {
+        self.rebuild_pipeline_if_changed();
+        if ctx.frame.highlights.is_empty() {
+            return;
+        }
+        let Some(pipeline) = self.pipeline.clone() else { return };
+        let settings = ctx.frame.settings.outline;
+        let output = ctx.image(self.output);
+        let extent = output.image().extent();
+        let framebuffer = Framebuffer::new(self.render_pass.clone(), FramebufferCreateInfo {
+            attachments: vec![output],
+            ..Default::default()
+        })
+        .unwrap();
+        let descriptor_set = DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            pipeline.layout().set_layouts()[0].clone(),
+            [
+                WriteDescriptorSet::image_view(0, ctx.image(self.mask)),
+                WriteDescriptorSet::sampler(1, self.sampler.clone()),
+            ],
+            [],
+        )
+        .unwrap();
+
+        ctx.builder
+            .begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: vec![None],
+                    ..RenderPassBeginInfo::framebuffer(framebuffer)
+                },
+                SubpassBeginInfo { contents: SubpassContents::Inline, ..Default::default() },
+            )
+            .unwrap()
+            .bind_pipeline_graphics(pipeline.clone())
+            .unwrap()
+            .set_viewport(
+                0,
+                [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [extent[0] as f32, extent[1] as f32],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap()
+            .bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                pipeline.layout().clone(),
+                0,
+                descriptor_set,
+            )
+            .unwrap()
+            .push_constants(pipeline.layout().clone(), 0, NOutlinePushConstants {
+                selected_color: settings.selected_color,
+                hovered_color: settings.hovered_color,
+                thickness: settings.thickness.clamp(1, MAX_OUTLINE_THICKNESS) as i32,
+            })
+            .unwrap();
+        unsafe {
+            ctx.builder.draw(3, 1, 0, 0).unwrap();
+        }
+        ctx.builder.end_render_pass(Default::default()).unwrap();
+    }
+}
+
+// Повноекранний пайплайн з альфа-змішуванням поверх вихідного зображення
+fn create_pipeline(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    render_pass: &Arc<RenderPass>,
+    vs: EntryPoint,
+    fs: EntryPoint,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    let device = gfx_queue.device().clone();
+    let subpass = Subpass::from(render_pass.clone(), 0).unwrap();
+    let stages = [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    GraphicsPipeline::new(device, Some(pipeline_cache.clone()), GraphicsPipelineCreateInfo {
+        stages: stages.into_iter().collect(),
+        vertex_input_state: Some(VertexInputState::default()),
+        input_assembly_state: Some(InputAssemblyState::default()),
+        viewport_state: Some(ViewportState::default()),
+        rasterization_state: Some(RasterizationState::default()),
+        multisample_state: Some(MultisampleState::default()),
+        color_blend_state: Some(ColorBlendState::with_attachment_states(
+            subpass.num_color_attachments(),
+            ColorBlendAttachmentState {
+                blend: Some(AttachmentBlend::alpha()),
+                ..Default::default()
+            },
+        )),
+        dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+        subpass: Some(subpass.into()),
+        ..GraphicsPipelineCreateInfo::layout(layout)
+    })
+    .map_err(|err| err.to_string())
+}
@@ -1,63 +1,344 @@
+use std::sync::{Arc, Mutex};
 
-
-use std::sync::Arc;
-
-use cgmath::{Matrix4, SquareMatrix};
 use vulkano::{
-    command_buffer::allocator::StandardCommandBufferAllocator, device::Queue, format::Format,
-    image::view::ImageView, memory::allocator::StandardMemoryAllocator, sync::GpuFuture,
+    command_buffer::allocator::StandardCommandBufferAllocator,
+    descriptor_set::allocator::StandardDescriptorSetAllocator, device::Queue, format::Format,
+    image::{view::ImageView, SampleCount}, memory::allocator::StandardMemoryAllocator,
+    pipeline::cache::PipelineCache,
+    sync::{future::FenceSignalFuture, GpuFuture},
 };
 
 use crate::{
-    graphics::frame::{NFrameSystem, Pass},
-    graphics::systems::triangle::NTriangleDrawSystem,
+    graphics::compute::NComputeQueues,
+    graphics::environment::NEnvironmentCache,
+    graphics::frame::{
+        NFrameSystem, NLayer, OUTLINE_MASK_PASS, OVERLAY_PASS, PRE_COMPUTE_PASS, SCENE_PASS,
+    },
+    graphics::graph::NFrameContext,
+    graphics::outline::NHighlights,
+    graphics::profiler::NSharedTimings,
+    graphics::ring::{NFrameRing, FRAMES_IN_FLIGHT},
+    graphics::settings::NRenderSettings,
+    graphics::shader::NShaderLibrary,
+    graphics::stats::NRenderStats,
+    graphics::workers::NWorkerPool,
+    graphics::systems::{
+        debug::{NDebugDraw, NDebugDrawSystem},
+        mesh::NMeshDrawSystem,
+        outline::NOutlineMaskSystem,
+        particles::{NParticleBuffers, NParticleComputeSystem, NParticleDrawSystem},
+        shape::{NShapeDraw, NShapeDrawSystem},
+        skybox::NSkyboxDrawSystem, sprite::NSpriteDrawSystem,
+        text::NTextDrawSystem, tilemap::NTilemapDrawSystem,
+    },
+    scene::{bounds::NFrustum, scene::NScene},
 };
 
 #[derive(Clone)]
 pub struct NAllocators {
     pub command_buffers: Arc<StandardCommandBufferAllocator>,  // Аллокатор командных буферов
     pub memory: Arc<StandardMemoryAllocator>,                  // Аллокатор памяти
+    pub descriptor_sets: Arc<StandardDescriptorSetAllocator>,  // Аллокатор наборов дескрипторов
+    pub pipeline_cache: Arc<PipelineCache>,                    // Общий кэш пайплайнов (с диска)
+    pub shaders: NShaderLibrary,                               // Шейдеры из файлов (горячая перезагрузка)
+    pub compute: NComputeQueues,                               // Очереди для compute систем
 }
 
 // Основной пайплайн рендеринга
 pub struct NRenderPipeline {
-    frame_system: NFrameSystem,      // Система кадров
-    draw_pipeline: NTriangleDrawSystem,  // Система отрисовки треугольников
+    gfx_queue: Arc<Queue>,           // Очередь графических команд
+    image_format: Format,            // Формат целевого изображения
+    allocators: NAllocators,         // Аллокаторы памяти и команд
+    settings: NRenderSettings,       // Текущие настройки рендеринга
+    frame_system: NFrameSystem,      // Система кадров (граф проходов и draw системы)
+    stats: NRenderStats,             // Статистика последнего кадра
+    debug_draw: NDebugDraw,          // Debug линии и подписи текущего кадра
+    shapes: NShapeDraw,              // 2D фигуры текущего кадра
+    timings: NSharedTimings,         // История CPU/GPU таймингов кадров
+    environments: NEnvironmentCache, // Запечённые окружения (IBL и skybox)
+    workers: NWorkerPool,            // Потоки для записи командных буферов
+    ring: NFrameRing,                // Буферы данных кадров в полёте (по потокам)
+    fences: Vec<Option<Arc<FenceSignalFuture<Box<dyn GpuFuture>>>>>, // Fence кадров по слотам
+    frame_index: u64,                // Номер записываемого кадра
 }
 
 impl NRenderPipeline {
     pub fn new(queue: Arc<Queue>, image_format: Format, allocators: &NAllocators) -> Self {
-        let frame_system = NFrameSystem::new(queue.clone(), image_format, allocators.clone());
-        let draw_pipeline =
-            NTriangleDrawSystem::new(queue, frame_system.deferred_subpass(), allocators);
+        let settings = NRenderSettings::default();
+        let debug_draw = NDebugDraw::default();
+        debug_draw.set_enabled(settings.debug_draw);
+        let shapes = NShapeDraw::default();
+        let timings = NSharedTimings::default();
+        let environments = NEnvironmentCache::new(queue.clone(), allocators);
+        let workers = NWorkerPool::new();
+        let ring = NFrameRing::new(&allocators.memory, &workers);
+        let frame_system = Self::build_frame_system(
+            &queue,
+            image_format,
+            settings.msaa_sample_count(queue.device()),
+            settings.depth_format(queue.device()),
+            allocators,
+            &debug_draw,
+            &shapes,
+            &timings,
+            &environments,
+        );
+
+        Self {
+            gfx_queue: queue,
+            image_format,
+            allocators: allocators.clone(),
+            settings,
+            frame_system,
+            stats: NRenderStats::default(),
+            debug_draw,
+            shapes,
+            timings,
+            environments,
+            workers,
+            ring,
+            fences: (0..FRAMES_IN_FLIGHT).map(|_| None).collect(),
+            frame_index: 0,
+        }
+    }
+
+    // Будує граф кадру та реєструє draw і compute системи в їхні проходи.
+    // Пайплайни draw систем створюються під сабпас свого проходу.
+    // Частинки симулюються заново після перебудови (стан живе в NParticleBuffers).
+    fn build_frame_system(
+        queue: &Arc<Queue>,
+        image_format: Format,
+        samples: SampleCount,
+        depth_format: Format,
+        allocators: &NAllocators,
+        debug_draw: &NDebugDraw,
+        shapes: &NShapeDraw,
+        timings: &NSharedTimings,
+        environments: &NEnvironmentCache,
+    ) -> NFrameSystem {
+        let mut frame_system = NFrameSystem::new(
+            queue.clone(),
+            image_format,
+            samples,
+            depth_format,
+            allocators.clone(),
+            timings.clone(),
+        );
+        let scene_subpass = frame_system.subpass(SCENE_PASS).unwrap();
+
+        frame_system.register_draw_system(
+            SCENE_PASS,
+            NMeshDrawSystem::new(
+                queue.clone(),
+                scene_subpass.clone(),
+                allocators,
+                environments.clone(),
+            ),
+        );
+        // Skybox малюється на дальній площині лише там, де немає непрозорої геометрії
+        frame_system.register_draw_system(
+            SCENE_PASS,
+            NSkyboxDrawSystem::new(
+                queue.clone(),
+                scene_subpass.clone(),
+                allocators,
+                environments.clone(),
+            ),
+        );
+        // Тайлові мапи, спрайти, світові фігури і текст напівпрозорі, тому малюються
+        // після непрозорих мешів; мапи першими як тло 2D рівня
+        frame_system.register_draw_system(
+            SCENE_PASS,
+            NTilemapDrawSystem::new(queue.clone(), scene_subpass.clone(), allocators),
+        );
+        frame_system.register_draw_system(
+            SCENE_PASS,
+            NSpriteDrawSystem::new(queue.clone(), scene_subpass.clone(), allocators),
+        );
+        frame_system.register_draw_system(
+            SCENE_PASS,
+            NShapeDrawSystem::new(
+                queue.clone(),
+                scene_subpass.clone(),
+                allocators,
+                NLayer::World,
+                shapes.clone(),
+            ),
+        );
+        frame_system.register_draw_system(
+            SCENE_PASS,
+            NTextDrawSystem::new(
+                queue.clone(),
+                scene_subpass.clone(),
+                allocators,
+                NLayer::World,
+                debug_draw.clone(),
+            ),
+        );
+        let particle_buffers = NParticleBuffers::default();
+        frame_system.register_compute_system(
+            PRE_COMPUTE_PASS,
+            NParticleComputeSystem::new(queue.clone(), allocators, particle_buffers.clone()),
+        );
+        frame_system.register_draw_system(
+            SCENE_PASS,
+            NParticleDrawSystem::new(
+                queue.clone(),
+                scene_subpass.clone(),
+                allocators,
+                particle_buffers,
+            ),
+        );
+        frame_system.register_draw_system(
+            SCENE_PASS,
+            NDebugDrawSystem::new(queue.clone(), scene_subpass, allocators, debug_draw.clone()),
+        );
+        // Маска обведення підсвічених сутностей і debug фігур
+        let outline_subpass = frame_system.subpass(OUTLINE_MASK_PASS).unwrap();
+        frame_system.register_draw_system(
+            OUTLINE_MASK_PASS,
+            NOutlineMaskSystem::new(queue.clone(), outline_subpass, allocators, debug_draw.clone()),
+        );
+        // Екранні фігури, текст (HUD) і підписи debug draw поверх готового кадру;
+        // фігури першими, щоб бути підкладкою під текст
+        let overlay_subpass = frame_system.subpass(OVERLAY_PASS).unwrap();
+        frame_system.register_draw_system(
+            OVERLAY_PASS,
+            NShapeDrawSystem::new(
+                queue.clone(),
+                overlay_subpass.clone(),
+                allocators,
+                NLayer::Screen,
+                shapes.clone(),
+            ),
+        );
+        frame_system.register_draw_system(
+            OVERLAY_PASS,
+            NTextDrawSystem::new(
+                queue.clone(),
+                overlay_subpass,
+                allocators,
+                NLayer::Screen,
+                debug_draw.clone(),
+            ),
+        );
+        frame_system
+    }
+
+    #[allow(dead_code)]
+    pub fn settings(&self) -> &NRenderSettings {
+        &self.settings
+    }
+
+    // Застосовує нові налаштування. Кількість семплів і формат глибини входять
+    // у RenderPass (семпли — ще й у MultisampleState пайплайнів), тому при їх зміні
+    // перебудовуємо граф кадру і всі draw системи під новий сабпас.
+    pub fn apply_settings(&mut self, settings: &NRenderSettings) {
+        if self.settings == *settings {
+            return;
+        }
+        let samples = settings.msaa_sample_count(self.gfx_queue.device());
+        let depth_format = settings.depth_format(self.gfx_queue.device());
+        if samples != self.frame_system.samples()
+            || depth_format != self.frame_system.depth_format()
+        {
+            self.frame_system = Self::build_frame_system(
+                &self.gfx_queue,
+                self.image_format,
+                samples,
+                depth_format,
+                &self.allocators,
+                &self.debug_draw,
+                &self.shapes,
+                &self.timings,
+                &self.environments,
+            );
+        }
+        self.debug_draw.set_enabled(settings.debug_draw);
+        self.settings = *settings;
+    }
+
+    // Debug draw рендерера; клони можна передавати будь-яким системам
+    pub fn debug_draw(&self) -> &NDebugDraw {
+        &self.debug_draw
+    }
+
+    // 2D фігури кадру; клони можна передавати будь-яким системам
+    pub fn shapes(&self) -> &NShapeDraw {
+        &self.shapes
+    }
 
-        Self { frame_system, draw_pipeline }
+    // Історія таймінгів кадрів для панелі Profiler
+    pub fn timings(&self) -> &NSharedTimings {
+        &self.timings
+    }
+
+    // Статистика останнього відрендереного кадру (батчі та кількість інстансів)
+    pub fn stats(&self) -> &NRenderStats {
+        &self.stats
+    }
+
+    // Помилки компіляції шейдерів і створення пайплайнів для редактора
+    pub fn shader_errors(&self) -> Vec<String> {
+        self.allocators.shaders.errors()
+    }
+
+    // Текстовий дамп графа кадру для налагодження
+    pub fn dump_graph(&self) -> String {
+        self.frame_system.dump_graph()
     }
 
     pub fn render(
         &mut self,
         before_future: Box<dyn GpuFuture>,  // Future от предыдущей операции
         image: Arc<ImageView>,              // Целевое изображение
+        scene: &NScene,                     // Сцена (камера, её пост-обработка)
+        highlights: &NHighlights,           // Подсвеченные сущности (выделение редактора)
+        delta_time: f32,                    // Время кадра в секундах
     ) -> Box<dyn GpuFuture> {              // Возвращает Future завершения рендеринга
-        let mut frame = self.frame_system.frame(
-            before_future,
-            image.clone(),
-            Matrix4::identity(),
-        );
-        let dims = image.image().extent();
-        // Draw each render pass that's related to scene
-        let mut after_future = None;
-        while let Some(pass) = frame.next_pass() {
-            match pass {
-                Pass::Deferred(mut draw_pass) => {
-                    let cb = self.draw_pipeline.draw([dims[0], dims[1]]);
-                    draw_pass.execute(cb);
-                }
-                Pass::Finished(af) => {
-                    after_future = Some(af);
-                }
-            }
+        // Кадри в польоті: CPU чекає лише на кадр, що займав цей слот FRAMES_IN_FLIGHT
+        // кадрів тому. У стабільному стані він уже виконаний, і очікування немає.
+        let slot = (self.frame_index % FRAMES_IN_FLIGHT as u64) as usize;
+        self.frame_index += 1;
+        if let Some(fence) = self.fences[slot].take() {
+            fence.wait(None).unwrap();
         }
-        after_future.unwrap().then_signal_fence_and_flush().unwrap().boxed()
+        self.ring.begin_frame(slot);
+
+        // Перекомпілюємо змінені шейдери; draw системи перебудують пайплайни в draw()
+        self.allocators.shaders.poll();
+        let dims = image.image().extent();
+        let viewport_dimensions = [dims[0], dims[1]];
+        let world_to_framebuffer = scene.camera.view_projection(viewport_dimensions);
+        // Frustum culling по BVH сцени; без нього видимими вважаються всі сутності
+        let visible = if self.settings.frustum_culling {
+            scene.visible_entities(&NFrustum::from_view_projection(world_to_framebuffer))
+        } else {
+            (0..scene.entities.len()).collect()
+        };
+        let stats = Mutex::new(NRenderStats {
+            visible: visible.len().min(scene.bvh().len()) as u32,
+            culled: scene.bvh().len().saturating_sub(visible.len()) as u32,
+            ..Default::default()
+        });
+        let frame = NFrameContext {
+            scene,
+            viewport_dimensions,
+            world_to_framebuffer,
+            delta_time,
+            stats: &stats,
+            ring: &self.ring,
+            workers: &self.workers,
+            visible: &visible,
+            highlights,
+            settings: &self.settings,
+        };
+        let future = self.frame_system.frame(before_future, image, &frame);
+        let future = Arc::new(future.then_signal_fence_and_flush().unwrap());
+        self.fences[slot] = Some(future.clone());
+        self.stats = stats.into_inner().unwrap();
+        self.debug_draw.end_frame(delta_time);
+        self.shapes.end_frame();
+        future.boxed()
     }
 }
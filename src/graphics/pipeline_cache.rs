@@ -0,0 +1,253 @@
+// Кеш пайплайнів на диску.
+// Файл прив'язаний до vendor/device id та driver UUID: після зміни драйвера
+// або GPU використовується інший файл, а старий просто ігнорується.
+// Формат файлу: заголовок NCacheHeader + дані VkPipelineCache.
+// Пошкоджений файл (інший magic, розмір, контрольна сума або заголовок Vulkan,
+// що не відповідає пристрою) відкидається, і кеш стартує порожнім.
+// Помилки читання й збереження показуються в редакторі разом з помилками
+// пайплайнів (NShaderLibrary::set_pipeline_error).
+// Кеш зберігається не лише при знищенні: NRenderer::update раз на кілька секунд
+// перевіряє, чи додались пайплайни, а при виході з програми кеш зберігається явно,
+// тож аварійне завершення втрачає щонайбільше останні секунди компіляції.
+
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
+
+use vulkano::{
+    device::Device,
+    pipeline::cache::{PipelineCache, PipelineCacheCreateInfo},
+};
+
+use crate::graphics::shader::NShaderLibrary;
+
+// Змінна оточення, яка задає каталог кешу явно
+const CACHE_DIR_ENV: &str = "NOVA_CACHE_DIR";
+// Каталог кешу поруч з виконуваним файлом
+const CACHE_DIR: &str = "cache";
+// Як часто перевіряти, чи з'явились нові пайплайни
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+// Ім'я, під яким помилки кешу показуються в редакторі
+const ERROR_SOURCE: &str = "Pipeline cache";
+const CACHE_MAGIC: [u8; 8] = *b"NOVAPSO\0";
+const CACHE_VERSION: u32 = 1;
+// magic + version + vendor + device + driver uuid + pipeline cache uuid + len + checksum
+const HEADER_SIZE: usize = 8 + 4 + 4 + 4 + 16 + 16 + 8 + 8;
+
+// Ідентифікація пристрою, для якого збережено кеш
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct NCacheKey {
+    vendor_id: u32,
+    device_id: u32,
+    driver_uuid: [u8; 16],
+    pipeline_cache_uuid: [u8; 16],
+}
+
+impl NCacheKey {
+    fn of(device: &Arc<Device>) -> Self {
+        let properties = device.physical_device().properties();
+        NCacheKey {
+            vendor_id: properties.vendor_id,
+            device_id: properties.device_id,
+            driver_uuid: properties.driver_uuid,
+            pipeline_cache_uuid: properties.pipeline_cache_uuid,
+        }
+    }
+
+    fn file_name(&self) -> String {
+        let driver: String = self.driver_uuid.iter().map(|byte| format!("{:02x}", byte)).collect();
+        format!("pipelines_{:04x}_{:04x}_{}.bin", self.vendor_id, self.device_id, driver)
+    }
+}
+
+// Каталог кешу: NOVA_CACHE_DIR або cache поруч з виконуваним файлом, як і
+// встановлені шейдери в shader_dir, тож робочий каталог на нього не впливає.
+// Без шляху до бінарника — cache у робочому каталозі.
+fn cache_dir() -> &'static PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        if let Some(dir) = std::env::var_os(CACHE_DIR_ENV) {
+            return PathBuf::from(dir);
+        }
+        std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join(CACHE_DIR)))
+            .unwrap_or_else(|| PathBuf::from(CACHE_DIR))
+    })
+}
+
+/// Спільний PipelineCache усіх draw систем, що завантажується з диска
+/// при старті і зберігається назад, коли в ньому з'являються нові пайплайни.
+pub struct NPipelineCache {
+    cache: Arc<PipelineCache>,  // Кэш пайплайнов Vulkan
+    key: NCacheKey,             // Устройство, к которому привязан файл
+    path: PathBuf,              // Путь к файлу кэша
+    shaders: NShaderLibrary,    // Канал ошибок для редактора
+    saved_size: usize,          // Размер данных, записанных в файл (или загруженных)
+    last_check: Instant,        // Последняя проверка новых пайплайнов
+}
+
+impl NPipelineCache {
+    pub fn load(device: &Arc<Device>, shaders: &NShaderLibrary) -> Self {
+        let key = NCacheKey::of(device);
+        let path = cache_dir().join(key.file_name());
+
+        let initial_data = match fs::read(&path) {
+            Ok(bytes) => match decode(&bytes, &key) {
+                Ok(data) => data,
+                Err(reason) => {
+                    let error = format!("{} is discarded: {}", path.display(), reason);
+                    shaders.set_pipeline_error(ERROR_SOURCE, Some(error));
+                    Vec::new()
+                }
+            },
+            Err(_) => Vec::new(),
+        };
+
+        let saved_size = initial_data.len();
+        // Дані перевірені власним заголовком і заголовком Vulkan вище
+        let cache = unsafe {
+            PipelineCache::new(device.clone(), PipelineCacheCreateInfo {
+                initial_data,
+                ..Default::default()
+            })
+        }
+        .unwrap();
+
+        NPipelineCache {
+            cache,
+            key,
+            path,
+            shaders: shaders.clone(),
+            saved_size,
+            last_check: Instant::now(),
+        }
+    }
+
+    #[inline]
+    pub fn cache(&self) -> &Arc<PipelineCache> {
+        &self.cache
+    }
+
+    // Раз на SAVE_INTERVAL зберігає кеш, якщо в ньому з'явились нові пайплайни.
+    // Викликається щокадру.
+    pub fn save_if_changed(&mut self) {
+        if self.last_check.elapsed() < SAVE_INTERVAL {
+            return;
+        }
+        self.last_check = Instant::now();
+        self.save();
+    }
+
+    // Записує кеш у тимчасовий файл і атомарно замінює ним попередній.
+    // Нічого не пише, якщо розмір даних не змінився з останнього збереження:
+    // VkPipelineCache лише доповнюється, тож той самий розмір — ті самі пайплайни.
+    pub fn save(&mut self) {
+        let data = match self.cache.get_data() {
+            Ok(data) => data,
+            Err(err) => {
+                let error = format!("failed to read pipeline cache data: {}", err);
+                self.shaders.set_pipeline_error(ERROR_SOURCE, Some(error));
+                return;
+            }
+        };
+        if data.len() == self.saved_size {
+            return;
+        }
+        let temp_path = self.path.with_extension("tmp");
+        let result = fs::create_dir_all(cache_dir())
+            .and_then(|_| fs::write(&temp_path, encode(&data, &self.key)))
+            .and_then(|_| fs::rename(&temp_path, &self.path));
+        match result {
+            Ok(()) => self.saved_size = data.len(),
+            Err(err) => {
+                let error = format!("failed to save {}: {}", self.path.display(), err);
+                self.shaders.set_pipeline_error(ERROR_SOURCE, Some(error));
+            }
+        }
+    }
+}
+
+impl Drop for NPipelineCache {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+fn encode(data: &[u8], key: &NCacheKey) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + data.len());
+    bytes.extend_from_slice(&CACHE_MAGIC);
+    bytes.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&key.vendor_id.to_le_bytes());
+    bytes.extend_from_slice(&key.device_id.to_le_bytes());
+    bytes.extend_from_slice(&key.driver_uuid);
+    bytes.extend_from_slice(&key.pipeline_cache_uuid);
+    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&checksum(data).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+fn decode(bytes: &[u8], key: &NCacheKey) -> Result<Vec<u8>, &'static str> {
+    if bytes.len() < HEADER_SIZE {
+        return Err("file is truncated");
+    }
+    let (header, data) = bytes.split_at(HEADER_SIZE);
+    let u32_at = |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+    let u64_at = |offset: usize| u64::from_le_bytes(header[offset..offset + 8].try_into().unwrap());
+
+    if header[..8] != CACHE_MAGIC {
+        return Err("unknown file format");
+    }
+    if u32_at(8) != CACHE_VERSION {
+        return Err("unsupported version");
+    }
+    let stored = NCacheKey {
+        vendor_id: u32_at(12),
+        device_id: u32_at(16),
+        driver_uuid: header[20..36].try_into().unwrap(),
+        pipeline_cache_uuid: header[36..52].try_into().unwrap(),
+    };
+    if stored != *key {
+        return Err("saved for another device or driver");
+    }
+    if u64_at(52) != data.len() as u64 {
+        return Err("data size mismatch");
+    }
+    if u64_at(60) != checksum(data) {
+        return Err("checksum mismatch");
+    }
+    validate_vulkan_header(data, key)?;
+    Ok(data.to_vec())
+}
+
+// Заголовок VkPipelineCache (VK_PIPELINE_CACHE_HEADER_VERSION_ONE):
+// довжина, версія, vendor id, device id, pipeline cache UUID
+fn validate_vulkan_header(data: &[u8], key: &NCacheKey) -> Result<(), &'static str> {
+    if data.is_empty() {
+        return Ok(());
+    }
+    if data.len() < 32 {
+        return Err("vulkan header is truncated");
+    }
+    let u32_at = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    let header_length = u32_at(0) as usize;
+    if header_length < 32 || header_length > data.len() || u32_at(4) != 1 {
+        return Err("invalid vulkan header");
+    }
+    if u32_at(8) != key.vendor_id || u32_at(12) != key.device_id || data[16..32] != key.pipeline_cache_uuid
+    {
+        return Err("vulkan header does not match the device");
+    }
+    Ok(())
+}
+
+// FNV-1a 64
+fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
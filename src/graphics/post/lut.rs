@@ -0,0 +1,148 @@
+// 3D LUT для колірної корекції (NColorGradingSettings::lut).
+// Дані зберігаються на CPU як RGBA8 у порядку r -> g -> b (r змінюється найшвидше);
+// NPostProcessSystem завантажує LUT на GPU при першому використанні камерою.
+// Джерела: текстура-смужка (size*size x size, як експортують Unity/UE) або
+// .cube файл (Adobe/Resolve) з LUT_3D_SIZE і доменом 0..1.
+
+use crate::graphics::{
+    asset::{next_asset_id, NAssetId},
+    texture::NTexture,
+};
+
+/// 3D LUT колірної корекції
+#[derive(Debug)]
+pub struct NColorLut {
+    id: NAssetId,               // Идентификатор ассета
+    pub name: String,           // Имя
+    pub size: u32,              // Число ячеек по каждой оси
+    pub data: Vec<u8>,          // Ячейки RGBA8 (r -> g -> b)
+}
+
+impl NColorLut {
+    pub fn new(name: impl Into<String>, size: u32, data: Vec<u8>) -> Self {
+        assert!(size >= 2, "LUT needs at least 2 cells per axis");
+        assert_eq!(data.len(), (size * size * size * 4) as usize, "LUT data size mismatch");
+        NColorLut { id: next_asset_id(), name: name.into(), size, data }
+    }
+
+    #[inline]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    // LUT зі смужки (size*size x size, RGBA8): піксель (x, y) відповідає
+    // r = x % size, g = y, b = x / size.
+    pub fn from_strip(
+        name: impl Into<String>,
+        size: u32,
+        strip_rgba: &[u8],
+    ) -> Result<Self, String> {
+        let size_us = size as usize;
+        if size < 2 || strip_rgba.len() != size_us * size_us * size_us * 4 {
+            return Err(format!("LUT strip must be {}x{} RGBA8", size * size, size));
+        }
+        let mut data = vec![0u8; strip_rgba.len()];
+        for b in 0..size_us {
+            for g in 0..size_us {
+                for r in 0..size_us {
+                    let src = (g * size_us * size_us + b * size_us + r) * 4;
+                    let dst = (b * size_us * size_us + g * size_us + r) * 4;
+                    data[dst..dst + 4].copy_from_slice(&strip_rgba[src..src + 4]);
+                }
+            }
+        }
+        Ok(NColorLut::new(name, size, data))
+    }
+
+    // LUT з текстури-смужки; розмір LUT-а — висота текстури
+    #[allow(dead_code)]
+    pub fn from_texture(texture: &NTexture) -> Result<Self, String> {
+        let [width, height] = texture.size;
+        if width != height * height {
+            return Err(format!(
+                "{}: LUT strip must be {}x{}, got {}x{}",
+                texture.name,
+                height * height,
+                height,
+                width,
+                height
+            ));
+        }
+        NColorLut::from_strip(texture.name.clone(), height, &texture.data)
+    }
+
+    // Розбирає .cube: ключі TITLE, LUT_3D_SIZE, DOMAIN_MIN/DOMAIN_MAX (лише 0..1),
+    // далі size^3 рядків "r g b" з r, що змінюється найшвидше. Коментарі — з '#'.
+    #[allow(dead_code)]
+    pub fn from_cube(name: impl Into<String>, text: &str) -> Result<Self, String> {
+        let mut size = None;
+        let mut data = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: &str| format!("line {}: {}", number + 1, message);
+            let mut words = line.split_whitespace();
+            let key = words.next().unwrap_or_default();
+            match key {
+                "TITLE" => {}
+                "LUT_1D_SIZE" => return Err(error("1D LUTs are not supported")),
+                "LUT_3D_SIZE" => {
+                    let value = words.next().and_then(|value| value.parse::<u32>().ok());
+                    match value {
+                        Some(value) if value >= 2 => size = Some(value),
+                        _ => return Err(error("bad LUT_3D_SIZE")),
+                    }
+                }
+                "DOMAIN_MIN" | "DOMAIN_MAX" => {
+                    let expected = if key == "DOMAIN_MIN" { 0.0 } else { 1.0 };
+                    let domain: Vec<f32> = words.filter_map(|value| value.parse().ok()).collect();
+                    if domain != [expected; 3] {
+                        return Err(error("only the 0..1 domain is supported"));
+                    }
+                }
+                _ => {
+                    let rgb: Vec<f32> =
+                        line.split_whitespace().filter_map(|value| value.parse().ok()).collect();
+                    if rgb.len() != 3 {
+                        return Err(error("expected three numbers"));
+                    }
+                    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+                    data.extend([channel(rgb[0]), channel(rgb[1]), channel(rgb[2]), 255]);
+                }
+            }
+        }
+        let size = size.ok_or("missing LUT_3D_SIZE")?;
+        if data.len() != (size * size * size * 4) as usize {
+            return Err(format!("expected {} entries, got {}", size * size * size, data.len() / 4));
+        }
+        Ok(NColorLut::new(name, size, data))
+    }
+
+    // Завантажує .cube файл; з feature "image" — також смужку з PNG/JPEG
+    #[allow(dead_code)]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        if !path.extension().is_some_and(|extension| extension == "cube") {
+            return NColorLut::load_strip(path);
+        }
+        let result = (|| -> Result<Self, String> {
+            let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+            let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+            NColorLut::from_cube(name.unwrap_or_default(), &text)
+        })();
+        result.map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    // Помилки NTexture::load і from_texture уже містять шлях або ім'я файлу
+    #[cfg(feature = "image")]
+    fn load_strip(path: &std::path::Path) -> Result<Self, String> {
+        NColorLut::from_texture(&NTexture::load(path)?)
+    }
+
+    #[cfg(not(feature = "image"))]
+    fn load_strip(path: &std::path::Path) -> Result<Self, String> {
+        Err(format!("{}: LUT strip images need the \"image\" feature", path.display()))
+    }
+}
@@ -0,0 +1,818 @@
+pub mod lut;
+mod push_constants;
+pub mod settings;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Weak},
+};
+
+use vulkano::{
+    buffer::{Buffer, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{
+        AutoCommandBufferBuilder, BlitImageInfo, CopyBufferToImageInfo, ImageBlit,
+        PrimaryAutoCommandBuffer, RenderPassBeginInfo, SubpassBeginInfo, SubpassContents,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::Queue,
+    format::Format,
+    image::{
+        sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo, LOD_CLAMP_NONE},
+        view::{ImageView, ImageViewCreateInfo},
+        Image, ImageCreateInfo, ImageSubresourceLayers, ImageSubresourceRange, ImageType,
+        ImageUsage,
+    },
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    pipeline::{
+        cache::PipelineCache,
+        graphics::{
+            color_blend::{ColorBlendAttachmentState, ColorBlendState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::VertexInputState,
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass},
+    shader::EntryPoint,
+};
+
+use crate::graphics::{
+    asset::NAssetId,
+    frame::HDR_IMAGE_FORMAT,
+    graph::{NPassContext, NRenderNode, NResourceId},
+    pipeline::NAllocators,
+    post::{
+        lut::NColorLut,
+        push_constants::{
+            NAdaptPushConstants, NBloomCompositePushConstants, NBloomPrefilterPushConstants,
+            NBlurPushConstants, NColorGradingPushConstants, NExposurePushConstants,
+            NFxaaPushConstants, NTonemapPushConstants, NVignettePushConstants,
+        },
+        settings::{NExposureSettings, NPostEffect, NPostProcessSettings, NTonemapOperator},
+    },
+    shader::{NShaderHandle, NShaderLibrary, NShaderStage},
+};
+
+// Розмір зображення, по якому рахується середня яскравість для автоекспозиції
+const LUMINANCE_SIZE: u32 = 256;
+// Розмір нейтрального 3D LUT-а
+const NEUTRAL_LUT_SIZE: u32 = 32;
+// Ім'я системи в помилках пайплайнів редактора
+const ERROR_SOURCE: &str = "Post process";
+// Фрагментні шейдери ефектів у порядку полів NPostPipelines
+const FRAGMENT_SHADERS: [&str; 11] = [
+    "post_copy.frag",
+    "post_luminance.frag",
+    "post_adapt.frag",
+    "post_exposure.frag",
+    "post_bloom_prefilter.frag",
+    "post_blur.frag",
+    "post_bloom_composite.frag",
+    "post_tonemap.frag",
+    "post_color_grading.frag",
+    "post_fxaa.frag",
+    "post_vignette.frag",
+];
+
+// Промежуточные изображения пост-обработки, зависят от размера кадра
+struct NPostTargets {
+    extent: [u32; 3],                   // Размер кадра
+    ping_pong: [Arc<ImageView>; 2],     // HDR изображения для цепочки эффектов
+    bloom: [Arc<ImageView>; 2],         // Изображения bloom половинного разрешения
+}
+
+// Пайплайни ефектів, зібрані з однієї версії шейдерів
+#[derive(Clone)]
+struct NPostPipelines {
+    copy: Arc<GraphicsPipeline>,
+    luminance: Arc<GraphicsPipeline>,
+    adapt: Arc<GraphicsPipeline>,
+    exposure: Arc<GraphicsPipeline>,
+    bloom_prefilter: Arc<GraphicsPipeline>,
+    blur: Arc<GraphicsPipeline>,
+    bloom_composite: Arc<GraphicsPipeline>,
+    tonemap: Arc<GraphicsPipeline>,
+    color_grading: Arc<GraphicsPipeline>,
+    fxaa: Arc<GraphicsPipeline>,
+    vignette: Arc<GraphicsPipeline>,
+}
+
+/// Стек пост-обробки: HDR зображення сцени проходить через увімкнені ефекти
+/// камери (по черзі, ping-pong між двома HDR зображеннями), а останній
+/// прохід копіює результат у вихідне зображення.
+pub struct NPostProcessSystem {
+    gfx_queue: Arc<Queue>,                  // Очередь графических команд
+    pipeline_cache: Arc<PipelineCache>,     // Кэш пайплайнов
+    memory_allocator: Arc<StandardMemoryAllocator>, // Аллокатор памяти
+    descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>, // Аллокатор дескрипторов
+    sampler: Arc<Sampler>,                  // Линейный сэмплер с clamp
+    hdr_render_pass: Arc<RenderPass>,       // Проход с одним HDR вложением
+    luminance_render_pass: Arc<RenderPass>, // Проход с одним R16 вложением
+    output_render_pass: Arc<RenderPass>,    // Проход в формат выходного изображения
+    shaders: NShaderLibrary,                // Библиотека шейдеров (горячая перезагрузка)
+    vs: NShaderHandle,                      // Полноэкранный вершинный шейдер
+    fs: [NShaderHandle; FRAGMENT_SHADERS.len()], // Фрагментные шейдеры эффектов
+    shader_versions: Vec<u64>,              // Версии шейдеров, из которых собраны пайплайны
+    pipelines: Option<NPostPipelines>,      // Последние удачно собранные пайплайны
+    targets: Option<NPostTargets>,          // Промежуточные изображения
+    luminance_image: Arc<Image>,            // Лог-яркость кадра с полной цепочкой mip
+    adapted_luminance: [Arc<ImageView>; 2], // Адаптированная яркость 1x1 (ping-pong)
+    adapted_index: usize,                   // Куда пишется адаптированная яркость
+    adaptation_valid: bool,                 // Есть ли валидная яркость с прошлого кадра
+    neutral_lut: Arc<ImageView>,            // Нейтральный 3D LUT
+    luts: HashMap<NAssetId, (Weak<NColorLut>, Arc<ImageView>)>, // Загруженные LUT камер
+    pending_uploads: Vec<(Subbuffer<[u8]>, Arc<Image>)>, // Загрузки LUT до начала кадра
+}
+
+impl NPostProcessSystem {
+    pub fn new(
+        gfx_queue: Arc<Queue>,
+        output_format: Format,
+        allocators: &NAllocators,
+    ) -> NPostProcessSystem {
+        let device = gfx_queue.device().clone();
+
+        let hdr_render_pass = single_attachment_render_pass(&gfx_queue, HDR_IMAGE_FORMAT);
+        let luminance_render_pass = single_attachment_render_pass(&gfx_queue, Format::R16_SFLOAT);
+        let output_render_pass = single_attachment_render_pass(&gfx_queue, output_format);
+
+        let shaders = allocators.shaders.clone();
+        let vs = shaders.load("post_fullscreen.vert", NShaderStage::Vertex);
+        let fs = FRAGMENT_SHADERS.map(|path| shaders.load(path, NShaderStage::Fragment));
+
+        let sampler = Sampler::new(device.clone(), SamplerCreateInfo {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            address_mode: [SamplerAddressMode::ClampToEdge; 3],
+            lod: 0.0..=LOD_CLAMP_NONE,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mip_levels = LUMINANCE_SIZE.ilog2() + 1;
+        let luminance_image = create_image(
+            &allocators.memory,
+            Format::R16_SFLOAT,
+            [LUMINANCE_SIZE, LUMINANCE_SIZE, 1],
+            mip_levels,
+            ImageUsage::COLOR_ATTACHMENT
+                | ImageUsage::SAMPLED
+                | ImageUsage::TRANSFER_SRC
+                | ImageUsage::TRANSFER_DST,
+        );
+        let adapted_luminance = [0, 1].map(|_| {
+            ImageView::new_default(create_image(
+                &allocators.memory,
+                Format::R16_SFLOAT,
+                [1, 1, 1],
+                1,
+                ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED,
+            ))
+            .unwrap()
+        });
+
+        let (neutral_lut, neutral_upload) = create_lut(
+            &allocators.memory,
+            NEUTRAL_LUT_SIZE,
+            &neutral_lut_data(NEUTRAL_LUT_SIZE),
+        );
+
+        let mut system = NPostProcessSystem {
+            gfx_queue,
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            memory_allocator: allocators.memory.clone(),
+            descriptor_set_allocator: allocators.descriptor_sets.clone(),
+            sampler,
+            hdr_render_pass,
+            luminance_render_pass,
+            output_render_pass,
+            shaders,
+            vs,
+            fs,
+            shader_versions: Vec::new(),
+            pipelines: None,
+            targets: None,
+            luminance_image,
+            adapted_luminance,
+            adapted_index: 0,
+            adaptation_valid: false,
+            neutral_lut,
+            luts: HashMap::new(),
+            pending_uploads: vec![neutral_upload],
+        };
+        system.rebuild_pipelines_if_changed();
+        system
+    }
+
+    // Перебудовує пайплайни ефектів, якщо змінилась версія хоча б одного шейдера.
+    // При помилці лишаються попередні пайплайни, а помилка йде в редактор.
+    fn rebuild_pipelines_if_changed(&mut self) {
+        let versions: Vec<u64> = std::iter::once(self.vs)
+            .chain(self.fs)
+            .map(|handle| self.shaders.version(handle))
+            .collect();
+        if versions == self.shader_versions {
+            return;
+        }
+        self.shader_versions = versions;
+
+        let Some(vs) = self.shaders.entry_point(self.vs) else { return };
+        let Some(fs) =
+            self.fs.iter().map(|&handle| self.shaders.entry_point(handle)).collect::<Option<_>>()
+        else {
+            return;
+        };
+        match self.create_pipelines(vs, fs) {
+            Ok(pipelines) => {
+                self.pipelines = Some(pipelines);
+                self.shaders.set_pipeline_error(ERROR_SOURCE, None);
+            }
+            Err(error) => self.shaders.set_pipeline_error(ERROR_SOURCE, Some(error)),
+        }
+    }
+
+    // Поля ініціалізуються по черзі, тож шейдери беруться в порядку FRAGMENT_SHADERS
+    fn create_pipelines(
+        &self,
+        vs: EntryPoint,
+        fs: Vec<EntryPoint>,
+    ) -> Result<NPostPipelines, String> {
+        let mut fs = FRAGMENT_SHADERS.iter().zip(fs);
+        let mut pipeline = |render_pass: &Arc<RenderPass>| {
+            let (path, fs) = fs.next().unwrap();
+            fullscreen_pipeline(&self.gfx_queue, &self.pipeline_cache, vs.clone(), fs, render_pass)
+                .map_err(|err| format!("{}: {}", path, err))
+        };
+        Ok(NPostPipelines {
+            copy: pipeline(&self.output_render_pass)?,
+            luminance: pipeline(&self.luminance_render_pass)?,
+            adapt: pipeline(&self.luminance_render_pass)?,
+            exposure: pipeline(&self.hdr_render_pass)?,
+            bloom_prefilter: pipeline(&self.hdr_render_pass)?,
+            blur: pipeline(&self.hdr_render_pass)?,
+            bloom_composite: pipeline(&self.hdr_render_pass)?,
+            tonemap: pipeline(&self.hdr_render_pass)?,
+            color_grading: pipeline(&self.hdr_render_pass)?,
+            fxaa: pipeline(&self.hdr_render_pass)?,
+            vignette: pipeline(&self.hdr_render_pass)?,
+        })
+    }
+
+    // GPU зображення LUT-а камери. Створюється при першому використанні, а дані
+    // завантажуються в цьому ж кадрі (до ефектів). LUT-и, асети яких уже звільнено,
+    // прибираються з кешу.
+    fn lut(&mut self, lut: &Arc<NColorLut>) -> Arc<ImageView> {
+        self.luts.retain(|_, (asset, _)| asset.strong_count() > 0);
+        if let Some((_, view)) = self.luts.get(&lut.id()) {
+            return view.clone();
+        }
+        let (view, upload) = create_lut(&self.memory_allocator, lut.size, &lut.data);
+        self.pending_uploads.push(upload);
+        self.luts.insert(lut.id(), (Arc::downgrade(lut), view.clone()));
+        view
+    }
+
+    // Оновлює проміжні зображення під розмір кадру
+    fn targets(&mut self, extent: [u32; 3]) -> &NPostTargets {
+        if self.targets.as_ref().map(|targets| targets.extent) != Some(extent) {
+            let usage = ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED;
+            let half = [(extent[0] / 2).max(1), (extent[1] / 2).max(1), 1];
+            self.targets = Some(NPostTargets {
+                extent,
+                ping_pong: [0, 1].map(|_| {
+                    ImageView::new_default(create_image(
+                        &self.memory_allocator,
+                        HDR_IMAGE_FORMAT,
+                        extent,
+                        1,
+                        usage,
+                    ))
+                    .unwrap()
+                }),
+                bloom: [0, 1].map(|_| {
+                    ImageView::new_default(create_image(
+                        &self.memory_allocator,
+                        HDR_IMAGE_FORMAT,
+                        half,
+                        1,
+                        usage,
+                    ))
+                    .unwrap()
+                }),
+            });
+        }
+        self.targets.as_ref().unwrap()
+    }
+
+    // execute:
+    // - спочатку створює LUT-и камери, яких ще немає на GPU, і завантажує їх
+    //   (copy_buffer_to_image),
+    // - проганяє увімкнені ефекти по черзі; кожен пише в інше ping-pong зображення,
+    // - останній прохід (copy) пише результат у output з його форматом.
+    // delta_time в секундах, потрібен для адаптації автоекспозиції.
+    pub fn execute(
+        &mut self,
+        builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        hdr_image: Arc<ImageView>,
+        output: Arc<ImageView>,
+        settings: &NPostProcessSettings,
+        delta_time: f32,
+    ) {
+        let luts: HashMap<NAssetId, Arc<ImageView>> = settings
+            .effects
+            .iter()
+            .filter(|entry| entry.enabled)
+            .filter_map(|entry| match &entry.effect {
+                NPostEffect::ColorGrading(grading) => grading.lut.as_ref(),
+                _ => None,
+            })
+            .map(|lut| (lut.id(), self.lut(lut)))
+            .collect();
+        for (staging, image) in self.pending_uploads.drain(..) {
+            builder
+                .copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(staging, image))
+                .unwrap();
+        }
+        self.rebuild_pipelines_if_changed();
+        let Some(pipelines) = self.pipelines.clone() else { return };
+
+        let extent = hdr_image.image().extent();
+        let targets = self.targets(extent);
+        let ping_pong = targets.ping_pong.clone();
+        let bloom = targets.bloom.clone();
+
+        let mut source = hdr_image;
+        let mut target_index = 0;
+        for entry in settings.effects.iter().filter(|entry| entry.enabled) {
+            let target = ping_pong[target_index].clone();
+            match &entry.effect {
+                NPostEffect::Exposure(exposure) => {
+                    let adapted = if exposure.auto_exposure {
+                        self.adapt_luminance(builder, &pipelines, &source, exposure, delta_time)
+                    } else {
+                        self.adaptation_valid = false;
+                        self.adapted_luminance[self.adapted_index].clone()
+                    };
+                    self.begin_fullscreen(builder, &pipelines.exposure, target.clone(), &[
+                        source.clone(),
+                        adapted,
+                    ]);
+                    builder
+                        .push_constants(
+                            pipelines.exposure.layout().clone(),
+                            0,
+                            NExposurePushConstants {
+                                exposure: exposure.exposure_ev.exp2(),
+                                key_value: exposure.key_value,
+                                auto_exposure: exposure.auto_exposure as i32,
+                            },
+                        )
+                        .unwrap();
+                    end_fullscreen(builder);
+                }
+                NPostEffect::Bloom(bloom_settings) => {
+                    let half = bloom[0].image().extent();
+                    let texel = [
+                        bloom_settings.radius / half[0] as f32,
+                        bloom_settings.radius / half[1] as f32,
+                    ];
+
+                    self.begin_fullscreen(builder, &pipelines.bloom_prefilter, bloom[0].clone(), &[
+                        source.clone(),
+                    ]);
+                    builder
+                        .push_constants(
+                            pipelines.bloom_prefilter.layout().clone(),
+                            0,
+                            NBloomPrefilterPushConstants {
+                                threshold: bloom_settings.threshold,
+                                knee: bloom_settings.knee,
+                            },
+                        )
+                        .unwrap();
+                    end_fullscreen(builder);
+
+                    // Роздільне гаусове розмиття: горизонтально, потім вертикально
+                    for (input, output, direction) in [
+                        (0, 1, [texel[0], 0.0]),
+                        (1, 0, [0.0, texel[1]]),
+                    ] {
+                        self.begin_fullscreen(builder, &pipelines.blur, bloom[output].clone(), &[
+                            bloom[input].clone(),
+                        ]);
+                        builder
+                            .push_constants(pipelines.blur.layout().clone(), 0, NBlurPushConstants {
+                                direction,
+                            })
+                            .unwrap();
+                        end_fullscreen(builder);
+                    }
+
+                    self.begin_fullscreen(builder, &pipelines.bloom_composite, target.clone(), &[
+                        source.clone(),
+                        bloom[0].clone(),
+                    ]);
+                    builder
+                        .push_constants(
+                            pipelines.bloom_composite.layout().clone(),
+                            0,
+                            NBloomCompositePushConstants {
+                                intensity: bloom_settings.intensity,
+                            },
+                        )
+                        .unwrap();
+                    end_fullscreen(builder);
+                }
+                NPostEffect::Tonemap(tonemap) => {
+                    self.begin_fullscreen(builder, &pipelines.tonemap, target.clone(), &[
+                        source.clone(),
+                    ]);
+                    builder
+                        .push_constants(
+                            pipelines.tonemap.layout().clone(),
+                            0,
+                            NTonemapPushConstants {
+                                tonemapper: match tonemap.operator {
+                                    NTonemapOperator::Aces => 0,
+                                    NTonemapOperator::AgX => 1,
+                                },
+                            },
+                        )
+                        .unwrap();
+                    end_fullscreen(builder);
+                }
+                NPostEffect::ColorGrading(grading) => {
+                    let lut = grading.lut.as_ref().map(|lut| luts[&lut.id()].clone());
+                    let lut = lut.unwrap_or_else(|| self.neutral_lut.clone());
+                    let lut_size = lut.image().extent()[0] as f32;
+                    self.begin_fullscreen(builder, &pipelines.color_grading, target.clone(), &[
+                        source.clone(),
+                        lut,
+                    ]);
+                    builder
+                        .push_constants(
+                            pipelines.color_grading.layout().clone(),
+                            0,
+                            NColorGradingPushConstants {
+                                intensity: grading.intensity,
+                                lut_size,
+                            },
+                        )
+                        .unwrap();
+                    end_fullscreen(builder);
+                }
+                NPostEffect::Fxaa(fxaa) => {
+                    self.begin_fullscreen(builder, &pipelines.fxaa, target.clone(), &[
+                        source.clone(),
+                    ]);
+                    builder
+                        .push_constants(pipelines.fxaa.layout().clone(), 0, NFxaaPushConstants {
+                            inverse_size: [1.0 / extent[0] as f32, 1.0 / extent[1] as f32],
+                            edge_threshold: fxaa.edge_threshold,
+                            edge_threshold_min: fxaa.edge_threshold_min,
+                            span_max: fxaa.span_max,
+                        })
+                        .unwrap();
+                    end_fullscreen(builder);
+                }
+                NPostEffect::Vignette(vignette) => {
+                    self.begin_fullscreen(builder, &pipelines.vignette, target.clone(), &[
+                        source.clone(),
+                    ]);
+                    builder
+                        .push_constants(
+                            pipelines.vignette.layout().clone(),
+                            0,
+                            NVignettePushConstants {
+                                color: vignette.color,
+                                intensity: vignette.intensity,
+                                smoothness: vignette.smoothness,
+                            },
+                        )
+                        .unwrap();
+                    end_fullscreen(builder);
+                }
+            }
+            source = target;
+            target_index ^= 1;
+        }
+
+        self.begin_fullscreen(builder, &pipelines.copy, output, &[source]);
+        end_fullscreen(builder);
+    }
+
+    // Автоекспозиція:
+    // - log-яскравість кадру рендериться в mip 0 зображення LUMINANCE_SIZE^2,
+    // - blit-ами будується ланцюжок mip до 1x1 (середнє значення),
+    // - adapt плавно наближає адаптовану яскравість до середньої.
+    // Повертає 1x1 зображення з адаптованою яскравістю цього кадру.
+    fn adapt_luminance(
+        &mut self,
+        builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        pipelines: &NPostPipelines,
+        source: &Arc<ImageView>,
+        exposure: &NExposureSettings,
+        delta_time: f32,
+    ) -> Arc<ImageView> {
+        let mip_levels = self.luminance_image.mip_levels();
+        let level_zero = ImageView::new(
+            self.luminance_image.clone(),
+            ImageViewCreateInfo {
+                subresource_range: ImageSubresourceRange {
+                    mip_levels: 0..1,
+                    ..self.luminance_image.subresource_range()
+                },
+                ..ImageViewCreateInfo::from_image(&self.luminance_image)
+            },
+        )
+        .unwrap();
+        self.begin_fullscreen(builder, &pipelines.luminance, level_zero, &[source.clone()]);
+        end_fullscreen(builder);
+
+        for level in 1..mip_levels {
+            let src_size = (LUMINANCE_SIZE >> (level - 1)).max(1);
+            let dst_size = (LUMINANCE_SIZE >> level).max(1);
+            builder
+                .blit_image(BlitImageInfo {
+                    regions: [ImageBlit {
+                        src_subresource: ImageSubresourceLayers {
+                            mip_level: level - 1,
+                            ..self.luminance_image.subresource_layers()
+                        },
+                        src_offsets: [[0, 0, 0], [src_size, src_size, 1]],
+                        dst_subresource: ImageSubresourceLayers {
+                            mip_level: level,
+                            ..self.luminance_image.subresource_layers()
+                        },
+                        dst_offsets: [[0, 0, 0], [dst_size, dst_size, 1]],
+                        ..Default::default()
+                    }]
+                    .into(),
+                    filter: Filter::Linear,
+                    ..BlitImageInfo::images(self.luminance_image.clone(), self.luminance_image.clone())
+                })
+                .unwrap();
+        }
+
+        let previous = self.adapted_luminance[self.adapted_index].clone();
+        self.adapted_index ^= 1;
+        let current = self.adapted_luminance[self.adapted_index].clone();
+        let log_luminance = ImageView::new_default(self.luminance_image.clone()).unwrap();
+
+        let inputs = [log_luminance, previous];
+        self.begin_fullscreen(builder, &pipelines.adapt, current.clone(), &inputs);
+        builder
+            .push_constants(pipelines.adapt.layout().clone(), 0, NAdaptPushConstants {
+                delta_time,
+                speed: exposure.adaptation_speed,
+                max_lod: (mip_levels - 1) as f32,
+                min_luminance: exposure.min_luminance,
+                max_luminance: exposure.max_luminance,
+                reset: !self.adaptation_valid as i32,
+            })
+            .unwrap();
+        end_fullscreen(builder);
+        self.adaptation_valid = true;
+        current
+    }
+
+    // Починає render pass з одним attachment-ом target, прив'язує пайплайн
+    // і дескриптори (вхід i — текстура на binding 2i і сэмплер на 2i + 1).
+    // Push constants задає викликач, після чого end_fullscreen малює трикутник
+    // і закриває render pass.
+    fn begin_fullscreen(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        pipeline: &Arc<GraphicsPipeline>,
+        target: Arc<ImageView>,
+        inputs: &[Arc<ImageView>],
+    ) {
+        let render_pass = if target.format() == HDR_IMAGE_FORMAT {
+            &self.hdr_render_pass
+        } else if target.format() == Format::R16_SFLOAT {
+            &self.luminance_render_pass
+        } else {
+            &self.output_render_pass
+        };
+        let extent = target.image().extent();
+        let framebuffer = Framebuffer::new(render_pass.clone(), FramebufferCreateInfo {
+            attachments: vec![target],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let layout = pipeline.layout().clone();
+        let descriptor_set = DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            layout.set_layouts()[0].clone(),
+            inputs.iter().enumerate().flat_map(|(index, view)| {
+                let binding = index as u32 * 2;
+                [
+                    WriteDescriptorSet::image_view(binding, view.clone()),
+                    WriteDescriptorSet::sampler(binding + 1, self.sampler.clone()),
+                ]
+            }),
+            [],
+        )
+        .unwrap();
+
+        builder
+            .begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: vec![None],
+                    ..RenderPassBeginInfo::framebuffer(framebuffer)
+                },
+                SubpassBeginInfo { contents: SubpassContents::Inline, ..Default::default() },
+            )
+            .unwrap()
+            .bind_pipeline_graphics(pipeline.clone())
+            .unwrap()
+            .set_viewport(
+                0,
+                [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [extent[0] as f32, extent[1] as f32],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap()
+            .bind_descriptor_sets(PipelineBindPoint::Graphics, layout, 0, descriptor_set)
+            .unwrap();
+    }
+}
+
+/// Вузол графа, який проганяє стек пост-обробки камери з input в output
+pub struct NPostProcessNode {
+    system: NPostProcessSystem,     // Система пост-обработки
+    input: NResourceId,             // HDR изображение сцены
+    output: NResourceId,            // Выходное изображение
+}
+
+impl NPostProcessNode {
+    pub fn new(system: NPostProcessSystem, input: NResourceId, output: NResourceId) -> Self {
+        NPostProcessNode { system, input, output }
+    }
+}
+
+impl NRenderNode for NPostProcessNode {
+    fn execute(&mut self, ctx: &mut NPassContext) {
+        let input = ctx.image(self.input);
+        let output = ctx.image(self.output);
+        // Відладкові режими вьюпорта показують значення без ефектів (лише копія)
+        let no_effects = NPostProcessSettings { effects: Vec::new() };
+        let settings = if ctx.frame.settings.view_mode.is_debug() {
+            &no_effects
+        } else {
+            &ctx.frame.scene.camera.post_process
+        };
+        self.system.execute(ctx.builder, input, output, settings, ctx.frame.delta_time);
+    }
+}
+
+fn end_fullscreen(builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>) {
+    unsafe {
+        builder.draw(3, 1, 0, 0).unwrap();
+    }
+    builder.end_render_pass(Default::default()).unwrap();
+}
+
+pub(crate) fn single_attachment_render_pass(
+    gfx_queue: &Arc<Queue>,
+    format: Format,
+) -> Arc<RenderPass> {
+    vulkano::single_pass_renderpass!(gfx_queue.device().clone(),
+        attachments: {
+            color: {
+                format: format,
+                samples: 1,
+                load_op: DontCare,
+                store_op: Store,
+            }
+        },
+        pass: {
+            color: [color],
+            depth_stencil: {}
+        }
+    )
+    .unwrap()
+}
+
+pub(crate) fn fullscreen_pipeline(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    vs: EntryPoint,
+    fs: EntryPoint,
+    render_pass: &Arc<RenderPass>,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    let device = gfx_queue.device().clone();
+    let subpass = Subpass::from(render_pass.clone(), 0).unwrap();
+    let stages = [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    GraphicsPipeline::new(device, Some(pipeline_cache.clone()), GraphicsPipelineCreateInfo {
+        stages: stages.into_iter().collect(),
+        vertex_input_state: Some(VertexInputState::default()),
+        input_assembly_state: Some(InputAssemblyState::default()),
+        viewport_state: Some(ViewportState::default()),
+        rasterization_state: Some(RasterizationState::default()),
+        multisample_state: Some(MultisampleState::default()),
+        color_blend_state: Some(ColorBlendState::with_attachment_states(
+            subpass.num_color_attachments(),
+            ColorBlendAttachmentState::default(),
+        )),
+        dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+        subpass: Some(subpass.into()),
+        ..GraphicsPipelineCreateInfo::layout(layout)
+    })
+    .map_err(|err| err.to_string())
+}
+
+fn create_image(
+    memory: &Arc<StandardMemoryAllocator>,
+    format: Format,
+    extent: [u32; 3],
+    mip_levels: u32,
+    usage: ImageUsage,
+) -> Arc<Image> {
+    Image::new(
+        memory.clone(),
+        ImageCreateInfo {
+            image_type: ImageType::Dim2d,
+            format,
+            extent,
+            mip_levels,
+            usage,
+            ..Default::default()
+        },
+        AllocationCreateInfo::default(),
+    )
+    .unwrap()
+}
+
+// Створює 3D LUT та staging буфер з його даними (RGBA8, порядок r -> g -> b).
+// Копіювання записується в командний буфер кадру, тому тут без очікування GPU.
+fn create_lut(
+    memory: &Arc<StandardMemoryAllocator>,
+    size: u32,
+    data: &[u8],
+) -> (Arc<ImageView>, (Subbuffer<[u8]>, Arc<Image>)) {
+    let image = Image::new(
+        memory.clone(),
+        ImageCreateInfo {
+            image_type: ImageType::Dim3d,
+            format: Format::R8G8B8A8_UNORM,
+            extent: [size, size, size],
+            usage: ImageUsage::SAMPLED | ImageUsage::TRANSFER_DST,
+            ..Default::default()
+        },
+        AllocationCreateInfo::default(),
+    )
+    .unwrap();
+    let staging = Buffer::from_iter(
+        memory.clone(),
+        BufferCreateInfo { usage: BufferUsage::TRANSFER_SRC, ..Default::default() },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        },
+        data.iter().copied(),
+    )
+    .unwrap();
+    (ImageView::new_default(image.clone()).unwrap(), (staging, image))
+}
+
+// Нейтральний LUT: кожна комірка містить власні координати
+fn neutral_lut_data(size: u32) -> Vec<u8> {
+    let scale = 255.0 / (size - 1) as f32;
+    let mut data = Vec::with_capacity((size * size * size * 4) as usize);
+    for b in 0..size {
+        for g in 0..size {
+            for r in 0..size {
+                data.extend_from_slice(&[
+                    (r as f32 * scale).round() as u8,
+                    (g as f32 * scale).round() as u8,
+                    (b as f32 * scale).round() as u8,
+                    255,
+                ]);
+            }
+        }
+    }
+    data
+}
@@ -0,0 +1,73 @@
+// Push constant-и ефектів пост-обробки. Шейдери лежать у assets/shaders/post_*.frag
+// і вантажаться бібліотекою шейдерів; порядок і типи полів мають збігатися з
+// блоками `Push` у шейдерах.
+
+use vulkano::buffer::BufferContents;
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+pub struct NAdaptPushConstants {
+    pub delta_time: f32,        // Время кадра в секундах
+    pub speed: f32,             // Скорость адаптации
+    pub max_lod: f32,           // Последний mip (1x1) лог-яркости
+    pub min_luminance: f32,     // Нижняя граница средней яркости
+    pub max_luminance: f32,     // Верхняя граница средней яркости
+    pub reset: i32,             // Сбросить адаптацию к текущему кадру
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+pub struct NExposurePushConstants {
+    pub exposure: f32,          // Множитель экспозиции
+    pub key_value: f32,         // Целевая средняя яркость
+    pub auto_exposure: i32,     // Учитывать адаптированную яркость
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+pub struct NBloomPrefilterPushConstants {
+    pub threshold: f32,         // Порог яркости
+    pub knee: f32,              // Ширина мягкого порога
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+pub struct NBlurPushConstants {
+    pub direction: [f32; 2],    // Шаг выборок в UV
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+pub struct NBloomCompositePushConstants {
+    pub intensity: f32,         // Сила bloom
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+pub struct NTonemapPushConstants {
+    pub tonemapper: i32,        // 0 — ACES, 1 — AgX
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+pub struct NColorGradingPushConstants {
+    pub intensity: f32,         // Сила цветокоррекции
+    pub lut_size: f32,          // Размер LUT-а
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+pub struct NFxaaPushConstants {
+    pub inverse_size: [f32; 2], // Размер пикселя в UV
+    pub edge_threshold: f32,    // Относительный порог контраста
+    pub edge_threshold_min: f32, // Абсолютный порог контраста
+    pub span_max: f32,          // Максимальная длина поиска края
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+pub struct NVignettePushConstants {
+    pub color: [f32; 4],        // Цвет виньетки
+    pub intensity: f32,         // Сила
+    pub smoothness: f32,        // Мягкость края
+}
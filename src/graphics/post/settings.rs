@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use crate::graphics::post::lut::NColorLut;
+
+/// Оператор тонмапінгу (HDR -> LDR)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NTonemapOperator {
+    Aces,   // Аппроксимация ACES (Stephen Hill fit)
+    AgX,    // AgX с базовым look
+}
+
+#[derive(Clone, Debug)]
+pub struct NExposureSettings {
+    pub exposure_ev: f32,       // Ручная компенсация экспозиции (EV)
+    pub auto_exposure: bool,    // Автоэкспозиция по средней яркости кадра
+    pub key_value: f32,         // Целевая средняя яркость для автоэкспозиции
+    pub adaptation_speed: f32,  // Скорость адаптации глаза (1/с)
+    pub min_luminance: f32,     // Ограничения средней яркости
+    pub max_luminance: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct NBloomSettings {
+    pub threshold: f32,         // Порог яркости для bloom
+    pub knee: f32,              // Мягкость порога
+    pub intensity: f32,         // Сила подмешивания bloom
+    pub radius: f32,            // Радиус размытия (в текселях половинного разрешения)
+}
+
+#[derive(Clone, Debug)]
+pub struct NTonemapSettings {
+    pub operator: NTonemapOperator,
+}
+
+#[derive(Clone, Debug)]
+pub struct NColorGradingSettings {
+    pub lut: Option<Arc<NColorLut>>,  // 3D LUT (None -> нейтральный LUT)
+    pub intensity: f32,               // Смешивание с исходным цветом
+}
+
+#[derive(Clone, Debug)]
+pub struct NFxaaSettings {
+    pub edge_threshold: f32,        // Минимальный относительный контраст края
+    pub edge_threshold_min: f32,    // Абсолютный порог для тёмных областей
+    pub span_max: f32,              // Максимальная длина поиска вдоль края (в пикселях)
+}
+
+#[derive(Clone, Debug)]
+pub struct NVignetteSettings {
+    pub intensity: f32,         // Сила затемнения по краям
+    pub smoothness: f32,        // Мягкость перехода
+    pub color: [f32; 4],        // Цвет виньетки
+}
+
+/// Один ефект у стеку пост-обробки
+#[derive(Clone, Debug)]
+pub enum NPostEffect {
+    Exposure(NExposureSettings),
+    Bloom(NBloomSettings),
+    Tonemap(NTonemapSettings),
+    ColorGrading(NColorGradingSettings),
+    Fxaa(NFxaaSettings),
+    Vignette(NVignetteSettings),
+}
+
+impl NPostEffect {
+    pub fn name(&self) -> &'static str {
+        match self {
+            NPostEffect::Exposure(_) => "Exposure",
+            NPostEffect::Bloom(_) => "Bloom",
+            NPostEffect::Tonemap(_) => "Tonemap",
+            NPostEffect::ColorGrading(_) => "Color Grading",
+            NPostEffect::Fxaa(_) => "FXAA",
+            NPostEffect::Vignette(_) => "Vignette",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NPostEffectEntry {
+    pub enabled: bool,          // Включён ли эффект
+    pub effect: NPostEffect,    // Эффект и его параметры
+}
+
+impl NPostEffectEntry {
+    pub fn new(effect: NPostEffect) -> Self {
+        NPostEffectEntry { enabled: true, effect }
+    }
+}
+
+/// Стек пост-обробки камери. Ефекти виконуються в порядку `effects`,
+/// кожен читає результат попереднього.
+#[derive(Clone, Debug)]
+pub struct NPostProcessSettings {
+    pub effects: Vec<NPostEffectEntry>,
+}
+
+impl Default for NPostProcessSettings {
+    fn default() -> Self {
+        NPostProcessSettings {
+            effects: vec![
+                NPostEffectEntry::new(NPostEffect::Exposure(NExposureSettings {
+                    exposure_ev: 0.0,
+                    auto_exposure: true,
+                    key_value: 0.18,
+                    adaptation_speed: 1.5,
+                    min_luminance: 0.03,
+                    max_luminance: 8.0,
+                })),
+                NPostEffectEntry::new(NPostEffect::Bloom(NBloomSettings {
+                    threshold: 1.0,
+                    knee: 0.5,
+                    intensity: 0.05,
+                    radius: 1.0,
+                })),
+                NPostEffectEntry::new(NPostEffect::Tonemap(NTonemapSettings {
+                    operator: NTonemapOperator::Aces,
+                })),
+                NPostEffectEntry {
+                    enabled: false,
+                    effect: NPostEffect::ColorGrading(NColorGradingSettings {
+                        lut: None,
+                        intensity: 1.0,
+                    }),
+                },
+                NPostEffectEntry::new(NPostEffect::Fxaa(NFxaaSettings {
+                    edge_threshold: 0.125,
+                    edge_threshold_min: 0.0312,
+                    span_max: 8.0,
+                })),
+                NPostEffectEntry {
+                    enabled: false,
+                    effect: NPostEffect::Vignette(NVignetteSettings {
+                        intensity: 0.3,
+                        smoothness: 0.4,
+                        color: [0.0, 0.0, 0.0, 1.0],
+                    }),
+                },
+            ],
+        }
+    }
+}
+
+impl NPostProcessSettings {
+    // Ставить LUT колірної корекції камери й вмикає ефект (None — нейтральний LUT)
+    #[allow(dead_code)]
+    pub fn set_lut(&mut self, lut: Option<Arc<NColorLut>>) {
+        for entry in &mut self.effects {
+            if let NPostEffect::ColorGrading(grading) = &mut entry.effect {
+                grading.lut = lut.clone();
+                entry.enabled = true;
+            }
+        }
+    }
+
+    // Стек без ефектів: HDR зображення просто копіюється у вихідне
+    #[allow(dead_code)]
+    pub fn disabled() -> Self {
+        NPostProcessSettings { effects: Vec::new() }
+    }
+}
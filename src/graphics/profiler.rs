@@ -0,0 +1,278 @@
+// Профайлер кадру.
+// Кожен прохід графа і кожна draw система обгортаються в scope: на CPU міряється
+// час запису команд, на GPU — різниця timestamp-ів з query pool-а.
+// Результати GPU читаються без очікування: у кожного кадру в польоті свій query pool,
+// і на початку наступних кадрів перевіряється, чи вже доступні його результати
+// (зазвичай через кадр). Готові кадри потрапляють в історію NTimingHistory,
+// яку показує панель Profiler.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use vulkano::{
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+        CommandBufferInheritanceInfo, CommandBufferUsage, SecondaryAutoCommandBuffer,
+    },
+    device::Queue,
+    query::{QueryPool, QueryPoolCreateInfo, QueryResultFlags, QueryType},
+    render_pass::Subpass,
+    sync::PipelineStage,
+};
+
+use crate::graphics::ring::FRAMES_IN_FLIGHT;
+
+// Скільки кадрів зберігається в історії
+pub const PROFILER_HISTORY: usize = 240;
+// Кількість query pool-ів (кадрів, результати яких можуть очікувати читання):
+// на один більше за кадри в польоті, щоб пул встигав звільнитися
+const PROFILER_FRAMES: usize = FRAMES_IN_FLIGHT + 1;
+// Максимум scope-ів з GPU часом за кадр (по два timestamp-и на scope)
+const MAX_GPU_SCOPES: u32 = 64;
+
+/// Час одного scope-а (проходу або draw системи)
+#[derive(Clone, Debug)]
+pub struct NScopeTiming {
+    pub name: String,           // Имя прохода / draw системы
+    pub depth: u32,             // Вложенность (0 -> кадр целиком)
+    pub cpu_ms: f32,            // Время записи команд на CPU
+    pub gpu_ms: Option<f32>,    // Время выполнения на GPU (None -> нет данных)
+    gpu_scope: Option<u32>,     // Индекс пары timestamp-ов в query pool-е
+}
+
+/// Таймінги одного кадру
+#[derive(Clone, Debug, Default)]
+pub struct NFrameTimings {
+    pub frame_ms: f32,              // Полное время кадра на CPU (delta time)
+    pub scopes: Vec<NScopeTiming>,  // Scope-ы в порядке начала; scopes[0] -> весь кадр
+}
+
+impl NFrameTimings {
+    // Час запису всього кадру на CPU
+    pub fn cpu_ms(&self) -> f32 {
+        self.scopes.first().map_or(0.0, |scope| scope.cpu_ms)
+    }
+
+    // Час виконання всього кадру на GPU
+    pub fn gpu_ms(&self) -> Option<f32> {
+        self.scopes.first().and_then(|scope| scope.gpu_ms)
+    }
+}
+
+/// Історія таймінгів останніх PROFILER_HISTORY кадрів
+#[derive(Debug, Default)]
+pub struct NTimingHistory {
+    pub frames: VecDeque<NFrameTimings>,
+}
+
+impl NTimingHistory {
+    fn push(&mut self, frame: NFrameTimings) {
+        if self.frames.len() == PROFILER_HISTORY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+}
+
+pub type NSharedTimings = Arc<Mutex<NTimingHistory>>;
+
+// Query pool одного кадру в польоті та таймінги, що чекають на GPU результати
+struct NProfilerSlot {
+    pool: Arc<QueryPool>,
+    pending: Option<NFrameTimings>,
+}
+
+pub struct NGpuProfiler {
+    gfx_queue: Arc<Queue>,                  // Очередь графических команд
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>, // Аллокатор команд
+    slots: Vec<NProfilerSlot>,              // Пусто, если очередь не поддерживает timestamp-ы
+    slot: usize,                            // Слот текущего кадра
+    timestamp_period: f32,                  // Наносекунд на тик timestamp-а
+    timestamp_mask: u64,                    // Маска значащих битов timestamp-а
+    history: NSharedTimings,                // История для панели Profiler
+    current: NFrameTimings,                 // Таймінги записываемого кадра
+    open: Vec<(usize, Instant)>,            // Открытые scope-ы и время их начала
+    next_gpu_scope: u32,                    // Следующая свободная пара timestamp-ов
+}
+
+impl NGpuProfiler {
+    pub fn new(
+        gfx_queue: Arc<Queue>,
+        command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
+        history: NSharedTimings,
+    ) -> Self {
+        let device = gfx_queue.device().clone();
+        let physical_device = device.physical_device();
+        let valid_bits = physical_device.queue_family_properties()
+            [gfx_queue.queue_family_index() as usize]
+            .timestamp_valid_bits;
+
+        let slots = match valid_bits {
+            Some(_) => (0..PROFILER_FRAMES)
+                .map(|_| NProfilerSlot {
+                    pool: QueryPool::new(device.clone(), QueryPoolCreateInfo {
+                        query_count: MAX_GPU_SCOPES * 2,
+                        ..QueryPoolCreateInfo::query_type(QueryType::Timestamp)
+                    })
+                    .unwrap(),
+                    pending: None,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        let timestamp_mask = match valid_bits {
+            Some(bits) if bits < 64 => (1u64 << bits) - 1,
+            _ => u64::MAX,
+        };
+
+        NGpuProfiler {
+            gfx_queue,
+            command_buffer_allocator,
+            slots,
+            slot: 0,
+            timestamp_period: physical_device.properties().timestamp_period,
+            timestamp_mask,
+            history,
+            current: NFrameTimings::default(),
+            open: Vec::new(),
+            next_gpu_scope: 0,
+        }
+    }
+
+    // begin_frame:
+    // - забирає готові результати попередніх кадрів (без очікування),
+    // - якщо слот поточного кадру досі зайнятий, його кадр (найстаріший з тих, що
+    //   чекають) іде в історію без GPU часу,
+    // - скидає query pool слоту та відкриває scope всього кадру.
+    pub fn begin_frame<L>(&mut self, builder: &mut AutoCommandBufferBuilder<L>, delta_time: f32) {
+        self.collect_results();
+
+        if let Some(slot) = self.slots.get_mut(self.slot) {
+            if let Some(stale) = slot.pending.take() {
+                self.history.lock().unwrap().push(stale);
+            }
+            unsafe {
+                builder.reset_query_pool(slot.pool.clone(), 0..MAX_GPU_SCOPES * 2).unwrap();
+            }
+        }
+
+        self.current = NFrameTimings { frame_ms: delta_time * 1000.0, scopes: Vec::new() };
+        self.open.clear();
+        self.next_gpu_scope = 0;
+        self.begin_scope(builder, "frame");
+    }
+
+    pub fn end_frame<L>(&mut self, builder: &mut AutoCommandBufferBuilder<L>) {
+        self.end_scope(builder);
+        let timings = std::mem::take(&mut self.current);
+        match self.slots.get_mut(self.slot) {
+            Some(slot) => slot.pending = Some(timings),
+            None => self.history.lock().unwrap().push(timings),
+        }
+        if !self.slots.is_empty() {
+            self.slot = (self.slot + 1) % self.slots.len();
+        }
+    }
+
+    pub fn begin_scope<L>(&mut self, builder: &mut AutoCommandBufferBuilder<L>, name: &str) {
+        let gpu_scope = self.write_timestamp(builder, None, PipelineStage::TopOfPipe);
+        self.current.scopes.push(NScopeTiming {
+            name: name.to_owned(),
+            depth: self.open.len() as u32,
+            cpu_ms: 0.0,
+            gpu_ms: None,
+            gpu_scope,
+        });
+        self.open.push((self.current.scopes.len() - 1, Instant::now()));
+    }
+
+    pub fn end_scope<L>(&mut self, builder: &mut AutoCommandBufferBuilder<L>) {
+        let cpu_ms = self.open.last().map_or(0.0, |(_, start)| start.elapsed().as_secs_f32());
+        self.end_scope_with_cpu(builder, cpu_ms * 1000.0);
+    }
+
+    // Закриває scope з CPU часом, виміряним в іншому місці
+    // (draw системи записуються паралельно на воркерах)
+    pub fn end_scope_with_cpu<L>(
+        &mut self,
+        builder: &mut AutoCommandBufferBuilder<L>,
+        cpu_ms: f32,
+    ) {
+        let (index, _) = self.open.pop().expect("profiler scope is not open");
+        let gpu_scope = self.current.scopes[index].gpu_scope;
+        self.write_timestamp(builder, gpu_scope, PipelineStage::BottomOfPipe);
+        self.current.scopes[index].cpu_ms = cpu_ms;
+    }
+
+    // Вторинний буфер для timestamp-ів всередині render pass-у, де первинний
+    // буфер може лише виконувати вторинні (SubpassContents::SecondaryCommandBuffers)
+    pub fn secondary_builder(
+        &self,
+        subpass: &Subpass,
+    ) -> AutoCommandBufferBuilder<SecondaryAutoCommandBuffer> {
+        AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.clone(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    // Пише timestamp початку (scope = None, виділяє нову пару) або кінця scope-а
+    fn write_timestamp<L>(
+        &mut self,
+        builder: &mut AutoCommandBufferBuilder<L>,
+        scope: Option<u32>,
+        stage: PipelineStage,
+    ) -> Option<u32> {
+        let slot = self.slots.get(self.slot)?;
+        let (scope, query) = match scope {
+            Some(scope) => (scope, scope * 2 + 1),
+            None if self.next_gpu_scope < MAX_GPU_SCOPES => {
+                self.next_gpu_scope += 1;
+                (self.next_gpu_scope - 1, (self.next_gpu_scope - 1) * 2)
+            }
+            None => return None,
+        };
+        unsafe {
+            builder.write_timestamp(slot.pool.clone(), query, stage).unwrap();
+        }
+        Some(scope)
+    }
+
+    // Переносить в історію кадри, чиї timestamp-и вже доступні. Слоти обходяться від
+    // найстарішого кадру (слот поточного кадру) до найновішого; на першому кадрі без
+    // результатів обхід зупиняється, щоб історія лишалась у порядку кадрів.
+    fn collect_results(&mut self) {
+        let count = self.slots.len();
+        for offset in 0..count {
+            let slot = &mut self.slots[(self.slot + offset) % count];
+            let Some(timings) = &mut slot.pending else { continue };
+            let scope_count = timings.scopes.iter().filter(|scope| scope.gpu_scope.is_some()).count();
+            let mut ticks = vec![0u64; scope_count * 2];
+            let ready = slot
+                .pool
+                .get_results(0..scope_count as u32 * 2, &mut ticks, QueryResultFlags::empty())
+                .unwrap_or(false);
+            if !ready {
+                break;
+            }
+            for scope in &mut timings.scopes {
+                if let Some(index) = scope.gpu_scope {
+                    let begin = ticks[index as usize * 2];
+                    let end = ticks[index as usize * 2 + 1];
+                    let elapsed = end.wrapping_sub(begin) & self.timestamp_mask;
+                    scope.gpu_ms = Some(elapsed as f32 * self.timestamp_period / 1_000_000.0);
+                }
+            }
+            self.history.lock().unwrap().push(slot.pending.take().unwrap());
+        }
+    }
+}
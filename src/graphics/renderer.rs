@@ -8,25 +8,35 @@ use std::sync::Arc;
 use vulkano::{
     command_buffer::allocator::{
         StandardCommandBufferAllocator, StandardCommandBufferAllocatorCreateInfo,
-    }};
+    },
+    descriptor_set::allocator::StandardDescriptorSetAllocator,
+};
 
 
 use crate::graphics::{pipeline::NRenderPipeline,
-                        pipeline::NAllocators
+                        compute::NComputeQueues,
+                        pipeline::NAllocators,
+                        pipeline_cache::NPipelineCache,
+                        shader::NShaderLibrary,
 };
 
 
 pub struct NRenderer{
     pub render_pipeline: NRenderPipeline,
+    pipeline_cache: NPipelineCache,    // Кэш пайплайнов, периодически сохраняется на диск
 }
 
 impl NRenderer {
     pub fn new(context: &VulkanoContext) -> Self {
+        let shaders = NShaderLibrary::new(context.device().clone());
+        let pipeline_cache = NPipelineCache::load(context.device(), &shaders);
         Self{
             render_pipeline: NRenderPipeline::new(
                 context.graphics_queue().clone(),
                 DEFAULT_IMAGE_FORMAT,
                 &NAllocators {
+                    // Пули аллокатора окремі для кожного потоку, тому воркери
+                    // записують вторинні буфери паралельно без блокувань
                     command_buffers: Arc::new(StandardCommandBufferAllocator::new(
                         context.device().clone(),
                         StandardCommandBufferAllocatorCreateInfo {
@@ -35,8 +45,29 @@ impl NRenderer {
                         },
                     )),
                     memory: context.memory_allocator().clone(),
+                    descriptor_sets: Arc::new(StandardDescriptorSetAllocator::new(
+                        context.device().clone(),
+                        Default::default(),
+                    )),
+                    pipeline_cache: pipeline_cache.cache().clone(),
+                    shaders,
+                    compute: NComputeQueues::new(
+                        context.graphics_queue().clone(),
+                        context.compute_queue().clone(),
+                    ),
                 },
-            )
+            ),
+            pipeline_cache,
         }
     }
+
+    // Викликається щокадру: зберігає кеш пайплайнів, якщо додались нові
+    pub fn update(&mut self) {
+        self.pipeline_cache.save_if_changed();
+    }
+
+    // Явне збереження при виході з програми
+    pub fn shutdown(&mut self) {
+        self.pipeline_cache.save();
+    }
 }
\ No newline at end of file
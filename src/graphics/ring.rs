@@ -0,0 +1,96 @@
+// Кільцеві буфери даних кадру.
+// Кожен кадр у польоті має свій слот з SubbufferAllocator-ами під uniform-и,
+// вершини/інстанси та storage буфери (палітри суглобів). Перед записом кадру
+// NRenderPipeline чекає fence кадру,
+// який використовував цей слот FRAMES_IN_FLIGHT кадрів тому (у стабільному стані
+// він уже сигналізований), тож арени слота вільні й перевикористовуються без
+// нових виділень пам'яті та без очікування GPU.
+// SubbufferAllocator не Sync, тому кожен потік пулу воркерів має в слоті
+// власну пару алокаторів (під Mutex-ом, який ніхто інший не бере).
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use vulkano::{
+    buffer::{
+        allocator::{SubbufferAllocator, SubbufferAllocatorCreateInfo},
+        BufferContents, BufferUsage, Subbuffer,
+    },
+    memory::allocator::{MemoryTypeFilter, StandardMemoryAllocator},
+};
+
+use crate::graphics::workers::NWorkerPool;
+
+// Скільки кадрів CPU може записати наперед, поки GPU виконує попередні
+pub const FRAMES_IN_FLIGHT: usize = 2;
+
+// Алокатори одного потоку в одному кадрі в польоті
+struct NRingArena {
+    uniforms: SubbufferAllocator,   // Uniform буферы
+    vertices: SubbufferAllocator,   // Вершины и инстансы
+    storage: SubbufferAllocator,    // Storage буферы
+}
+
+/// Дані кадру, що живуть до завершення кадру на GPU
+pub struct NFrameRing {
+    slots: Vec<Vec<Mutex<NRingArena>>>, // Слоты по кадрам в полёте, в них арены по потокам
+    slot: usize,            // Слот записываемого кадра
+    workers: NWorkerPool,   // Пул воркеров (определяет арену потока)
+}
+
+impl NFrameRing {
+    pub fn new(memory: &Arc<StandardMemoryAllocator>, workers: &NWorkerPool) -> Self {
+        let allocator = |buffer_usage| {
+            SubbufferAllocator::new(memory.clone(), SubbufferAllocatorCreateInfo {
+                buffer_usage,
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            })
+        };
+        let slots = (0..FRAMES_IN_FLIGHT)
+            .map(|_| {
+                (0..workers.thread_slots())
+                    .map(|_| {
+                        Mutex::new(NRingArena {
+                            uniforms: allocator(BufferUsage::UNIFORM_BUFFER),
+                            vertices: allocator(BufferUsage::VERTEX_BUFFER),
+                            storage: allocator(BufferUsage::STORAGE_BUFFER),
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        NFrameRing { slots, slot: 0, workers: workers.clone() }
+    }
+
+    // Арена потоку, який зараз пише кадр
+    fn arena(&self) -> MutexGuard<'_, NRingArena> {
+        self.slots[self.slot][self.workers.thread_slot()].lock().unwrap()
+    }
+
+    // Перемикає на слот нового кадру (кадр цього слота вже завершений на GPU)
+    pub fn begin_frame(&mut self, slot: usize) {
+        self.slot = slot;
+    }
+
+    // Uniform буфер з `value` на один кадр
+    pub fn uniform<T: BufferContents>(&self, value: T) -> Subbuffer<T> {
+        let buffer = self.arena().uniforms.allocate_sized().unwrap();
+        *buffer.write().unwrap() = value;
+        buffer
+    }
+
+    // Вершинний (інстансний) буфер з `data` на один кадр
+    pub fn vertices<T: BufferContents + Copy>(&self, data: &[T]) -> Subbuffer<[T]> {
+        let buffer = self.arena().vertices.allocate_slice(data.len() as u64).unwrap();
+        buffer.write().unwrap().copy_from_slice(data);
+        buffer
+    }
+
+    // Storage буфер з `data` на один кадр
+    pub fn storage<T: BufferContents + Copy>(&self, data: &[T]) -> Subbuffer<[T]> {
+        let buffer = self.arena().storage.allocate_slice(data.len() as u64).unwrap();
+        buffer.write().unwrap().copy_from_slice(data);
+        buffer
+    }
+}
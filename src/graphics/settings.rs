@@ -0,0 +1,244 @@
+use std::sync::Arc;
+
+use vulkano::{
+    device::Device,
+    format::{Format, FormatFeatures},
+    image::SampleCount,
+};
+
+// Варіанти MSAA, які показуються в налаштуваннях редактора
+pub const MSAA_SAMPLE_OPTIONS: [u32; 4] = [1, 2, 4, 8];
+
+/// Формат буфера глибини сцени (варіанти з трафаретом — для проходів, яким він потрібен)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NDepthFormat {
+    D16,        // 16 бит глубины
+    D32,        // 32 бита глубины (float)
+    D24S8,      // 24 бита глубины + 8 бит трафарета
+    D32S8,      // 32 бита глубины (float) + 8 бит трафарета
+}
+
+impl NDepthFormat {
+    pub const ALL: [NDepthFormat; 4] =
+        [NDepthFormat::D16, NDepthFormat::D32, NDepthFormat::D24S8, NDepthFormat::D32S8];
+
+    pub fn format(self) -> Format {
+        match self {
+            NDepthFormat::D16 => Format::D16_UNORM,
+            NDepthFormat::D32 => Format::D32_SFLOAT,
+            NDepthFormat::D24S8 => Format::D24_UNORM_S8_UINT,
+            NDepthFormat::D32S8 => Format::D32_SFLOAT_S8_UINT,
+        }
+    }
+
+    pub fn has_stencil(self) -> bool {
+        matches!(self, NDepthFormat::D24S8 | NDepthFormat::D32S8)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NDepthFormat::D16 => "D16",
+            NDepthFormat::D32 => "D32",
+            NDepthFormat::D24S8 => "D24 S8",
+            NDepthFormat::D32S8 => "D32 S8",
+        }
+    }
+}
+
+/// Режим перегляду вьюпорта. Кожен режим — варіант пайплайнів мешів: інший
+/// RasterizationState та/або фрагментний шейдер, зібраний з define-ом режиму.
+/// Спрайти й тайлові мапи мають лише варіанти FLAT_VIEW_MODES; частинки, текст,
+/// фігури, debug draw, скайбокс і обведення режим ігнорують.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum NViewMode {
+    #[default]
+    Lit,        // Обычный рендеринг
+    Unlit,      // Цвет материала без освещения
+    Wireframe,  // Каркас (линии или барицентрический fallback)
+    Normals,    // Нормали в мировых координатах
+    Uvs,        // Текстурные координаты
+    Depth,      // Линейная глубина от near до far камеры
+    Overdraw,   // Тепловая карта перерисовки (аддитивно, без теста глубины)
+    BaseColor,  // Канал G-буфера: базовый цвет
+    Metallic,   // Канал G-буфера: metallic
+    Roughness,  // Канал G-буфера: roughness
+    Occlusion,  // Канал G-буфера: ambient occlusion
+    Emissive,   // Канал G-буфера: излучение
+}
+
+// Режими, які мають варіанти пайплайнів спрайтів і тайлових мап: каркас квадратів
+// і overdraw. В інших режимах плоскі системи малюються як у Lit.
+pub const FLAT_VIEW_MODES: [NViewMode; 3] =
+    [NViewMode::Lit, NViewMode::Wireframe, NViewMode::Overdraw];
+
+impl NViewMode {
+    pub const ALL: [NViewMode; 12] = [
+        NViewMode::Lit,
+        NViewMode::Unlit,
+        NViewMode::Wireframe,
+        NViewMode::Normals,
+        NViewMode::Uvs,
+        NViewMode::Depth,
+        NViewMode::Overdraw,
+        NViewMode::BaseColor,
+        NViewMode::Metallic,
+        NViewMode::Roughness,
+        NViewMode::Occlusion,
+        NViewMode::Emissive,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NViewMode::Lit => "Lit",
+            NViewMode::Unlit => "Unlit",
+            NViewMode::Wireframe => "Wireframe",
+            NViewMode::Normals => "Normals",
+            NViewMode::Uvs => "UVs",
+            NViewMode::Depth => "Depth",
+            NViewMode::Overdraw => "Overdraw",
+            NViewMode::BaseColor => "G-buffer: base color",
+            NViewMode::Metallic => "G-buffer: metallic",
+            NViewMode::Roughness => "G-buffer: roughness",
+            NViewMode::Occlusion => "G-buffer: occlusion",
+            NViewMode::Emissive => "G-buffer: emissive",
+        }
+    }
+
+    // Define, з яким компілюється фрагментний шейдер варіанта (None — звичайний шейдер)
+    pub fn define(self) -> Option<&'static str> {
+        Some(match self {
+            NViewMode::Lit => return None,
+            NViewMode::Unlit => "VIEW_UNLIT",
+            NViewMode::Wireframe => "VIEW_WIREFRAME",
+            NViewMode::Normals => "VIEW_NORMALS",
+            NViewMode::Uvs => "VIEW_UVS",
+            NViewMode::Depth => "VIEW_DEPTH",
+            NViewMode::Overdraw => "VIEW_OVERDRAW",
+            NViewMode::BaseColor => "VIEW_BASE_COLOR",
+            NViewMode::Metallic => "VIEW_METALLIC",
+            NViewMode::Roughness => "VIEW_ROUGHNESS",
+            NViewMode::Occlusion => "VIEW_OCCLUSION",
+            NViewMode::Emissive => "VIEW_EMISSIVE",
+        })
+    }
+
+    // Режими, яким потрібна лише геометрія: фрагментний шейдер матеріалу замінюється
+    // спільним debug_view.frag. Решта режимів — варіанти шейдера самого матеріалу.
+    pub fn replaces_material(self) -> bool {
+        matches!(
+            self,
+            NViewMode::Wireframe
+                | NViewMode::Normals
+                | NViewMode::Uvs
+                | NViewMode::Depth
+                | NViewMode::Overdraw
+        )
+    }
+
+    // Індекс варіанта плоских систем у FLAT_VIEW_MODES
+    pub fn flat_index(self) -> usize {
+        FLAT_VIEW_MODES.iter().position(|&mode| mode == self).unwrap_or(0)
+    }
+
+    // Відладкові режими показують значення як є, без пост-обробки камери
+    pub fn is_debug(self) -> bool {
+        self != NViewMode::Lit
+    }
+}
+
+/// Обведення виділених сутностей і сутності під курсором
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NOutlineSettings {
+    pub selected_color: [f32; 4],   // Цвет обводки выделенных (линейный RGBA)
+    pub hovered_color: [f32; 4],    // Цвет обводки под курсором (линейный RGBA)
+    pub thickness: u32,             // Толщина в пикселях (1..=MAX_OUTLINE_THICKNESS)
+}
+
+// Найбільша товщина обведення: шейдер перебирає квадрат (2t + 1)^2 пікселів маски
+pub const MAX_OUTLINE_THICKNESS: u32 = 8;
+
+impl Default for NOutlineSettings {
+    fn default() -> Self {
+        NOutlineSettings {
+            selected_color: [1.0, 0.35, 0.02, 1.0],
+            hovered_color: [0.2, 0.55, 1.0, 0.8],
+            thickness: 2,
+        }
+    }
+}
+
+/// Налаштування рендерингу, які можна змінювати під час роботи
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NRenderSettings {
+    pub msaa_samples: u32,      // Запрошенное количество семплов MSAA (1/2/4/8)
+    pub debug_draw: bool,       // Рисовать ли debug линии и подписи
+    pub frustum_culling: bool,  // Отсекать сущности вне frustum камеры (через BVH сцены)
+    pub depth_format: NDepthFormat, // Запрошенный формат буфера глубины
+    pub outline: NOutlineSettings,  // Обводка выделения
+    pub view_mode: NViewMode,       // Режим просмотра вьюпорта
+}
+
+impl Default for NRenderSettings {
+    fn default() -> Self {
+        // Debug draw увімкнено за замовчуванням лише в debug збірках
+        NRenderSettings {
+            msaa_samples: 1,
+            debug_draw: cfg!(debug_assertions),
+            frustum_culling: true,
+            depth_format: NDepthFormat::D16,
+            outline: NOutlineSettings::default(),
+            view_mode: NViewMode::Lit,
+        }
+    }
+}
+
+impl NRenderSettings {
+    // Обмежує запитану кількість семплів можливостями пристрою.
+    // Беремо найбільше значення <= msaa_samples, яке підтримується
+    // одночасно для color та depth attachment-ів фреймбуфера.
+    pub fn msaa_sample_count(&self, device: &Arc<Device>) -> SampleCount {
+        let properties = device.physical_device().properties();
+        let color_counts = properties.framebuffer_color_sample_counts;
+        let depth_counts = properties.framebuffer_depth_sample_counts;
+        // Для форматів з трафаретом семпли мають підтримуватися і для нього
+        let depth_counts = if self.depth_format.has_stencil() {
+            depth_counts & properties.framebuffer_stencil_sample_counts
+        } else {
+            depth_counts
+        };
+
+        [SampleCount::Sample8, SampleCount::Sample4, SampleCount::Sample2]
+            .into_iter()
+            .filter(|samples| *samples as u32 <= self.msaa_samples)
+            .find(|samples| {
+                color_counts.contains_enum(*samples) && depth_counts.contains_enum(*samples)
+            })
+            .unwrap_or(SampleCount::Sample1)
+    }
+
+    // Запитаний формат глибини, якщо пристрій підтримує його як depth/stencil
+    // attachment; інакше перший підтримуваний з того ж класу (з трафаретом чи без),
+    // а в крайньому разі D16, обов'язковий для всіх пристроїв
+    pub fn depth_format(&self, device: &Arc<Device>) -> Format {
+        let supported = |format: NDepthFormat| {
+            device
+                .physical_device()
+                .format_properties(format.format())
+                .is_ok_and(|properties| {
+                    properties
+                        .optimal_tiling_features
+                        .intersects(FormatFeatures::DEPTH_STENCIL_ATTACHMENT)
+                })
+        };
+        let requested = self.depth_format;
+        std::iter::once(requested)
+            .chain(
+                NDepthFormat::ALL
+                    .into_iter()
+                    .filter(|format| format.has_stencil() == requested.has_stencil()),
+            )
+            .find(|format| supported(*format))
+            .unwrap_or(NDepthFormat::D16)
+            .format()
+    }
+}
@@ -0,0 +1,374 @@
+// Завантаження шейдерів з файлів під час роботи.
+// GLSL (.vert/.frag/.comp) та WGSL (.wgsl) компілюються naga в SPIR-V.
+// NShaderLibrary періодично перевіряє час зміни файлів і перекомпілює змінені;
+// draw системи порівнюють version() своїх шейдерів і перебудовують пайплайни.
+// Якщо компіляція не вдалася, лишається останній вдалий модуль, а помилка
+// показується в редакторі.
+// Один GLSL файл можна завантажити кількома варіантами з різними define-ами
+// препроцесора (перестановки шейдера); WGSL define-ів не має.
+// Каталог шейдерів шукається під час роботи (див. shader_dir), а не вшивається
+// у збірку: редактор і гра знаходять шейдери після перенесення в інше місце.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant, SystemTime},
+};
+
+use naga::{
+    back::spv,
+    front::{glsl, wgsl},
+    valid::{Capabilities, ValidationFlags, Validator},
+    AddressSpace, Module, ScalarKind, ShaderStage, TypeInner, VectorSize,
+};
+use vulkano::{
+    device::Device,
+    shader::{EntryPoint, ShaderModule, ShaderModuleCreateInfo},
+};
+
+// Змінна оточення, яка задає каталог шейдерів явно
+const SHADER_DIR_ENV: &str = "NOVA_SHADER_DIR";
+// Каталог шейдерів поруч з виконуваним файлом або в робочому каталозі
+const INSTALLED_SHADER_DIR: &str = "assets/shaders";
+// Каталог шейдерів у дереві проєкту
+const SOURCE_SHADER_DIR: &str = "src/assets/shaders";
+// Як часто перевіряти зміну файлів
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// Каталог шейдерів, перший знайдений з:
+// 1. NOVA_SHADER_DIR;
+// 2. src/assets/shaders у робочому каталозі — запуск з дерева проєкту (cargo run),
+//    правки файлів одразу підхоплює гаряче перезавантаження;
+// 3. assets/shaders поруч з виконуваним файлом — туди їх копіює build.rs,
+//    для поставки гри каталог кладеться поруч з бінарником;
+// 4. assets/shaders у робочому каталозі.
+// Якщо нічого не знайдено — шлях поруч з бінарником, щоб помилки показали, де шукали.
+pub fn shader_dir() -> &'static Path {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        if let Some(dir) = std::env::var_os(SHADER_DIR_ENV) {
+            return PathBuf::from(dir);
+        }
+        let working = std::env::current_dir().ok();
+        let installed = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join(INSTALLED_SHADER_DIR)));
+        let candidates = [
+            working.as_ref().map(|dir| dir.join(SOURCE_SHADER_DIR)),
+            installed.clone(),
+            working.as_ref().map(|dir| dir.join(INSTALLED_SHADER_DIR)),
+        ];
+        candidates
+            .into_iter()
+            .flatten()
+            .find(|dir| dir.is_dir())
+            .or(installed)
+            .unwrap_or_else(|| PathBuf::from(INSTALLED_SHADER_DIR))
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NShaderStage {
+    Vertex,
+    Fragment,
+    Compute,
+}
+
+impl NShaderStage {
+    fn naga(self) -> ShaderStage {
+        match self {
+            NShaderStage::Vertex => ShaderStage::Vertex,
+            NShaderStage::Fragment => ShaderStage::Fragment,
+            NShaderStage::Compute => ShaderStage::Compute,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NShaderHandle(usize);
+
+/// Тип поля uniform блоку, яке можна задати параметром матеріалу
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NParamType {
+    Float,
+    Vec2,
+    Vec3,
+    Vec4,
+}
+
+#[derive(Clone, Debug)]
+pub struct NUniformMember {
+    pub name: String,           // Имя поля в блоке
+    pub offset: u32,            // Смещение в байтах (std140)
+    pub ty: NParamType,         // Тип поля
+}
+
+#[derive(Clone, Debug)]
+pub enum NBindingKind {
+    Uniform { size: u32, members: Vec<NUniformMember> },
+    Texture,
+    Sampler,
+}
+
+/// Ресурс шейдера з іменем, отриманий рефлексією модуля naga
+#[derive(Clone, Debug)]
+pub struct NShaderBinding {
+    pub set: u32,               // Номер набора дескрипторов
+    pub binding: u32,           // Номер привязки
+    pub name: String,           // Имя переменной в шейдере
+    pub kind: NBindingKind,     // Тип ресурса
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NShaderReflection {
+    pub bindings: Vec<NShaderBinding>,
+}
+
+impl NShaderReflection {
+    // Рефлексія vulkano не містить імен, тому імена ресурсів і полів
+    // uniform блоків беремо з модуля naga
+    fn of(module: &Module) -> Self {
+        let param_type = |inner: &TypeInner| match *inner {
+            TypeInner::Scalar(scalar) if scalar.kind == ScalarKind::Float => Some(NParamType::Float),
+            TypeInner::Vector { size, scalar } if scalar.kind == ScalarKind::Float => {
+                Some(match size {
+                    VectorSize::Bi => NParamType::Vec2,
+                    VectorSize::Tri => NParamType::Vec3,
+                    VectorSize::Quad => NParamType::Vec4,
+                })
+            }
+            _ => None,
+        };
+
+        let mut bindings = Vec::new();
+        for (_, variable) in module.global_variables.iter() {
+            let Some(binding) = &variable.binding else { continue };
+            let inner = &module.types[variable.ty].inner;
+            let kind = match (variable.space, inner) {
+                (AddressSpace::Uniform, TypeInner::Struct { members, span }) => {
+                    NBindingKind::Uniform {
+                        size: *span,
+                        members: members
+                            .iter()
+                            .filter_map(|member| {
+                                Some(NUniformMember {
+                                    name: member.name.clone()?,
+                                    offset: member.offset,
+                                    ty: param_type(&module.types[member.ty].inner)?,
+                                })
+                            })
+                            .collect(),
+                    }
+                }
+                (AddressSpace::Handle, TypeInner::Image { .. }) => NBindingKind::Texture,
+                (AddressSpace::Handle, TypeInner::Sampler { .. }) => NBindingKind::Sampler,
+                _ => continue,
+            };
+            bindings.push(NShaderBinding {
+                set: binding.group,
+                binding: binding.binding,
+                name: variable.name.clone().unwrap_or_default(),
+                kind,
+            });
+        }
+        NShaderReflection { bindings }
+    }
+}
+
+struct NShaderSource {
+    path: PathBuf,                          // Файл шейдера
+    stage: NShaderStage,                    // Стадия
+    defines: Vec<String>,                   // Define-ы препроцессора варианта
+    modified: Option<SystemTime>,           // Время изменения при последней компиляции
+    module: Option<NCompiledShader>,        // Последний удачный модуль
+    version: u64,                           // Растёт при каждой удачной компиляции
+    error: Option<String>,                  // Ошибка последней компиляции
+}
+
+struct NCompiledShader {
+    module: Arc<ShaderModule>,              // Модуль Vulkan
+    entry_point: String,                    // Имя точки входа
+    reflection: Arc<NShaderReflection>,     // Ресурсы шейдера с именами
+}
+
+struct NShaderLibraryState {
+    device: Arc<Device>,
+    sources: Vec<NShaderSource>,
+    pipeline_errors: BTreeMap<String, String>, // Ошибки создания пайплайнов по имени системы
+    last_poll: Instant,
+}
+
+/// Бібліотека шейдерів з гарячим перезавантаженням. Клони ділять один стан.
+#[derive(Clone)]
+pub struct NShaderLibrary {
+    state: Arc<Mutex<NShaderLibraryState>>,
+}
+
+impl NShaderLibrary {
+    pub fn new(device: Arc<Device>) -> Self {
+        NShaderLibrary {
+            state: Arc::new(Mutex::new(NShaderLibraryState {
+                device,
+                sources: Vec::new(),
+                pipeline_errors: BTreeMap::new(),
+                last_poll: Instant::now(),
+            })),
+        }
+    }
+
+    // Реєструє файл шейдера (шлях відносно shader_dir()) і одразу компілює його.
+    // Повторний виклик з тим самим файлом повертає той самий handle.
+    pub fn load(&self, path: impl AsRef<Path>, stage: NShaderStage) -> NShaderHandle {
+        self.load_variant(path, stage, &[])
+    }
+
+    // Як load, але файл компілюється з define-ами `defines` (кожен варіант —
+    // окремий модуль зі своєю версією, файл перевіряється на зміни для кожного)
+    pub fn load_variant(
+        &self,
+        path: impl AsRef<Path>,
+        stage: NShaderStage,
+        defines: &[&str],
+    ) -> NShaderHandle {
+        let path = shader_dir().join(path);
+        let defines: Vec<String> = defines.iter().map(|define| define.to_string()).collect();
+        let mut state = self.state.lock().unwrap();
+        if let Some(index) = state.sources.iter().position(|source| {
+            source.path == path && source.stage == stage && source.defines == defines
+        }) {
+            return NShaderHandle(index);
+        }
+        let mut source = NShaderSource {
+            path,
+            stage,
+            defines,
+            modified: None,
+            module: None,
+            version: 0,
+            error: None,
+        };
+        source.recompile(&state.device);
+        state.sources.push(source);
+        NShaderHandle(state.sources.len() - 1)
+    }
+
+    pub fn entry_point(&self, handle: NShaderHandle) -> Option<EntryPoint> {
+        let state = self.state.lock().unwrap();
+        let compiled = state.sources[handle.0].module.as_ref()?;
+        compiled.module.entry_point(&compiled.entry_point)
+    }
+
+    pub fn reflection(&self, handle: NShaderHandle) -> Option<Arc<NShaderReflection>> {
+        let state = self.state.lock().unwrap();
+        state.sources[handle.0].module.as_ref().map(|compiled| compiled.reflection.clone())
+    }
+
+    pub fn version(&self, handle: NShaderHandle) -> u64 {
+        self.state.lock().unwrap().sources[handle.0].version
+    }
+
+    // Помилка створення пайплайну системи (None — помилку виправлено)
+    pub fn set_pipeline_error(&self, system: &str, error: Option<String>) {
+        let mut state = self.state.lock().unwrap();
+        match error {
+            Some(error) => state.pipeline_errors.insert(system.to_owned(), error),
+            None => state.pipeline_errors.remove(system),
+        };
+    }
+
+    // Перекомпілює файли, змінені з останньої перевірки
+    pub fn poll(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.last_poll.elapsed() < POLL_INTERVAL {
+            return;
+        }
+        state.last_poll = Instant::now();
+        let device = state.device.clone();
+        for source in &mut state.sources {
+            let modified = fs::metadata(&source.path).and_then(|meta| meta.modified()).ok();
+            if modified != source.modified {
+                source.recompile(&device);
+            }
+        }
+    }
+
+    // Усі поточні помилки компіляції шейдерів та створення пайплайнів
+    pub fn errors(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        let compile_errors = state.sources.iter().filter_map(|source| {
+            let error = source.error.as_ref()?;
+            let path = source.path.display();
+            Some(if source.defines.is_empty() {
+                format!("{}:\n{}", path, error)
+            } else {
+                format!("{} [{}]:\n{}", path, source.defines.join(", "), error)
+            })
+        });
+        let pipeline_errors =
+            state.pipeline_errors.iter().map(|(system, error)| format!("{}:\n{}", system, error));
+        compile_errors.chain(pipeline_errors).collect()
+    }
+}
+
+impl NShaderSource {
+    fn recompile(&mut self, device: &Arc<Device>) {
+        self.modified = fs::metadata(&self.path).and_then(|meta| meta.modified()).ok();
+        match self.compile(device) {
+            Ok(module) => {
+                self.module = Some(module);
+                self.version += 1;
+                self.error = None;
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn compile(&self, device: &Arc<Device>) -> Result<NCompiledShader, String> {
+        let source = fs::read_to_string(&self.path).map_err(|err| err.to_string())?;
+        let is_wgsl = self.path.extension().is_some_and(|ext| ext == "wgsl");
+        let stage = self.stage.naga();
+
+        let module = if is_wgsl {
+            wgsl::parse_str(&source).map_err(|err| err.emit_to_string(&source))?
+        } else {
+            let mut options = glsl::Options::from(stage);
+            for define in &self.defines {
+                options.defines.insert(define.clone(), String::new());
+            }
+            glsl::Frontend::default()
+                .parse(&options, &source)
+                .map_err(|err| err.emit_to_string(&source))?
+        };
+        let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+            .validate(&module)
+            .map_err(|err| err.emit_to_string(&source))?;
+
+        let entry_point = module
+            .entry_points
+            .iter()
+            .find(|entry| entry.stage == stage)
+            .map(|entry| entry.name.clone())
+            .ok_or_else(|| format!("no {:?} entry point", stage))?;
+
+        // GLSL пишеться під систему координат Vulkan, WGSL — під wgpu (y вгору)
+        let mut options = spv::Options::default();
+        if !is_wgsl {
+            options.flags.remove(spv::WriterFlags::ADJUST_COORDINATE_SPACE);
+        }
+        let words = spv::write_vec(
+            &module,
+            &info,
+            &options,
+            Some(&spv::PipelineOptions { shader_stage: stage, entry_point: entry_point.clone() }),
+        )
+        .map_err(|err| err.to_string())?;
+
+        // SPIR-V згенерований і провалідований naga
+        let reflection = Arc::new(NShaderReflection::of(&module));
+        let module = unsafe { ShaderModule::new(device.clone(), ShaderModuleCreateInfo::new(&words)) }
+            .map_err(|err| err.to_string())?;
+        Ok(NCompiledShader { module, entry_point, reflection })
+    }
+}
@@ -0,0 +1,26 @@
+/// Статистика одного інстансованого батча
+#[derive(Clone, Debug)]
+pub struct NBatchStats {
+    pub system: &'static str,   // Draw система, нарисовавшая батч
+    pub name: String,           // Меш / материал / текстура батча
+    pub instances: u32,         // Количество инстансов в одном draw call
+}
+
+/// Статистика кадру, яку заповнюють draw системи
+#[derive(Clone, Debug, Default)]
+pub struct NRenderStats {
+    pub batches: Vec<NBatchStats>,  // Батчи кадра в порядке отрисовки
+    pub visible: u32,               // Сущностей с границами в frustum камеры
+    pub culled: u32,                // Сущностей, отсечённых frustum culling-ом
+}
+
+impl NRenderStats {
+    // Кожен батч — один draw call
+    pub fn draw_calls(&self) -> usize {
+        self.batches.len()
+    }
+
+    pub fn instances(&self) -> u32 {
+        self.batches.iter().map(|batch| batch.instances).sum()
+    }
+}
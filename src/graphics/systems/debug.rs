@@ -0,0 +1,539 @@
+// Immediate-mode debug draw.
+// Будь-яка система протягом кадру викликає line/ray/aabb/sphere/frustum/axes/grid/text_3d
+// на клоні NDebugDraw. Фігури одразу розкладаються на відрізки і зберігаються до
+// кінця свого часу життя: duration = 0 — лише поточний кадр, інакше — задану кількість секунд.
+// NDebugDrawSystem малює відрізки в прохід сцени двома пайплайнами:
+// з перевіркою глибини та поверх усього. text_3d не має геометрії — підписи
+// проектуються на екран і малюються шрифтом set_font системою екранного тексту
+// (NTextDrawSystem) у проході overlay.
+// Фігури зі стилем highlight також потрапляють у маску обведення (NOutlineMaskSystem).
+
+use std::{
+    f32::consts::TAU,
+    sync::{Arc, Mutex},
+};
+
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, SquareMatrix, Vector3, Vector4};
+use vulkano::{
+    buffer::BufferContents,
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+        CommandBufferInheritanceInfo, CommandBufferUsage, SecondaryAutoCommandBuffer,
+    },
+    device::Queue,
+    image::SampleCount,
+    pipeline::{
+        cache::PipelineCache,
+        graphics::{
+            color_blend::{AttachmentBlend, ColorBlendAttachmentState, ColorBlendState},
+            depth_stencil::{CompareOp, DepthState, DepthStencilState},
+            input_assembly::{InputAssemblyState, PrimitiveTopology},
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::{Vertex, VertexDefinition},
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineLayout, PipelineShaderStageCreateInfo,
+    },
+    render_pass::Subpass,
+    shader::EntryPoint,
+};
+
+use crate::graphics::{
+    font::NFont,
+    graph::{NDrawSystem, NFrameContext},
+    outline::NHighlight,
+    pipeline::NAllocators,
+    shader::{NShaderHandle, NShaderLibrary, NShaderStage},
+    stats::NBatchStats,
+};
+
+// Кількість відрізків кола сфери
+const CIRCLE_SEGMENTS: usize = 32;
+
+/// Колір, режим глибини та час життя debug фігури
+#[derive(Clone, Copy, Debug)]
+pub struct NDebugStyle {
+    pub color: [f32; 4],    // Цвет линий
+    pub depth_test: bool,   // Скрывать ли линии за геометрией сцены
+    pub duration: f32,      // Время жизни в секундах (0 -> один кадр)
+    pub highlight: Option<NHighlight>, // Обводка фигуры (как у выделенных сущностей)
+}
+
+impl NDebugStyle {
+    pub fn new(color: [f32; 4]) -> Self {
+        NDebugStyle { color, depth_test: true, duration: 0.0, highlight: None }
+    }
+
+    // Малювати поверх геометрії сцени
+    pub fn on_top(mut self) -> Self {
+        self.depth_test = false;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn duration(mut self, seconds: f32) -> Self {
+        self.duration = seconds;
+        self
+    }
+
+    // Обводити фігуру в маску обведення
+    pub fn highlight(mut self, highlight: NHighlight) -> Self {
+        self.highlight = Some(highlight);
+        self
+    }
+}
+
+// Вершина debug лінії
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Copy, Debug)]
+pub struct NDebugVertex {
+    #[format(R32G32B32_SFLOAT)]
+    pub position: [f32; 3],     // Позиция в мировых координатах
+    #[format(R32G32B32A32_SFLOAT)]
+    pub color: [f32; 4],        // Цвет
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+struct NDebugPushConstants {
+    view_proj: [[f32; 4]; 4],   // Матрица мир -> clip space
+}
+
+/// Підпис text_3d, спроектований на екран
+#[derive(Clone, Debug)]
+pub struct NDebugLabel {
+    pub uv: [f32; 2],           // Положение в долях размера кадра (0..1)
+    pub text: String,           // Текст
+    pub color: [f32; 4],        // Цвет
+}
+
+// Відрізки однієї фігури
+struct NDebugShape {
+    vertices: Vec<NDebugVertex>,    // Пары вершин (line list)
+    depth_test: bool,               // Режим глубины
+    remaining: f32,                 // Оставшееся время жизни
+    highlight: Option<NHighlight>,  // Обводка фигуры
+}
+
+struct NDebugText {
+    position: Point3<f32>,          // Точка привязки в мире
+    text: String,                   // Текст
+    color: [f32; 4],                // Цвет
+    remaining: f32,                 // Оставшееся время жизни
+}
+
+#[derive(Default)]
+struct NDebugState {
+    enabled: bool,              // Выключенный debug draw игнорирует вызовы
+    shapes: Vec<NDebugShape>,
+    texts: Vec<NDebugText>,
+    font: Option<Arc<NFont>>,   // Шрифт подписей text_3d (без него подписи не рисуются)
+}
+
+/// Дескриптор debug draw. Клони ділять один список фігур.
+#[derive(Clone, Default)]
+pub struct NDebugDraw {
+    state: Arc<Mutex<NDebugState>>,
+}
+
+impl NDebugDraw {
+    // Вимкнення також прибирає вже додані фігури
+    pub fn set_enabled(&self, enabled: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.enabled = enabled;
+        if !enabled {
+            state.shapes.clear();
+            state.texts.clear();
+        }
+    }
+
+    fn push(&self, style: &NDebugStyle, points: impl IntoIterator<Item = Point3<f32>>) {
+        let vertices = points
+            .into_iter()
+            .map(|point| NDebugVertex { position: point.into(), color: style.color })
+            .collect();
+        let mut state = self.state.lock().unwrap();
+        if !state.enabled {
+            return;
+        }
+        state.shapes.push(NDebugShape {
+            vertices,
+            depth_test: style.depth_test,
+            remaining: style.duration,
+            highlight: style.highlight,
+        });
+    }
+
+    pub fn line(&self, from: Point3<f32>, to: Point3<f32>, style: NDebugStyle) {
+        self.push(&style, [from, to]);
+    }
+
+    #[allow(dead_code)]
+    pub fn ray(&self, origin: Point3<f32>, direction: Vector3<f32>, style: NDebugStyle) {
+        self.push(&style, [origin, origin + direction]);
+    }
+
+    pub fn aabb(&self, min: Point3<f32>, max: Point3<f32>, style: NDebugStyle) {
+        let corner = |i: usize| {
+            Point3::new(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            )
+        };
+        self.push(&style, box_edges().flat_map(|(a, b)| [corner(a), corner(b)]));
+    }
+
+    // Три кола у площинах XY, YZ та XZ
+    pub fn sphere(&self, center: Point3<f32>, radius: f32, style: NDebugStyle) {
+        let mut points = Vec::with_capacity(CIRCLE_SEGMENTS * 6);
+        for (u, v) in [
+            (Vector3::unit_x(), Vector3::unit_y()),
+            (Vector3::unit_y(), Vector3::unit_z()),
+            (Vector3::unit_x(), Vector3::unit_z()),
+        ] {
+            let point = |i: usize| {
+                let angle = i as f32 / CIRCLE_SEGMENTS as f32 * TAU;
+                center + (u * angle.cos() + v * angle.sin()) * radius
+            };
+            for i in 0..CIRCLE_SEGMENTS {
+                points.push(point(i));
+                points.push(point(i + 1));
+            }
+        }
+        self.push(&style, points);
+    }
+
+    // Усічена піраміда камери з матрицею view_projection (z кліпу у 0..1)
+    #[allow(dead_code)]
+    pub fn frustum(&self, view_projection: Matrix4<f32>, style: NDebugStyle) {
+        let Some(inverse) = view_projection.invert() else { return };
+        let corner = |i: usize| {
+            let clip = Vector4::new(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { 0.0 } else { 1.0 },
+                1.0,
+            );
+            let world = inverse * clip;
+            Point3::from_vec(world.truncate() / world.w)
+        };
+        self.push(&style, box_edges().flat_map(|(a, b)| [corner(a), corner(b)]));
+    }
+
+    // Осі X/Y/Z трансформу червоним, зеленим і синім (колір стилю ігнорується)
+    pub fn axes(&self, transform: Matrix4<f32>, size: f32, style: NDebugStyle) {
+        let origin = Point3::from_vec(transform.w.truncate());
+        for (axis, color) in [
+            (transform.x.truncate(), [1.0, 0.2, 0.2, 1.0]),
+            (transform.y.truncate(), [0.2, 1.0, 0.2, 1.0]),
+            (transform.z.truncate(), [0.2, 0.4, 1.0, 1.0]),
+        ] {
+            let direction = if axis.magnitude2() > 0.0 { axis.normalize() } else { axis };
+            self.push(&NDebugStyle { color, ..style }, [origin, origin + direction * size]);
+        }
+    }
+
+    // Сітка у площині XZ з центром `center`: `cells` клітинок по кожній осі
+    pub fn grid(&self, center: Point3<f32>, cell_size: f32, cells: u32, style: NDebugStyle) {
+        let half = cells as f32 * cell_size * 0.5;
+        let mut points = Vec::with_capacity((cells as usize + 1) * 4);
+        for i in 0..=cells {
+            let offset = i as f32 * cell_size - half;
+            points.push(center + Vector3::new(offset, 0.0, -half));
+            points.push(center + Vector3::new(offset, 0.0, half));
+            points.push(center + Vector3::new(-half, 0.0, offset));
+            points.push(center + Vector3::new(half, 0.0, offset));
+        }
+        self.push(&style, points);
+    }
+
+    // Текст у точці світу. Завжди малюється поверх сцени (depth_test ігнорується).
+    pub fn text_3d(&self, position: Point3<f32>, text: impl Into<String>, style: NDebugStyle) {
+        let mut state = self.state.lock().unwrap();
+        if !state.enabled {
+            return;
+        }
+        state.texts.push(NDebugText {
+            position,
+            text: text.into(),
+            color: style.color,
+            remaining: style.duration,
+        });
+    }
+
+    // Шрифт підписів text_3d
+    pub fn set_font(&self, font: Option<Arc<NFont>>) {
+        self.state.lock().unwrap().font = font;
+    }
+
+    pub fn font(&self) -> Option<Arc<NFont>> {
+        self.state.lock().unwrap().font.clone()
+    }
+
+    // Проектує підписи text_3d, що перед камерою, у частки розміру кадру
+    pub fn labels(&self, view_projection: Matrix4<f32>) -> Vec<NDebugLabel> {
+        let state = self.state.lock().unwrap();
+        state
+            .texts
+            .iter()
+            .filter_map(|text| {
+                let clip = view_projection * text.position.to_homogeneous();
+                if clip.w <= 0.0 {
+                    return None;
+                }
+                let ndc = clip.truncate() / clip.w;
+                Some(NDebugLabel {
+                    uv: [ndc.x * 0.5 + 0.5, ndc.y * 0.5 + 0.5],
+                    text: text.text.clone(),
+                    color: text.color,
+                })
+            })
+            .collect()
+    }
+
+    // Викликається рендерером після кадру: прибирає фігури з вичерпаним часом життя
+    pub fn end_frame(&self, delta_time: f32) {
+        let mut state = self.state.lock().unwrap();
+        state.shapes.retain_mut(|shape| {
+            shape.remaining -= delta_time;
+            shape.remaining > 0.0
+        });
+        state.texts.retain_mut(|text| {
+            text.remaining -= delta_time;
+            text.remaining > 0.0
+        });
+    }
+
+    // Вершини всіх живих фігур: (з перевіркою глибини, поверх сцени)
+    fn vertices(&self) -> (Vec<NDebugVertex>, Vec<NDebugVertex>) {
+        let state = self.state.lock().unwrap();
+        let (mut depth_tested, mut on_top) = (Vec::new(), Vec::new());
+        for shape in &state.shapes {
+            let target = if shape.depth_test { &mut depth_tested } else { &mut on_top };
+            target.extend_from_slice(&shape.vertices);
+        }
+        (depth_tested, on_top)
+    }
+
+    // Вершини фігур з highlight; колір замінено значенням маски обведення
+    pub fn highlighted_vertices(&self) -> Vec<NDebugVertex> {
+        let state = self.state.lock().unwrap();
+        state
+            .shapes
+            .iter()
+            .filter_map(|shape| Some((shape, shape.highlight?.mask())))
+            .flat_map(|(shape, mask)| {
+                shape.vertices.iter().map(move |vertex| NDebugVertex { color: mask, ..*vertex })
+            })
+            .collect()
+    }
+}
+
+// 12 ребер коробки; вершина i має біти (x, y, z)
+fn box_edges() -> impl Iterator<Item = (usize, usize)> {
+    (0..8usize).flat_map(|i| {
+        [1usize, 2, 4].into_iter().filter(move |bit| i & bit == 0).map(move |bit| (i, i | bit))
+    })
+}
+
+// Система відрисовки debug ліній
+pub struct NDebugDrawSystem {
+    gfx_queue: Arc<Queue>,                  // Очередь графических команд
+    debug_draw: NDebugDraw,                 // Общий список фигур
+    subpass: Subpass,                       // Подпроход рендеринга
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>, // Аллокатор команд
+    pipeline_cache: Arc<PipelineCache>,     // Кэш пайплайнов
+    shaders: NShaderLibrary,                // Библиотека шейдеров (горячая перезагрузка)
+    vs: NShaderHandle,                      // Вершинный шейдер
+    fs: NShaderHandle,                      // Фрагментный шейдер
+    shader_versions: (u64, u64),            // Версии шейдеров, из которых собраны пайплайны
+    depth_tested: Option<Arc<GraphicsPipeline>>, // Пайплайн с тестом глубины
+    on_top: Option<Arc<GraphicsPipeline>>,  // Пайплайн без теста глубины
+}
+
+impl NDebugDrawSystem {
+    pub fn new(
+        gfx_queue: Arc<Queue>,
+        subpass: Subpass,
+        allocators: &NAllocators,
+        debug_draw: NDebugDraw,
+    ) -> Self {
+        let shaders = allocators.shaders.clone();
+        let vs = shaders.load("debug.vert", NShaderStage::Vertex);
+        let fs = shaders.load("debug.frag", NShaderStage::Fragment);
+
+        let mut system = NDebugDrawSystem {
+            gfx_queue,
+            debug_draw,
+            subpass,
+            command_buffer_allocator: allocators.command_buffers.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            shaders,
+            vs,
+            fs,
+            shader_versions: (0, 0),
+            depth_tested: None,
+            on_top: None,
+        };
+        system.rebuild_pipelines_if_changed();
+        system
+    }
+
+    // Перебудовує обидва пайплайни, якщо змінилась версія хоча б одного шейдера.
+    // При помилці лишаються попередні пайплайни, а помилка йде в редактор.
+    fn rebuild_pipelines_if_changed(&mut self) {
+        let versions = (self.shaders.version(self.vs), self.shaders.version(self.fs));
+        if versions == self.shader_versions {
+            return;
+        }
+        self.shader_versions = versions;
+
+        let (Some(vs), Some(fs)) =
+            (self.shaders.entry_point(self.vs), self.shaders.entry_point(self.fs))
+        else {
+            return;
+        };
+        let create = |depth_test| {
+            create_pipeline(
+                &self.gfx_queue,
+                &self.pipeline_cache,
+                &self.subpass,
+                vs.clone(),
+                fs.clone(),
+                depth_test,
+            )
+        };
+        match create(true).and_then(|depth_tested| Ok((depth_tested, create(false)?))) {
+            Ok((depth_tested, on_top)) => {
+                self.depth_tested = Some(depth_tested);
+                self.on_top = Some(on_top);
+                self.shaders.set_pipeline_error(self.name(), None);
+            }
+            Err(error) => self.shaders.set_pipeline_error(self.name(), Some(error)),
+        }
+    }
+}
+
+impl NDrawSystem for NDebugDrawSystem {
+    // draw: спершу лінії з перевіркою глибини, потім лінії поверх сцени
+    fn draw(&mut self, frame: &NFrameContext) -> Vec<Arc<SecondaryAutoCommandBuffer>> {
+        self.rebuild_pipelines_if_changed();
+        let (depth_tested, on_top) = self.debug_draw.vertices();
+        let (Some(depth_tested_pipeline), Some(on_top_pipeline)) =
+            (self.depth_tested.clone(), self.on_top.clone())
+        else {
+            return Vec::new();
+        };
+
+        let viewport_dimensions = frame.viewport_dimensions;
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.clone(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(self.subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        builder
+            .set_viewport(
+                0,
+                [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [viewport_dimensions[0] as f32, viewport_dimensions[1] as f32],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap();
+
+        for (pipeline, vertices, name) in [
+            (depth_tested_pipeline, depth_tested, "depth tested"),
+            (on_top_pipeline, on_top, "on top"),
+        ] {
+            if vertices.is_empty() {
+                continue;
+            }
+            let vertex_buffer = frame.ring.vertices(&vertices);
+
+            builder
+                .bind_pipeline_graphics(pipeline.clone())
+                .unwrap()
+                .push_constants(pipeline.layout().clone(), 0, NDebugPushConstants {
+                    view_proj: frame.world_to_framebuffer.into(),
+                })
+                .unwrap()
+                .bind_vertex_buffers(0, vertex_buffer)
+                .unwrap();
+            unsafe {
+                builder.draw(vertices.len() as u32, 1, 0, 0).unwrap();
+            }
+
+            frame.stats.lock().unwrap().batches.push(NBatchStats {
+                system: "debug",
+                name: format!("lines ({})", name),
+                instances: 1,
+            });
+        }
+        vec![builder.build().unwrap()]
+    }
+}
+
+fn create_pipeline(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    subpass: &Subpass,
+    vs: EntryPoint,
+    fs: EntryPoint,
+    depth_test: bool,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    let device = gfx_queue.device().clone();
+    let vertex_input_state =
+        NDebugVertex::per_vertex().definition(&vs).map_err(|err| err.to_string())?;
+
+    let stages = [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
+
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    // Лінії перевіряють глибину, але не пишуть її, щоб не перекривати одна одну
+    let depth = depth_test.then_some(DepthState { write_enable: false, compare_op: CompareOp::LessOrEqual });
+
+    GraphicsPipeline::new(device, Some(pipeline_cache.clone()), GraphicsPipelineCreateInfo {
+        stages: stages.into_iter().collect(),
+        vertex_input_state: Some(vertex_input_state),
+        input_assembly_state: Some(InputAssemblyState {
+            topology: PrimitiveTopology::LineList,
+            ..Default::default()
+        }),
+        viewport_state: Some(ViewportState::default()),
+        rasterization_state: Some(RasterizationState::default()),
+        multisample_state: Some(MultisampleState {
+            rasterization_samples: subpass.num_samples().unwrap_or(SampleCount::Sample1),
+            ..Default::default()
+        }),
+        color_blend_state: Some(ColorBlendState::with_attachment_states(
+            subpass.num_color_attachments(),
+            ColorBlendAttachmentState {
+                blend: Some(AttachmentBlend::alpha()),
+                ..Default::default()
+            },
+        )),
+        depth_stencil_state: Some(DepthStencilState { depth, ..Default::default() }),
+        dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+        subpass: Some(subpass.clone().into()),
+        ..GraphicsPipelineCreateInfo::layout(layout)
+    })
+    .map_err(|err| err.to_string())
+}
@@ -0,0 +1,893 @@
+// Матеріальна система мешів.
+// Кожен матеріал посилається на пару шейдерів; для кожної пари будується
+// пайплайн, а layout набору дескрипторів матеріалу береться з рефлексії
+// шейдерів (PipelineDescriptorSetLayoutCreateInfo::from_stages). Набори
+// дескрипторів матеріалів кешуються і перебудовуються лише після перезбирання
+// пайплайну (гаряче перезавантаження шейдерів змінює layout).
+// Draw call-и сортуються за пайплайном, потім за матеріалом, потім за мешем,
+// щоб мінімізувати перемикання пайплайнів і наборів дескрипторів.
+// Шейдери з набором ENVIRONMENT_SET (PBR) отримують запечені карти оточення сцени.
+// Режим перегляду вьюпорта (NViewMode) обирає варіант пайплайну: пайплайни
+// кешуються за парою (шейдери матеріалу, режим) і збираються при першому
+// використанні, тож режим перемикається без перебудови системи.
+// Скіновані меші сутностей з машиною станів анімації малюються SKINNED варіантом
+// вершинного шейдера: палітри суглобів інстансів батча лягають підряд у storage буфер
+// кадру (набір SKIN_SET), а зміщення палітри інстанса — окремим інстансним атрибутом.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use cgmath::Matrix4;
+use vulkano::{
+    buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+        CommandBufferInheritanceInfo, CommandBufferUsage, SecondaryAutoCommandBuffer,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::Queue,
+    image::{
+        sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
+        SampleCount,
+    },
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    pipeline::{
+        cache::PipelineCache,
+        graphics::{
+            color_blend::{AttachmentBlend, ColorBlendAttachmentState, ColorBlendState},
+            depth_stencil::{DepthState, DepthStencilState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::{CullMode, PolygonMode, RasterizationState},
+            vertex_input::{Vertex, VertexDefinition},
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::Subpass,
+    shader::EntryPoint,
+};
+
+use crate::graphics::{
+    asset::NAssetId,
+    environment::{
+        NEnvironment, NEnvironmentCache, NEnvironmentMaps, ENVIRONMENT_BRDF_LUT,
+        ENVIRONMENT_IRRADIANCE, ENVIRONMENT_SAMPLER, ENVIRONMENT_SET, ENVIRONMENT_SPECULAR,
+    },
+    graph::{NDrawSystem, NFrameContext},
+    material::{NMaterial, NMaterialShader, MATERIAL_SET},
+    mesh::{NMesh, NMeshVertex, NSkinVertex, SKIN_SET},
+    pipeline::NAllocators,
+    settings::NViewMode,
+    shader::{NBindingKind, NShaderBinding, NShaderHandle, NShaderLibrary, NShaderStage},
+    stats::NBatchStats,
+    texture::{NTexture, NTextureCache},
+};
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+struct NMeshPushConstants {
+    view_proj: [[f32; 4]; 4],   // Матрица мир -> clip space
+    camera_position: [f32; 4],  // Позиция камеры в мире
+    environment: [f32; 4],      // x — интенсивность окружения, y — последний mip specular,
+                                // z, w — near и far камеры (режим Depth)
+}
+
+// Дані одного інстанса: матриця local -> world по стовпцях і колір
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Copy)]
+pub struct NMeshInstance {
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_x: [f32; 4],      // Столбцы матрицы модели
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_y: [f32; 4],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_z: [f32; 4],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_w: [f32; 4],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub color: [f32; 4],        // Цвет инстанса
+}
+
+impl NMeshInstance {
+    pub fn new(model: Matrix4<f32>, color: [f32; 4]) -> Self {
+        NMeshInstance {
+            model_x: model.x.into(),
+            model_y: model.y.into(),
+            model_z: model.z.into(),
+            model_w: model.w.into(),
+            color,
+        }
+    }
+}
+
+// Зміщення палітри суглобів інстанса в storage буфері батча
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Copy)]
+pub struct NSkinInstance {
+    #[format(R32_UINT)]
+    pub joint_offset: u32,      // Первая матрица палитры инстанса
+}
+
+// Вершина каркаса без PolygonMode::Line: кут розгорнутого трикутника
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Copy)]
+struct NWireVertex {
+    #[format(R32G32B32_SFLOAT)]
+    position: [f32; 3],         // Позиция вершины
+    #[format(R32G32B32_SFLOAT)]
+    barycentric: [f32; 3],      // Барицентрическая координата угла треугольника
+}
+
+// Буфери меша на GPU (кешуються draw системами за id меша)
+pub struct NGpuMesh {
+    pub vertices: Subbuffer<[NMeshVertex]>, // Вершины
+    pub indices: Subbuffer<[u32]>,          // Индексы
+    pub skin: Option<Subbuffer<[NSkinVertex]>>, // Привязка к суставам (скинованный меш)
+}
+
+impl NGpuMesh {
+    pub fn new(memory_allocator: &Arc<StandardMemoryAllocator>, mesh: &NMesh) -> Self {
+        let allocation_info = || AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        };
+        let vertices = Buffer::from_iter(
+            memory_allocator.clone(),
+            BufferCreateInfo { usage: BufferUsage::VERTEX_BUFFER, ..Default::default() },
+            allocation_info(),
+            mesh.vertices.iter().copied(),
+        )
+        .unwrap();
+        let indices = Buffer::from_iter(
+            memory_allocator.clone(),
+            BufferCreateInfo { usage: BufferUsage::INDEX_BUFFER, ..Default::default() },
+            allocation_info(),
+            mesh.indices.iter().copied(),
+        )
+        .unwrap();
+        let skin = mesh.is_skinned().then(|| {
+            Buffer::from_iter(
+                memory_allocator.clone(),
+                BufferCreateInfo { usage: BufferUsage::VERTEX_BUFFER, ..Default::default() },
+                allocation_info(),
+                mesh.skin.iter().copied(),
+            )
+            .unwrap()
+        });
+        NGpuMesh { vertices, indices, skin }
+    }
+}
+
+// Каркас меша: трикутники, розгорнуті в окремі вершини з барицентричними координатами,
+// і так само розгорнута прив'язка до суглобів
+#[derive(Clone)]
+struct NWireMesh {
+    vertices: Subbuffer<[NWireVertex]>,         // Вершины
+    skin: Option<Subbuffer<[NSkinVertex]>>,     // Привязка к суставам (скинованный меш)
+}
+
+impl NWireMesh {
+    fn new(memory_allocator: &Arc<StandardMemoryAllocator>, mesh: &NMesh) -> Self {
+        const CORNERS: [[f32; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let vertices = mesh.indices.iter().enumerate().map(|(corner, &index)| NWireVertex {
+            position: mesh.vertices[index as usize].position,
+            barycentric: CORNERS[corner % 3],
+        });
+        let skin = mesh.is_skinned().then(|| {
+            let skin = mesh.indices.iter().map(|&index| mesh.skin[index as usize]);
+            vertex_buffer(memory_allocator, skin)
+        });
+        NWireMesh { vertices: vertex_buffer(memory_allocator, vertices), skin }
+    }
+}
+
+fn vertex_buffer<T: BufferContents>(
+    memory_allocator: &Arc<StandardMemoryAllocator>,
+    data: impl ExactSizeIterator<Item = T>,
+) -> Subbuffer<[T]> {
+    Buffer::from_iter(
+        memory_allocator.clone(),
+        BufferCreateInfo { usage: BufferUsage::VERTEX_BUFFER, ..Default::default() },
+        AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        },
+        data,
+    )
+    .unwrap()
+}
+
+// Ключ пайплайну: шейдери матеріалу, режим перегляду і скінінг
+type NPipelineKey = (NMaterialShader, NViewMode, bool);
+
+// Пайплайн пари шейдерів матеріалу в одному режимі перегляду
+struct NMaterialPipeline {
+    vs: NShaderHandle,                      // Вершинный шейдер
+    fs: NShaderHandle,                      // Фрагментный шейдер
+    versions: (u64, u64),                   // Версии шейдеров, из которых собран пайплайн
+    pipeline: Option<Arc<GraphicsPipeline>>,// Последний удачно собранный пайплайн
+    bindings: Vec<NShaderBinding>,          // Ресурсы набора MATERIAL_SET с именами
+    environment_bindings: Vec<NShaderBinding>, // Ресурсы набора ENVIRONMENT_SET
+    generation: u64,                        // Растёт при пересборке (инвалидирует наборы)
+    environment_set: Option<(Option<NAssetId>, Arc<DescriptorSet>)>, // Набор окружения сцены
+}
+
+// Закешований набір дескрипторів матеріалу
+struct NMaterialSet {
+    generation: u64,                        // Поколение пайплайна, под которое создан набор
+    set: Option<Arc<DescriptorSet>>,        // None -> у шейдера нет ресурсов материала
+}
+
+// Батч: один меш з одним матеріалом
+struct NMeshBatch {
+    material: Arc<NMaterial>,
+    mesh: Arc<NMesh>,
+    instances: Vec<NMeshInstance>,
+    palette: Vec<[[f32; 4]; 4]>,    // Палитры суставов инстансов подряд (скиннинг)
+    skin: Vec<NSkinInstance>,       // Смещения палитр инстансов (скиннинг)
+}
+
+// Геометрія батча: індексований меш або розгорнуті трикутники каркаса
+enum NMeshGeometry {
+    Indexed { vertices: Subbuffer<[NMeshVertex]>, indices: Subbuffer<[u32]> },
+    Barycentric(Subbuffer<[NWireVertex]>),
+}
+
+// Скінінг батча: прив'язка вершин, набір палітри та зміщення палітр інстансів
+struct NMeshSkin {
+    vertices: Subbuffer<[NSkinVertex]>, // Привязка вершин к суставам
+    set: Arc<DescriptorSet>,            // Набор SKIN_SET с палитрой батча
+    instances: Vec<NSkinInstance>,      // Смещения палитр
+}
+
+// Батч, готовий до запису на воркері: всі ресурси вже створені
+struct NMeshDraw {
+    pipeline: Arc<GraphicsPipeline>,                // Пайплайн шейдеров материала
+    environment_set: Option<Arc<DescriptorSet>>,    // Набор окружения (PBR)
+    material: NAssetId,                             // Материал (для пропуска перепривязки)
+    material_set: Option<Arc<DescriptorSet>>,       // Набор материала
+    geometry: NMeshGeometry,                        // Буферы меша
+    skin: Option<NMeshSkin>,                        // Скиннинг (если есть)
+    name: String,                                   // Имя батча для статистики
+    instances: Vec<NMeshInstance>,                  // Инстансы
+}
+
+// Мінімум батчів у чанку: менший чанк не окупає окремий вторинний буфер
+const MESH_CHUNK_BATCHES: usize = 16;
+
+// Система інстансованої відрисовки мешів сутностей з матеріалами
+pub struct NMeshDrawSystem {
+    gfx_queue: Arc<Queue>,                  // Очередь графических команд
+    subpass: Subpass,                       // Подпроход рендеринга
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>, // Аллокатор команд
+    descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>, // Аллокатор дескрипторов
+    memory_allocator: Arc<StandardMemoryAllocator>, // Аллокатор памяти для буферов
+    pipeline_cache: Arc<PipelineCache>,     // Кэш пайплайнов
+    shaders: NShaderLibrary,                // Библиотека шейдеров
+    meshes: HashMap<NAssetId, NGpuMesh>,    // Загруженные меши по id ассета
+    wire_meshes: HashMap<NAssetId, NWireMesh>, // Каркасы без PolygonMode::Line
+    pipelines: BTreeMap<NPipelineKey, NMaterialPipeline>, // Пайплайны по шейдерам и варианту
+    material_sets: HashMap<(NAssetId, NPipelineKey), NMaterialSet>, // Наборы материалов
+    textures: NTextureCache,                // Загруженные текстуры
+    white: Arc<NTexture>,                   // Текстура для незаданных параметров
+    sampler: Arc<Sampler>,                  // Сэмплер для всех sampler-ов материалов
+    environments: NEnvironmentCache,        // Запечённые окружения для IBL
+    wireframe_lines: bool,                  // Каркас через PolygonMode::Line (fill_mode_non_solid)
+}
+
+impl NMeshDrawSystem {
+    pub fn new(
+        gfx_queue: Arc<Queue>,
+        subpass: Subpass,
+        allocators: &NAllocators,
+        environments: NEnvironmentCache,
+    ) -> Self {
+        let sampler = Sampler::new(gfx_queue.device().clone(), SamplerCreateInfo {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            address_mode: [SamplerAddressMode::Repeat; 3],
+            ..Default::default()
+        })
+        .unwrap();
+        let wireframe_lines = gfx_queue.device().enabled_features().fill_mode_non_solid;
+
+        NMeshDrawSystem {
+            gfx_queue: gfx_queue.clone(),
+            subpass,
+            command_buffer_allocator: allocators.command_buffers.clone(),
+            descriptor_set_allocator: allocators.descriptor_sets.clone(),
+            memory_allocator: allocators.memory.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            shaders: allocators.shaders.clone(),
+            meshes: HashMap::new(),
+            wire_meshes: HashMap::new(),
+            pipelines: BTreeMap::new(),
+            material_sets: HashMap::new(),
+            textures: NTextureCache::new(gfx_queue, allocators),
+            white: Arc::new(NTexture::white()),
+            sampler,
+            environments,
+            wireframe_lines,
+        }
+    }
+
+    // Каркас малюється розгорнутими трикутниками з барицентричними координатами
+    fn barycentric(&self, mode: NViewMode) -> bool {
+        mode == NViewMode::Wireframe && !self.wireframe_lines
+    }
+
+    // Створює пайплайн для шейдерів матеріалу в режимі `mode` або перебудовує його,
+    // якщо шейдери змінилися. При помилці лишається попередній пайплайн.
+    // Режими, що замінюють матеріал, беруть debug_view.frag (а барицентричний
+    // каркас — ще й wireframe.vert); решта — варіант фрагментного шейдера матеріалу.
+    // Скіновані меші беруть SKINNED варіант вершинного шейдера.
+    fn update_pipeline(&mut self, key: &NPipelineKey) {
+        let (shader, mode, skinned) = key;
+        let barycentric = self.barycentric(*mode);
+        let shaders = &self.shaders;
+        let entry = self.pipelines.entry(key.clone()).or_insert_with(|| {
+            let vertex_defines: &[&str] = if *skinned { &["SKINNED"] } else { &[] };
+            let mut defines: Vec<&str> = mode.define().into_iter().collect();
+            let vertex = if barycentric {
+                defines.push("WIREFRAME_BARYCENTRIC");
+                "wireframe.vert"
+            } else {
+                shader.vertex.as_str()
+            };
+            let fragment =
+                if mode.replaces_material() { "debug_view.frag" } else { shader.fragment.as_str() };
+            NMaterialPipeline {
+                vs: shaders.load_variant(vertex, NShaderStage::Vertex, vertex_defines),
+                fs: shaders.load_variant(fragment, NShaderStage::Fragment, &defines),
+                versions: (0, 0),
+                pipeline: None,
+                bindings: Vec::new(),
+                environment_bindings: Vec::new(),
+                generation: 0,
+                environment_set: None,
+            }
+        });
+
+        let versions = (shaders.version(entry.vs), shaders.version(entry.fs));
+        if versions == entry.versions {
+            return;
+        }
+        entry.versions = versions;
+
+        let (Some(vs), Some(fs)) = (shaders.entry_point(entry.vs), shaders.entry_point(entry.fs))
+        else {
+            return;
+        };
+        let skinning = if *skinned { ", skinned" } else { "" };
+        let name =
+            format!("{} + {} ({}{})", shader.vertex, shader.fragment, mode.label(), skinning);
+        let variant = NPipelineVariant {
+            mode: *mode,
+            barycentric,
+            lines: self.wireframe_lines,
+            skinned: *skinned,
+        };
+        let pipeline =
+            create_pipeline(&self.gfx_queue, &self.pipeline_cache, &self.subpass, vs, fs, variant);
+        match pipeline {
+            Ok(pipeline) => {
+                // Ресурси набору обох стадій (одна прив'язка може бути в обох).
+                // Лише ті, що лишились у layout: варіант шейдера з define-ом може
+                // не використовувати частину оголошених ресурсів.
+                let handles = [entry.vs, entry.fs];
+                let set_layouts = pipeline.layout().set_layouts();
+                let in_layout = |set: u32, binding: u32| {
+                    set_layouts
+                        .get(set as usize)
+                        .is_some_and(|layout| layout.bindings().contains_key(&binding))
+                };
+                let set_bindings = |set: u32| {
+                    let mut bindings: Vec<NShaderBinding> = Vec::new();
+                    for handle in handles {
+                        let Some(reflection) = shaders.reflection(handle) else { continue };
+                        for binding in &reflection.bindings {
+                            if binding.set == set
+                                && in_layout(set, binding.binding)
+                                && !bindings.iter().any(|known| known.binding == binding.binding)
+                            {
+                                bindings.push(binding.clone());
+                            }
+                        }
+                    }
+                    bindings
+                };
+                entry.bindings = set_bindings(MATERIAL_SET);
+                entry.environment_bindings = set_bindings(ENVIRONMENT_SET);
+                entry.pipeline = Some(pipeline);
+                entry.generation += 1;
+                entry.environment_set = None;
+                shaders.set_pipeline_error(&name, None);
+            }
+            Err(error) => shaders.set_pipeline_error(&name, Some(error)),
+        }
+    }
+
+    // Набір дескрипторів матеріалу з кешу або новий, якщо пайплайн перезібрано
+    fn material_set(
+        &mut self,
+        material: &NMaterial,
+        key: &NPipelineKey,
+    ) -> Option<Arc<DescriptorSet>> {
+        let pipeline = self.pipelines.get(key)?;
+        let graphics_pipeline = pipeline.pipeline.as_ref()?;
+        if let Some(cached) = self.material_sets.get(&(material.id(), key.clone())) {
+            if cached.generation == pipeline.generation {
+                return cached.set.clone();
+            }
+        }
+
+        let set = match graphics_pipeline.layout().set_layouts().get(MATERIAL_SET as usize) {
+            None => None,
+            Some(layout) => {
+                let mut writes = Vec::with_capacity(pipeline.bindings.len());
+                for binding in &pipeline.bindings {
+                    writes.push(match &binding.kind {
+                        NBindingKind::Uniform { .. } => {
+                            let buffer = Buffer::from_iter(
+                                self.memory_allocator.clone(),
+                                BufferCreateInfo {
+                                    usage: BufferUsage::UNIFORM_BUFFER,
+                                    ..Default::default()
+                                },
+                                AllocationCreateInfo {
+                                    memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                                        | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                                    ..Default::default()
+                                },
+                                material.uniform_data(&binding.kind),
+                            )
+                            .unwrap();
+                            WriteDescriptorSet::buffer(binding.binding, buffer)
+                        }
+                        NBindingKind::Texture => {
+                            let texture = material.texture(&binding.name).unwrap_or(&self.white);
+                            WriteDescriptorSet::image_view(binding.binding, self.textures.get(texture))
+                        }
+                        NBindingKind::Sampler => {
+                            WriteDescriptorSet::sampler(binding.binding, self.sampler.clone())
+                        }
+                    });
+                }
+                // Ключ помилки — id матеріалу: імена матеріалів можуть збігатися
+                let source = format!("Material #{}", material.id());
+                match DescriptorSet::new(
+                    self.descriptor_set_allocator.clone(),
+                    layout.clone(),
+                    writes,
+                    [],
+                ) {
+                    Ok(set) => {
+                        self.shaders.set_pipeline_error(&source, None);
+                        Some(set)
+                    }
+                    Err(error) => {
+                        let error = format!("{}: {}", material.name, error);
+                        self.shaders.set_pipeline_error(&source, Some(error));
+                        None
+                    }
+                }
+            }
+        };
+
+        self.material_sets.insert(
+            (material.id(), key.clone()),
+            NMaterialSet { generation: pipeline.generation, set: set.clone() },
+        );
+        set
+    }
+
+    // Набір дескрипторів оточення для пайплайну `key`
+    // (None — шейдер не використовує оточення)
+    fn environment_set(
+        &mut self,
+        key: &NPipelineKey,
+        environment: Option<&NEnvironment>,
+        maps: &NEnvironmentMaps,
+    ) -> Option<Arc<DescriptorSet>> {
+        let pipeline = self.pipelines.get_mut(key)?;
+        if pipeline.environment_bindings.is_empty() {
+            return None;
+        }
+        let environment_id = environment.map(|environment| environment.id());
+        if let Some((id, set)) = &pipeline.environment_set {
+            if *id == environment_id {
+                return Some(set.clone());
+            }
+        }
+
+        let layout =
+            pipeline.pipeline.as_ref()?.layout().set_layouts()[ENVIRONMENT_SET as usize].clone();
+        let writes = pipeline.environment_bindings.iter().filter_map(|binding| {
+            Some(match binding.binding {
+                ENVIRONMENT_IRRADIANCE => {
+                    WriteDescriptorSet::image_view(binding.binding, maps.irradiance.clone())
+                }
+                ENVIRONMENT_SPECULAR => {
+                    WriteDescriptorSet::image_view(binding.binding, maps.specular.clone())
+                }
+                ENVIRONMENT_BRDF_LUT => {
+                    WriteDescriptorSet::image_view(binding.binding, maps.brdf_lut.clone())
+                }
+                ENVIRONMENT_SAMPLER => {
+                    WriteDescriptorSet::sampler(binding.binding, maps.sampler.clone())
+                }
+                _ => return None,
+            })
+        });
+        let set = DescriptorSet::new(self.descriptor_set_allocator.clone(), layout, writes, [])
+            .unwrap();
+        pipeline.environment_set = Some((environment_id, set.clone()));
+        Some(set)
+    }
+}
+
+impl NDrawSystem for NMeshDrawSystem {
+    // draw:
+    // - групує видимі сутності з мешем за (шейдер, матеріал, меш) — BTreeMap дає
+    //   порядок відрисовки з мінімумом перемикань пайплайнів і матеріалів,
+    // - готує пайплайни, набори дескрипторів і буфери мешів (кеші системи),
+    // - ділить підготовлені батчі на чанки й записує їх паралельно на воркерах;
+    //   кожен батч малюється одним draw_indexed з instance_count = кількості сутностей
+    //   (барицентричний каркас — одним draw без індексів).
+    // Скіновані сутності йдуть окремими від звичайних батчами з палітрами суглобів.
+    fn draw(&mut self, frame: &NFrameContext) -> Vec<Arc<SecondaryAutoCommandBuffer>> {
+        let mode = frame.settings.view_mode;
+        let mut batches: BTreeMap<(NMaterialShader, bool, NAssetId, NAssetId), NMeshBatch> =
+            BTreeMap::new();
+        for entity in frame.visible_entities() {
+            let Some(renderer) = &entity.mesh else { continue };
+            let palette = entity
+                .animation
+                .as_ref()
+                .map(|animation| animation.palette())
+                .filter(|palette| renderer.mesh.is_skinned() && !palette.is_empty());
+            let key = (
+                renderer.material.shader.clone(),
+                palette.is_some(),
+                renderer.material.id(),
+                renderer.mesh.id(),
+            );
+            let batch = batches.entry(key).or_insert_with(|| NMeshBatch {
+                material: renderer.material.clone(),
+                mesh: renderer.mesh.clone(),
+                instances: Vec::new(),
+                palette: Vec::new(),
+                skin: Vec::new(),
+            });
+            batch.instances.push(NMeshInstance::new(entity.transform.matrix(), renderer.color));
+            if let Some(palette) = palette {
+                batch.skin.push(NSkinInstance { joint_offset: batch.palette.len() as u32 });
+                batch.palette.extend_from_slice(palette);
+            }
+        }
+
+        let keys: Vec<(NMaterialShader, bool)> =
+            batches.keys().map(|key| (key.0.clone(), key.1)).collect();
+        for (shader, skinned) in keys {
+            self.update_pipeline(&(shader, mode, skinned));
+        }
+
+        let environment = frame.scene.environment.as_deref();
+        let environment_maps = self.environments.get(environment);
+        let camera = &frame.scene.camera;
+        let camera_position = camera.position;
+        let push_constants = NMeshPushConstants {
+            view_proj: frame.world_to_framebuffer.into(),
+            camera_position: [camera_position.x, camera_position.y, camera_position.z, 1.0],
+            environment: [
+                self.environments.intensity(environment),
+                (environment_maps.specular_mips - 1) as f32,
+                camera.near,
+                camera.far,
+            ],
+        };
+
+        let mut draws = Vec::with_capacity(batches.len());
+        let barycentric = self.barycentric(mode);
+        for ((shader, skinned, material_id, _), batch) in batches {
+            let key = (shader, mode, skinned);
+            let Some(pipeline) = self.pipelines[&key].pipeline.clone() else { continue };
+            let environment_set = self.environment_set(&key, environment, &environment_maps);
+            let material_set = self.material_set(&batch.material, &key);
+            // Layout чекає набір матеріалу, а його не вдалося створити: батч пропускається,
+            // помилка вже в редакторі
+            let needs_material = pipeline.layout().set_layouts().len() > MATERIAL_SET as usize;
+            if needs_material && material_set.is_none() {
+                continue;
+            }
+            let mesh = &batch.mesh;
+            let (geometry, skin_vertices) = if barycentric {
+                let wire_mesh = self
+                    .wire_meshes
+                    .entry(mesh.id())
+                    .or_insert_with(|| NWireMesh::new(&self.memory_allocator, mesh));
+                (NMeshGeometry::Barycentric(wire_mesh.vertices.clone()), wire_mesh.skin.clone())
+            } else {
+                let gpu_mesh = self
+                    .meshes
+                    .entry(mesh.id())
+                    .or_insert_with(|| NGpuMesh::new(&self.memory_allocator, mesh));
+                let geometry = NMeshGeometry::Indexed {
+                    vertices: gpu_mesh.vertices.clone(),
+                    indices: gpu_mesh.indices.clone(),
+                };
+                (geometry, gpu_mesh.skin.clone())
+            };
+            let skin = match (skinned, skin_vertices) {
+                (false, _) => None,
+                (true, Some(vertices)) => {
+                    let Some(layout) = pipeline.layout().set_layouts().get(SKIN_SET as usize)
+                    else {
+                        continue;
+                    };
+                    let palette = frame.ring.storage(&batch.palette);
+                    let set = DescriptorSet::new(
+                        self.descriptor_set_allocator.clone(),
+                        layout.clone(),
+                        [WriteDescriptorSet::buffer(0, palette)],
+                        [],
+                    )
+                    .unwrap();
+                    Some(NMeshSkin { vertices, set, instances: batch.skin })
+                }
+                (true, None) => continue,
+            };
+            draws.push(NMeshDraw {
+                pipeline,
+                environment_set,
+                material: material_id,
+                material_set,
+                geometry,
+                skin,
+                name: format!("{} / {}", mesh.name, batch.material.name),
+                instances: batch.instances,
+            });
+        }
+
+        let chunks = frame.workers.map_chunks(&draws, MESH_CHUNK_BATCHES, |chunk| {
+            self.record_chunk(frame, chunk, push_constants)
+        });
+        let mut stats = frame.stats.lock().unwrap();
+        chunks
+            .into_iter()
+            .map(|(command_buffer, batches)| {
+                stats.batches.extend(batches);
+                command_buffer
+            })
+            .collect()
+    }
+}
+
+impl NMeshDrawSystem {
+    // Записує чанк підготовлених батчів у власний вторинний буфер (на воркері).
+    // Пайплайн і набори дескрипторів перев'язуються лише при зміні.
+    fn record_chunk(
+        &self,
+        frame: &NFrameContext,
+        draws: &[NMeshDraw],
+        push_constants: NMeshPushConstants,
+    ) -> (Arc<SecondaryAutoCommandBuffer>, Vec<NBatchStats>) {
+        let viewport_dimensions = frame.viewport_dimensions;
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.clone(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(self.subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        builder
+            .set_viewport(
+                0,
+                [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [viewport_dimensions[0] as f32, viewport_dimensions[1] as f32],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap();
+
+        let mut stats = Vec::with_capacity(draws.len());
+        let mut bound_pipeline: Option<&Arc<GraphicsPipeline>> = None;
+        let mut bound_material: Option<NAssetId> = None;
+        for draw in draws {
+            let pipeline = &draw.pipeline;
+            if !bound_pipeline.is_some_and(|bound| Arc::ptr_eq(bound, pipeline)) {
+                builder
+                    .bind_pipeline_graphics(pipeline.clone())
+                    .unwrap()
+                    .push_constants(pipeline.layout().clone(), 0, push_constants)
+                    .unwrap();
+                if let Some(set) = draw.environment_set.clone() {
+                    builder
+                        .bind_descriptor_sets(
+                            PipelineBindPoint::Graphics,
+                            pipeline.layout().clone(),
+                            ENVIRONMENT_SET,
+                            set,
+                        )
+                        .unwrap();
+                }
+                bound_pipeline = Some(pipeline);
+                bound_material = None;
+            }
+            if bound_material != Some(draw.material) {
+                if let Some(set) = draw.material_set.clone() {
+                    builder
+                        .bind_descriptor_sets(
+                            PipelineBindPoint::Graphics,
+                            pipeline.layout().clone(),
+                            MATERIAL_SET,
+                            set,
+                        )
+                        .unwrap();
+                }
+                bound_material = Some(draw.material);
+            }
+
+            let instance_buffer = frame.ring.vertices(&draw.instances);
+            let instance_count = draw.instances.len() as u32;
+            // Буфери 2 і 3 скінованого варіанту — прив'язка вершин і зміщення палітр
+            if let Some(skin) = &draw.skin {
+                builder
+                    .bind_descriptor_sets(
+                        PipelineBindPoint::Graphics,
+                        pipeline.layout().clone(),
+                        SKIN_SET,
+                        skin.set.clone(),
+                    )
+                    .unwrap()
+                    .bind_vertex_buffers(
+                        2,
+                        (skin.vertices.clone(), frame.ring.vertices(&skin.instances)),
+                    )
+                    .unwrap();
+            }
+            match &draw.geometry {
+                NMeshGeometry::Indexed { vertices, indices } => {
+                    builder
+                        .bind_vertex_buffers(0, (vertices.clone(), instance_buffer))
+                        .unwrap()
+                        .bind_index_buffer(indices.clone())
+                        .unwrap();
+                    unsafe {
+                        builder
+                            .draw_indexed(indices.len() as u32, instance_count, 0, 0, 0)
+                            .unwrap();
+                    }
+                }
+                NMeshGeometry::Barycentric(vertices) => {
+                    builder.bind_vertex_buffers(0, (vertices.clone(), instance_buffer)).unwrap();
+                    unsafe {
+                        builder.draw(vertices.len() as u32, instance_count, 0, 0).unwrap();
+                    }
+                }
+            }
+
+            stats.push(NBatchStats {
+                system: "mesh",
+                name: draw.name.clone(),
+                instances: draw.instances.len() as u32,
+            });
+        }
+        (builder.build().unwrap(), stats)
+    }
+}
+
+// Що відрізняє пайплайн режиму перегляду від звичайного
+#[derive(Clone, Copy)]
+struct NPipelineVariant {
+    mode: NViewMode,        // Режим просмотра
+    barycentric: bool,      // Вершины каркаса с барицентрическими координатами
+    lines: bool,            // Каркас через PolygonMode::Line
+    skinned: bool,          // Скиннинг: привязка вершин и смещения палитр (буферы 2 и 3)
+}
+
+impl NPipelineVariant {
+    // Каркас без відсікання граней; лініями, якщо пристрій це підтримує
+    fn rasterization_state(self) -> RasterizationState {
+        match self.mode {
+            NViewMode::Wireframe => RasterizationState {
+                polygon_mode: if self.lines { PolygonMode::Line } else { PolygonMode::Fill },
+                cull_mode: CullMode::None,
+                ..Default::default()
+            },
+            _ => RasterizationState { cull_mode: CullMode::Back, ..Default::default() },
+        }
+    }
+
+    // Overdraw складає внески шарів адитивно
+    fn color_blend_attachment_state(self) -> ColorBlendAttachmentState {
+        match self.mode {
+            NViewMode::Overdraw => ColorBlendAttachmentState {
+                blend: Some(AttachmentBlend::additive()),
+                ..Default::default()
+            },
+            _ => ColorBlendAttachmentState::default(),
+        }
+    }
+
+    // Overdraw рахує всі шари, тож без тесту й запису глибини
+    fn depth_stencil_state(self) -> DepthStencilState {
+        match self.mode {
+            NViewMode::Overdraw => DepthStencilState::default(),
+            _ => DepthStencilState { depth: Some(DepthState::simple()), ..Default::default() },
+        }
+    }
+}
+
+fn create_pipeline(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    subpass: &Subpass,
+    vs: EntryPoint,
+    fs: EntryPoint,
+    variant: NPipelineVariant,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    let device = gfx_queue.device().clone();
+
+    // Буфер 0 — вершини меша (або каркаса), буфер 1 — дані інстансів,
+    // у скінованого варіанту буфер 2 — прив'язка вершин, буфер 3 — зміщення палітр
+    let vertex_buffer = if variant.barycentric {
+        NWireVertex::per_vertex()
+    } else {
+        NMeshVertex::per_vertex()
+    };
+    let mut vertex_buffers = vec![vertex_buffer, NMeshInstance::per_instance()];
+    if variant.skinned {
+        vertex_buffers.extend([NSkinVertex::per_vertex(), NSkinInstance::per_instance()]);
+    }
+    let vertex_input_state =
+        vertex_buffers.as_slice().definition(&vs).map_err(|err| err.to_string())?;
+
+    let stages = [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
+
+    // Layout наборів дескрипторів будується рефлексією шейдерів
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    GraphicsPipeline::new(device, Some(pipeline_cache.clone()), GraphicsPipelineCreateInfo {
+        stages: stages.into_iter().collect(),
+        vertex_input_state: Some(vertex_input_state),
+        input_assembly_state: Some(InputAssemblyState::default()),
+        viewport_state: Some(ViewportState::default()),
+        rasterization_state: Some(variant.rasterization_state()),
+        multisample_state: Some(MultisampleState {
+            rasterization_samples: subpass.num_samples().unwrap_or(SampleCount::Sample1),
+            ..Default::default()
+        }),
+        color_blend_state: Some(ColorBlendState::with_attachment_states(
+            subpass.num_color_attachments(),
+            variant.color_blend_attachment_state(),
+        )),
+        depth_stencil_state: Some(variant.depth_stencil_state()),
+        dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+        subpass: Some(subpass.clone().into()),
+        ..GraphicsPipelineCreateInfo::layout(layout)
+    })
+    .map_err(|err| err.to_string())
+}
@@ -1,3 +1,10 @@
+pub mod debug;
 pub mod mesh;
+pub mod outline;
+pub mod particles;
+pub mod sanitize;
+pub mod shape;
+pub mod skybox;
 pub mod sprite;
-pub mod triangle;
\ No newline at end of file
+pub mod text;
+pub mod tilemap;
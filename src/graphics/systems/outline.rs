@@ -0,0 +1,545 @@
+// Маска обведення: підсвічені сутності (меші та спрайти) і debug фігури зі стилем
+// highlight малюються пласким значенням маски (NHighlights::mask) у прохід outline_mask.
+// Змішування max об'єднує канали, тож сутність може бути і виділеною, і під курсором.
+// Спрайти відсікають прозорі пікселі текстури, щоб обведення йшло по силуету.
+// Скіновані меші з машиною станів анімації обводяться в поточній позі: палітри
+// суглобів батча лягають у storage буфер кадру, як у NMeshDrawSystem.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use cgmath::Matrix4;
+use vulkano::{
+    buffer::BufferContents,
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+        CommandBufferInheritanceInfo, CommandBufferUsage, SecondaryAutoCommandBuffer,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::Queue,
+    image::{
+        sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
+        SampleCount,
+    },
+    memory::allocator::StandardMemoryAllocator,
+    pipeline::{
+        cache::PipelineCache,
+        graphics::{
+            color_blend::{
+                AttachmentBlend, BlendFactor, BlendOp, ColorBlendAttachmentState,
+                ColorBlendState,
+            },
+            input_assembly::{InputAssemblyState, PrimitiveTopology},
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::{
+                Vertex, VertexBufferDescription, VertexDefinition, VertexInputState,
+            },
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::Subpass,
+    shader::EntryPoint,
+};
+
+use crate::graphics::{
+    asset::NAssetId,
+    graph::{NDrawSystem, NFrameContext},
+    mesh::{NMesh, NMeshVertex, NSkinVertex},
+    pipeline::NAllocators,
+    shader::{NShaderHandle, NShaderLibrary, NShaderStage},
+    stats::NBatchStats,
+    systems::{
+        debug::{NDebugDraw, NDebugVertex},
+        mesh::{NGpuMesh, NMeshInstance, NSkinInstance},
+        sprite::NSpriteInstance,
+    },
+    texture::{NTexture, NTextureCache},
+};
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+struct NOutlineMaskPushConstants {
+    view_proj: [[f32; 4]; 4],   // Матрица мир -> clip space
+}
+
+// Шейдери маски: вершинні для кожного виду об'єктів і два фрагментні
+#[derive(Clone, Copy)]
+struct NOutlineMaskShaders {
+    mesh_vs: NShaderHandle,     // Меши
+    skinned_vs: NShaderHandle,  // Скинованные меши (вариант SKINNED)
+    sprite_vs: NShaderHandle,   // Спрайты
+    line_vs: NShaderHandle,     // Debug линии
+    mask_fs: NShaderHandle,     // Плоское значение маски
+    sprite_fs: NShaderHandle,   // Маска с отсечением по альфе текстуры
+}
+
+impl NOutlineMaskShaders {
+    fn handles(&self) -> [NShaderHandle; 6] {
+        [self.mesh_vs, self.skinned_vs, self.sprite_vs, self.line_vs, self.mask_fs, self.sprite_fs]
+    }
+}
+
+// Пайплайни маски, зібрані з однієї версії шейдерів
+#[derive(Clone)]
+struct NOutlineMaskPipelines {
+    mesh: Arc<GraphicsPipeline>,    // Меши
+    skinned: Arc<GraphicsPipeline>, // Скинованные меши
+    sprite: Arc<GraphicsPipeline>,  // Спрайты (с отсечением по альфе текстуры)
+    line: Arc<GraphicsPipeline>,    // Debug линии
+}
+
+// Підсвічені сутності зі скінованим мешем: інстанси та їхні палітри суглобів підряд
+struct NSkinnedBatch {
+    mesh: Arc<NMesh>,                   // Меш
+    instances: Vec<NMeshInstance>,      // Инстансы
+    skin: Vec<NSkinInstance>,           // Смещения палитр инстансов
+    palette: Vec<[[f32; 4]; 4]>,        // Палитры суставов
+}
+
+// Система відрисовки маски обведення
+pub struct NOutlineMaskSystem {
+    gfx_queue: Arc<Queue>,                  // Очередь графических команд
+    subpass: Subpass,                       // Подпроход маски
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>, // Аллокатор команд
+    descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>, // Аллокатор дескрипторов
+    memory_allocator: Arc<StandardMemoryAllocator>, // Аллокатор памяти для буферов мешей
+    pipeline_cache: Arc<PipelineCache>,     // Кэш пайплайнов
+    shaders: NShaderLibrary,                // Библиотека шейдеров (горячая перезагрузка)
+    shader_handles: NOutlineMaskShaders,    // Шейдеры маски
+    shader_versions: Vec<u64>,              // Версии шейдеров, из которых собраны пайплайны
+    pipelines: Option<NOutlineMaskPipelines>, // Последние удачно собранные пайплайны
+    meshes: HashMap<NAssetId, NGpuMesh>,    // Загруженные меши подсвеченных сущностей
+    quad: NGpuMesh,                         // Единичный квадрат спрайтов
+    sampler: Arc<Sampler>,                  // Сэмплер текстур спрайтов
+    white: Arc<NTexture>,                   // Текстура для спрайтов без текстуры
+    textures: NTextureCache,                // Загруженные текстуры
+    descriptor_sets: HashMap<NAssetId, Arc<DescriptorSet>>, // Наборы дескрипторов по текстуре
+    debug_draw: NDebugDraw,                 // Общий список debug фигур
+}
+
+impl NOutlineMaskSystem {
+    pub fn new(
+        gfx_queue: Arc<Queue>,
+        subpass: Subpass,
+        allocators: &NAllocators,
+        debug_draw: NDebugDraw,
+    ) -> Self {
+        let device = gfx_queue.device().clone();
+        let shaders = allocators.shaders.clone();
+        let shader_handles = NOutlineMaskShaders {
+            mesh_vs: shaders.load("outline_mask_mesh.vert", NShaderStage::Vertex),
+            skinned_vs: shaders.load_variant(
+                "outline_mask_mesh.vert",
+                NShaderStage::Vertex,
+                &["SKINNED"],
+            ),
+            sprite_vs: shaders.load("outline_mask_sprite.vert", NShaderStage::Vertex),
+            line_vs: shaders.load("outline_mask_line.vert", NShaderStage::Vertex),
+            mask_fs: shaders.load("outline_mask.frag", NShaderStage::Fragment),
+            sprite_fs: shaders.load("outline_mask_sprite.frag", NShaderStage::Fragment),
+        };
+
+        let sampler = Sampler::new(device, SamplerCreateInfo {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            address_mode: [SamplerAddressMode::ClampToEdge; 3],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut system = NOutlineMaskSystem {
+            gfx_queue: gfx_queue.clone(),
+            subpass,
+            command_buffer_allocator: allocators.command_buffers.clone(),
+            descriptor_set_allocator: allocators.descriptor_sets.clone(),
+            memory_allocator: allocators.memory.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            shaders,
+            shader_handles,
+            shader_versions: Vec::new(),
+            pipelines: None,
+            meshes: HashMap::new(),
+            quad: NGpuMesh::new(&allocators.memory, &NMesh::quad()),
+            sampler,
+            white: Arc::new(NTexture::white()),
+            textures: NTextureCache::new(gfx_queue, allocators),
+            descriptor_sets: HashMap::new(),
+            debug_draw,
+        };
+        system.rebuild_pipelines_if_changed();
+        system
+    }
+
+    // Перебудовує пайплайни маски, якщо змінилась версія хоча б одного шейдера.
+    // При помилці лишаються попередні пайплайни, а помилка йде в редактор.
+    fn rebuild_pipelines_if_changed(&mut self) {
+        let handles = self.shader_handles.handles();
+        let versions: Vec<u64> =
+            handles.iter().map(|&handle| self.shaders.version(handle)).collect();
+        if versions == self.shader_versions {
+            return;
+        }
+        self.shader_versions = versions;
+
+        let entry_points = handles.map(|handle| self.shaders.entry_point(handle));
+        if entry_points.iter().any(Option::is_none) {
+            return;
+        }
+        match self.create_pipelines(entry_points.map(Option::unwrap)) {
+            Ok(pipelines) => {
+                self.pipelines = Some(pipelines);
+                // Набори створено під layout попереднього пайплайну спрайтів
+                self.descriptor_sets.clear();
+                self.shaders.set_pipeline_error(self.name(), None);
+            }
+            Err(error) => self.shaders.set_pipeline_error(self.name(), Some(error)),
+        }
+    }
+
+    // Точки входу — у порядку NOutlineMaskShaders::handles
+    fn create_pipelines(
+        &self,
+        entry_points: [EntryPoint; 6],
+    ) -> Result<NOutlineMaskPipelines, String> {
+        let [mesh_vs, skinned_vs, sprite_vs, line_vs, mask_fs, sprite_fs] = entry_points;
+        let definition = |input: &[VertexBufferDescription], vs: &EntryPoint| {
+            input.definition(vs).map_err(|err| err.to_string())
+        };
+
+        // Буфер 0 — вершини меша, буфер 1 — дані інстансів
+        let mesh_input =
+            definition(&[NMeshVertex::per_vertex(), NMeshInstance::per_instance()], &mesh_vs)?;
+        // Скіновані: буфер 2 — прив'язка вершин, буфер 3 — зміщення палітр
+        let skinned_input = definition(
+            &[
+                NMeshVertex::per_vertex(),
+                NMeshInstance::per_instance(),
+                NSkinVertex::per_vertex(),
+                NSkinInstance::per_instance(),
+            ],
+            &skinned_vs,
+        )?;
+        let sprite_input =
+            definition(&[NMeshVertex::per_vertex(), NSpriteInstance::per_instance()], &sprite_vs)?;
+        let line_input = definition(&[NDebugVertex::per_vertex()], &line_vs)?;
+
+        let pipeline = |vs, fs, input, topology| {
+            create_pipeline(
+                &self.gfx_queue,
+                &self.subpass,
+                &self.pipeline_cache,
+                vs,
+                fs,
+                input,
+                topology,
+            )
+        };
+        let triangles = PrimitiveTopology::TriangleList;
+        Ok(NOutlineMaskPipelines {
+            mesh: pipeline(mesh_vs, mask_fs.clone(), mesh_input, triangles)?,
+            skinned: pipeline(skinned_vs, mask_fs.clone(), skinned_input, triangles)?,
+            sprite: pipeline(sprite_vs, sprite_fs, sprite_input, triangles)?,
+            line: pipeline(line_vs, mask_fs, line_input, PrimitiveTopology::LineList)?,
+        })
+    }
+
+    fn descriptor_set(
+        &mut self,
+        pipeline: &Arc<GraphicsPipeline>,
+        texture: &NTexture,
+    ) -> Arc<DescriptorSet> {
+        if let Some(set) = self.descriptor_sets.get(&texture.id()) {
+            return set.clone();
+        }
+        let set = DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            pipeline.layout().set_layouts()[0].clone(),
+            [
+                WriteDescriptorSet::image_view(0, self.textures.get(texture)),
+                WriteDescriptorSet::sampler(1, self.sampler.clone()),
+            ],
+            [],
+        )
+        .unwrap();
+        self.descriptor_sets.insert(texture.id(), set.clone());
+        set
+    }
+}
+
+impl NDrawSystem for NOutlineMaskSystem {
+    // draw: меші батчами за мешем, спрайти — за текстурою, потім усі
+    // підсвічені debug лінії одним draw call-ом. Усе пишеться в один буфер:
+    // підсвічених об'єктів зазвичай одиниці.
+    fn draw(&mut self, frame: &NFrameContext) -> Vec<Arc<SecondaryAutoCommandBuffer>> {
+        self.rebuild_pipelines_if_changed();
+        let Some(pipelines) = self.pipelines.clone() else { return Vec::new() };
+        let mut meshes: BTreeMap<NAssetId, (Arc<NMesh>, Vec<NMeshInstance>)> = BTreeMap::new();
+        let mut skinned: BTreeMap<NAssetId, NSkinnedBatch> = BTreeMap::new();
+        let mut sprites: BTreeMap<NAssetId, (Arc<NTexture>, Vec<NSpriteInstance>)> =
+            BTreeMap::new();
+        for entity in frame.visible_entities() {
+            let Some(mask) = frame.highlights.mask(entity.id()) else { continue };
+            let matrix = entity.transform.matrix();
+            let palette = entity.animation.as_ref().map(|animation| animation.palette());
+            let skinned_mesh = entity.mesh.as_ref().zip(palette).filter(|(renderer, palette)| {
+                renderer.mesh.is_skinned() && !palette.is_empty()
+            });
+            if let Some((renderer, palette)) = skinned_mesh {
+                let batch = skinned.entry(renderer.mesh.id()).or_insert_with(|| NSkinnedBatch {
+                    mesh: renderer.mesh.clone(),
+                    instances: Vec::new(),
+                    skin: Vec::new(),
+                    palette: Vec::new(),
+                });
+                batch.instances.push(NMeshInstance::new(matrix, mask));
+                batch.skin.push(NSkinInstance { joint_offset: batch.palette.len() as u32 });
+                batch.palette.extend_from_slice(palette);
+            } else if let Some(renderer) = &entity.mesh {
+                meshes
+                    .entry(renderer.mesh.id())
+                    .or_insert_with(|| (renderer.mesh.clone(), Vec::new()))
+                    .1
+                    .push(NMeshInstance::new(matrix, mask));
+            }
+            if let Some(sprite) = &entity.sprite {
+                let texture = sprite.texture.clone().unwrap_or_else(|| self.white.clone());
+                let model =
+                    matrix * Matrix4::from_nonuniform_scale(sprite.size[0], sprite.size[1], 1.0);
+                sprites
+                    .entry(texture.id())
+                    .or_insert_with(|| (texture, Vec::new()))
+                    .1
+                    .push(NSpriteInstance {
+                        model_x: model.x.into(),
+                        model_y: model.y.into(),
+                        model_z: model.z.into(),
+                        model_w: model.w.into(),
+                        color: mask,
+                        uv_rect: sprite.uv_rect,
+                    });
+            }
+        }
+        let lines = self.debug_draw.highlighted_vertices();
+
+        let viewport_dimensions = frame.viewport_dimensions;
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.clone(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(self.subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        builder
+            .set_viewport(
+                0,
+                [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [viewport_dimensions[0] as f32, viewport_dimensions[1] as f32],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap();
+        let push_constants =
+            NOutlineMaskPushConstants { view_proj: frame.world_to_framebuffer.into() };
+        let mut stats = Vec::new();
+
+        if !meshes.is_empty() {
+            builder
+                .bind_pipeline_graphics(pipelines.mesh.clone())
+                .unwrap()
+                .push_constants(pipelines.mesh.layout().clone(), 0, push_constants)
+                .unwrap();
+        }
+        for (mesh, instances) in meshes.into_values() {
+            let gpu_mesh = self
+                .meshes
+                .entry(mesh.id())
+                .or_insert_with(|| NGpuMesh::new(&self.memory_allocator, &mesh));
+            let instance_buffer = frame.ring.vertices(&instances);
+            builder
+                .bind_vertex_buffers(0, (gpu_mesh.vertices.clone(), instance_buffer))
+                .unwrap()
+                .bind_index_buffer(gpu_mesh.indices.clone())
+                .unwrap();
+            unsafe {
+                builder
+                    .draw_indexed(gpu_mesh.indices.len() as u32, instances.len() as u32, 0, 0, 0)
+                    .unwrap();
+            }
+            stats.push(NBatchStats {
+                system: "outline",
+                name: mesh.name.clone(),
+                instances: instances.len() as u32,
+            });
+        }
+
+        if !skinned.is_empty() {
+            builder
+                .bind_pipeline_graphics(pipelines.skinned.clone())
+                .unwrap()
+                .push_constants(pipelines.skinned.layout().clone(), 0, push_constants)
+                .unwrap();
+        }
+        for batch in skinned.into_values() {
+            let gpu_mesh = self
+                .meshes
+                .entry(batch.mesh.id())
+                .or_insert_with(|| NGpuMesh::new(&self.memory_allocator, &batch.mesh));
+            let Some(skin_vertices) = gpu_mesh.skin.clone() else { continue };
+            let palette = DescriptorSet::new(
+                self.descriptor_set_allocator.clone(),
+                pipelines.skinned.layout().set_layouts()[0].clone(),
+                [WriteDescriptorSet::buffer(0, frame.ring.storage(&batch.palette))],
+                [],
+            )
+            .unwrap();
+            let instance_buffer = frame.ring.vertices(&batch.instances);
+            builder
+                .bind_descriptor_sets(
+                    PipelineBindPoint::Graphics,
+                    pipelines.skinned.layout().clone(),
+                    0,
+                    palette,
+                )
+                .unwrap()
+                .bind_vertex_buffers(0, (
+                    gpu_mesh.vertices.clone(),
+                    instance_buffer,
+                    skin_vertices,
+                    frame.ring.vertices(&batch.skin),
+                ))
+                .unwrap()
+                .bind_index_buffer(gpu_mesh.indices.clone())
+                .unwrap();
+            let instance_count = batch.instances.len() as u32;
+            unsafe {
+                builder
+                    .draw_indexed(gpu_mesh.indices.len() as u32, instance_count, 0, 0, 0)
+                    .unwrap();
+            }
+            stats.push(NBatchStats {
+                system: "outline",
+                name: batch.mesh.name.clone(),
+                instances: instance_count,
+            });
+        }
+
+        if !sprites.is_empty() {
+            builder
+                .bind_pipeline_graphics(pipelines.sprite.clone())
+                .unwrap()
+                .push_constants(pipelines.sprite.layout().clone(), 0, push_constants)
+                .unwrap()
+                .bind_index_buffer(self.quad.indices.clone())
+                .unwrap();
+        }
+        for (texture, instances) in sprites.into_values() {
+            let descriptor_set = self.descriptor_set(&pipelines.sprite, &texture);
+            let instance_buffer = frame.ring.vertices(&instances);
+            builder
+                .bind_descriptor_sets(
+                    PipelineBindPoint::Graphics,
+                    pipelines.sprite.layout().clone(),
+                    0,
+                    descriptor_set,
+                )
+                .unwrap()
+                .bind_vertex_buffers(0, (self.quad.vertices.clone(), instance_buffer))
+                .unwrap();
+            unsafe {
+                builder
+                    .draw_indexed(self.quad.indices.len() as u32, instances.len() as u32, 0, 0, 0)
+                    .unwrap();
+            }
+            stats.push(NBatchStats {
+                system: "outline",
+                name: texture.name.clone(),
+                instances: instances.len() as u32,
+            });
+        }
+
+        if !lines.is_empty() {
+            builder
+                .bind_pipeline_graphics(pipelines.line.clone())
+                .unwrap()
+                .push_constants(pipelines.line.layout().clone(), 0, push_constants)
+                .unwrap()
+                .bind_vertex_buffers(0, frame.ring.vertices(&lines))
+                .unwrap();
+            unsafe {
+                builder.draw(lines.len() as u32, 1, 0, 0).unwrap();
+            }
+            stats.push(NBatchStats {
+                system: "outline",
+                name: "debug lines".to_owned(),
+                instances: 1,
+            });
+        }
+
+        frame.stats.lock().unwrap().batches.extend(stats);
+        vec![builder.build().unwrap()]
+    }
+}
+
+// Пайплайн маски: без глибини, змішування max по всіх каналах
+fn create_pipeline(
+    gfx_queue: &Arc<Queue>,
+    subpass: &Subpass,
+    pipeline_cache: &Arc<PipelineCache>,
+    vs: EntryPoint,
+    fs: EntryPoint,
+    vertex_input_state: VertexInputState,
+    topology: PrimitiveTopology,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    let device = gfx_queue.device().clone();
+    let stages = [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    let max = AttachmentBlend {
+        src_color_blend_factor: BlendFactor::One,
+        dst_color_blend_factor: BlendFactor::One,
+        color_blend_op: BlendOp::Max,
+        src_alpha_blend_factor: BlendFactor::One,
+        dst_alpha_blend_factor: BlendFactor::One,
+        alpha_blend_op: BlendOp::Max,
+    };
+    GraphicsPipeline::new(device, Some(pipeline_cache.clone()), GraphicsPipelineCreateInfo {
+        stages: stages.into_iter().collect(),
+        vertex_input_state: Some(vertex_input_state),
+        input_assembly_state: Some(InputAssemblyState { topology, ..Default::default() }),
+        viewport_state: Some(ViewportState::default()),
+        rasterization_state: Some(RasterizationState::default()),
+        multisample_state: Some(MultisampleState {
+            rasterization_samples: subpass.num_samples().unwrap_or(SampleCount::Sample1),
+            ..Default::default()
+        }),
+        color_blend_state: Some(ColorBlendState::with_attachment_states(
+            subpass.num_color_attachments(),
+            ColorBlendAttachmentState { blend: Some(max), ..Default::default() },
+        )),
+        dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+        subpass: Some(subpass.clone().into()),
+        ..GraphicsPipelineCreateInfo::layout(layout)
+    })
+    .map_err(|err| err.to_string())
+}
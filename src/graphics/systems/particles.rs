@@ -0,0 +1,742 @@
+// GPU частинки.
+// NParticleComputeSystem (прохід pre_compute) для кожного емітера сцени:
+// - рахує на CPU, скільки частинок народжується цього кадру (rate + bursts),
+//   і куди вони пишуться в кільцевому буфері частинок,
+// - запускає particles_simulate.comp: народження, рух з гравітацією, смерть,
+//   ключі сортування за відстанню до камери,
+// - для альфа-змішування сортує ключі бітонним сортуванням (particles_sort.comp).
+// NParticleDrawSystem (прохід scene) малює частинки інстансованими білбордами,
+// читаючи буфери частинок і ключів у вершинному шейдері.
+// Стан емітерів на GPU обидві системи ділять через NParticleBuffers.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Quaternion, Vector3};
+use vulkano::{
+    buffer::{BufferContents, BufferUsage, Subbuffer},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+        CommandBufferInheritanceInfo, CommandBufferUsage, SecondaryAutoCommandBuffer,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::Queue,
+    image::{
+        sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
+        SampleCount,
+    },
+    memory::allocator::StandardMemoryAllocator,
+    pipeline::{
+        cache::PipelineCache,
+        compute::ComputePipelineCreateInfo,
+        graphics::{
+            color_blend::{AttachmentBlend, ColorBlendAttachmentState, ColorBlendState},
+            depth_stencil::{CompareOp, DepthState, DepthStencilState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::VertexInputState,
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        ComputePipeline, DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint,
+        PipelineLayout, PipelineShaderStageCreateInfo,
+    },
+    render_pass::Subpass,
+    shader::EntryPoint,
+};
+
+use crate::{
+    graphics::{
+        asset::NAssetId,
+        compute::NComputeQueues,
+        graph::{NComputeContext, NComputeSystem, NDrawSystem, NFrameContext},
+        pipeline::NAllocators,
+        shader::{NShaderHandle, NShaderLibrary, NShaderStage},
+        stats::NBatchStats,
+        texture::{NTexture, NTextureCache},
+    },
+    scene::{
+        entity::{NEntity, NEntityId},
+        particles::{NParticleBlend, NParticleBurst, NParticleEmitter},
+    },
+};
+
+// Кількість семплів кривих кольору та розміру (як у particles.vert)
+const CURVE_SAMPLES: usize = 8;
+// Розмір робочої групи compute шейдерів частинок
+const WORKGROUP_SIZE: u32 = 64;
+
+// Частинка на GPU (див. Particle у particles_simulate.comp)
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+struct NParticle {
+    position_age: [f32; 4],         // xyz — позиция, w — возраст
+    velocity_lifetime: [f32; 4],    // xyz — скорость, w — время жизни (0 — мёртвая)
+}
+
+// Ключ сортування: відстань до камери та індекс частинки
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+struct NParticleSortKey {
+    depth: f32,     // Расстояние до камеры
+    index: u32,     // Индекс частицы
+}
+
+// Параметри емітера на кадр (uniform Emitter у шейдерах частинок)
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+struct NParticleUniforms {
+    emission: [[f32; 4]; 4],            // Поворот конуса вылета
+    origin: [f32; 4],                   // Позиция эмиттера
+    gravity: [f32; 4],                  // xyz — ускорение, w — время кадра
+    speed_lifetime: [f32; 4],           // Скорость (мин, макс), время жизни (мин, макс)
+    shape: [f32; 4],                    // x — радиус рождения, y — cos половины угла конуса
+    camera_position: [f32; 4],          // Позиция камеры
+    camera_right: [f32; 4],             // Ось X камеры в мире
+    camera_up: [f32; 4],                // Ось Y камеры в мире
+    sheet: [f32; 4],                    // Размер, столбцы, строки, циклы анимации
+    counts: [u32; 4],                   // Начало рождения, количество, ёмкость, размер сортировки
+    seed: [u32; 4],                     // x — зерно случайных чисел
+    colors: [[f32; 4]; CURVE_SAMPLES],  // Цвет за жизнь
+    sizes: [[f32; 4]; CURVE_SAMPLES / 4], // Множитель размера за жизнь
+}
+
+#[repr(C)]
+#[derive(BufferContents)]
+struct NParticleSortStep {
+    block: u32,     // Размер битонной последовательности
+    distance: u32,  // Расстояние между сравниваемыми ключами
+    count: u32,     // Количество ключей
+}
+
+#[repr(C)]
+#[derive(BufferContents)]
+struct NParticlePushConstants {
+    view_proj: [[f32; 4]; 4],   // Матрица мир -> clip space
+    options: [u32; 4],          // x — 1, если инстансы идут в порядке ключей сортировки
+}
+
+// Стан одного емітера на GPU та його цикл народження на CPU
+struct NParticleEmitterState {
+    capacity: u32,                              // Ёмкость буфера частиц
+    generation: u64,                            // Поколение эмиттера
+    particles: Subbuffer<[NParticle]>,          // Частицы (кольцевой буфер)
+    keys: Subbuffer<[NParticleSortKey]>,        // Ключи сортировки (степень двойки)
+    cleared: bool,                              // Буфер частиц обнулён на GPU
+    time: f32,                                  // Время внутри цикла эмиттера
+    spawn_accumulator: f32,                     // Дробная часть частиц от rate
+    next: u32,                                  // Следующий слот для рождения
+    uniforms: Option<Subbuffer<NParticleUniforms>>, // Параметры последнего кадра
+    sorted: bool,                               // Ключи отсортированы в этом кадре
+}
+
+impl NParticleEmitterState {
+    fn new(
+        memory: &Arc<StandardMemoryAllocator>,
+        compute: &NComputeQueues,
+        emitter: &NParticleEmitter,
+    ) -> Self {
+        let capacity = emitter.max_particles.max(1);
+        NParticleEmitterState {
+            capacity,
+            generation: emitter.generation,
+            particles: compute.storage_buffer(
+                memory,
+                capacity as u64,
+                BufferUsage::TRANSFER_DST,
+            ),
+            keys: compute.storage_buffer(
+                memory,
+                capacity.next_power_of_two() as u64,
+                BufferUsage::empty(),
+            ),
+            cleared: false,
+            time: 0.0,
+            spawn_accumulator: 0.0,
+            next: 0,
+            uniforms: None,
+            sorted: false,
+        }
+    }
+
+    // Діапазон слотів (початок, кількість), в яких народжуються частинки цього кадру
+    fn emit(&mut self, emitter: &NParticleEmitter, dt: f32) -> (u32, u32) {
+        let duration = emitter.duration.max(1.0e-3);
+        let mut count = 0;
+        if emitter.looping || self.time < duration {
+            let end = self.time + dt;
+            self.spawn_accumulator += emitter.rate.max(0.0) * dt;
+            count += bursts_between(&emitter.bursts, self.time, end);
+            if emitter.looping && end >= duration {
+                self.time = end % duration;
+                count += bursts_between(&emitter.bursts, 0.0, self.time);
+            } else {
+                self.time = end;
+            }
+        }
+        let spawned = self.spawn_accumulator.floor();
+        self.spawn_accumulator -= spawned;
+
+        let count = (count + spawned as u32).min(self.capacity);
+        let start = self.next;
+        self.next = (self.next + count) % self.capacity;
+        (start, count)
+    }
+}
+
+// Кількість частинок від bursts з часом у [from, to)
+fn bursts_between(bursts: &[NParticleBurst], from: f32, to: f32) -> u32 {
+    bursts.iter().filter(|burst| burst.time >= from && burst.time < to).map(|b| b.count).sum()
+}
+
+/// Стан емітерів на GPU, спільний для compute та draw систем частинок
+#[derive(Clone, Default)]
+pub struct NParticleBuffers(Arc<Mutex<HashMap<NEntityId, NParticleEmitterState>>>);
+
+impl NParticleBuffers {
+    fn lock(&self) -> MutexGuard<'_, HashMap<NEntityId, NParticleEmitterState>> {
+        self.0.lock().unwrap()
+    }
+}
+
+// Система симуляції частинок у compute шейдерах
+pub struct NParticleComputeSystem {
+    gfx_queue: Arc<Queue>,          // Очередь (для устройства)
+    memory: Arc<StandardMemoryAllocator>, // Аллокатор памяти буферов частиц
+    compute: NComputeQueues,        // Очереди (общий доступ к буферам)
+    descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>, // Аллокатор дескрипторов
+    pipeline_cache: Arc<PipelineCache>,  // Кэш пайплайнов
+    shaders: NShaderLibrary,        // Библиотека шейдеров (горячая перезагрузка)
+    simulate_shader: NShaderHandle, // Шейдер симуляции
+    sort_shader: NShaderHandle,     // Шейдер шага сортировки
+    shader_versions: (u64, u64),    // Версии шейдеров, из которых собраны пайплайны
+    simulate: Option<Arc<ComputePipeline>>, // Пайплайн симуляции
+    sort: Option<Arc<ComputePipeline>>,     // Пайплайн сортировки
+    buffers: NParticleBuffers,      // Состояние эмиттеров на GPU
+    frame_index: u32,               // Номер кадра (зерно случайных чисел)
+}
+
+impl NParticleComputeSystem {
+    pub fn new(
+        gfx_queue: Arc<Queue>,
+        allocators: &NAllocators,
+        buffers: NParticleBuffers,
+    ) -> NParticleComputeSystem {
+        let shaders = allocators.shaders.clone();
+        let simulate_shader = shaders.load("particles_simulate.comp", NShaderStage::Compute);
+        let sort_shader = shaders.load("particles_sort.comp", NShaderStage::Compute);
+
+        let mut system = NParticleComputeSystem {
+            gfx_queue,
+            memory: allocators.memory.clone(),
+            compute: allocators.compute.clone(),
+            descriptor_set_allocator: allocators.descriptor_sets.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            shaders,
+            simulate_shader,
+            sort_shader,
+            shader_versions: (0, 0),
+            simulate: None,
+            sort: None,
+            buffers,
+            frame_index: 0,
+        };
+        system.rebuild_pipelines_if_changed();
+        system
+    }
+
+    // Перебудовує пайплайни, якщо змінилася версія хоча б одного шейдера.
+    // При помилці лишаються попередні пайплайни, а помилка йде в редактор.
+    fn rebuild_pipelines_if_changed(&mut self) {
+        let versions = (
+            self.shaders.version(self.simulate_shader),
+            self.shaders.version(self.sort_shader),
+        );
+        if versions == self.shader_versions {
+            return;
+        }
+        self.shader_versions = versions;
+
+        let (Some(simulate), Some(sort)) = (
+            self.shaders.entry_point(self.simulate_shader),
+            self.shaders.entry_point(self.sort_shader),
+        ) else {
+            return;
+        };
+        let result = create_compute_pipeline(&self.gfx_queue, &self.pipeline_cache, simulate)
+            .and_then(|simulate| {
+                create_compute_pipeline(&self.gfx_queue, &self.pipeline_cache, sort)
+                    .map(|sort| (simulate, sort))
+            });
+        match result {
+            Ok((simulate, sort)) => {
+                self.simulate = Some(simulate);
+                self.sort = Some(sort);
+                self.shaders.set_pipeline_error(self.name(), None);
+            }
+            Err(error) => self.shaders.set_pipeline_error(self.name(), Some(error)),
+        }
+    }
+
+    fn uniforms(
+        &self,
+        entity: &NEntity,
+        emitter: &NParticleEmitter,
+        frame: &NFrameContext,
+        spawn: (u32, u32),
+        capacity: u32,
+    ) -> NParticleUniforms {
+        let direction = if emitter.direction.magnitude2() > 1.0e-6 {
+            emitter.direction.normalize()
+        } else {
+            Vector3::unit_y()
+        };
+        let rotation = entity.transform.rotation
+            * Quaternion::from_arc(Vector3::unit_z(), direction, None);
+        let spread = emitter.spread.clamp(0.0, 180.0).to_radians();
+
+        // Рядки матриці виду — осі камери у світі
+        let camera = &frame.scene.camera;
+        let view = camera.view();
+        let position = camera.position;
+
+        let mut sizes = [[0.0; 4]; CURVE_SAMPLES / 4];
+        for index in 0..CURVE_SAMPLES {
+            let t = index as f32 / (CURVE_SAMPLES - 1) as f32;
+            sizes[index / 4][index % 4] = emitter.size_over_lifetime.sample(t);
+        }
+        let seed = self.frame_index.wrapping_mul(0x9e37_79b9) ^ (entity.id().0 as u32);
+
+        NParticleUniforms {
+            emission: Matrix4::from(rotation).into(),
+            origin: entity.transform.position.extend(1.0).into(),
+            gravity: emitter.gravity.extend(frame.delta_time).into(),
+            speed_lifetime: [
+                emitter.speed[0],
+                emitter.speed[1],
+                emitter.lifetime[0],
+                emitter.lifetime[1],
+            ],
+            shape: [emitter.radius.max(0.0), spread.cos(), 0.0, 0.0],
+            camera_position: [position.x, position.y, position.z, 1.0],
+            camera_right: [view.x.x, view.y.x, view.z.x, 0.0],
+            camera_up: [view.x.y, view.y.y, view.z.y, 0.0],
+            sheet: [
+                emitter.size,
+                emitter.sheet[0].max(1) as f32,
+                emitter.sheet[1].max(1) as f32,
+                emitter.sheet_cycles,
+            ],
+            counts: [spawn.0, spawn.1, capacity, capacity.next_power_of_two()],
+            seed: [seed, 0, 0, 0],
+            colors: std::array::from_fn(|index| {
+                emitter.color_over_lifetime.sample(index as f32 / (CURVE_SAMPLES - 1) as f32)
+            }),
+            sizes,
+        }
+    }
+}
+
+pub(crate) fn create_compute_pipeline(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    entry_point: EntryPoint,
+) -> Result<Arc<ComputePipeline>, String> {
+    let device = gfx_queue.device().clone();
+    let stage = PipelineShaderStageCreateInfo::new(entry_point);
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages([&stage])
+            .into_pipeline_layout_create_info(device.clone())
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    ComputePipeline::new(
+        device,
+        Some(pipeline_cache.clone()),
+        ComputePipelineCreateInfo::stage_layout(stage, layout),
+    )
+    .map_err(|err| err.to_string())
+}
+
+impl NComputeSystem for NParticleComputeSystem {
+    // dispatch:
+    // - прибирає стан емітерів, яких більше немає в сцені,
+    // - (пере)створює буфери при зміні max_particles або перезапуску емітера,
+    // - симулює кожен емітер і сортує ключі емітерів з альфа-змішуванням.
+    fn dispatch(&mut self, ctx: &mut NComputeContext) {
+        self.rebuild_pipelines_if_changed();
+        self.frame_index = self.frame_index.wrapping_add(1);
+        let (Some(simulate), Some(sort)) = (self.simulate.clone(), self.sort.clone()) else {
+            return;
+        };
+
+        let buffers = self.buffers.clone();
+        let mut states = buffers.lock();
+        let scene = ctx.frame.scene;
+        let alive: HashSet<NEntityId> = scene
+            .entities
+            .iter()
+            .filter(|entity| entity.particles.is_some())
+            .map(NEntity::id)
+            .collect();
+        states.retain(|id, _| alive.contains(id));
+
+        for entity in &scene.entities {
+            let Some(emitter) = &entity.particles else { continue };
+            let create = || NParticleEmitterState::new(&self.memory, &self.compute, emitter);
+            let state = states.entry(entity.id()).or_insert_with(create);
+            if state.capacity != emitter.max_particles.max(1)
+                || state.generation != emitter.generation
+            {
+                *state = create();
+            }
+
+            let spawn = state.emit(emitter, ctx.frame.delta_time);
+            let uniforms = ctx
+                .frame
+                .ring
+                .uniform(self.uniforms(entity, emitter, ctx.frame, spawn, state.capacity));
+
+            if !state.cleared {
+                ctx.builder.fill_buffer(state.particles.clone().reinterpret(), 0).unwrap();
+                state.cleared = true;
+            }
+
+            let descriptor_set = DescriptorSet::new(
+                self.descriptor_set_allocator.clone(),
+                simulate.layout().set_layouts()[0].clone(),
+                [
+                    WriteDescriptorSet::buffer(0, state.particles.clone()),
+                    WriteDescriptorSet::buffer(1, state.keys.clone()),
+                    WriteDescriptorSet::buffer(2, uniforms.clone()),
+                ],
+                [],
+            )
+            .unwrap();
+            let key_count = state.keys.len() as u32;
+            ctx.builder
+                .bind_pipeline_compute(simulate.clone())
+                .unwrap()
+                .bind_descriptor_sets(
+                    PipelineBindPoint::Compute,
+                    simulate.layout().clone(),
+                    0,
+                    descriptor_set,
+                )
+                .unwrap();
+            unsafe {
+                ctx.builder.dispatch([key_count.div_ceil(WORKGROUP_SIZE), 1, 1]).unwrap();
+            }
+
+            // Бітонне сортування: log2(n) * (log2(n) + 1) / 2 кроків, бар'єри між
+            // кроками вставляє AutoCommandBufferBuilder
+            state.sorted = emitter.blend == NParticleBlend::Alpha;
+            if state.sorted {
+                let descriptor_set = DescriptorSet::new(
+                    self.descriptor_set_allocator.clone(),
+                    sort.layout().set_layouts()[0].clone(),
+                    [WriteDescriptorSet::buffer(0, state.keys.clone())],
+                    [],
+                )
+                .unwrap();
+                ctx.builder
+                    .bind_pipeline_compute(sort.clone())
+                    .unwrap()
+                    .bind_descriptor_sets(
+                        PipelineBindPoint::Compute,
+                        sort.layout().clone(),
+                        0,
+                        descriptor_set,
+                    )
+                    .unwrap();
+                let mut block = 2;
+                while block <= key_count {
+                    let mut distance = block / 2;
+                    while distance > 0 {
+                        ctx.builder
+                            .push_constants(sort.layout().clone(), 0, NParticleSortStep {
+                                block,
+                                distance,
+                                count: key_count,
+                            })
+                            .unwrap();
+                        unsafe {
+                            ctx.builder
+                                .dispatch([key_count.div_ceil(WORKGROUP_SIZE), 1, 1])
+                                .unwrap();
+                        }
+                        distance /= 2;
+                    }
+                    block *= 2;
+                }
+            }
+            state.uniforms = Some(uniforms);
+        }
+    }
+}
+
+// Система відрисовки частинок емітерів сцени. Частинки з альфа-змішуванням
+// малюються у відсортованому порядку (від дальніх до ближніх), емітери між собою
+// теж сортуються за відстанню; адитивні емітери малюються після них.
+pub struct NParticleDrawSystem {
+    gfx_queue: Arc<Queue>,          // Очередь графических команд
+    subpass: Subpass,               // Подпроход рендеринга
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>, // Аллокатор команд
+    descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>, // Аллокатор дескрипторов
+    pipeline_cache: Arc<PipelineCache>,  // Кэш пайплайнов
+    shaders: NShaderLibrary,        // Библиотека шейдеров (горячая перезагрузка)
+    vs: NShaderHandle,              // Вершинный шейдер
+    fs: NShaderHandle,              // Фрагментный шейдер
+    shader_versions: (u64, u64),    // Версии шейдеров, из которых собраны пайплайны
+    pipelines: Option<(Arc<GraphicsPipeline>, Arc<GraphicsPipeline>)>, // Alpha и additive
+    sampler: Arc<Sampler>,          // Сэмплер текстур частиц
+    white: Arc<NTexture>,           // Текстура для эмиттеров без текстуры
+    textures: NTextureCache,        // Загруженные текстуры
+    texture_sets: HashMap<NAssetId, Arc<DescriptorSet>>, // Наборы дескрипторов по текстуре
+    buffers: NParticleBuffers,      // Состояние эмиттеров на GPU
+}
+
+impl NParticleDrawSystem {
+    pub fn new(
+        gfx_queue: Arc<Queue>,
+        subpass: Subpass,
+        allocators: &NAllocators,
+        buffers: NParticleBuffers,
+    ) -> NParticleDrawSystem {
+        let shaders = allocators.shaders.clone();
+        let vs = shaders.load("particles.vert", NShaderStage::Vertex);
+        let fs = shaders.load("particles.frag", NShaderStage::Fragment);
+
+        let sampler = Sampler::new(gfx_queue.device().clone(), SamplerCreateInfo {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            address_mode: [SamplerAddressMode::ClampToEdge; 3],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut system = NParticleDrawSystem {
+            gfx_queue: gfx_queue.clone(),
+            subpass,
+            command_buffer_allocator: allocators.command_buffers.clone(),
+            descriptor_set_allocator: allocators.descriptor_sets.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            shaders,
+            vs,
+            fs,
+            shader_versions: (0, 0),
+            pipelines: None,
+            sampler,
+            white: Arc::new(NTexture::white()),
+            textures: NTextureCache::new(gfx_queue, allocators),
+            texture_sets: HashMap::new(),
+            buffers,
+        };
+        system.rebuild_pipelines_if_changed();
+        system
+    }
+
+    // Перебудовує пайплайни, якщо змінилася версія хоча б одного шейдера.
+    // При помилці лишаються попередні пайплайни, а помилка йде в редактор.
+    fn rebuild_pipelines_if_changed(&mut self) {
+        let versions = (self.shaders.version(self.vs), self.shaders.version(self.fs));
+        if versions == self.shader_versions {
+            return;
+        }
+        self.shader_versions = versions;
+
+        let (Some(vs), Some(fs)) =
+            (self.shaders.entry_point(self.vs), self.shaders.entry_point(self.fs))
+        else {
+            return;
+        };
+        match create_pipelines(&self.gfx_queue, &self.pipeline_cache, &self.subpass, vs, fs) {
+            Ok(pipelines) => {
+                self.pipelines = Some(pipelines);
+                self.texture_sets.clear();
+                self.shaders.set_pipeline_error(self.name(), None);
+            }
+            Err(error) => self.shaders.set_pipeline_error(self.name(), Some(error)),
+        }
+    }
+
+    fn texture_set(
+        &mut self,
+        pipeline: &Arc<GraphicsPipeline>,
+        texture: &NTexture,
+    ) -> Arc<DescriptorSet> {
+        if let Some(set) = self.texture_sets.get(&texture.id()) {
+            return set.clone();
+        }
+        let set = DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            pipeline.layout().set_layouts()[1].clone(),
+            [
+                WriteDescriptorSet::image_view(0, self.textures.get(texture)),
+                WriteDescriptorSet::sampler(1, self.sampler.clone()),
+            ],
+            [],
+        )
+        .unwrap();
+        self.texture_sets.insert(texture.id(), set.clone());
+        set
+    }
+}
+
+// Пайплайни з альфа- та адитивним змішуванням з одним layout-ом,
+// щоб набори дескрипторів підходили обом
+fn create_pipelines(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    subpass: &Subpass,
+    vs: EntryPoint,
+    fs: EntryPoint,
+) -> Result<(Arc<GraphicsPipeline>, Arc<GraphicsPipeline>), String> {
+    let device = gfx_queue.device().clone();
+    let stages = [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
+
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    // Частинки перевіряють глибину, але не пишуть її
+    let create = |blend: AttachmentBlend| {
+        let create_info = GraphicsPipelineCreateInfo {
+            stages: stages.iter().cloned().collect(),
+            vertex_input_state: Some(VertexInputState::default()),
+            input_assembly_state: Some(InputAssemblyState::default()),
+            viewport_state: Some(ViewportState::default()),
+            rasterization_state: Some(RasterizationState::default()),
+            multisample_state: Some(MultisampleState {
+                rasterization_samples: subpass.num_samples().unwrap_or(SampleCount::Sample1),
+                ..Default::default()
+            }),
+            color_blend_state: Some(ColorBlendState::with_attachment_states(
+                subpass.num_color_attachments(),
+                ColorBlendAttachmentState { blend: Some(blend), ..Default::default() },
+            )),
+            depth_stencil_state: Some(DepthStencilState {
+                depth: Some(DepthState { write_enable: false, compare_op: CompareOp::Less }),
+                ..Default::default()
+            }),
+            dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+            subpass: Some(subpass.clone().into()),
+            ..GraphicsPipelineCreateInfo::layout(layout.clone())
+        };
+        GraphicsPipeline::new(device.clone(), Some(pipeline_cache.clone()), create_info)
+            .map_err(|err| err.to_string())
+    };
+    Ok((create(AttachmentBlend::alpha())?, create(AttachmentBlend::additive())?))
+}
+
+impl NDrawSystem for NParticleDrawSystem {
+    fn draw(&mut self, frame: &NFrameContext) -> Vec<Arc<SecondaryAutoCommandBuffer>> {
+        self.rebuild_pipelines_if_changed();
+        let viewport_dimensions = frame.viewport_dimensions;
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.clone(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(self.subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let Some((alpha, additive)) = self.pipelines.clone() else {
+            return vec![builder.build().unwrap()];
+        };
+
+        // Альфа-емітери від дальніх до ближніх, потім адитивні
+        let camera_position = frame.scene.camera.position.to_vec();
+        let mut emitters: Vec<(&NEntity, &NParticleEmitter)> = frame
+            .scene
+            .entities
+            .iter()
+            .filter_map(|entity| entity.particles.as_ref().map(|emitter| (entity, emitter)))
+            .collect();
+        emitters.sort_by(|(a, a_emitter), (b, b_emitter)| {
+            let distance =
+                |entity: &NEntity| (entity.transform.position - camera_position).magnitude2();
+            (a_emitter.blend == NParticleBlend::Additive)
+                .cmp(&(b_emitter.blend == NParticleBlend::Additive))
+                .then(distance(b).total_cmp(&distance(a)))
+        });
+
+        builder
+            .set_viewport(
+                0,
+                [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [viewport_dimensions[0] as f32, viewport_dimensions[1] as f32],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap();
+
+        let buffers = self.buffers.clone();
+        let states = buffers.lock();
+        for (entity, emitter) in emitters {
+            let Some(state) = states.get(&entity.id()) else { continue };
+            let Some(uniforms) = state.uniforms.clone() else { continue };
+            let pipeline = match emitter.blend {
+                NParticleBlend::Alpha => alpha.clone(),
+                NParticleBlend::Additive => additive.clone(),
+            };
+
+            let particle_set = DescriptorSet::new(
+                self.descriptor_set_allocator.clone(),
+                pipeline.layout().set_layouts()[0].clone(),
+                [
+                    WriteDescriptorSet::buffer(0, state.particles.clone()),
+                    WriteDescriptorSet::buffer(1, state.keys.clone()),
+                    WriteDescriptorSet::buffer(2, uniforms),
+                ],
+                [],
+            )
+            .unwrap();
+            let texture = emitter.texture.clone().unwrap_or_else(|| self.white.clone());
+            let texture_set = self.texture_set(&pipeline, &texture);
+
+            builder
+                .bind_pipeline_graphics(pipeline.clone())
+                .unwrap()
+                .bind_descriptor_sets(
+                    PipelineBindPoint::Graphics,
+                    pipeline.layout().clone(),
+                    0,
+                    vec![particle_set, texture_set],
+                )
+                .unwrap()
+                .push_constants(pipeline.layout().clone(), 0, NParticlePushConstants {
+                    view_proj: frame.world_to_framebuffer.into(),
+                    options: [state.sorted as u32, 0, 0, 0],
+                })
+                .unwrap();
+            unsafe {
+                builder.draw(6, state.capacity, 0, 0).unwrap();
+            }
+
+            frame.stats.lock().unwrap().batches.push(NBatchStats {
+                system: "particles",
+                name: entity.name.clone(),
+                instances: state.capacity,
+            });
+        }
+        vec![builder.build().unwrap()]
+    }
+}
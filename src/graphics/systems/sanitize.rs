@@ -0,0 +1,112 @@
+// Очищення HDR зображення (прохід post_compute).
+// NHdrSanitizeSystem замінює NaN/Inf пікселі на чорний (hdr_sanitize.comp)
+// перед пост-обробкою: інакше блум розмазує їх по кадру, а середня яскравість
+// для автоекспозиції стає NaN назавжди.
+
+use std::sync::Arc;
+
+use vulkano::{
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::Queue,
+    pipeline::{cache::PipelineCache, ComputePipeline, Pipeline, PipelineBindPoint},
+};
+
+use crate::graphics::{
+    graph::{NComputeContext, NComputeSystem, NResourceId},
+    pipeline::NAllocators,
+    shader::{NShaderHandle, NShaderLibrary, NShaderStage},
+    systems::particles::create_compute_pipeline,
+};
+
+// Розмір робочої групи hdr_sanitize.comp по кожній осі
+const WORKGROUP_SIZE: u32 = 8;
+
+pub struct NHdrSanitizeSystem {
+    gfx_queue: Arc<Queue>,                                  // Графическая очередь
+    descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>, // Аллокатор дескрипторов
+    pipeline_cache: Arc<PipelineCache>,                     // Кэш пайплайнов
+    shaders: NShaderLibrary,                                // Библиотека шейдеров
+    shader: NShaderHandle,                                  // hdr_sanitize.comp
+    shader_version: u64,                                    // Версия шейдера пайплайна
+    pipeline: Option<Arc<ComputePipeline>>,                 // Пайплайн очистки
+    hdr: NResourceId,                                       // HDR изображение графа
+}
+
+impl NHdrSanitizeSystem {
+    pub fn new(gfx_queue: Arc<Queue>, allocators: &NAllocators, hdr: NResourceId) -> Self {
+        let shaders = allocators.shaders.clone();
+        let shader = shaders.load("hdr_sanitize.comp", NShaderStage::Compute);
+        let mut system = NHdrSanitizeSystem {
+            gfx_queue,
+            descriptor_set_allocator: allocators.descriptor_sets.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            shaders,
+            shader,
+            shader_version: 0,
+            pipeline: None,
+            hdr,
+        };
+        system.rebuild_pipeline_if_changed();
+        system
+    }
+
+    // Перебудовує пайплайн при зміні шейдера; при помилці лишається попередній
+    fn rebuild_pipeline_if_changed(&mut self) {
+        let version = self.shaders.version(self.shader);
+        if version == self.shader_version {
+            return;
+        }
+        self.shader_version = version;
+
+        let Some(entry_point) = self.shaders.entry_point(self.shader) else { return };
+        match create_compute_pipeline(&self.gfx_queue, &self.pipeline_cache, entry_point) {
+            Ok(pipeline) => {
+                self.pipeline = Some(pipeline);
+                self.shaders.set_pipeline_error(self.name(), None);
+            }
+            Err(error) => self.shaders.set_pipeline_error(self.name(), Some(error)),
+        }
+    }
+}
+
+impl NComputeSystem for NHdrSanitizeSystem {
+    fn dispatch(&mut self, ctx: &mut NComputeContext) {
+        self.rebuild_pipeline_if_changed();
+        let Some(pipeline) = self.pipeline.clone() else { return };
+
+        let hdr = ctx.image(self.hdr);
+        let extent = hdr.image().extent();
+        let descriptor_set = DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            pipeline.layout().set_layouts()[0].clone(),
+            [WriteDescriptorSet::image_view(0, hdr)],
+            [],
+        )
+        .unwrap();
+        ctx.builder
+            .bind_pipeline_compute(pipeline.clone())
+            .unwrap()
+            .bind_descriptor_sets(
+                PipelineBindPoint::Compute,
+                pipeline.layout().clone(),
+                0,
+                descriptor_set,
+            )
+            .unwrap();
+        unsafe {
+            ctx.builder
+                .dispatch([
+                    extent[0].div_ceil(WORKGROUP_SIZE),
+                    extent[1].div_ceil(WORKGROUP_SIZE),
+                    1,
+                ])
+                .unwrap();
+        }
+    }
+
+    fn images(&self) -> Vec<NResourceId> {
+        vec![self.hdr]
+    }
+}
@@ -0,0 +1,473 @@
+// Immediate-mode 2D фігури.
+// Будь-яка система протягом кадру викликає rect/rounded_rect/circle/polygon/path
+// на клоні NShapeDraw. Фігура одразу тесселюється lyon у трикутники з кольором
+// у кожній вершині, тож усі фігури шару за кадр — один динамічний вершинний буфер
+// і один draw. Фігури живуть один кадр: рендерер очищує їх після render().
+// Шари (NLayer):
+// - Screen — пікселі кадру (0,0 — лівий верхній кут), прохід overlay поверх кадру,
+// - World — площина XY трансформа стилю у світі, прохід сцени з перевіркою глибини.
+// Фігури шару малюються в порядку викликів (пізніша — зверху).
+
+use std::sync::{Arc, Mutex};
+
+use cgmath::{Matrix4, SquareMatrix, Vector4};
+use lyon::{
+    math::{point, Box2D, Point},
+    path::{builder::BorderRadii, Path, Polygon, Winding},
+    tessellation::{
+        BuffersBuilder, FillOptions, FillRule, FillTessellator, FillVertex, StrokeOptions,
+        StrokeTessellator, StrokeVertex, VertexBuffers,
+    },
+};
+use vulkano::{
+    buffer::BufferContents,
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+        CommandBufferInheritanceInfo, CommandBufferUsage, SecondaryAutoCommandBuffer,
+    },
+    device::Queue,
+    image::SampleCount,
+    pipeline::{
+        cache::PipelineCache,
+        graphics::{
+            color_blend::{AttachmentBlend, ColorBlendAttachmentState, ColorBlendState},
+            depth_stencil::{CompareOp, DepthState, DepthStencilState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::{Vertex, VertexDefinition},
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineLayout, PipelineShaderStageCreateInfo,
+    },
+    render_pass::Subpass,
+    shader::EntryPoint,
+};
+
+use crate::graphics::{
+    frame::NLayer,
+    graph::{NDrawSystem, NFrameContext},
+    pipeline::NAllocators,
+    shader::{NShaderHandle, NShaderLibrary, NShaderStage},
+    stats::NBatchStats,
+};
+
+// Допустиме відхилення ламаної від кривої: пікселі екрана / одиниці світу
+const SCREEN_TOLERANCE: f32 = 0.25;
+const WORLD_TOLERANCE: f32 = 0.01;
+
+/// Колір, заливка чи обводка, шар і трансформ 2D фігури
+#[derive(Clone, Copy, Debug)]
+pub struct NShapeStyle {
+    pub color: [f32; 4],            // Цвет
+    pub stroke: Option<f32>,        // Толщина обводки (None — заливка)
+    pub layer: NLayer,              // Слой (экран или мир)
+    pub transform: Matrix4<f32>,    // Пространство фигуры -> пространство слоя
+}
+
+impl NShapeStyle {
+    // Заливка на екрані
+    pub fn fill(color: [f32; 4]) -> Self {
+        NShapeStyle { color, stroke: None, layer: NLayer::Screen, transform: Matrix4::identity() }
+    }
+
+    // Обводка товщиною width на екрані
+    pub fn stroke(color: [f32; 4], width: f32) -> Self {
+        NShapeStyle { stroke: Some(width), ..Self::fill(color) }
+    }
+
+    // Фігура у світі: transform переводить площину XY фігури у світ
+    #[allow(dead_code)]
+    pub fn world(mut self, transform: Matrix4<f32>) -> Self {
+        self.layer = NLayer::World;
+        self.transform = transform;
+        self
+    }
+}
+
+/// Контур з відрізків і кривих Безьє для NShapeDraw::path.
+/// Кожен move_to починає новий підконтур; без move_to контур починається
+/// з першої точки line_to/quad_to/cubic_to.
+pub struct NPath {
+    builder: lyon::path::path::Builder, // Построитель контура lyon
+    open: bool,                         // Начат ли незакрытый подконтур
+}
+
+impl Default for NPath {
+    fn default() -> Self {
+        NPath { builder: Path::builder(), open: false }
+    }
+}
+
+#[allow(dead_code)]
+impl NPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn move_to(mut self, to: [f32; 2]) -> Self {
+        if self.open {
+            self.builder.end(false);
+        }
+        self.builder.begin(point(to[0], to[1]));
+        self.open = true;
+        self
+    }
+
+    pub fn line_to(mut self, to: [f32; 2]) -> Self {
+        if !self.open {
+            return self.move_to(to);
+        }
+        self.builder.line_to(point(to[0], to[1]));
+        self
+    }
+
+    pub fn quad_to(mut self, control: [f32; 2], to: [f32; 2]) -> Self {
+        if !self.open {
+            return self.move_to(to);
+        }
+        self.builder.quadratic_bezier_to(point(control[0], control[1]), point(to[0], to[1]));
+        self
+    }
+
+    pub fn cubic_to(mut self, control1: [f32; 2], control2: [f32; 2], to: [f32; 2]) -> Self {
+        if !self.open {
+            return self.move_to(to);
+        }
+        self.builder.cubic_bezier_to(
+            point(control1[0], control1[1]),
+            point(control2[0], control2[1]),
+            point(to[0], to[1]),
+        );
+        self
+    }
+
+    // Замикає поточний підконтур відрізком до його початку
+    pub fn close(mut self) -> Self {
+        if self.open {
+            self.builder.end(true);
+            self.open = false;
+        }
+        self
+    }
+
+    fn build(mut self) -> Path {
+        if self.open {
+            self.builder.end(false);
+        }
+        self.builder.build()
+    }
+}
+
+// Вершина 2D фігури (як MyVertex колишньої системи трикутника, але в просторі шару)
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Copy, Debug)]
+pub struct NShapeVertex {
+    #[format(R32G32B32_SFLOAT)]
+    pub position: [f32; 3],     // Позиция в пространстве слоя
+    #[format(R32G32B32A32_SFLOAT)]
+    pub color: [f32; 4],        // Цвет
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+struct NShapePushConstants {
+    view_proj: [[f32; 4]; 4],   // Матрица пространства слоя -> clip space
+}
+
+#[derive(Default)]
+struct NShapeState {
+    world: Vec<NShapeVertex>,   // Треугольники мирового слоя (triangle list)
+    screen: Vec<NShapeVertex>,  // Треугольники экранного слоя (triangle list)
+}
+
+/// Дескриптор 2D фігур кадру. Клони ділять один список трикутників.
+#[derive(Clone, Default)]
+pub struct NShapeDraw {
+    state: Arc<Mutex<NShapeState>>,
+}
+
+#[allow(dead_code)]
+impl NShapeDraw {
+    pub fn rect(&self, min: [f32; 2], max: [f32; 2], style: NShapeStyle) {
+        let mut builder = Path::builder();
+        builder.add_rectangle(&rect_box(min, max), Winding::Positive);
+        self.push(&builder.build(), &style);
+    }
+
+    pub fn rounded_rect(&self, min: [f32; 2], max: [f32; 2], radius: f32, style: NShapeStyle) {
+        let mut builder = Path::builder();
+        builder.add_rounded_rectangle(
+            &rect_box(min, max),
+            &BorderRadii::new(radius),
+            Winding::Positive,
+        );
+        self.push(&builder.build(), &style);
+    }
+
+    pub fn circle(&self, center: [f32; 2], radius: f32, style: NShapeStyle) {
+        let mut builder = Path::builder();
+        builder.add_circle(point(center[0], center[1]), radius, Winding::Positive);
+        self.push(&builder.build(), &style);
+    }
+
+    // Замкнений многокутник; самоперетини заливаються за правилом nonzero
+    pub fn polygon(&self, points: &[[f32; 2]], style: NShapeStyle) {
+        if points.len() < 2 {
+            return;
+        }
+        let points: Vec<Point> = points.iter().map(|p| point(p[0], p[1])).collect();
+        let mut builder = Path::builder();
+        builder.add_polygon(Polygon { points: &points, closed: true });
+        self.push(&builder.build(), &style);
+    }
+
+    pub fn path(&self, path: NPath, style: NShapeStyle) {
+        self.push(&path.build(), &style);
+    }
+
+    // Тесселює контур і додає трикутники в список шару стилю.
+    // Вироджений контур, на якому lyon повертає помилку, просто не малюється.
+    fn push(&self, path: &Path, style: &NShapeStyle) {
+        let tolerance = match style.layer {
+            NLayer::World => WORLD_TOLERANCE,
+            NLayer::Screen => SCREEN_TOLERANCE,
+        };
+        let mut buffers: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
+        let result = match style.stroke {
+            Some(width) => StrokeTessellator::new().tessellate_path(
+                path,
+                &StrokeOptions::tolerance(tolerance).with_line_width(width),
+                &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex| {
+                    vertex.position().to_array()
+                }),
+            ),
+            None => FillTessellator::new().tessellate_path(
+                path,
+                &FillOptions::tolerance(tolerance).with_fill_rule(FillRule::NonZero),
+                &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| {
+                    vertex.position().to_array()
+                }),
+            ),
+        };
+        if result.is_err() {
+            return;
+        }
+
+        let vertices = buffers.indices.iter().map(|&index| {
+            let [x, y] = buffers.vertices[index as usize];
+            let position = style.transform * Vector4::new(x, y, 0.0, 1.0);
+            NShapeVertex { position: position.truncate().into(), color: style.color }
+        });
+        let mut state = self.state.lock().unwrap();
+        match style.layer {
+            NLayer::World => state.world.extend(vertices),
+            NLayer::Screen => state.screen.extend(vertices),
+        }
+    }
+
+    // Викликається рендерером після кадру: фігури живуть один кадр
+    pub fn end_frame(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.world.clear();
+        state.screen.clear();
+    }
+
+    // Трикутники шару в порядку викликів
+    fn vertices(&self, layer: NLayer) -> Vec<NShapeVertex> {
+        let state = self.state.lock().unwrap();
+        match layer {
+            NLayer::World => state.world.clone(),
+            NLayer::Screen => state.screen.clone(),
+        }
+    }
+}
+
+fn rect_box(min: [f32; 2], max: [f32; 2]) -> Box2D {
+    Box2D::new(point(min[0], min[1]), point(max[0], max[1]))
+}
+
+// Система відрисовки 2D фігур одного шару
+pub struct NShapeDrawSystem {
+    gfx_queue: Arc<Queue>,                  // Очередь графических команд
+    layer: NLayer,                          // Рисуемый слой
+    shapes: NShapeDraw,                     // Общий список фигур
+    subpass: Subpass,                       // Подпроход рендеринга
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>, // Аллокатор команд
+    pipeline_cache: Arc<PipelineCache>,     // Кэш пайплайнов
+    shaders: NShaderLibrary,                // Библиотека шейдеров (горячая перезагрузка)
+    vs: NShaderHandle,                      // Вершинный шейдер
+    fs: NShaderHandle,                      // Фрагментный шейдер
+    shader_versions: (u64, u64),            // Версии шейдеров, из которых собран пайплайн
+    pipeline: Option<Arc<GraphicsPipeline>>, // Последний удачно собранный пайплайн
+}
+
+impl NShapeDrawSystem {
+    pub fn new(
+        gfx_queue: Arc<Queue>,
+        subpass: Subpass,
+        allocators: &NAllocators,
+        layer: NLayer,
+        shapes: NShapeDraw,
+    ) -> Self {
+        let shaders = allocators.shaders.clone();
+        let vs = shaders.load("shape.vert", NShaderStage::Vertex);
+        let fs = shaders.load("shape.frag", NShaderStage::Fragment);
+
+        let mut system = NShapeDrawSystem {
+            gfx_queue,
+            layer,
+            shapes,
+            subpass,
+            command_buffer_allocator: allocators.command_buffers.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            shaders,
+            vs,
+            fs,
+            shader_versions: (0, 0),
+            pipeline: None,
+        };
+        system.rebuild_pipeline_if_changed();
+        system
+    }
+
+    // Перебудовує пайплайн, якщо змінилась версія хоча б одного шейдера.
+    // При помилці лишається попередній пайплайн, а помилка йде в редактор.
+    fn rebuild_pipeline_if_changed(&mut self) {
+        let versions = (self.shaders.version(self.vs), self.shaders.version(self.fs));
+        if versions == self.shader_versions {
+            return;
+        }
+        self.shader_versions = versions;
+
+        let (Some(vs), Some(fs)) =
+            (self.shaders.entry_point(self.vs), self.shaders.entry_point(self.fs))
+        else {
+            return;
+        };
+        let name = format!("{} ({:?})", self.name(), self.layer);
+        let (queue, cache, subpass) = (&self.gfx_queue, &self.pipeline_cache, &self.subpass);
+        match create_pipeline(queue, cache, subpass, vs, fs, self.layer) {
+            Ok(pipeline) => {
+                self.pipeline = Some(pipeline);
+                self.shaders.set_pipeline_error(&name, None);
+            }
+            Err(error) => self.shaders.set_pipeline_error(&name, Some(error)),
+        }
+    }
+}
+
+fn create_pipeline(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    subpass: &Subpass,
+    vs: EntryPoint,
+    fs: EntryPoint,
+    layer: NLayer,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    let device = gfx_queue.device().clone();
+    let vertex_input_state =
+        NShapeVertex::per_vertex().definition(&vs).map_err(|err| err.to_string())?;
+
+    let stages = [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
+
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    // Фігури можуть бути напівпрозорими: світові перевіряють глибину, але не пишуть її.
+    // У проході overlay глибини немає.
+    let depth_stencil_state = match layer {
+        NLayer::World => Some(DepthStencilState {
+            depth: Some(DepthState { write_enable: false, compare_op: CompareOp::Less }),
+            ..Default::default()
+        }),
+        NLayer::Screen => None,
+    };
+
+    GraphicsPipeline::new(device, Some(pipeline_cache.clone()), GraphicsPipelineCreateInfo {
+        stages: stages.into_iter().collect(),
+        vertex_input_state: Some(vertex_input_state),
+        input_assembly_state: Some(InputAssemblyState::default()),
+        viewport_state: Some(ViewportState::default()),
+        rasterization_state: Some(RasterizationState::default()),
+        multisample_state: Some(MultisampleState {
+            rasterization_samples: subpass.num_samples().unwrap_or(SampleCount::Sample1),
+            ..Default::default()
+        }),
+        color_blend_state: Some(ColorBlendState::with_attachment_states(
+            subpass.num_color_attachments(),
+            ColorBlendAttachmentState {
+                blend: Some(AttachmentBlend::alpha()),
+                ..Default::default()
+            },
+        )),
+        depth_stencil_state,
+        dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+        subpass: Some(subpass.clone().into()),
+        ..GraphicsPipelineCreateInfo::layout(layout)
+    })
+    .map_err(|err| err.to_string())
+}
+
+impl NDrawSystem for NShapeDrawSystem {
+    // draw: трикутники всіх фігур шару за кадр — один вершинний буфер кільця кадрів
+    // і один draw у вторинному буфері
+    fn draw(&mut self, frame: &NFrameContext) -> Vec<Arc<SecondaryAutoCommandBuffer>> {
+        self.rebuild_pipeline_if_changed();
+        let Some(pipeline) = self.pipeline.clone() else { return Vec::new() };
+        let vertices = self.shapes.vertices(self.layer);
+        if vertices.is_empty() {
+            return Vec::new();
+        }
+        let vertex_buffer = frame.ring.vertices(&vertices);
+
+        let [width, height] = frame.viewport_dimensions.map(|extent| extent as f32);
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.clone(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(self.subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        builder
+            .bind_pipeline_graphics(pipeline.clone())
+            .unwrap()
+            .set_viewport(
+                0,
+                [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [width, height],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap()
+            .push_constants(pipeline.layout().clone(), 0, NShapePushConstants {
+                view_proj: self.layer.view_projection(frame).into(),
+            })
+            .unwrap()
+            .bind_vertex_buffers(0, vertex_buffer)
+            .unwrap();
+        unsafe {
+            builder.draw(vertices.len() as u32, 1, 0, 0).unwrap();
+        }
+
+        frame.stats.lock().unwrap().batches.push(NBatchStats {
+            system: "shape",
+            name: format!("{:?}", self.layer).to_lowercase(),
+            instances: (vertices.len() / 3) as u32,
+        });
+        vec![builder.build().unwrap()]
+    }
+}
@@ -0,0 +1,303 @@
+use std::sync::Arc;
+
+use cgmath::{Matrix4, SquareMatrix};
+use vulkano::{
+    buffer::BufferContents,
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+        CommandBufferInheritanceInfo, CommandBufferUsage, SecondaryAutoCommandBuffer,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::Queue,
+    image::SampleCount,
+    pipeline::{
+        cache::PipelineCache,
+        graphics::{
+            color_blend::{ColorBlendAttachmentState, ColorBlendState},
+            depth_stencil::{CompareOp, DepthState, DepthStencilState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::VertexInputState,
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::Subpass,
+    shader::EntryPoint,
+};
+
+use crate::{
+    graphics::{
+        asset::NAssetId,
+        environment::{NEnvironment, NEnvironmentCache},
+        graph::{NCachedCommandBuffer, NDrawSystem, NFrameContext},
+        pipeline::NAllocators,
+        shader::{NShaderHandle, NShaderLibrary, NShaderStage},
+    },
+    scene::camera::NBackground,
+};
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy, PartialEq)]
+struct NSkyboxPushConstants {
+    inverse_view_proj: [[f32; 4]; 4],   // Матрица clip space -> мир
+    environment: [f32; 4],              // x — интенсивность окружения
+    zenith: [f32; 4],                   // Цвета градиентного неба (rgb)
+    horizon: [f32; 4],
+    ground: [f32; 4],
+}
+
+// Пайплайн одного варіанта фону (skybox.frag з define-ом або без)
+struct NSkyboxPipeline {
+    fs: NShaderHandle,              // Фрагментный шейдер варианта
+    shader_versions: (u64, u64),    // Версии шейдеров, из которых собран пайплайн
+    pipeline: Option<Arc<GraphicsPipeline>>,  // Последний удачно собранный пайплайн
+}
+
+// Система відрисовки фону камери (NBackground): skybox-а з кубмапи оточення
+// сцени або процедурного градієнтного неба; фон-колір — це лише очищення кадру.
+// Малює повноекранний трикутник на дальній площині (z = 1) з перевіркою
+// глибини LessOrEqual без запису, тому реєструється після непрозорих мешів
+// і заповнює лише пікселі, не закриті геометрією.
+pub struct NSkyboxDrawSystem {
+    gfx_queue: Arc<Queue>,          // Очередь графических команд
+    subpass: Subpass,               // Подпроход рендеринга
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>, // Аллокатор команд
+    descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>, // Аллокатор дескрипторов
+    pipeline_cache: Arc<PipelineCache>,  // Кэш пайплайнов
+    shaders: NShaderLibrary,        // Библиотека шейдеров (горячая перезагрузка)
+    environments: NEnvironmentCache, // Запечённые окружения
+    vs: NShaderHandle,              // Вершинный шейдер
+    skybox: NSkyboxPipeline,        // Кубмапа окружения
+    gradient: NSkyboxPipeline,      // Градиентное небо (GRADIENT_SKY)
+    descriptor_set: Option<(Option<NAssetId>, Arc<DescriptorSet>)>, // Набор для окружения
+    commands: NCachedCommandBuffer<NSkyboxKey>, // Записанный буфер и его параметры
+}
+
+// Від чого залежить записаний командний буфер фону
+type NSkyboxKey = (Arc<GraphicsPipeline>, Option<NAssetId>, [u32; 2], NSkyboxPushConstants);
+
+impl NSkyboxDrawSystem {
+    pub fn new(
+        gfx_queue: Arc<Queue>,
+        subpass: Subpass,
+        allocators: &NAllocators,
+        environments: NEnvironmentCache,
+    ) -> NSkyboxDrawSystem {
+        let shaders = allocators.shaders.clone();
+        let vs = shaders.load("skybox.vert", NShaderStage::Vertex);
+        let variant = |defines: &[&str]| NSkyboxPipeline {
+            fs: shaders.load_variant("skybox.frag", NShaderStage::Fragment, defines),
+            shader_versions: (0, 0),
+            pipeline: None,
+        };
+        let skybox = variant(&[]);
+        let gradient = variant(&["GRADIENT_SKY"]);
+
+        let mut system = NSkyboxDrawSystem {
+            gfx_queue,
+            subpass,
+            command_buffer_allocator: allocators.command_buffers.clone(),
+            descriptor_set_allocator: allocators.descriptor_sets.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            shaders,
+            environments,
+            vs,
+            skybox,
+            gradient,
+            descriptor_set: None,
+            commands: NCachedCommandBuffer::default(),
+        };
+        system.rebuild_pipelines_if_changed();
+        system
+    }
+
+    // Перебудовує пайплайни варіантів, у яких змінилася версія хоча б одного шейдера.
+    // При помилці лишається попередній пайплайн, а помилка йде в редактор.
+    fn rebuild_pipelines_if_changed(&mut self) {
+        let vs_version = self.shaders.version(self.vs);
+        let name = self.name();
+        for (variant, sky) in [("skybox", &mut self.skybox), ("gradient", &mut self.gradient)] {
+            let versions = (vs_version, self.shaders.version(sky.fs));
+            if versions == sky.shader_versions {
+                continue;
+            }
+            sky.shader_versions = versions;
+
+            let (Some(vs), Some(fs)) =
+                (self.shaders.entry_point(self.vs), self.shaders.entry_point(sky.fs))
+            else {
+                continue;
+            };
+            let name = format!("{} ({})", name, variant);
+            match create_pipeline(&self.gfx_queue, &self.pipeline_cache, &self.subpass, vs, fs) {
+                Ok(pipeline) => {
+                    sky.pipeline = Some(pipeline);
+                    // Набір оточення створено під layout попереднього пайплайну
+                    self.descriptor_set = None;
+                    self.shaders.set_pipeline_error(&name, None);
+                }
+                Err(error) => self.shaders.set_pipeline_error(&name, Some(error)),
+            }
+        }
+    }
+
+    // Набір дескрипторів з кубмапою оточення для пайплайну skybox-а
+    fn environment_set(
+        &mut self,
+        pipeline: &Arc<GraphicsPipeline>,
+        environment: Option<&NEnvironment>,
+    ) -> Arc<DescriptorSet> {
+        let environment_id = environment.map(|environment| environment.id());
+        if let Some((id, set)) = &self.descriptor_set {
+            if *id == environment_id {
+                return set.clone();
+            }
+        }
+        let maps = self.environments.get(environment);
+        let set = DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            pipeline.layout().set_layouts()[0].clone(),
+            [
+                WriteDescriptorSet::image_view(0, maps.environment.clone()),
+                WriteDescriptorSet::sampler(1, maps.sampler.clone()),
+            ],
+            [],
+        )
+        .unwrap();
+        self.descriptor_set = Some((environment_id, set.clone()));
+        set
+    }
+}
+
+fn create_pipeline(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    subpass: &Subpass,
+    vs: EntryPoint,
+    fs: EntryPoint,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    let device = gfx_queue.device().clone();
+    let stages = [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
+
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    GraphicsPipeline::new(device, Some(pipeline_cache.clone()), GraphicsPipelineCreateInfo {
+        stages: stages.into_iter().collect(),
+        vertex_input_state: Some(VertexInputState::default()),
+        input_assembly_state: Some(InputAssemblyState::default()),
+        viewport_state: Some(ViewportState::default()),
+        rasterization_state: Some(RasterizationState::default()),
+        multisample_state: Some(MultisampleState {
+            rasterization_samples: subpass.num_samples().unwrap_or(SampleCount::Sample1),
+            ..Default::default()
+        }),
+        color_blend_state: Some(ColorBlendState::with_attachment_states(
+            subpass.num_color_attachments(),
+            ColorBlendAttachmentState::default(),
+        )),
+        // Глибина очищується в 1.0, тому skybox проходить лише там, де нічого не намальовано
+        depth_stencil_state: Some(DepthStencilState {
+            depth: Some(DepthState { write_enable: false, compare_op: CompareOp::LessOrEqual }),
+            ..Default::default()
+        }),
+        dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+        subpass: Some(subpass.clone().into()),
+        ..GraphicsPipelineCreateInfo::layout(layout)
+    })
+    .map_err(|err| err.to_string())
+}
+
+impl NDrawSystem for NSkyboxDrawSystem {
+    // draw: буфер перезаписується лише коли змінилися пайплайн, окружение, розмір
+    // viewport або push constants (камера, інтенсивність, кольори неба); інакше
+    // повертається записаний раніше (SimultaneousUse — він може бути в кількох кадрах
+    // у польоті). Фон-колір нічого не малює.
+    fn draw(&mut self, frame: &NFrameContext) -> Vec<Arc<SecondaryAutoCommandBuffer>> {
+        self.rebuild_pipelines_if_changed();
+        let viewport_dimensions = frame.viewport_dimensions;
+        let (pipeline, sky) = match frame.scene.camera.background {
+            NBackground::Color(_) => return Vec::new(),
+            NBackground::Skybox => (self.skybox.pipeline.clone(), None),
+            NBackground::Gradient(sky) => (self.gradient.pipeline.clone(), Some(sky)),
+        };
+        let Some(pipeline) = pipeline else { return Vec::new() };
+
+        // Градієнт не читає оточення, тож і не залежить від нього
+        let environment = frame.scene.environment.as_deref();
+        let (environment_id, descriptor_set) = match sky {
+            Some(_) => (None, None),
+            None => (
+                environment.map(|environment| environment.id()),
+                Some(self.environment_set(&pipeline, environment)),
+            ),
+        };
+        let inverse_view_proj =
+            frame.world_to_framebuffer.invert().unwrap_or_else(Matrix4::identity);
+        let sky = sky.unwrap_or_default();
+        let color = |[r, g, b]: [f32; 3]| [r, g, b, 1.0];
+        let push_constants = NSkyboxPushConstants {
+            inverse_view_proj: inverse_view_proj.into(),
+            environment: [self.environments.intensity(environment), 0.0, 0.0, 0.0],
+            zenith: color(sky.zenith),
+            horizon: color(sky.horizon),
+            ground: color(sky.ground),
+        };
+
+        let key = (pipeline.clone(), environment_id, viewport_dimensions, push_constants);
+        vec![self.commands.get_or_record(key, || {
+            let mut builder = AutoCommandBufferBuilder::secondary(
+                self.command_buffer_allocator.clone(),
+                self.gfx_queue.queue_family_index(),
+                CommandBufferUsage::SimultaneousUse,
+                CommandBufferInheritanceInfo {
+                    render_pass: Some(self.subpass.clone().into()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            builder
+                .bind_pipeline_graphics(pipeline.clone())
+                .unwrap()
+                .set_viewport(
+                    0,
+                    [Viewport {
+                        offset: [0.0, 0.0],
+                        extent: [viewport_dimensions[0] as f32, viewport_dimensions[1] as f32],
+                        depth_range: 0.0..=1.0,
+                    }]
+                    .into_iter()
+                    .collect(),
+                )
+                .unwrap()
+                .push_constants(pipeline.layout().clone(), 0, push_constants)
+                .unwrap();
+            if let Some(descriptor_set) = descriptor_set {
+                builder
+                    .bind_descriptor_sets(
+                        PipelineBindPoint::Graphics,
+                        pipeline.layout().clone(),
+                        0,
+                        descriptor_set,
+                    )
+                    .unwrap();
+            }
+            unsafe {
+                builder.draw(3, 1, 0, 0).unwrap();
+            }
+            builder.build().unwrap()
+        })]
+    }
+}
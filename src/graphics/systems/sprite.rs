@@ -0,0 +1,433 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use cgmath::{Matrix4, Vector4};
+use vulkano::{
+    buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+        CommandBufferInheritanceInfo, CommandBufferUsage, SecondaryAutoCommandBuffer,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::Queue,
+    image::{
+        sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
+        SampleCount,
+    },
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter},
+    pipeline::{
+        cache::PipelineCache,
+        graphics::{
+            color_blend::{AttachmentBlend, ColorBlendAttachmentState, ColorBlendState},
+            depth_stencil::{CompareOp, DepthState, DepthStencilState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::{Vertex, VertexDefinition},
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::Subpass,
+    shader::EntryPoint,
+};
+
+use crate::graphics::{
+    asset::NAssetId,
+    graph::{NDrawSystem, NFrameContext},
+    mesh::{NMesh, NMeshVertex},
+    pipeline::NAllocators,
+    settings::{NViewMode, FLAT_VIEW_MODES},
+    shader::{NShaderHandle, NShaderLibrary, NShaderStage},
+    stats::NBatchStats,
+    texture::{NTexture, NTextureCache},
+};
+
+// Дані одного спрайта: матриця local -> world (з розміром), колір та область текстури
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Copy)]
+pub struct NSpriteInstance {
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_x: [f32; 4],      // Столбцы матрицы модели
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_y: [f32; 4],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_z: [f32; 4],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_w: [f32; 4],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub color: [f32; 4],        // Цвет инстанса
+    #[format(R32G32B32A32_SFLOAT)]
+    pub uv_rect: [f32; 4],      // Область текстуры (x, y, ширина, высота)
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+struct NSpritePushConstants {
+    view_proj: [[f32; 4]; 4],   // Матрица мир -> clip space
+}
+
+// Батч спрайтів з однією текстурою
+struct NSpriteBatch {
+    texture: Arc<NTexture>,                     // Текстура батча
+    instances: Vec<(f32, NSpriteInstance)>,     // Инстансы с глубиной для сортировки
+}
+
+// Батч, готовий до запису на воркері (інстанси вже відсортовані)
+struct NSpriteDraw {
+    descriptor_set: Arc<DescriptorSet>,         // Набор с текстурой батча
+    name: String,                               // Имя текстуры для статистики
+    instances: Vec<NSpriteInstance>,            // Инстансы от дальних к ближним
+}
+
+// Мінімум батчів у чанку: менший чанк не окупає окремий вторинний буфер
+const SPRITE_CHUNK_BATCHES: usize = 8;
+
+// Система інстансованої відрисовки спрайтів сутностей
+pub struct NSpriteDrawSystem {
+    gfx_queue: Arc<Queue>,                  // Очередь графических команд
+    subpass: Subpass,                       // Подпроход рендеринга
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>, // Аллокатор команд
+    descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>, // Аллокатор дескрипторов
+    pipeline_cache: Arc<PipelineCache>,     // Кэш пайплайнов
+    shaders: NShaderLibrary,                // Библиотека шейдеров (горячая перезагрузка)
+    vs: NShaderHandle,                      // Вершинный шейдер
+    fs: [NShaderHandle; 3],                 // Фрагментные шейдеры по FLAT_VIEW_MODES
+    shader_versions: Vec<u64>,              // Версии шейдеров, из которых собраны пайплайны
+    pipelines: Option<Vec<Arc<GraphicsPipeline>>>, // Последние удачно собранные пайплайны
+    quad_vertices: Subbuffer<[NMeshVertex]>,// Вершины единичного квадрата
+    quad_indices: Subbuffer<[u32]>,         // Индексы квадрата
+    sampler: Arc<Sampler>,                  // Сэмплер текстур спрайтов
+    white: Arc<NTexture>,                   // Текстура для спрайтов без текстуры
+    textures: NTextureCache,                // Загруженные текстуры
+    descriptor_sets: HashMap<NAssetId, Arc<DescriptorSet>>, // Наборы дескрипторов по текстуре
+}
+
+impl NSpriteDrawSystem {
+    pub fn new(gfx_queue: Arc<Queue>, subpass: Subpass, allocators: &NAllocators) -> Self {
+        let device = gfx_queue.device().clone();
+        let shaders = allocators.shaders.clone();
+        let vs = shaders.load("sprite.vert", NShaderStage::Vertex);
+        let fs = FLAT_VIEW_MODES.map(|mode| {
+            let defines: Vec<&str> = mode.define().into_iter().collect();
+            shaders.load_variant("sprite.frag", NShaderStage::Fragment, &defines)
+        });
+
+        let quad = NMesh::quad();
+        let allocation_info = || AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        };
+        let quad_vertices = Buffer::from_iter(
+            allocators.memory.clone(),
+            BufferCreateInfo { usage: BufferUsage::VERTEX_BUFFER, ..Default::default() },
+            allocation_info(),
+            quad.vertices.iter().copied(),
+        )
+        .unwrap();
+        let quad_indices = Buffer::from_iter(
+            allocators.memory.clone(),
+            BufferCreateInfo { usage: BufferUsage::INDEX_BUFFER, ..Default::default() },
+            allocation_info(),
+            quad.indices.iter().copied(),
+        )
+        .unwrap();
+
+        let sampler = Sampler::new(device, SamplerCreateInfo {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            address_mode: [SamplerAddressMode::ClampToEdge; 3],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut system = NSpriteDrawSystem {
+            gfx_queue: gfx_queue.clone(),
+            subpass,
+            command_buffer_allocator: allocators.command_buffers.clone(),
+            descriptor_set_allocator: allocators.descriptor_sets.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            shaders,
+            vs,
+            fs,
+            shader_versions: Vec::new(),
+            pipelines: None,
+            quad_vertices,
+            quad_indices,
+            sampler,
+            white: Arc::new(NTexture::white()),
+            textures: NTextureCache::new(gfx_queue, allocators),
+            descriptor_sets: HashMap::new(),
+        };
+        system.rebuild_pipelines_if_changed();
+        system
+    }
+
+    // Перебудовує пайплайни режимів, якщо змінилась версія хоча б одного шейдера.
+    // При помилці лишаються попередні пайплайни, а помилка йде в редактор.
+    fn rebuild_pipelines_if_changed(&mut self) {
+        let versions: Vec<u64> = std::iter::once(self.vs)
+            .chain(self.fs)
+            .map(|handle| self.shaders.version(handle))
+            .collect();
+        if versions == self.shader_versions {
+            return;
+        }
+        self.shader_versions = versions;
+
+        let Some(vs) = self.shaders.entry_point(self.vs) else { return };
+        let fs = self.fs.map(|handle| self.shaders.entry_point(handle));
+        if fs.iter().any(Option::is_none) {
+            return;
+        }
+        let fs = fs.map(Option::unwrap);
+        match create_pipelines(&self.gfx_queue, &self.pipeline_cache, &self.subpass, vs, fs) {
+            Ok(pipelines) => {
+                self.pipelines = Some(pipelines);
+                // Набори створено під layout попередніх пайплайнів
+                self.descriptor_sets.clear();
+                self.shaders.set_pipeline_error(self.name(), None);
+            }
+            Err(error) => self.shaders.set_pipeline_error(self.name(), Some(error)),
+        }
+    }
+
+    fn descriptor_set(
+        &mut self,
+        pipeline: &Arc<GraphicsPipeline>,
+        texture: &NTexture,
+    ) -> Arc<DescriptorSet> {
+        if let Some(set) = self.descriptor_sets.get(&texture.id()) {
+            return set.clone();
+        }
+        let set = DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            pipeline.layout().set_layouts()[0].clone(),
+            [
+                WriteDescriptorSet::image_view(0, self.textures.get(texture)),
+                WriteDescriptorSet::sampler(1, self.sampler.clone()),
+            ],
+            [],
+        )
+        .unwrap();
+        self.descriptor_sets.insert(texture.id(), set.clone());
+        set
+    }
+}
+
+// Пайплайни в порядку FLAT_VIEW_MODES. Layout спільний і береться з Lit варіанта,
+// тож набори дескрипторів підходять до всіх режимів, навіть якщо шейдер варіанта
+// текстуру не читає.
+fn create_pipelines(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    subpass: &Subpass,
+    vs: EntryPoint,
+    fs: [EntryPoint; 3],
+) -> Result<Vec<Arc<GraphicsPipeline>>, String> {
+    let device = gfx_queue.device().clone();
+    let stages = [
+        PipelineShaderStageCreateInfo::new(vs.clone()),
+        PipelineShaderStageCreateInfo::new(fs[0].clone()),
+    ];
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device)
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    FLAT_VIEW_MODES
+        .into_iter()
+        .zip(fs)
+        .map(|(mode, fs)| {
+            create_pipeline(gfx_queue, pipeline_cache, subpass, &layout, vs.clone(), fs, mode)
+        })
+        .collect()
+}
+
+fn create_pipeline(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    subpass: &Subpass,
+    layout: &Arc<PipelineLayout>,
+    vs: EntryPoint,
+    fs: EntryPoint,
+    mode: NViewMode,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    let device = gfx_queue.device().clone();
+    let vertex_input_state = [NMeshVertex::per_vertex(), NSpriteInstance::per_instance()]
+        .definition(&vs)
+        .map_err(|err| err.to_string())?;
+
+    let stages = [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
+
+    // Overdraw складає внески шарів адитивно і рахує всі шари, без тесту глибини
+    let (blend, depth_stencil_state) = match mode {
+        NViewMode::Overdraw => (AttachmentBlend::additive(), DepthStencilState::default()),
+        _ => (AttachmentBlend::alpha(), DepthStencilState {
+            depth: Some(DepthState { write_enable: false, compare_op: CompareOp::Less }),
+            ..Default::default()
+        }),
+    };
+
+    // Спрайти напівпрозорі: перевіряють глибину, але не пишуть її
+    GraphicsPipeline::new(device, Some(pipeline_cache.clone()), GraphicsPipelineCreateInfo {
+        stages: stages.into_iter().collect(),
+        vertex_input_state: Some(vertex_input_state),
+        input_assembly_state: Some(InputAssemblyState::default()),
+        viewport_state: Some(ViewportState::default()),
+        rasterization_state: Some(RasterizationState::default()),
+        multisample_state: Some(MultisampleState {
+            rasterization_samples: subpass.num_samples().unwrap_or(SampleCount::Sample1),
+            ..Default::default()
+        }),
+        color_blend_state: Some(ColorBlendState::with_attachment_states(
+            subpass.num_color_attachments(),
+            ColorBlendAttachmentState { blend: Some(blend), ..Default::default() },
+        )),
+        depth_stencil_state: Some(depth_stencil_state),
+        dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+        subpass: Some(subpass.clone().into()),
+        ..GraphicsPipelineCreateInfo::layout(layout.clone())
+    })
+    .map_err(|err| err.to_string())
+}
+
+impl NDrawSystem for NSpriteDrawSystem {
+    // draw:
+    // - групує спрайти видимих сутностей за текстурою,
+    // - всередині батча сортує інстанси від дальніх до ближніх (альфа-блендінг),
+    // - записує батчі чанками паралельно на воркерах, кожен батч одним
+    //   draw_indexed по квадрату; порядок батчів зберігається.
+    fn draw(&mut self, frame: &NFrameContext) -> Vec<Arc<SecondaryAutoCommandBuffer>> {
+        self.rebuild_pipelines_if_changed();
+        let Some(pipelines) = &self.pipelines else { return Vec::new() };
+        let pipeline = pipelines[frame.settings.view_mode.flat_index()].clone();
+        let mut batches: BTreeMap<NAssetId, NSpriteBatch> = BTreeMap::new();
+        for entity in frame.visible_entities() {
+            let Some(sprite) = &entity.sprite else { continue };
+            let texture = sprite.texture.clone().unwrap_or_else(|| self.white.clone());
+            let model = entity.transform.matrix()
+                * Matrix4::from_nonuniform_scale(sprite.size[0], sprite.size[1], 1.0);
+            let depth = (frame.world_to_framebuffer * model * Vector4::unit_w()).w;
+            batches
+                .entry(texture.id())
+                .or_insert_with(|| NSpriteBatch { texture, instances: Vec::new() })
+                .instances
+                .push((depth, NSpriteInstance {
+                    model_x: model.x.into(),
+                    model_y: model.y.into(),
+                    model_z: model.z.into(),
+                    model_w: model.w.into(),
+                    color: sprite.color,
+                    uv_rect: sprite.uv_rect,
+                }));
+        }
+
+        let draws: Vec<NSpriteDraw> = batches
+            .into_values()
+            .map(|mut batch| {
+                batch.instances.sort_by(|a, b| b.0.total_cmp(&a.0));
+                NSpriteDraw {
+                    descriptor_set: self.descriptor_set(&pipeline, &batch.texture),
+                    name: batch.texture.name.clone(),
+                    instances: batch.instances.into_iter().map(|(_, instance)| instance).collect(),
+                }
+            })
+            .collect();
+
+        let chunks = frame
+            .workers
+            .map_chunks(&draws, SPRITE_CHUNK_BATCHES, |chunk| {
+                self.record_chunk(frame, &pipeline, chunk)
+            });
+        let mut stats = frame.stats.lock().unwrap();
+        chunks
+            .into_iter()
+            .map(|(command_buffer, batches)| {
+                stats.batches.extend(batches);
+                command_buffer
+            })
+            .collect()
+    }
+}
+
+impl NSpriteDrawSystem {
+    // Записує чанк батчів у власний вторинний буфер (на воркері)
+    fn record_chunk(
+        &self,
+        frame: &NFrameContext,
+        pipeline: &Arc<GraphicsPipeline>,
+        draws: &[NSpriteDraw],
+    ) -> (Arc<SecondaryAutoCommandBuffer>, Vec<NBatchStats>) {
+        let viewport_dimensions = frame.viewport_dimensions;
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.clone(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(self.subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        builder
+            .bind_pipeline_graphics(pipeline.clone())
+            .unwrap()
+            .set_viewport(
+                0,
+                [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [viewport_dimensions[0] as f32, viewport_dimensions[1] as f32],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap()
+            .push_constants(pipeline.layout().clone(), 0, NSpritePushConstants {
+                view_proj: frame.world_to_framebuffer.into(),
+            })
+            .unwrap()
+            .bind_index_buffer(self.quad_indices.clone())
+            .unwrap();
+
+        let mut stats = Vec::with_capacity(draws.len());
+        for draw in draws {
+            let count = draw.instances.len();
+            let instance_buffer = frame.ring.vertices(&draw.instances);
+            builder
+                .bind_descriptor_sets(
+                    PipelineBindPoint::Graphics,
+                    pipeline.layout().clone(),
+                    0,
+                    draw.descriptor_set.clone(),
+                )
+                .unwrap()
+                .bind_vertex_buffers(0, (self.quad_vertices.clone(), instance_buffer))
+                .unwrap();
+            unsafe {
+                builder
+                    .draw_indexed(self.quad_indices.len() as u32, count as u32, 0, 0, 0)
+                    .unwrap();
+            }
+
+            stats.push(NBatchStats {
+                system: "sprite",
+                name: draw.name.clone(),
+                instances: count as u32,
+            });
+        }
+        (builder.build().unwrap(), stats)
+    }
+}
@@ -0,0 +1,490 @@
+// Система відрисовки тексту (NText) гліфами з SDF атласів шрифтів.
+// Як і спрайти, гліфи — інстанси одиничного квадрата, згруповані в батч за шрифтом:
+// один draw_indexed на атлас. Кожен екземпляр системи малює один шар (NLayer):
+// - World — текст видимих сутностей у площині XY їхнього трансформа, у проході
+//   сцени з перевіркою глибини без запису; гліфи сортуються від дальніх до ближніх,
+// - Screen — екранний текст (HUD) у пікселях кадру та підписи debug text_3d,
+//   у проході overlay поверх пост-обробки; порядок — порядок сутностей у сцені.
+// Заливку, обводку й тінь фрагментний шейдер будує з відстані в атласі.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use cgmath::{Matrix4, Vector3, Vector4};
+use vulkano::{
+    buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+        CommandBufferInheritanceInfo, CommandBufferUsage, SecondaryAutoCommandBuffer,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::Queue,
+    image::{
+        sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
+        SampleCount,
+    },
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter},
+    pipeline::{
+        cache::PipelineCache,
+        graphics::{
+            color_blend::{AttachmentBlend, ColorBlendAttachmentState, ColorBlendState},
+            depth_stencil::{CompareOp, DepthState, DepthStencilState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::{Vertex, VertexDefinition},
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::Subpass,
+    shader::EntryPoint,
+};
+
+use crate::{
+    graphics::{
+        asset::NAssetId,
+        font::NFont,
+        frame::NLayer,
+        graph::{NDrawSystem, NFrameContext},
+        mesh::{NMesh, NMeshVertex},
+        pipeline::NAllocators,
+        shader::{NShaderHandle, NShaderLibrary, NShaderStage},
+        stats::NBatchStats,
+        systems::debug::NDebugDraw,
+        texture::NTextureCache,
+    },
+    scene::text::{NText, NTextShadow, NTextSpace},
+};
+
+// Розмір підписів debug text_3d у пікселях
+const DEBUG_LABEL_SIZE: f32 = 14.0;
+
+// Дані одного гліфа: матриця квадрата, кольори та параметри SDF ефектів
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Copy)]
+pub struct NGlyphInstance {
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_x: [f32; 4],      // Столбцы матрицы квадрата глифа
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_y: [f32; 4],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_z: [f32; 4],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub model_w: [f32; 4],
+    #[format(R32G32B32A32_SFLOAT)]
+    pub color: [f32; 4],        // Цвет заливки
+    #[format(R32G32B32A32_SFLOAT)]
+    pub uv_rect: [f32; 4],      // Область атласа (x, y, ширина, высота)
+    #[format(R32G32B32A32_SFLOAT)]
+    pub outline_color: [f32; 4], // Цвет обводки (без обводки — цвет заливки)
+    #[format(R32G32B32A32_SFLOAT)]
+    pub shadow_color: [f32; 4], // Цвет тени (без тени — прозрачный)
+    #[format(R32G32B32A32_SFLOAT)]
+    pub effects: [f32; 4],      // Ширина обводки, сдвиг тени (UV), размытие тени
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+struct NTextPushConstants {
+    view_proj: [[f32; 4]; 4],   // Матрица пространства слоя -> clip space
+}
+
+// Гліфи одного шрифту
+struct NTextBatch {
+    font: Arc<NFont>,                           // Шрифт батча
+    instances: Vec<(f32, NGlyphInstance)>,      // Инстансы с глубиной для сортировки
+}
+
+// Батч, готовий до запису
+struct NTextDraw {
+    descriptor_set: Arc<DescriptorSet>,         // Набор с атласом шрифта
+    name: String,                               // Имя шрифта для статистики
+    instances: Vec<NGlyphInstance>,             // Инстансы в порядке отрисовки
+}
+
+// Система інстансованої відрисовки тексту одного шару
+pub struct NTextDrawSystem {
+    gfx_queue: Arc<Queue>,                  // Очередь графических команд
+    layer: NLayer,                      // Рисуемый слой
+    subpass: Subpass,                       // Подпроход рендеринга
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>, // Аллокатор команд
+    descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>, // Аллокатор дескрипторов
+    pipeline_cache: Arc<PipelineCache>,     // Кэш пайплайнов
+    shaders: NShaderLibrary,                // Библиотека шейдеров (горячая перезагрузка)
+    vs: NShaderHandle,                      // Вершинный шейдер
+    fs: NShaderHandle,                      // Фрагментный шейдер
+    shader_versions: (u64, u64),            // Версии шейдеров, из которых собран пайплайн
+    pipeline: Option<Arc<GraphicsPipeline>>, // Последний удачно собранный пайплайн
+    quad_vertices: Subbuffer<[NMeshVertex]>,// Вершины единичного квадрата
+    quad_indices: Subbuffer<[u32]>,         // Индексы квадрата
+    sampler: Arc<Sampler>,                  // Линейный сэмплер атласов
+    textures: NTextureCache,                // Загруженные атласы
+    descriptor_sets: HashMap<NAssetId, Arc<DescriptorSet>>, // Наборы дескрипторов по шрифту
+    debug_draw: NDebugDraw,                 // Подписи text_3d (слой Screen)
+}
+
+impl NTextDrawSystem {
+    pub fn new(
+        gfx_queue: Arc<Queue>,
+        subpass: Subpass,
+        allocators: &NAllocators,
+        layer: NLayer,
+        debug_draw: NDebugDraw,
+    ) -> Self {
+        let device = gfx_queue.device().clone();
+        let shaders = allocators.shaders.clone();
+        let vs = shaders.load("text.vert", NShaderStage::Vertex);
+        let fs = shaders.load("text.frag", NShaderStage::Fragment);
+
+        let quad = NMesh::quad();
+        let allocation_info = || AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        };
+        let quad_vertices = Buffer::from_iter(
+            allocators.memory.clone(),
+            BufferCreateInfo { usage: BufferUsage::VERTEX_BUFFER, ..Default::default() },
+            allocation_info(),
+            quad.vertices.iter().copied(),
+        )
+        .unwrap();
+        let quad_indices = Buffer::from_iter(
+            allocators.memory.clone(),
+            BufferCreateInfo { usage: BufferUsage::INDEX_BUFFER, ..Default::default() },
+            allocation_info(),
+            quad.indices.iter().copied(),
+        )
+        .unwrap();
+
+        let sampler = Sampler::new(device, SamplerCreateInfo {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            address_mode: [SamplerAddressMode::ClampToEdge; 3],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut system = NTextDrawSystem {
+            gfx_queue: gfx_queue.clone(),
+            layer,
+            subpass,
+            command_buffer_allocator: allocators.command_buffers.clone(),
+            descriptor_set_allocator: allocators.descriptor_sets.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            shaders,
+            vs,
+            fs,
+            shader_versions: (0, 0),
+            pipeline: None,
+            quad_vertices,
+            quad_indices,
+            sampler,
+            textures: NTextureCache::new(gfx_queue, allocators),
+            descriptor_sets: HashMap::new(),
+            debug_draw,
+        };
+        system.rebuild_pipeline_if_changed();
+        system
+    }
+
+    // Перебудовує пайплайн, якщо змінилась версія хоча б одного шейдера.
+    // При помилці лишається попередній пайплайн, а помилка йде в редактор.
+    fn rebuild_pipeline_if_changed(&mut self) {
+        let versions = (self.shaders.version(self.vs), self.shaders.version(self.fs));
+        if versions == self.shader_versions {
+            return;
+        }
+        self.shader_versions = versions;
+
+        let (Some(vs), Some(fs)) =
+            (self.shaders.entry_point(self.vs), self.shaders.entry_point(self.fs))
+        else {
+            return;
+        };
+        let name = format!("{} ({:?})", self.name(), self.layer);
+        let (queue, cache, subpass) = (&self.gfx_queue, &self.pipeline_cache, &self.subpass);
+        match create_pipeline(queue, cache, subpass, vs, fs, self.layer) {
+            Ok(pipeline) => {
+                self.pipeline = Some(pipeline);
+                // Набори створено під layout попереднього пайплайну
+                self.descriptor_sets.clear();
+                self.shaders.set_pipeline_error(&name, None);
+            }
+            Err(error) => self.shaders.set_pipeline_error(&name, Some(error)),
+        }
+    }
+
+    // Набір дескрипторів з атласом шрифту
+    fn descriptor_set(
+        &mut self,
+        pipeline: &Arc<GraphicsPipeline>,
+        font: &NFont,
+    ) -> Arc<DescriptorSet> {
+        if let Some(set) = self.descriptor_sets.get(&font.id()) {
+            return set.clone();
+        }
+        let set = DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            pipeline.layout().set_layouts()[0].clone(),
+            [
+                WriteDescriptorSet::image_view(0, self.textures.get(&font.atlas)),
+                WriteDescriptorSet::sampler(1, self.sampler.clone()),
+            ],
+            [],
+        )
+        .unwrap();
+        self.descriptor_sets.insert(font.id(), set.clone());
+        set
+    }
+}
+
+fn create_pipeline(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    subpass: &Subpass,
+    vs: EntryPoint,
+    fs: EntryPoint,
+    layer: NLayer,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    let device = gfx_queue.device().clone();
+    let vertex_input_state = [NMeshVertex::per_vertex(), NGlyphInstance::per_instance()]
+        .definition(&vs)
+        .map_err(|err| err.to_string())?;
+
+    let stages = [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
+
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device.clone())
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    // Світовий текст напівпрозорий, як спрайти: перевіряє глибину, але не пише її.
+    // У проході overlay глибини немає.
+    let depth_stencil_state = match layer {
+        NLayer::World => Some(DepthStencilState {
+            depth: Some(DepthState { write_enable: false, compare_op: CompareOp::Less }),
+            ..Default::default()
+        }),
+        NLayer::Screen => None,
+    };
+
+    GraphicsPipeline::new(device, Some(pipeline_cache.clone()), GraphicsPipelineCreateInfo {
+        stages: stages.into_iter().collect(),
+        vertex_input_state: Some(vertex_input_state),
+        input_assembly_state: Some(InputAssemblyState::default()),
+        viewport_state: Some(ViewportState::default()),
+        rasterization_state: Some(RasterizationState::default()),
+        multisample_state: Some(MultisampleState {
+            rasterization_samples: subpass.num_samples().unwrap_or(SampleCount::Sample1),
+            ..Default::default()
+        }),
+        color_blend_state: Some(ColorBlendState::with_attachment_states(
+            subpass.num_color_attachments(),
+            ColorBlendAttachmentState {
+                blend: Some(AttachmentBlend::alpha()),
+                ..Default::default()
+            },
+        )),
+        depth_stencil_state,
+        dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+        subpass: Some(subpass.clone().into()),
+        ..GraphicsPipelineCreateInfo::layout(layout)
+    })
+    .map_err(|err| err.to_string())
+}
+
+// Матриця em простору тексту -> пікселі кадру: em розміром size пікселів
+// у точці position, y em простору (вгору) перевертається у y екрана (вниз)
+fn screen_matrix(position: [f32; 2], size: f32) -> Matrix4<f32> {
+    Matrix4::from_translation(Vector3::new(position[0], position[1], 0.0))
+        * Matrix4::from_nonuniform_scale(size, -size, 1.0)
+}
+
+// Додає гліфи тексту в батч його шрифту; base — матриця em простору тексту
+// в простір шару. Для світового шару view_proj дає глибину гліфа для сортування.
+fn push_text(
+    batches: &mut BTreeMap<NAssetId, NTextBatch>,
+    text: &NText,
+    base: Matrix4<f32>,
+    view_proj: Option<Matrix4<f32>>,
+) {
+    let font = &text.font;
+    let distance_scale = font.distance_scale();
+    let em_uv = font.em_uv();
+    let shadow = text.shadow.unwrap_or(NTextShadow {
+        offset: [0.0, 0.0],
+        softness: 0.0,
+        color: [0.0; 4],
+    });
+    // Зсув тіні в UV атласу: v атласу росте вниз, y em простору — вгору
+    let effects = [
+        text.outline.map_or(0.0, |outline| outline.width * distance_scale),
+        shadow.offset[0] * em_uv[0],
+        -shadow.offset[1] * em_uv[1],
+        shadow.softness * distance_scale,
+    ];
+    let outline_color = text.outline.map_or(text.color, |outline| outline.color);
+
+    let batch = batches
+        .entry(font.id())
+        .or_insert_with(|| NTextBatch { font: font.clone(), instances: Vec::new() });
+    for glyph in text.layout() {
+        let [x, y, width, height] = glyph.rect;
+        let model = base
+            * Matrix4::from_translation(Vector3::new(x + width * 0.5, y + height * 0.5, 0.0))
+            * Matrix4::from_nonuniform_scale(width, height, 1.0);
+        let depth = view_proj.map_or(0.0, |view_proj| (view_proj * model * Vector4::unit_w()).w);
+        batch.instances.push((depth, NGlyphInstance {
+            model_x: model.x.into(),
+            model_y: model.y.into(),
+            model_z: model.z.into(),
+            model_w: model.w.into(),
+            color: text.color,
+            uv_rect: glyph.uv_rect,
+            outline_color,
+            shadow_color: shadow.color,
+            effects,
+        }));
+    }
+}
+
+impl NDrawSystem for NTextDrawSystem {
+    // draw:
+    // - розкладає текст свого шару в гліфи і групує їх за шрифтом,
+    // - у світовому шарі сортує гліфи батча від дальніх до ближніх (альфа-змішування),
+    // - записує всі батчі в один вторинний буфер, кожен одним draw_indexed по квадрату.
+    fn draw(&mut self, frame: &NFrameContext) -> Vec<Arc<SecondaryAutoCommandBuffer>> {
+        self.rebuild_pipeline_if_changed();
+        let Some(pipeline) = self.pipeline.clone() else { return Vec::new() };
+        let viewport_dimensions = frame.viewport_dimensions;
+        let [width, height] = viewport_dimensions.map(|extent| extent as f32);
+
+        let mut batches: BTreeMap<NAssetId, NTextBatch> = BTreeMap::new();
+        match self.layer {
+            NLayer::World => {
+                for entity in frame.visible_entities() {
+                    let Some(text) = &entity.text else { continue };
+                    if text.space != NTextSpace::World {
+                        continue;
+                    }
+                    let base = entity.transform.matrix()
+                        * Matrix4::from_nonuniform_scale(text.size, text.size, 1.0);
+                    push_text(&mut batches, text, base, Some(frame.world_to_framebuffer));
+                }
+            }
+            NLayer::Screen => {
+                for entity in &frame.scene.entities {
+                    let Some(text) = &entity.text else { continue };
+                    let Some(position) = text.screen_position([width, height]) else { continue };
+                    push_text(&mut batches, text, screen_matrix(position, text.size), None);
+                }
+                // Підписи text_3d центруються над своєю точкою і мають тінь для читабельності
+                if let Some(font) = self.debug_draw.font() {
+                    for label in self.debug_draw.labels(frame.world_to_framebuffer) {
+                        let text = NText {
+                            size: DEBUG_LABEL_SIZE,
+                            color: label.color,
+                            pivot: [0.5, 1.0],
+                            shadow: Some(NTextShadow {
+                                offset: [0.06, -0.06],
+                                softness: 0.04,
+                                color: [0.0, 0.0, 0.0, 0.8],
+                            }),
+                            ..NText::new(font.clone(), label.text)
+                        };
+                        let position = [label.uv[0] * width, label.uv[1] * height];
+                        push_text(&mut batches, &text, screen_matrix(position, text.size), None);
+                    }
+                }
+            }
+        }
+
+        let draws: Vec<NTextDraw> = batches
+            .into_values()
+            .filter(|batch| !batch.instances.is_empty())
+            .map(|mut batch| {
+                if self.layer == NLayer::World {
+                    batch.instances.sort_by(|a, b| b.0.total_cmp(&a.0));
+                }
+                NTextDraw {
+                    descriptor_set: self.descriptor_set(&pipeline, &batch.font),
+                    name: batch.font.name.clone(),
+                    instances: batch.instances.into_iter().map(|(_, instance)| instance).collect(),
+                }
+            })
+            .collect();
+        if draws.is_empty() {
+            return Vec::new();
+        }
+
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.clone(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(self.subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        builder
+            .bind_pipeline_graphics(pipeline.clone())
+            .unwrap()
+            .set_viewport(
+                0,
+                [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [width, height],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap()
+            .push_constants(pipeline.layout().clone(), 0, NTextPushConstants {
+                view_proj: self.layer.view_projection(frame).into(),
+            })
+            .unwrap()
+            .bind_index_buffer(self.quad_indices.clone())
+            .unwrap();
+
+        let mut stats = frame.stats.lock().unwrap();
+        for draw in draws {
+            let count = draw.instances.len();
+            let instance_buffer = frame.ring.vertices(&draw.instances);
+            builder
+                .bind_descriptor_sets(
+                    PipelineBindPoint::Graphics,
+                    pipeline.layout().clone(),
+                    0,
+                    draw.descriptor_set,
+                )
+                .unwrap()
+                .bind_vertex_buffers(0, (self.quad_vertices.clone(), instance_buffer))
+                .unwrap();
+            unsafe {
+                builder
+                    .draw_indexed(self.quad_indices.len() as u32, count as u32, 0, 0, 0)
+                    .unwrap();
+            }
+            stats.batches.push(NBatchStats {
+                system: "text",
+                name: draw.name,
+                instances: count as u32,
+            });
+        }
+        vec![builder.build().unwrap()]
+    }
+}
@@ -0,0 +1,541 @@
+// Система відрисовки тайлових мап (NTilemap).
+// Чанк шару має GPU меш на кожен тайлсет, тайли якого в ньому є: квадрат на клітинку
+// з кутом тайла (з урахуванням віддзеркалення) і локальним номером тайла. Меш
+// перебудовується лише тоді, коли змінилась ревізія чанка; меші мап, яких уже немає
+// в сцені, звільняються. UV кадру шейдер рахує з номера тайла, сітки атласу і таблиці
+// анімацій тайлсета (storage буфер), тож анімації тайлів мешів не торкаються.
+// Мапи малюються в проході сцени після непрозорих мешів, шари — знизу вгору,
+// з перевіркою глибини без запису (як спрайти); чанки поза frustum відкидаються.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+use cgmath::{Matrix4, Point3};
+use vulkano::{
+    buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+        CommandBufferInheritanceInfo, CommandBufferUsage, SecondaryAutoCommandBuffer,
+    },
+    descriptor_set::{
+        allocator::StandardDescriptorSetAllocator, DescriptorSet, WriteDescriptorSet,
+    },
+    device::Queue,
+    image::{
+        sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo},
+        SampleCount,
+    },
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator},
+    pipeline::{
+        cache::PipelineCache,
+        graphics::{
+            color_blend::{AttachmentBlend, ColorBlendAttachmentState, ColorBlendState},
+            depth_stencil::{CompareOp, DepthState, DepthStencilState},
+            input_assembly::InputAssemblyState,
+            multisample::MultisampleState,
+            rasterization::RasterizationState,
+            vertex_input::{Vertex, VertexDefinition},
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreateInfo,
+        },
+        layout::PipelineDescriptorSetLayoutCreateInfo,
+        DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineLayout,
+        PipelineShaderStageCreateInfo,
+    },
+    render_pass::Subpass,
+    shader::EntryPoint,
+};
+
+use crate::{
+    graphics::{
+        asset::NAssetId,
+        graph::{NDrawSystem, NFrameContext},
+        pipeline::NAllocators,
+        settings::{NViewMode, FLAT_VIEW_MODES},
+        shader::{NShaderHandle, NShaderLibrary, NShaderStage},
+        stats::NBatchStats,
+        texture::NTextureCache,
+    },
+    scene::{
+        bounds::{NAabb, NFrustum},
+        tilemap::{NTile, NTilemap, NTileset, TILE_CHUNK_SIZE},
+    },
+};
+
+// Вершина тайла в локальних координатах мапи
+#[repr(C)]
+#[derive(BufferContents, Vertex, Clone, Copy, Debug)]
+pub struct NTileVertex {
+    #[format(R32G32_SFLOAT)]
+    pub position: [f32; 2],     // Позиция в плоскости XY карты
+    #[format(R32G32_SFLOAT)]
+    pub corner: [f32; 2],       // Угол тайла в атласе (0..1, v вниз) после отражений
+    #[format(R32_UINT)]
+    pub tile: u32,              // Локальный номер тайла в тайлсете
+}
+
+#[repr(C)]
+#[derive(BufferContents, Clone, Copy)]
+struct NTilemapPushConstants {
+    model_view_proj: [[f32; 4]; 4], // Матрица карта -> clip space
+    color: [f32; 4],                // Цвет карты и слоя
+    tile_uv: [f32; 4],              // Тайл в UV без полутекселя с краёв, шаг сетки
+    origin_uv: [f32; 2],            // Начало сетки атласа (с полутекселем)
+    columns: u32,                   // Тайлов в строке атласа
+    time_ms: u32,                   // Время анимаций в миллисекундах
+}
+
+// Частина меша чанка з тайлами одного тайлсета
+#[derive(Clone)]
+struct NChunkPart {
+    tileset: usize,                         // Индекс тайлсета в карте
+    vertices: Subbuffer<[NTileVertex]>,     // Вершины квадратов
+    indices: Subbuffer<[u32]>,              // Индексы квадратов
+}
+
+// GPU меш чанка шару
+struct NChunkMesh {
+    revision: u64,              // Ревизия чанка, из которой построен меш
+    parts: Vec<NChunkPart>,     // Части по тайлсетам
+}
+
+// Draw одного чанка з одним тайлсетом
+struct NTileDraw {
+    descriptor_set: Arc<DescriptorSet>,     // Атлас и таблица анимаций
+    push_constants: NTilemapPushConstants,  // Матрица, цвет и сетка атласа
+    part: NChunkPart,                       // Меш
+}
+
+// Система відрисовки тайлових мап сутностей
+pub struct NTilemapDrawSystem {
+    gfx_queue: Arc<Queue>,                  // Очередь графических команд
+    subpass: Subpass,                       // Подпроход рендеринга
+    command_buffer_allocator: Arc<StandardCommandBufferAllocator>, // Аллокатор команд
+    descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>, // Аллокатор дескрипторов
+    memory_allocator: Arc<StandardMemoryAllocator>, // Аллокатор мешей чанков
+    pipeline_cache: Arc<PipelineCache>,     // Кэш пайплайнов
+    shaders: NShaderLibrary,                // Библиотека шейдеров (горячая перезагрузка)
+    vs: NShaderHandle,                      // Вершинный шейдер
+    fs: [NShaderHandle; 3],                 // Фрагментные шейдеры по FLAT_VIEW_MODES
+    shader_versions: Vec<u64>,              // Версии шейдеров, из которых собраны пайплайны
+    pipelines: Option<Vec<Arc<GraphicsPipeline>>>, // Последние удачно собранные пайплайны
+    sampler: Arc<Sampler>,                  // Сэмплер атласов (nearest для пиксель-арта)
+    textures: NTextureCache,                // Загруженные атласы
+    descriptor_sets: HashMap<NAssetId, Arc<DescriptorSet>>, // Наборы дескрипторов по тайлсету
+    chunks: HashMap<(NAssetId, usize, usize), NChunkMesh>, // Меши по (карта, слой, чанк)
+    time: f64,                              // Время анимаций тайлов в секундах
+}
+
+impl NTilemapDrawSystem {
+    pub fn new(gfx_queue: Arc<Queue>, subpass: Subpass, allocators: &NAllocators) -> Self {
+        let device = gfx_queue.device().clone();
+        let shaders = allocators.shaders.clone();
+        let vs = shaders.load("tilemap.vert", NShaderStage::Vertex);
+        let fs = FLAT_VIEW_MODES.map(|mode| {
+            let defines: Vec<&str> = mode.define().into_iter().collect();
+            shaders.load_variant("tilemap.frag", NShaderStage::Fragment, &defines)
+        });
+
+        let sampler = Sampler::new(device, SamplerCreateInfo {
+            mag_filter: Filter::Nearest,
+            min_filter: Filter::Nearest,
+            address_mode: [SamplerAddressMode::ClampToEdge; 3],
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut system = NTilemapDrawSystem {
+            gfx_queue: gfx_queue.clone(),
+            subpass,
+            command_buffer_allocator: allocators.command_buffers.clone(),
+            descriptor_set_allocator: allocators.descriptor_sets.clone(),
+            memory_allocator: allocators.memory.clone(),
+            pipeline_cache: allocators.pipeline_cache.clone(),
+            shaders,
+            vs,
+            fs,
+            shader_versions: Vec::new(),
+            pipelines: None,
+            sampler,
+            textures: NTextureCache::new(gfx_queue, allocators),
+            descriptor_sets: HashMap::new(),
+            chunks: HashMap::new(),
+            time: 0.0,
+        };
+        system.rebuild_pipelines_if_changed();
+        system
+    }
+
+    // Перебудовує пайплайни режимів, якщо змінилась версія хоча б одного шейдера.
+    // При помилці лишаються попередні пайплайни, а помилка йде в редактор.
+    fn rebuild_pipelines_if_changed(&mut self) {
+        let versions: Vec<u64> = std::iter::once(self.vs)
+            .chain(self.fs)
+            .map(|handle| self.shaders.version(handle))
+            .collect();
+        if versions == self.shader_versions {
+            return;
+        }
+        self.shader_versions = versions;
+
+        let Some(vs) = self.shaders.entry_point(self.vs) else { return };
+        let fs = self.fs.map(|handle| self.shaders.entry_point(handle));
+        if fs.iter().any(Option::is_none) {
+            return;
+        }
+        let fs = fs.map(Option::unwrap);
+        match create_pipelines(&self.gfx_queue, &self.pipeline_cache, &self.subpass, vs, fs) {
+            Ok(pipelines) => {
+                self.pipelines = Some(pipelines);
+                // Набори створено під layout попередніх пайплайнів
+                self.descriptor_sets.clear();
+                self.shaders.set_pipeline_error(self.name(), None);
+            }
+            Err(error) => self.shaders.set_pipeline_error(self.name(), Some(error)),
+        }
+    }
+
+    // Набір дескрипторів тайлсета: атлас, сэмплер і таблиця анімацій
+    fn descriptor_set(
+        &mut self,
+        pipeline: &Arc<GraphicsPipeline>,
+        tileset: &NTileset,
+    ) -> Arc<DescriptorSet> {
+        if let Some(set) = self.descriptor_sets.get(&tileset.id()) {
+            return set.clone();
+        }
+        let animations = Buffer::from_iter(
+            self.memory_allocator.clone(),
+            BufferCreateInfo { usage: BufferUsage::STORAGE_BUFFER, ..Default::default() },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            tileset.animation_table(),
+        )
+        .unwrap();
+        let set = DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            pipeline.layout().set_layouts()[0].clone(),
+            [
+                WriteDescriptorSet::image_view(0, self.textures.get(&tileset.texture)),
+                WriteDescriptorSet::sampler(1, self.sampler.clone()),
+                WriteDescriptorSet::buffer(2, animations),
+            ],
+            [],
+        )
+        .unwrap();
+        self.descriptor_sets.insert(tileset.id(), set.clone());
+        set
+    }
+
+    // Меш чанка шару; будується заново, лише якщо ревізія чанка змінилась
+    fn chunk_parts(&mut self, map: &NTilemap, layer: usize, chunk: usize) -> Vec<NChunkPart> {
+        let revision = map.layers[layer].revision(chunk);
+        let key = (map.id(), layer, chunk);
+        if let Some(mesh) = self.chunks.get(&key).filter(|mesh| mesh.revision == revision) {
+            return mesh.parts.clone();
+        }
+        let mesh = NChunkMesh { revision, parts: self.build_chunk(map, layer, chunk) };
+        let parts = mesh.parts.clone();
+        self.chunks.insert(key, mesh);
+        parts
+    }
+
+    // Квадрати непорожніх клітинок чанка, згруповані за тайлсетом
+    fn build_chunk(&self, map: &NTilemap, layer: usize, chunk: usize) -> Vec<NChunkPart> {
+        let mut groups: BTreeMap<usize, (Vec<NTileVertex>, Vec<u32>)> = BTreeMap::new();
+        for (x, y, tile) in map.chunk_tiles(layer, chunk) {
+            let Some((tileset, local)) = map.resolve(tile.gid()) else { continue };
+            let (min, max) = map.cell_rect(x, y);
+            let (vertices, indices) = groups.entry(tileset).or_default();
+            let base = vertices.len() as u32;
+            for (position, corner) in [
+                ([min[0], max[1]], [0.0, 0.0]),
+                ([max[0], max[1]], [1.0, 0.0]),
+                ([max[0], min[1]], [1.0, 1.0]),
+                ([min[0], min[1]], [0.0, 1.0]),
+            ] {
+                let corner = flip_corner(corner, tile);
+                vertices.push(NTileVertex { position, corner, tile: local });
+            }
+            indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+
+        let allocation_info = || AllocationCreateInfo {
+            memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
+                | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+            ..Default::default()
+        };
+        groups
+            .into_iter()
+            .map(|(tileset, (vertices, indices))| NChunkPart {
+                tileset,
+                vertices: Buffer::from_iter(
+                    self.memory_allocator.clone(),
+                    BufferCreateInfo { usage: BufferUsage::VERTEX_BUFFER, ..Default::default() },
+                    allocation_info(),
+                    vertices,
+                )
+                .unwrap(),
+                indices: Buffer::from_iter(
+                    self.memory_allocator.clone(),
+                    BufferCreateInfo { usage: BufferUsage::INDEX_BUFFER, ..Default::default() },
+                    allocation_info(),
+                    indices,
+                )
+                .unwrap(),
+            })
+            .collect()
+    }
+}
+
+// Кут атласу для кута клітинки. Tiled спершу транспонує тайл (діагональ), потім
+// віддзеркалює, тому зворотне перетворення йде у зворотному порядку.
+fn flip_corner(corner: [f32; 2], tile: NTile) -> [f32; 2] {
+    let (horizontal, vertical, diagonal) = tile.flips();
+    let [mut u, mut v] = corner;
+    if horizontal {
+        u = 1.0 - u;
+    }
+    if vertical {
+        v = 1.0 - v;
+    }
+    if diagonal { [v, u] } else { [u, v] }
+}
+
+// Межі чанка в локальних координатах мапи
+fn chunk_bounds(map: &NTilemap, chunk: usize) -> NAabb {
+    let columns = map.chunk_count()[0];
+    let first =
+        [chunk as u32 % columns, chunk as u32 / columns].map(|cell| cell * TILE_CHUNK_SIZE);
+    let last = [0, 1].map(|axis| (first[axis] + TILE_CHUNK_SIZE).min(map.size[axis]) - 1);
+    let (_, max) = map.cell_rect(last[0], first[1]);
+    let (min, _) = map.cell_rect(first[0], last[1]);
+    NAabb::new(Point3::new(min[0], min[1], 0.0), Point3::new(max[0], max[1], 0.0))
+}
+
+// Сітка атласу в UV. Кути тайла зсунуті на півтекселя всередину, щоб сусідні
+// тайли атласу не просочувались на краях.
+fn push_constants(
+    tileset: &NTileset,
+    model_view_proj: Matrix4<f32>,
+    color: [f32; 4],
+    time_ms: u32,
+) -> NTilemapPushConstants {
+    let texel = tileset.texture.size.map(|size| 1.0 / size.max(1) as f32);
+    let [width, height] = tileset.tile_size.map(|size| size as f32);
+    let spacing = tileset.spacing as f32;
+    let origin = tileset.margin as f32 + 0.5;
+    NTilemapPushConstants {
+        model_view_proj: model_view_proj.into(),
+        color,
+        tile_uv: [
+            (width - 1.0) * texel[0],
+            (height - 1.0) * texel[1],
+            (width + spacing) * texel[0],
+            (height + spacing) * texel[1],
+        ],
+        origin_uv: [origin * texel[0], origin * texel[1]],
+        columns: tileset.columns,
+        time_ms,
+    }
+}
+
+// Пайплайни в порядку FLAT_VIEW_MODES. Layout спільний і береться з Lit варіанта,
+// тож набори дескрипторів підходять до всіх режимів, навіть якщо шейдер варіанта
+// текстуру не читає.
+fn create_pipelines(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    subpass: &Subpass,
+    vs: EntryPoint,
+    fs: [EntryPoint; 3],
+) -> Result<Vec<Arc<GraphicsPipeline>>, String> {
+    let device = gfx_queue.device().clone();
+    let stages = [
+        PipelineShaderStageCreateInfo::new(vs.clone()),
+        PipelineShaderStageCreateInfo::new(fs[0].clone()),
+    ];
+    let layout = PipelineLayout::new(
+        device.clone(),
+        PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
+            .into_pipeline_layout_create_info(device)
+            .map_err(|err| format!("{:?}", err))?,
+    )
+    .map_err(|err| err.to_string())?;
+
+    FLAT_VIEW_MODES
+        .into_iter()
+        .zip(fs)
+        .map(|(mode, fs)| {
+            create_pipeline(gfx_queue, pipeline_cache, subpass, &layout, vs.clone(), fs, mode)
+        })
+        .collect()
+}
+
+fn create_pipeline(
+    gfx_queue: &Arc<Queue>,
+    pipeline_cache: &Arc<PipelineCache>,
+    subpass: &Subpass,
+    layout: &Arc<PipelineLayout>,
+    vs: EntryPoint,
+    fs: EntryPoint,
+    mode: NViewMode,
+) -> Result<Arc<GraphicsPipeline>, String> {
+    let device = gfx_queue.device().clone();
+    let vertex_input_state =
+        NTileVertex::per_vertex().definition(&vs).map_err(|err| err.to_string())?;
+
+    let stages = [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
+
+    // Overdraw складає внески шарів адитивно і рахує всі шари, без тесту глибини
+    let (blend, depth_stencil_state) = match mode {
+        NViewMode::Overdraw => (AttachmentBlend::additive(), DepthStencilState::default()),
+        _ => (AttachmentBlend::alpha(), DepthStencilState {
+            depth: Some(DepthState { write_enable: false, compare_op: CompareOp::Less }),
+            ..Default::default()
+        }),
+    };
+
+    // Шари лежать в одній площині: без запису глибини верхній шар малюється
+    // поверх нижнього, а геометрія сцени перед мапою її закриває
+    GraphicsPipeline::new(device, Some(pipeline_cache.clone()), GraphicsPipelineCreateInfo {
+        stages: stages.into_iter().collect(),
+        vertex_input_state: Some(vertex_input_state),
+        input_assembly_state: Some(InputAssemblyState::default()),
+        viewport_state: Some(ViewportState::default()),
+        rasterization_state: Some(RasterizationState::default()),
+        multisample_state: Some(MultisampleState {
+            rasterization_samples: subpass.num_samples().unwrap_or(SampleCount::Sample1),
+            ..Default::default()
+        }),
+        color_blend_state: Some(ColorBlendState::with_attachment_states(
+            subpass.num_color_attachments(),
+            ColorBlendAttachmentState { blend: Some(blend), ..Default::default() },
+        )),
+        depth_stencil_state: Some(depth_stencil_state),
+        dynamic_state: [DynamicState::Viewport].into_iter().collect(),
+        subpass: Some(subpass.clone().into()),
+        ..GraphicsPipelineCreateInfo::layout(layout.clone())
+    })
+    .map_err(|err| err.to_string())
+}
+
+impl NDrawSystem for NTilemapDrawSystem {
+    // draw:
+    // - звільняє меші мап, яких немає в сцені,
+    // - для видимих мап перебудовує змінені чанки у frustum і збирає draw-и
+    //   шар за шаром (порядок шарів — порядок змішування),
+    // - записує все в один вторинний буфер, кожен чанк тайлсета одним draw_indexed.
+    fn draw(&mut self, frame: &NFrameContext) -> Vec<Arc<SecondaryAutoCommandBuffer>> {
+        self.rebuild_pipelines_if_changed();
+        self.time += frame.delta_time as f64;
+        let live: HashSet<NAssetId> = frame
+            .scene
+            .entities
+            .iter()
+            .filter_map(|entity| entity.tilemap.as_ref().map(NTilemap::id))
+            .collect();
+        self.chunks.retain(|(map, _, _), _| live.contains(map));
+        let Some(pipelines) = &self.pipelines else { return Vec::new() };
+        let pipeline = pipelines[frame.settings.view_mode.flat_index()].clone();
+        // Час у мс по модулю 2^32: анімації перескакують раз на ~50 днів
+        let time_ms = (self.time * 1000.0) as u64 as u32;
+
+        let mut draws = Vec::new();
+        let mut tiles: BTreeMap<String, (u32, u32)> = BTreeMap::new();
+        for entity in frame.visible_entities() {
+            let Some(map) = &entity.tilemap else { continue };
+            let model_view_proj = frame.world_to_framebuffer * entity.transform.matrix();
+            let frustum = NFrustum::from_view_projection(model_view_proj);
+            let [columns, rows] = map.chunk_count();
+            let chunks: Vec<usize> = (0..(columns * rows) as usize)
+                .filter(|&chunk| frustum.intersects_aabb(&chunk_bounds(map, chunk)))
+                .collect();
+            for (layer_index, layer) in map.layers.iter().enumerate() {
+                if !layer.visible {
+                    continue;
+                }
+                let color = [0, 1, 2, 3].map(|channel| map.color[channel] * layer.color[channel]);
+                for &chunk in &chunks {
+                    for part in self.chunk_parts(map, layer_index, chunk) {
+                        let tileset = map.tilesets[part.tileset].tileset.clone();
+                        let entry = tiles.entry(tileset.name.clone()).or_default();
+                        entry.0 += 1;
+                        entry.1 += (part.indices.len() / 6) as u32;
+                        let push_constants =
+                            push_constants(&tileset, model_view_proj, color, time_ms);
+                        draws.push(NTileDraw {
+                            descriptor_set: self.descriptor_set(&pipeline, &tileset),
+                            push_constants,
+                            part,
+                        });
+                    }
+                }
+            }
+        }
+        if draws.is_empty() {
+            return Vec::new();
+        }
+
+        let [width, height] = frame.viewport_dimensions.map(|extent| extent as f32);
+        let mut builder = AutoCommandBufferBuilder::secondary(
+            self.command_buffer_allocator.clone(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+            CommandBufferInheritanceInfo {
+                render_pass: Some(self.subpass.clone().into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        builder
+            .bind_pipeline_graphics(pipeline.clone())
+            .unwrap()
+            .set_viewport(
+                0,
+                [Viewport {
+                    offset: [0.0, 0.0],
+                    extent: [width, height],
+                    depth_range: 0.0..=1.0,
+                }]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap();
+        for draw in draws {
+            let index_count = draw.part.indices.len() as u32;
+            builder
+                .bind_descriptor_sets(
+                    PipelineBindPoint::Graphics,
+                    pipeline.layout().clone(),
+                    0,
+                    draw.descriptor_set,
+                )
+                .unwrap()
+                .push_constants(pipeline.layout().clone(), 0, draw.push_constants)
+                .unwrap()
+                .bind_vertex_buffers(0, draw.part.vertices)
+                .unwrap()
+                .bind_index_buffer(draw.part.indices)
+                .unwrap();
+            unsafe {
+                builder.draw_indexed(index_count, 1, 0, 0, 0).unwrap();
+            }
+        }
+
+        let mut stats = frame.stats.lock().unwrap();
+        for (name, (chunks, count)) in tiles {
+            stats.batches.push(NBatchStats {
+                system: "tilemap",
+                name: format!("{} ({} chunks)", name, chunks),
+                instances: count,
+            });
+        }
+        vec![builder.build().unwrap()]
+    }
+}
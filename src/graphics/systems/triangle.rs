@@ -1,192 +0,0 @@
-
-use std::sync::Arc;
-
-use vulkano::{
-    buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer},
-    command_buffer::{
-        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
-        CommandBufferInheritanceInfo, CommandBufferUsage, SecondaryAutoCommandBuffer,
-    },
-    device::Queue,
-    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter},
-    pipeline::{
-        graphics::{
-            color_blend::{ColorBlendAttachmentState, ColorBlendState},
-            depth_stencil::DepthStencilState,
-            input_assembly::InputAssemblyState,
-            multisample::MultisampleState,
-            rasterization::RasterizationState,
-            vertex_input::{Vertex, VertexDefinition},
-            viewport::{Viewport, ViewportState},
-            GraphicsPipelineCreateInfo,
-        },
-        layout::PipelineDescriptorSetLayoutCreateInfo,
-        DynamicState, GraphicsPipeline, PipelineLayout, PipelineShaderStageCreateInfo,
-    },
-    render_pass::Subpass,
-};
-
-use crate::graphics::pipeline::NAllocators;
-
-// Система отрисовки треугольников
-pub struct NTriangleDrawSystem {
-    gfx_queue: Arc<Queue>,          // Очередь графических команд
-    vertex_buffer: Subbuffer<[MyVertex]>,  // Буфер вершин треугольника
-    pipeline: Arc<GraphicsPipeline>,  // Графический пайплайн
-    subpass: Subpass,               // Подпроход рендеринга
-    command_buffer_allocator: Arc<StandardCommandBufferAllocator>, // Аллокатор команд
-}
-
-impl NTriangleDrawSystem {
-    // TriangleDrawSystem: будує вершинний буфер з трьома вершинами та GraphicsPipeline,
-    // який використовує вбудовані шейдери (vulkan GLSL через vulkano_shaders).
-    // В draw() генерується SecondaryAutoCommandBuffer що малює ці вершини.
-    pub fn new(
-        gfx_queue: Arc<Queue>,
-        subpass: Subpass,
-        allocators: &NAllocators,
-    ) -> NTriangleDrawSystem {
-        let vertex_buffer = Buffer::from_iter(
-            allocators.memory.clone(),
-            BufferCreateInfo { usage: BufferUsage::VERTEX_BUFFER, ..Default::default() },
-            AllocationCreateInfo {
-                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE
-                    | MemoryTypeFilter::HOST_RANDOM_ACCESS,
-                ..Default::default()
-            },
-            [
-                MyVertex { position: [-0.5, -0.25], color: [1.0, 0.0, 0.0, 1.0] },
-                MyVertex { position: [0.0, 0.5], color: [0.0, 1.0, 0.0, 1.0] },
-                MyVertex { position: [0.25, -0.1], color: [0.0, 0.0, 1.0, 1.0] },
-            ],
-        )
-        .unwrap();
-
-        let pipeline = {
-            let vs = vs::load(gfx_queue.device().clone())
-                .expect("failed to create shader module")
-                .entry_point("main")
-                .unwrap();
-            let fs = fs::load(gfx_queue.device().clone())
-                .expect("failed to create shader module")
-                .entry_point("main")
-                .unwrap();
-
-            let vertex_input_state = MyVertex::per_vertex().definition(&vs).unwrap();
-
-            let stages =
-                [PipelineShaderStageCreateInfo::new(vs), PipelineShaderStageCreateInfo::new(fs)];
-
-            let layout = PipelineLayout::new(
-                gfx_queue.device().clone(),
-                PipelineDescriptorSetLayoutCreateInfo::from_stages(&stages)
-                    .into_pipeline_layout_create_info(gfx_queue.device().clone())
-                    .unwrap(),
-            )
-            .unwrap();
-
-            GraphicsPipeline::new(gfx_queue.device().clone(), None, GraphicsPipelineCreateInfo {
-                stages: stages.into_iter().collect(),
-                vertex_input_state: Some(vertex_input_state),
-                input_assembly_state: Some(InputAssemblyState::default()),
-                viewport_state: Some(ViewportState::default()),
-                rasterization_state: Some(RasterizationState::default()),
-                multisample_state: Some(MultisampleState::default()),
-                color_blend_state: Some(ColorBlendState::with_attachment_states(
-                    subpass.num_color_attachments(),
-                    ColorBlendAttachmentState::default(),
-                )),
-                depth_stencil_state: Some(DepthStencilState::default()),
-                dynamic_state: [DynamicState::Viewport].into_iter().collect(),
-                subpass: Some(subpass.clone().into()),
-                ..GraphicsPipelineCreateInfo::layout(layout)
-            })
-            .unwrap()
-        };
-
-        NTriangleDrawSystem {
-            gfx_queue,
-            vertex_buffer,
-            pipeline,
-            subpass,
-            command_buffer_allocator: allocators.command_buffers.clone(),
-        }
-    }
-
-    // draw:
-    // - створює SecondaryAutoCommandBufferBuilder (MultipleSubmit, з CommandBufferInheritanceInfo),
-    // - прив'язує pipeline, встановлює viewport, підв'язує vertex buffer і викликає draw().
-    pub fn draw(&self, viewport_dimensions: [u32; 2]) -> Arc<SecondaryAutoCommandBuffer> {
-        let mut builder = AutoCommandBufferBuilder::secondary(
-            self.command_buffer_allocator.clone(),
-            self.gfx_queue.queue_family_index(),
-            CommandBufferUsage::MultipleSubmit,
-            CommandBufferInheritanceInfo {
-                render_pass: Some(self.subpass.clone().into()),
-                ..Default::default()
-            },
-        )
-        .unwrap();
-        builder
-            .bind_pipeline_graphics(self.pipeline.clone())
-            .unwrap()
-            .set_viewport(
-                0,
-                [Viewport {
-                    offset: [0.0, 0.0],
-                    extent: [viewport_dimensions[0] as f32, viewport_dimensions[1] as f32],
-                    depth_range: 0.0..=1.0,
-                }]
-                .into_iter()
-                .collect(),
-            )
-            .unwrap()
-            .bind_vertex_buffers(0, self.vertex_buffer.clone())
-            .unwrap();
-        unsafe {
-            builder.draw(self.vertex_buffer.len() as u32, 1, 0, 0).unwrap();
-        }
-        builder.build().unwrap()
-    }
-}
-
-// Структура вершини
-#[repr(C)]
-#[derive(BufferContents, Vertex)]
-struct MyVertex {
-    #[format(R32G32_SFLOAT)]
-    position: [f32; 2],     // Позиція вершини (x,y)
-    #[format(R32G32B32A32_SFLOAT)]
-    color: [f32; 4],        // Колір вершини (r,g,b,a)
-}
-
-mod vs {
-    vulkano_shaders::shader! {
-        ty: "vertex",
-        src: "
-#version 450
-layout(location = 0) in vec2 position;
-layout(location = 1) in vec4 color;
-
-layout(location = 0) out vec4 v_color;
-void main() {
-    gl_Position = vec4(position, 0.0, 1.0);
-    v_color = color;
-}"
-    }
-}
-
-mod fs {
-    vulkano_shaders::shader! {
-        ty: "fragment",
-        src: "
-#version 450
-layout(location = 0) in vec4 v_color;
-
-layout(location = 0) out vec4 f_color;
-
-void main() {
-    f_color = v_color;
-}"
-    }
-}
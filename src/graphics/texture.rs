@@ -0,0 +1,158 @@
+use std::{collections::HashMap, sync::Arc};
+
+use vulkano::{
+    buffer::{Buffer, BufferCreateInfo, BufferUsage},
+    command_buffer::{AutoCommandBufferBuilder, CommandBufferUsage, CopyBufferToImageInfo},
+    device::Queue,
+    format::Format,
+    image::{view::ImageView, Image, ImageCreateInfo, ImageType, ImageUsage},
+    memory::allocator::{AllocationCreateInfo, MemoryTypeFilter},
+    sync::{self, GpuFuture},
+};
+
+use crate::graphics::{
+    asset::{next_asset_id, NAssetId},
+    pipeline::NAllocators,
+};
+
+/// Текстура на CPU (RGBA8). GPU зображення створює NTextureCache за `id`.
+#[derive(Debug)]
+pub struct NTexture {
+    id: NAssetId,               // Идентификатор ассета
+    pub name: String,           // Имя текстуры
+    pub size: [u32; 2],         // Размер в пикселях
+    pub data: Vec<u8>,          // Пиксели RGBA8 построчно
+    pub srgb: bool,             // Цвет в sRGB (false — линейные данные: нормали, маски)
+}
+
+impl NTexture {
+    pub fn new(name: impl Into<String>, size: [u32; 2], data: Vec<u8>) -> Self {
+        assert_eq!(data.len(), (size[0] * size[1] * 4) as usize, "texture data size mismatch");
+        NTexture { id: next_asset_id(), name: name.into(), size, data, srgb: true }
+    }
+
+    // Текстура з лінійними даними (карти нормалей, metallic-roughness, occlusion, SDF шрифтів)
+    pub fn linear(name: impl Into<String>, size: [u32; 2], data: Vec<u8>) -> Self {
+        NTexture { srgb: false, ..NTexture::new(name, size, data) }
+    }
+
+    // Завантажує зображення (PNG, JPEG, ...) як sRGB текстуру
+    #[cfg(feature = "image")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let image = image::open(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let image = image.into_rgba8();
+        let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+        let size = [image.width(), image.height()];
+        Ok(NTexture::new(name.unwrap_or_default(), size, image.into_raw()))
+    }
+
+    // Атлас імпортованого ассета (Tiled, Aseprite) розміру `size` з файлу ассета.
+    // Без feature "image" зображення не декодується: замість нього шахівниця
+    // того ж розміру, тож області кадрів і тайлів лишаються правильними.
+    #[cfg(feature = "image")]
+    pub fn load_or_placeholder(
+        path: impl AsRef<std::path::Path>,
+        _size: [u32; 2],
+    ) -> Result<Self, String> {
+        NTexture::load(path)
+    }
+
+    #[cfg(not(feature = "image"))]
+    pub fn load_or_placeholder(
+        path: impl AsRef<std::path::Path>,
+        size: [u32; 2],
+    ) -> Result<Self, String> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(format!("{}: file not found", path.display()));
+        }
+        let [width, height] = size.map(|size| size.max(1));
+        let data = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x / 8 + y / 8) % 2 == 0))
+            .flat_map(|even| if even { [255, 0, 255, 255] } else { [64, 64, 64, 255] })
+            .collect();
+        let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+        Ok(NTexture::new(name.unwrap_or_default(), [width, height], data))
+    }
+
+    #[inline]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    // Біла текстура 1x1 (для спрайтів без текстури)
+    pub fn white() -> Self {
+        NTexture::new("White", [1, 1], vec![255; 4])
+    }
+}
+
+/// Кеш GPU зображень текстур. Завантаження синхронне і відбувається
+/// лише при першому використанні текстури.
+pub struct NTextureCache {
+    gfx_queue: Arc<Queue>,                          // Очередь для загрузки
+    allocators: NAllocators,                        // Аллокаторы памяти и команд
+    views: HashMap<NAssetId, Arc<ImageView>>,       // Загруженные текстуры
+}
+
+impl NTextureCache {
+    pub fn new(gfx_queue: Arc<Queue>, allocators: &NAllocators) -> Self {
+        NTextureCache { gfx_queue, allocators: allocators.clone(), views: HashMap::new() }
+    }
+
+    pub fn get(&mut self, texture: &NTexture) -> Arc<ImageView> {
+        if let Some(view) = self.views.get(&texture.id()) {
+            return view.clone();
+        }
+        let view = self.upload(texture);
+        self.views.insert(texture.id(), view.clone());
+        view
+    }
+
+    fn upload(&self, texture: &NTexture) -> Arc<ImageView> {
+        let staging = Buffer::from_iter(
+            self.allocators.memory.clone(),
+            BufferCreateInfo { usage: BufferUsage::TRANSFER_SRC, ..Default::default() },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            texture.data.iter().copied(),
+        )
+        .unwrap();
+        let image = Image::new(
+            self.allocators.memory.clone(),
+            ImageCreateInfo {
+                image_type: ImageType::Dim2d,
+                format: if texture.srgb { Format::R8G8B8A8_SRGB } else { Format::R8G8B8A8_UNORM },
+                extent: [texture.size[0], texture.size[1], 1],
+                usage: ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
+                ..Default::default()
+            },
+            AllocationCreateInfo::default(),
+        )
+        .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            self.allocators.command_buffers.clone(),
+            self.gfx_queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        builder
+            .copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(staging, image.clone()))
+            .unwrap();
+        let command_buffer = builder.build().unwrap();
+
+        sync::now(self.gfx_queue.device().clone())
+            .then_execute(self.gfx_queue.clone(), command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        ImageView::new_default(image).unwrap()
+    }
+}
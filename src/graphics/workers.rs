@@ -0,0 +1,79 @@
+// Пул потоків для паралельного запису вторинних командних буферів.
+// Draw системи проходу записуються паралельно, а великі системи (меші, спрайти)
+// додатково ділять свій список видимих об'єктів на чанки. Результати завжди
+// повертаються в порядку вхідних елементів, тому порядок відрисовки не залежить
+// від того, який потік закінчив першим.
+// StandardCommandBufferAllocator та StandardDescriptorSetAllocator тримають пули
+// окремо для кожного потоку, тож воркери виділяють буфери без блокувань.
+
+use std::sync::Arc;
+
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+
+/// Спільний пул воркерів рендерера (клони посилаються на той самий пул)
+#[derive(Clone)]
+pub struct NWorkerPool {
+    pool: Arc<ThreadPool>,  // Потоки rayon
+}
+
+impl NWorkerPool {
+    // Один потік лишаємо головному циклу (він чекає на результати запису)
+    pub fn new() -> Self {
+        let threads = std::thread::available_parallelism()
+            .map_or(1, |threads| threads.get().saturating_sub(1))
+            .max(1);
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("render worker {}", index))
+            .build()
+            .unwrap();
+        NWorkerPool { pool: Arc::new(pool) }
+    }
+
+    // Кількість воркерів
+    pub fn threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    // Кількість слотів під дані потоків: 0 — будь-який потік поза пулом, 1.. — воркери
+    pub fn thread_slots(&self) -> usize {
+        self.threads() + 1
+    }
+
+    // Слот поточного потоку (див. thread_slots)
+    pub fn thread_slot(&self) -> usize {
+        self.pool.current_thread_index().map_or(0, |index| index + 1)
+    }
+
+    // Застосовує `f` до елементів паралельно на воркерах; результати йдуть
+    // у порядку елементів. Викликаючий потік блокується до завершення.
+    pub fn map<I, R>(&self, items: I, f: impl Fn(I::Item) -> R + Sync + Send) -> Vec<R>
+    where
+        I: IntoParallelIterator,
+        I::Iter: IndexedParallelIterator,
+        R: Send,
+    {
+        self.pool.install(|| items.into_par_iter().map(f).collect())
+    }
+
+    // Ділить `items` на чанки (не більше одного на воркер і не менше `min_chunk`
+    // елементів у кожному) та обробляє їх паралельно, зберігаючи порядок
+    pub fn map_chunks<T: Sync, R: Send>(
+        &self,
+        items: &[T],
+        min_chunk: usize,
+        f: impl Fn(&[T]) -> R + Sync + Send,
+    ) -> Vec<R> {
+        if items.is_empty() {
+            return Vec::new();
+        }
+        let chunk = items.len().div_ceil(self.threads()).max(min_chunk.max(1));
+        self.map(items.par_chunks(chunk), f)
+    }
+}
+
+impl Default for NWorkerPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
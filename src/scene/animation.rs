@@ -0,0 +1,216 @@
+// Кліпи скелетної анімації.
+// Канал анімує одну властивість (зсув, поворот або масштаб) одного суглоба ключами
+// з інтерполяцією glTF: Linear (поворот — slerp), Step і CubicSpline (ермітовий сплайн,
+// на кожен ключ три значення: вхідна дотична, значення, вихідна дотична).
+// Кліп пише семпли своїх каналів у NPose; суглоби без каналів лишаються як були,
+// тому кліп семплиться поверх пози спокою або поверх іншої пози (шари з маскою).
+
+use std::sync::Arc;
+
+use cgmath::{InnerSpace, Quaternion, Vector3};
+
+use crate::{
+    graphics::asset::{next_asset_id, NAssetId},
+    scene::skeleton::NPose,
+};
+
+/// Інтерполяція між ключами
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NInterpolation {
+    #[default]
+    Linear,         // Линейная (повороты — slerp)
+    Step,           // Значение ключа до следующего ключа
+    CubicSpline,    // Кубический эрмитов сплайн с касательными из ключей
+}
+
+/// Властивість суглоба, яку анімує канал
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NAnimationTarget {
+    Translation,    // Смещение
+    Rotation,       // Поворот (кватернион x, y, z, w)
+    Scale,          // Масштаб
+}
+
+/// Канал кліпа: ключі однієї властивості одного суглоба
+#[derive(Clone, Debug)]
+pub struct NAnimationChannel {
+    pub joint: usize,                   // Сустав скелета
+    pub target: NAnimationTarget,       // Анимируемое свойство
+    pub interpolation: NInterpolation,  // Интерполяция
+    pub times: Vec<f32>,                // Время ключей в секундах по возрастанию
+    pub values: Vec<[f32; 4]>,          // Значения ключей (для CubicSpline по три на ключ)
+}
+
+impl NAnimationChannel {
+    pub fn new(
+        joint: usize,
+        target: NAnimationTarget,
+        interpolation: NInterpolation,
+        times: Vec<f32>,
+        values: Vec<[f32; 4]>,
+    ) -> Self {
+        let stride = if interpolation == NInterpolation::CubicSpline { 3 } else { 1 };
+        assert!(!times.is_empty(), "animation channel has no keys");
+        assert_eq!(values.len(), times.len() * stride, "animation channel values mismatch");
+        NAnimationChannel { joint, target, interpolation, times, values }
+    }
+
+    // Значення каналу в момент `time` (поза ключами — крайній ключ)
+    pub fn sample(&self, time: f32) -> [f32; 4] {
+        let cubic = self.interpolation == NInterpolation::CubicSpline;
+        let value = |key: usize| if cubic { self.values[key * 3 + 1] } else { self.values[key] };
+        let next = self.times.partition_point(|&key| key <= time);
+        if next == 0 {
+            return value(0);
+        }
+        if next == self.times.len() {
+            return value(next - 1);
+        }
+        let key = next - 1;
+        let span = self.times[next] - self.times[key];
+        let t = (time - self.times[key]) / span;
+        let rotation = self.target == NAnimationTarget::Rotation;
+        match self.interpolation {
+            NInterpolation::Step => value(key),
+            NInterpolation::Linear if rotation => {
+                let from = quaternion(value(key));
+                let to = quaternion(value(next));
+                let to = if from.dot(to) < 0.0 { -to } else { to };
+                let result = from.slerp(to, t);
+                [result.v.x, result.v.y, result.v.z, result.s]
+            }
+            NInterpolation::Linear => {
+                let (from, to) = (value(key), value(next));
+                [0, 1, 2, 3].map(|axis| from[axis] + (to[axis] - from[axis]) * t)
+            }
+            NInterpolation::CubicSpline => {
+                let (t2, t3) = (t * t, t * t * t);
+                let out_tangent = self.values[key * 3 + 2];
+                let in_tangent = self.values[next * 3];
+                let (from, to) = (value(key), value(next));
+                let result = [0, 1, 2, 3].map(|axis| {
+                    (2.0 * t3 - 3.0 * t2 + 1.0) * from[axis]
+                        + (t3 - 2.0 * t2 + t) * span * out_tangent[axis]
+                        + (-2.0 * t3 + 3.0 * t2) * to[axis]
+                        + (t3 - t2) * span * in_tangent[axis]
+                });
+                if rotation {
+                    let result = quaternion(result).normalize();
+                    [result.v.x, result.v.y, result.v.z, result.s]
+                } else {
+                    result
+                }
+            }
+        }
+    }
+}
+
+// Кватерніон з порядку glTF (x, y, z, w)
+fn quaternion(value: [f32; 4]) -> Quaternion<f32> {
+    Quaternion::new(value[3], value[0], value[1], value[2])
+}
+
+/// Кліп анімації скелета
+#[derive(Debug)]
+pub struct NAnimationClip {
+    id: NAssetId,                       // Идентификатор ассета
+    pub name: String,                   // Имя
+    pub duration: f32,                  // Длительность в секундах (последний ключ)
+    pub channels: Vec<NAnimationChannel>, // Каналы
+}
+
+impl NAnimationClip {
+    pub fn new(name: impl Into<String>, channels: Vec<NAnimationChannel>) -> Self {
+        let duration = channels
+            .iter()
+            .map(|channel| *channel.times.last().unwrap())
+            .fold(0.0, f32::max);
+        NAnimationClip { id: next_asset_id(), name: name.into(), duration, channels }
+    }
+
+    #[inline]
+    #[allow(dead_code)]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    // Пише значення каналів у момент `time` у позу
+    pub fn sample(&self, time: f32, pose: &mut NPose) {
+        for channel in &self.channels {
+            let Some(joint) = pose.joints.get_mut(channel.joint) else { continue };
+            let value = channel.sample(time);
+            match channel.target {
+                NAnimationTarget::Translation => {
+                    joint.position = Vector3::new(value[0], value[1], value[2]);
+                }
+                NAnimationTarget::Rotation => joint.rotation = quaternion(value).normalize(),
+                NAnimationTarget::Scale => joint.scale = Vector3::new(value[0], value[1], value[2]),
+            }
+        }
+    }
+
+    // Час відтворення: з повтором — по колу, без — обмежений тривалістю
+    pub fn wrap_time(&self, time: f32, looping: bool) -> f32 {
+        if self.duration <= 0.0 {
+            0.0
+        } else if looping {
+            time.rem_euclid(self.duration)
+        } else {
+            time.clamp(0.0, self.duration)
+        }
+    }
+}
+
+// Кліпи для демо-сцени на ланцюжку суглобів NSkeleton::chain(…, joints, …):
+// "sway" — плавне погойдування (Linear), "whip" — різкий удар (CubicSpline)
+// і "breathe" — ледь помітне стискання по Y для адитивного шару (Step).
+pub fn demo_clips(joints: usize) -> Vec<Arc<NAnimationClip>> {
+    let rotation = |angle: f32| {
+        let rotation = Quaternion::from_sv(
+            (angle * 0.5).cos(),
+            Vector3::new(0.0, 0.0, (angle * 0.5).sin()),
+        );
+        [rotation.v.x, rotation.v.y, rotation.v.z, rotation.s]
+    };
+    let sway = (1..joints)
+        .map(|joint| {
+            let angle = 0.15 * joint as f32 / joints as f32;
+            NAnimationChannel::new(
+                joint,
+                NAnimationTarget::Rotation,
+                NInterpolation::Linear,
+                vec![0.0, 1.0, 2.0, 3.0, 4.0],
+                [0.0, angle, 0.0, -angle, 0.0].map(rotation).to_vec(),
+            )
+        })
+        .collect();
+    let whip = (1..joints)
+        .map(|joint| {
+            let angle = 0.6 * joint as f32 / joints as f32;
+            let zero = [0.0; 4];
+            NAnimationChannel::new(
+                joint,
+                NAnimationTarget::Rotation,
+                NInterpolation::CubicSpline,
+                vec![0.0, 0.4, 1.2],
+                vec![
+                    zero, rotation(0.0), zero,
+                    zero, rotation(angle), zero,
+                    zero, rotation(0.0), zero,
+                ],
+            )
+        })
+        .collect();
+    let breathe = vec![NAnimationChannel::new(
+        0,
+        NAnimationTarget::Scale,
+        NInterpolation::Step,
+        vec![0.0, 0.5, 1.0, 1.5],
+        vec![[1.0, 1.0, 1.0, 0.0], [1.05, 0.97, 1.05, 0.0], [1.0, 1.0, 1.0, 0.0], [1.0; 4]],
+    )];
+    vec![
+        Arc::new(NAnimationClip::new("sway", sway)),
+        Arc::new(NAnimationClip::new("whip", whip)),
+        Arc::new(NAnimationClip::new("breathe", breathe)),
+    ]
+}
@@ -0,0 +1,337 @@
+// Машина станів скелетної анімації — компонент сутності зі скінованим мешем.
+// Стан програє кліп (з повтором або без) зі швидкістю, яку може множити float параметр.
+// Переходи перевіряються раз на update за порядком додавання: перший, у якого виконані
+// всі умови над параметрами (порівняння float, bool, тригери, кінець кліпа), запускає
+// кросфейд тривалістю `duration`. Стан, з якого йде перехід, грає далі, доки не згасне.
+// Поверх результату застосовуються шари: Override змішує свій кліп з вагою і маскою
+// суглобів, Additive додає різницю кліпа відносно його першого кадру (дихання, віддача).
+// Результат — поза й палітра суглобів, яку NMeshDrawSystem передає у вершинний шейдер.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::scene::{
+    animation::NAnimationClip,
+    skeleton::{NPose, NSkeleton},
+};
+
+/// Значення параметра машини станів
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NAnimationParam {
+    Float(f32),
+    Bool(bool),
+    Trigger(bool),  // Сбрасывается переходом, который его использовал
+}
+
+/// Умова переходу
+#[derive(Clone, Debug)]
+pub enum NAnimationCondition {
+    Greater(String, f32),   // Float параметр больше значения
+    Less(String, f32),      // Float параметр меньше значения
+    True(String),           // Bool параметр установлен
+    False(String),          // Bool параметр сброшен
+    Trigger(String),        // Триггер взведён
+    Finished,               // Клип текущего состояния доигран (для повтора — один круг)
+}
+
+/// Стан: кліп і параметри його відтворення
+#[derive(Clone, Debug)]
+pub struct NAnimationState {
+    pub name: String,                       // Имя состояния
+    pub clip: Arc<NAnimationClip>,          // Клип
+    pub speed: f32,                         // Скорость воспроизведения
+    pub speed_parameter: Option<String>,    // Float параметр-множитель скорости
+    pub looping: bool,                      // Повтор клипа
+}
+
+/// Перехід між станами
+#[derive(Clone, Debug)]
+pub struct NAnimationTransition {
+    pub from: Option<usize>,                // Исходное состояние (None — из любого)
+    pub to: usize,                          // Целевое состояние
+    pub conditions: Vec<NAnimationCondition>, // Условия (все должны выполняться)
+    pub duration: f32,                      // Длительность кроссфейда в секундах
+}
+
+impl NAnimationTransition {
+    pub fn when(&mut self, condition: NAnimationCondition) -> &mut Self {
+        self.conditions.push(condition);
+        self
+    }
+}
+
+/// Спосіб накладання шару
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NLayerBlend {
+    #[default]
+    Override,   // Смешивание с результатом по весу
+    Additive,   // Добавление разницы с первым кадром клипа
+}
+
+/// Шар поверх машини станів
+#[derive(Clone, Debug)]
+pub struct NAnimationLayer {
+    pub name: String,               // Имя слоя
+    pub clip: Arc<NAnimationClip>,  // Клип (всегда с повтором)
+    pub blend: NLayerBlend,         // Способ наложения
+    pub weight: f32,                // Вес 0..1
+    pub speed: f32,                 // Скорость воспроизведения
+    pub mask: Option<Vec<f32>>,     // Вес по суставам (см. NSkeleton::mask); None — все
+    time: f32,                      // Время воспроизведения
+}
+
+// Кросфейд зі стану, що згасає
+#[derive(Clone, Copy, Debug)]
+struct NCrossfade {
+    from: usize,        // Угасающее состояние
+    time: f32,          // Время его воспроизведения
+    elapsed: f32,       // Прошло с начала перехода
+    duration: f32,      // Длительность перехода
+}
+
+/// Машина станів анімації скелета
+#[derive(Clone, Debug)]
+pub struct NAnimationStateMachine {
+    pub skeleton: Arc<NSkeleton>,               // Скелет
+    pub states: Vec<NAnimationState>,           // Состояния; первое — начальное
+    pub transitions: Vec<NAnimationTransition>, // Переходы в порядке проверки
+    pub layers: Vec<NAnimationLayer>,           // Слои поверх состояний
+    parameters: BTreeMap<String, NAnimationParam>, // Параметры по имени
+    current: usize,                             // Текущее состояние
+    time: f32,                                  // Время в текущем состоянии
+    fade: Option<NCrossfade>,                   // Идущий кроссфейд
+    pose: NPose,                                // Поза последнего update
+    palette: Vec<[[f32; 4]; 4]>,                // Палитра суставов последнего update
+}
+
+impl NAnimationStateMachine {
+    pub fn new(skeleton: Arc<NSkeleton>) -> Self {
+        let pose = skeleton.rest_pose();
+        let palette = skeleton.palette(&pose);
+        NAnimationStateMachine {
+            skeleton,
+            states: Vec::new(),
+            transitions: Vec::new(),
+            layers: Vec::new(),
+            parameters: BTreeMap::new(),
+            current: 0,
+            time: 0.0,
+            fade: None,
+            pose,
+            palette,
+        }
+    }
+
+    // Додає стан з повтором кліпа і швидкістю 1
+    pub fn add_state(
+        &mut self,
+        name: impl Into<String>,
+        clip: Arc<NAnimationClip>,
+    ) -> &mut NAnimationState {
+        self.states.push(NAnimationState {
+            name: name.into(),
+            clip,
+            speed: 1.0,
+            speed_parameter: None,
+            looping: true,
+        });
+        self.states.last_mut().unwrap()
+    }
+
+    fn state_index(&self, name: &str) -> Option<usize> {
+        self.states.iter().position(|state| state.name == name)
+    }
+
+    // Додає перехід зі стану `from` (None — з будь-якого) у `to`; умови — через when
+    pub fn add_transition(
+        &mut self,
+        from: Option<&str>,
+        to: &str,
+        duration: f32,
+    ) -> &mut NAnimationTransition {
+        let from = from.map(|from| self.state_index(from).expect("unknown animation state"));
+        let to = self.state_index(to).expect("unknown animation state");
+        self.transitions.push(NAnimationTransition { from, to, conditions: Vec::new(), duration });
+        self.transitions.last_mut().unwrap()
+    }
+
+    // Додає шар з вагою 1 без маски
+    pub fn add_layer(
+        &mut self,
+        name: impl Into<String>,
+        clip: Arc<NAnimationClip>,
+        blend: NLayerBlend,
+    ) -> &mut NAnimationLayer {
+        self.layers.push(NAnimationLayer {
+            name: name.into(),
+            clip,
+            blend,
+            weight: 1.0,
+            speed: 1.0,
+            mask: None,
+            time: 0.0,
+        });
+        self.layers.last_mut().unwrap()
+    }
+
+    pub fn set_float(&mut self, name: impl Into<String>, value: f32) {
+        self.parameters.insert(name.into(), NAnimationParam::Float(value));
+    }
+
+    #[allow(dead_code)]
+    pub fn set_bool(&mut self, name: impl Into<String>, value: bool) {
+        self.parameters.insert(name.into(), NAnimationParam::Bool(value));
+    }
+
+    // Взводить тригер до першого переходу, що його використає
+    #[allow(dead_code)]
+    pub fn trigger(&mut self, name: impl Into<String>) {
+        self.parameters.insert(name.into(), NAnimationParam::Trigger(true));
+    }
+
+    #[allow(dead_code)]
+    pub fn parameter(&self, name: &str) -> Option<NAnimationParam> {
+        self.parameters.get(name).copied()
+    }
+
+    fn float(&self, name: &str) -> Option<f32> {
+        match self.parameters.get(name) {
+            Some(NAnimationParam::Float(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    // Переходить у стан `name` кросфейдом тривалістю `duration` в обхід переходів
+    #[allow(dead_code)]
+    pub fn play(&mut self, name: &str, duration: f32) -> bool {
+        match self.state_index(name) {
+            Some(state) => {
+                self.start(state, duration);
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn current_state(&self) -> Option<&str> {
+        self.states.get(self.current).map(|state| state.name.as_str())
+    }
+
+    // Поза і палітра суглобів після останнього update
+    #[allow(dead_code)]
+    pub fn pose(&self) -> &NPose {
+        &self.pose
+    }
+
+    pub fn palette(&self) -> &[[[f32; 4]; 4]] {
+        &self.palette
+    }
+
+    fn start(&mut self, state: usize, duration: f32) {
+        self.fade = (duration > 0.0 && !self.states.is_empty()).then_some(NCrossfade {
+            from: self.current,
+            time: self.time,
+            elapsed: 0.0,
+            duration,
+        });
+        self.current = state;
+        self.time = 0.0;
+    }
+
+    fn condition(&self, condition: &NAnimationCondition) -> bool {
+        let parameter = |name: &String| self.parameters.get(name).copied();
+        match condition {
+            NAnimationCondition::Greater(name, value) => {
+                self.float(name).is_some_and(|parameter| parameter > *value)
+            }
+            NAnimationCondition::Less(name, value) => {
+                self.float(name).is_some_and(|parameter| parameter < *value)
+            }
+            NAnimationCondition::True(name) => parameter(name) == Some(NAnimationParam::Bool(true)),
+            NAnimationCondition::False(name) => {
+                parameter(name) != Some(NAnimationParam::Bool(true))
+            }
+            NAnimationCondition::Trigger(name) => {
+                parameter(name) == Some(NAnimationParam::Trigger(true))
+            }
+            NAnimationCondition::Finished => {
+                self.time >= self.states[self.current].clip.duration
+            }
+        }
+    }
+
+    // Швидкість стану з урахуванням параметра-множника
+    fn speed(&self, state: usize) -> f32 {
+        let state = &self.states[state];
+        let factor = state.speed_parameter.as_deref().and_then(|name| self.float(name));
+        state.speed * factor.unwrap_or(1.0)
+    }
+
+    // Поза стану `state` у момент `time` поверх пози спокою
+    fn sample_state(&self, state: usize, time: f32) -> NPose {
+        let state = &self.states[state];
+        let mut pose = self.skeleton.rest_pose();
+        state.clip.sample(state.clip.wrap_time(time, state.looping), &mut pose);
+        pose
+    }
+
+    // Перевіряє переходи, просуває час станів і шарів на `delta_time` секунд
+    // і перераховує позу та палітру
+    pub fn update(&mut self, delta_time: f32) {
+        if self.states.is_empty() {
+            return;
+        }
+
+        let transition = self.transitions.iter().position(|transition| {
+            let from = transition.from.is_none_or(|from| from == self.current);
+            let to = transition.from.is_some() || transition.to != self.current;
+            from && to && transition.conditions.iter().all(|condition| self.condition(condition))
+        });
+        if let Some(index) = transition {
+            let (to, duration) = (self.transitions[index].to, self.transitions[index].duration);
+            for condition in self.transitions[index].conditions.clone() {
+                if let NAnimationCondition::Trigger(name) = condition {
+                    self.parameters.insert(name, NAnimationParam::Trigger(false));
+                }
+            }
+            self.start(to, duration);
+        }
+
+        self.time += delta_time * self.speed(self.current);
+        let mut pose = self.sample_state(self.current, self.time);
+        if let Some(mut fade) = self.fade {
+            fade.time += delta_time * self.speed(fade.from);
+            fade.elapsed += delta_time;
+            if fade.elapsed < fade.duration {
+                let mut from = self.sample_state(fade.from, fade.time);
+                from.blend(&pose, fade.elapsed / fade.duration, None);
+                pose = from;
+                self.fade = Some(fade);
+            } else {
+                self.fade = None;
+            }
+        }
+
+        for layer in &mut self.layers {
+            layer.time = layer.clip.wrap_time(layer.time + delta_time * layer.speed, true);
+            let mask = layer.mask.as_deref();
+            match layer.blend {
+                NLayerBlend::Override => {
+                    let mut target = pose.clone();
+                    layer.clip.sample(layer.time, &mut target);
+                    pose.blend(&target, layer.weight, mask);
+                }
+                NLayerBlend::Additive => {
+                    let (mut target, mut reference) =
+                        (self.skeleton.rest_pose(), self.skeleton.rest_pose());
+                    layer.clip.sample(layer.time, &mut target);
+                    layer.clip.sample(0.0, &mut reference);
+                    pose.add(&target, &reference, layer.weight, mask);
+                }
+            }
+        }
+
+        self.palette = self.skeleton.palette(&pose);
+        self.pose = pose;
+    }
+}
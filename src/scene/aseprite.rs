@@ -0,0 +1,257 @@
+// Імпорт листів анімацій з JSON експорту Aseprite (File > Export Sprite Sheet).
+// Підтримуються обидва формати списку кадрів: Hash (об'єкт ім'я -> кадр, порядок ключів
+// зберігається) і Array. Кадр — область "frame" атласу і "duration" у мілісекундах;
+// обрізані (trim) кадри розтягуються на весь спрайт, тому для персонажів експорт варто
+// робити без Trim. Мітки кадрів meta.frameTags стають кліпами: "forward" — Loop,
+// "pingpong" — PingPong, "reverse"/"pingpong_reverse" — ті самі режими з кадрами у
+// зворотному порядку, "repeat": "1" — Once.
+// Події кадрів задаються в User Data мітки ("data") рядком "номер:подія" через кому
+// або з нового рядка, номер — позиція кадру в кліпі, напр. "2:step, 6:step".
+// Без міток створюється один кліп "default" з усіх кадрів.
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::{
+    graphics::texture::NTexture,
+    scene::sprite_animation::{NPlayMode, NSpriteFrame, NSpriteSheet},
+};
+
+impl NSpriteSheet {
+    // Розбирає JSON Aseprite для вже завантаженого атласу `texture`
+    pub fn from_aseprite(
+        name: impl Into<String>,
+        json: &str,
+        texture: Arc<NTexture>,
+    ) -> Result<Self, String> {
+        let json: Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let frames: Vec<&Value> = match &json["frames"] {
+            Value::Object(frames) => frames.values().collect(),
+            Value::Array(frames) => frames.iter().collect(),
+            _ => return Err("missing frames".into()),
+        };
+        // Розмір атласу з meta.size, інакше з текстури
+        let size = &json["meta"]["size"];
+        let atlas = match (size["w"].as_f64(), size["h"].as_f64()) {
+            (Some(width), Some(height)) => [width as f32, height as f32],
+            _ => texture.size.map(|size| size as f32),
+        };
+
+        let mut sheet = NSpriteSheet::new(name, texture);
+        for frame in frames {
+            let rect = &frame["frame"];
+            let field = |key: &str| {
+                let value = rect[key].as_f64().ok_or(format!("frame.{} missing", key))?;
+                Ok::<f32, String>(value as f32)
+            };
+            if frame["rotated"].as_bool() == Some(true) {
+                return Err("rotated frames are not supported".into());
+            }
+            let [x, y, width, height] = [field("x")?, field("y")?, field("w")?, field("h")?];
+            sheet.frames.push(NSpriteFrame {
+                uv_rect: [x / atlas[0], y / atlas[1], width / atlas[0], height / atlas[1]],
+                duration: frame["duration"].as_f64().unwrap_or(100.0) as f32 / 1000.0,
+            });
+        }
+        if sheet.frames.is_empty() {
+            return Err("sprite sheet has no frames".into());
+        }
+
+        let tags = json["meta"]["frameTags"].as_array().cloned().unwrap_or_default();
+        for tag in &tags {
+            let name = tag["name"].as_str().unwrap_or_default();
+            let from = tag["from"].as_u64().unwrap_or(0) as usize;
+            let to = tag["to"].as_u64().unwrap_or(0) as usize;
+            if from > to || to >= sheet.frames.len() {
+                return Err(format!("tag {}: frames {}..{} out of range", name, from, to));
+            }
+            let direction = tag["direction"].as_str().unwrap_or("forward");
+            let mut frames: Vec<usize> = (from..=to).collect();
+            if direction.ends_with("reverse") {
+                frames.reverse();
+            }
+            // repeat у новіших версіях Aseprite — рядок, у старших — число
+            let repeat = match &tag["repeat"] {
+                Value::String(repeat) => repeat.parse().ok(),
+                repeat => repeat.as_u64(),
+            };
+            let mode = match direction {
+                _ if repeat == Some(1) => NPlayMode::Once,
+                "pingpong" | "pingpong_reverse" => NPlayMode::PingPong,
+                _ => NPlayMode::Loop,
+            };
+            let length = frames.len();
+            let clip = sheet.add_clip(name, frames, mode);
+            for event in tag["data"].as_str().unwrap_or_default().split([',', '\n']) {
+                let Some((frame, event)) = event.split_once(':') else { continue };
+                let frame: usize = frame.trim().parse().map_err(|_| {
+                    format!("tag {}: bad event frame '{}'", name, frame.trim())
+                })?;
+                if frame >= length {
+                    return Err(format!("tag {}: event frame {} out of range", name, frame));
+                }
+                clip.add_event(frame, event.trim());
+            }
+        }
+        if sheet.clips.is_empty() {
+            let frames = (0..sheet.frames.len()).collect();
+            sheet.add_clip("default", frames, NPlayMode::Loop);
+        }
+        Ok(sheet)
+    }
+
+    // Завантажує JSON Aseprite разом з атласом з meta.image (шлях відносно JSON);
+    // без feature "image" атлас — заглушка розміру meta.size
+    pub fn load_aseprite(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let result = (|| -> Result<Self, String> {
+            let source = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+            let json: Value = serde_json::from_str(&source).map_err(|err| err.to_string())?;
+            let image = json["meta"]["image"].as_str().ok_or("meta.image missing")?;
+            let image = path.parent().unwrap_or(std::path::Path::new("")).join(image);
+            let size = ["w", "h"].map(|key| json["meta"]["size"][key].as_u64().unwrap_or(0) as u32);
+            let texture = Arc::new(NTexture::load_or_placeholder(image, size)?);
+            let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+            NSpriteSheet::from_aseprite(name.unwrap_or_default(), &source, texture)
+        })();
+        result.map_err(|err| format!("{}: {}", path.display(), err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atlas() -> Arc<NTexture> {
+        Arc::new(NTexture::new("atlas", [64, 32], vec![0; 64 * 32 * 4]))
+    }
+
+    // Масив кадрів 16x16 у рядок і мітки `tags` (JSON)
+    fn sheet(tags: &str) -> Result<NSpriteSheet, String> {
+        let frames: Vec<String> = (0..4)
+            .map(|index| {
+                format!(
+                    r#"{{ "frame": {{ "x": {}, "y": 0, "w": 16, "h": 16 }}, "duration": 100 }}"#,
+                    index * 16
+                )
+            })
+            .collect();
+        let json = format!(
+            r#"{{
+                "frames": [{}],
+                "meta": {{ "size": {{ "w": 64, "h": 32 }}, "frameTags": {} }}
+            }}"#,
+            frames.join(", "),
+            tags
+        );
+        NSpriteSheet::from_aseprite("hero", &json, atlas())
+    }
+
+    fn clip<'a>(sheet: &'a NSpriteSheet, name: &str) -> &'a NSpriteClip {
+        sheet.clip(name).unwrap_or_else(|| panic!("missing clip {}", name))
+    }
+
+    #[test]
+    fn hash_frames_keep_order_and_durations() {
+        let json = r#"{
+            "frames": {
+                "hero 2.aseprite": { "frame": { "x": 32, "y": 16, "w": 16, "h": 16 },
+                                     "duration": 250 },
+                "hero 0.aseprite": { "frame": { "x": 0, "y": 0, "w": 16, "h": 32 },
+                                     "duration": 100 },
+                "hero 1.aseprite": { "frame": { "x": 16, "y": 0, "w": 32, "h": 16 } }
+            },
+            "meta": { "size": { "w": 64, "h": 32 } }
+        }"#;
+        let sheet = NSpriteSheet::from_aseprite("hero", json, atlas()).unwrap();
+        let durations: Vec<f32> = sheet.frames.iter().map(|frame| frame.duration).collect();
+        assert_eq!(durations, [0.25, 0.1, 0.1]);
+        let rects: Vec<[f32; 4]> = sheet.frames.iter().map(|frame| frame.uv_rect).collect();
+        assert_eq!(rects, [[0.5, 0.5, 0.25, 0.5], [0.0, 0.0, 0.25, 1.0], [0.25, 0.0, 0.5, 0.5]]);
+    }
+
+    #[test]
+    fn atlas_size_falls_back_to_texture() {
+        let json = r#"{ "frames": [{ "frame": { "x": 32, "y": 16, "w": 32, "h": 16 } }] }"#;
+        let sheet = NSpriteSheet::from_aseprite("hero", json, atlas()).unwrap();
+        assert_eq!(sheet.frames[0].uv_rect, [0.5, 0.5, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn default_clip_without_tags() {
+        let sheet = sheet("[]").unwrap();
+        assert_eq!(sheet.clips.len(), 1);
+        let default = clip(&sheet, "default");
+        assert_eq!(default.frames, [0, 1, 2, 3]);
+        assert_eq!(default.mode, NPlayMode::Loop);
+        assert!(default.events.is_empty());
+    }
+
+    #[test]
+    fn frame_tags_directions() {
+        let sheet = sheet(
+            r#"[
+                { "name": "idle", "from": 0, "to": 1, "direction": "forward" },
+                { "name": "back", "from": 1, "to": 3, "direction": "reverse" },
+                { "name": "swing", "from": 0, "to": 2, "direction": "pingpong" },
+                { "name": "swing back", "from": 0, "to": 2, "direction": "pingpong_reverse" },
+                { "name": "hit", "from": 2, "to": 3, "repeat": "1" },
+                { "name": "die", "from": 3, "to": 3, "direction": "reverse", "repeat": 1 },
+                { "name": "plain", "from": 1, "to": 2 }
+            ]"#,
+        )
+        .unwrap();
+        assert!(sheet.clip("default").is_none());
+        let expected = [
+            ("idle", vec![0, 1], NPlayMode::Loop),
+            ("back", vec![3, 2, 1], NPlayMode::Loop),
+            ("swing", vec![0, 1, 2], NPlayMode::PingPong),
+            ("swing back", vec![2, 1, 0], NPlayMode::PingPong),
+            ("hit", vec![2, 3], NPlayMode::Once),
+            ("die", vec![3], NPlayMode::Once),
+            ("plain", vec![1, 2], NPlayMode::Loop),
+        ];
+        for (name, frames, mode) in expected {
+            let clip = clip(&sheet, name);
+            assert_eq!((&clip.frames, clip.mode), (&frames, mode), "clip {}", name);
+        }
+    }
+
+    #[test]
+    fn frame_tag_events() {
+        let sheet = sheet(r#"[{ "name": "run", "from": 0, "to": 3, "data": "1:step,\n3: step" }]"#);
+        let sheet = sheet.unwrap();
+        let events: Vec<(usize, &str)> = clip(&sheet, "run")
+            .events
+            .iter()
+            .map(|event| (event.frame, event.name.as_str()))
+            .collect();
+        assert_eq!(events, [(1, "step"), (3, "step")]);
+
+        let error = sheet_error(r#"[{ "name": "run", "from": 0, "to": 1, "data": "2:step" }]"#);
+        assert_eq!(error, "tag run: event frame 2 out of range");
+        let error = sheet_error(r#"[{ "name": "run", "from": 0, "to": 1, "data": "x:step" }]"#);
+        assert_eq!(error, "tag run: bad event frame 'x'");
+    }
+
+    fn sheet_error(tags: &str) -> String {
+        match sheet(tags) {
+            Err(error) => error,
+            Ok(_) => panic!("tags {} should be rejected", tags),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_sheets() {
+        assert_eq!(
+            sheet_error(r#"[{ "name": "run", "from": 2, "to": 4 }]"#),
+            "tag run: frames 2..4 out of range"
+        );
+        let rotated = r#"{ "frames": [{ "frame": { "x": 0, "y": 0, "w": 1, "h": 1 },
+                                        "rotated": true }] }"#;
+        assert!(NSpriteSheet::from_aseprite("hero", rotated, atlas()).is_err());
+        assert!(NSpriteSheet::from_aseprite("hero", r#"{ "frames": [] }"#, atlas()).is_err());
+        assert!(NSpriteSheet::from_aseprite("hero", "{}", atlas()).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+// Обмежувальні об'єми та геометричні запити: AABB, промінь і frustum камери.
+
+use cgmath::{EuclideanSpace, InnerSpace, Matrix, Matrix4, Point3, Vector3, Vector4};
+
+/// Вирівняний по осях паралелепіпед
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NAabb {
+    pub min: Point3<f32>,   // Минимальный угол
+    pub max: Point3<f32>,   // Максимальный угол
+}
+
+impl NAabb {
+    pub fn new(min: Point3<f32>, max: Point3<f32>) -> Self {
+        NAabb { min, max }
+    }
+
+    // Найменший AABB, що містить усі точки (None для порожнього набору)
+    pub fn from_points(points: impl IntoIterator<Item = Point3<f32>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(NAabb::new(first, first), |aabb, point| {
+            aabb.union(&NAabb::new(point, point))
+        }))
+    }
+
+    pub fn center(&self) -> Point3<f32> {
+        self.min.midpoint(self.max)
+    }
+
+    // Половина розміру по кожній осі
+    pub fn half_extents(&self) -> Vector3<f32> {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn union(&self, other: &NAabb) -> NAabb {
+        NAabb::new(
+            Point3::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            Point3::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        )
+    }
+
+    // AABB, розширений на `margin` в усі боки
+    pub fn expanded(&self, margin: f32) -> NAabb {
+        let margin = Vector3::new(margin, margin, margin);
+        NAabb::new(self.min - margin, self.max + margin)
+    }
+
+    // Чи лежить `other` повністю всередині
+    pub fn contains(&self, other: &NAabb) -> bool {
+        self.min.x <= other.min.x
+            && self.min.y <= other.min.y
+            && self.min.z <= other.min.z
+            && self.max.x >= other.max.x
+            && self.max.y >= other.max.y
+            && self.max.z >= other.max.z
+    }
+
+    pub fn intersects(&self, other: &NAabb) -> bool {
+        self.min.x <= other.max.x
+            && self.min.y <= other.max.y
+            && self.min.z <= other.max.z
+            && self.max.x >= other.min.x
+            && self.max.y >= other.min.y
+            && self.max.z >= other.min.z
+    }
+
+    // Площа поверхні — вартість вузла в евристиці побудови BVH
+    pub fn surface_area(&self) -> f32 {
+        let size = self.max - self.min;
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    // AABB трансформованого паралелепіпеда (метод Арво: центр переноситься матрицею,
+    // а половини розмірів — модулями її елементів)
+    pub fn transformed(&self, matrix: &Matrix4<f32>) -> NAabb {
+        let center = Point3::from_homogeneous(matrix * self.center().to_homogeneous());
+        let half = self.half_extents();
+        let extent = |row: usize| {
+            matrix.x[row].abs() * half.x
+                + matrix.y[row].abs() * half.y
+                + matrix.z[row].abs() * half.z
+        };
+        let half = Vector3::new(extent(0), extent(1), extent(2));
+        NAabb::new(center - half, center + half)
+    }
+
+    // Відстань уздовж променя до входу в AABB (0, якщо початок всередині)
+    pub fn ray_distance(&self, ray: &NRay) -> Option<f32> {
+        let mut near = 0.0f32;
+        let mut far = f32::INFINITY;
+        for axis in 0..3 {
+            let inverse = 1.0 / ray.direction[axis];
+            let t0 = (self.min[axis] - ray.origin[axis]) * inverse;
+            let t1 = (self.max[axis] - ray.origin[axis]) * inverse;
+            // f32::min/max ігнорують NaN (промінь паралельний грані й лежить у ній)
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+        }
+        (near <= far).then_some(near)
+    }
+}
+
+/// Промінь з нормалізованим напрямком
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NRay {
+    pub origin: Point3<f32>,        // Начало
+    pub direction: Vector3<f32>,    // Направление (единичной длины)
+}
+
+impl NRay {
+    pub fn new(origin: Point3<f32>, direction: Vector3<f32>) -> Self {
+        NRay { origin, direction: direction.normalize() }
+    }
+
+    #[allow(dead_code)]
+    pub fn at(&self, distance: f32) -> Point3<f32> {
+        self.origin + self.direction * distance
+    }
+}
+
+/// Frustum камери: шість площин, нормалі дивляться всередину
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NFrustum {
+    planes: [Vector4<f32>; 6],  // (нормаль, смещение): точка внутри, если dot >= 0
+}
+
+impl NFrustum {
+    // Площини з матриці світ -> clip space (метод Гріба-Хартмана).
+    // Clip space Vulkan: x, y у -w..w, z у 0..w.
+    pub fn from_view_projection(matrix: Matrix4<f32>) -> Self {
+        let [r0, r1, r2, r3] = [0, 1, 2, 3].map(|row| matrix.row(row));
+        let planes = [r3 + r0, r3 - r0, r3 + r1, r3 - r1, r2, r3 - r2].map(|plane| {
+            let length = plane.truncate().magnitude();
+            if length > 0.0 { plane / length } else { plane }
+        });
+        NFrustum { planes }
+    }
+
+    // Консервативна перевірка: AABB відкидається, лише якщо він повністю
+    // за однією з площин (для кожної площини перевіряється найдальша вершина)
+    pub fn intersects_aabb(&self, aabb: &NAabb) -> bool {
+        self.planes.iter().all(|plane| {
+            let vertex = Vector4::new(
+                if plane.x >= 0.0 { aabb.max.x } else { aabb.min.x },
+                if plane.y >= 0.0 { aabb.max.y } else { aabb.min.y },
+                if plane.z >= 0.0 { aabb.max.z } else { aabb.min.z },
+                1.0,
+            );
+            plane.dot(vertex) >= 0.0
+        })
+    }
+}
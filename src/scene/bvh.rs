@@ -0,0 +1,437 @@
+// Динамічна ієрархія обмежувальних об'ємів (BVH) сутностей сцени.
+// Бінарне дерево AABB, як dynamic tree у Box2D:
+// - листок зберігає точні межі сутності та розширені (fat) на BVH_MARGIN,
+//   тож невеликі переміщення не змінюють дерево зовсім,
+// - якщо точні межі вийшли за розширені (або сильно зменшилися), листок
+//   виймається і вставляється заново — решта дерева лише перераховує AABB предків,
+// - місце вставки обирається спуском за мінімальним приростом площі поверхні.
+// Запити (frustum, промінь, AABB) обходять лише гілки, які перетинає об'єм.
+
+use std::collections::HashMap;
+
+use crate::scene::{
+    bounds::{NAabb, NFrustum, NRay},
+    entity::NEntityId,
+};
+
+// Запас розширених меж листка в одиницях світу
+const BVH_MARGIN: f32 = 0.1;
+// Листок перевставляється, якщо розширені межі в стільки разів більші за потрібні
+const BVH_SHRINK_RATIO: f32 = 4.0;
+
+#[derive(Clone, Copy, Debug)]
+enum NBvhNodeKind {
+    Leaf { entity: NEntityId, bounds: NAabb },  // Сущность и её точные границы
+    Branch { children: [usize; 2] },            // Два дочерних узла
+}
+
+#[derive(Clone, Debug)]
+struct NBvhNode {
+    bounds: NAabb,              // Границы узла (у листа — расширенные)
+    parent: Option<usize>,      // Родитель (None у корня)
+    kind: NBvhNodeKind,         // Лист или ветвь
+}
+
+/// BVH сутностей: вставка, видалення, інкрементальне оновлення та запити
+#[derive(Clone, Debug, Default)]
+pub struct NBvh {
+    nodes: Vec<NBvhNode>,                   // Узлы (освобождённые лежат в free)
+    free: Vec<usize>,                       // Свободные индексы узлов
+    root: Option<usize>,                    // Корень дерева
+    leaves: HashMap<NEntityId, usize>,      // Лист каждой сущности
+}
+
+impl NBvh {
+    // Кількість сутностей у дереві
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    // Додає сутність або оновлює її межі. Дерево змінюється, лише якщо
+    // нові межі не вміщаються в розширені межі листка.
+    pub fn update(&mut self, entity: NEntityId, bounds: NAabb) {
+        let Some(&leaf) = self.leaves.get(&entity) else {
+            let leaf = self.allocate(NBvhNode {
+                bounds: bounds.expanded(BVH_MARGIN),
+                parent: None,
+                kind: NBvhNodeKind::Leaf { entity, bounds },
+            });
+            self.leaves.insert(entity, leaf);
+            self.insert_leaf(leaf);
+            return;
+        };
+
+        let fat = bounds.expanded(BVH_MARGIN);
+        let node = &mut self.nodes[leaf];
+        node.kind = NBvhNodeKind::Leaf { entity, bounds };
+        if node.bounds.contains(&bounds)
+            && node.bounds.surface_area() <= fat.surface_area() * BVH_SHRINK_RATIO
+        {
+            return;
+        }
+        self.remove_leaf(leaf);
+        self.nodes[leaf].bounds = fat;
+        self.insert_leaf(leaf);
+    }
+
+    pub fn remove(&mut self, entity: NEntityId) -> bool {
+        let Some(leaf) = self.leaves.remove(&entity) else { return false };
+        self.remove_leaf(leaf);
+        self.free.push(leaf);
+        true
+    }
+
+    // Видаляє сутності, для яких `keep` повертає false
+    pub fn retain(&mut self, keep: impl Fn(NEntityId) -> bool) {
+        let removed: Vec<NEntityId> =
+            self.leaves.keys().copied().filter(|&entity| !keep(entity)).collect();
+        for entity in removed {
+            self.remove(entity);
+        }
+    }
+
+    // Точні межі сутності
+    pub fn bounds(&self, entity: NEntityId) -> Option<NAabb> {
+        match self.nodes[*self.leaves.get(&entity)?].kind {
+            NBvhNodeKind::Leaf { bounds, .. } => Some(bounds),
+            NBvhNodeKind::Branch { .. } => None,
+        }
+    }
+
+    // Сутності, межі яких хоча б частково всередині frustum
+    pub fn query_frustum(&self, frustum: &NFrustum) -> Vec<NEntityId> {
+        let mut result = Vec::new();
+        self.traverse(|bounds| frustum.intersects_aabb(bounds), |entity, _| result.push(entity));
+        result
+    }
+
+    // Сутності, межі яких перетинають `aabb`
+    #[allow(dead_code)]
+    pub fn query_box(&self, aabb: &NAabb) -> Vec<NEntityId> {
+        let mut result = Vec::new();
+        self.traverse(|bounds| bounds.intersects(aabb), |entity, _| result.push(entity));
+        result
+    }
+
+    // Сутності, межі яких перетинає промінь не далі `max_distance`,
+    // від найближчої до найдальшої разом з відстанню до меж
+    pub fn query_ray(&self, ray: &NRay, max_distance: f32) -> Vec<(NEntityId, f32)> {
+        let hit = |bounds: &NAabb| bounds.ray_distance(ray).filter(|&t| t <= max_distance);
+        let mut result = Vec::new();
+        self.traverse(
+            |bounds| hit(bounds).is_some(),
+            |entity, bounds| {
+                if let Some(distance) = hit(bounds) {
+                    result.push((entity, distance));
+                }
+            },
+        );
+        result.sort_by(|a, b| a.1.total_cmp(&b.1));
+        result
+    }
+
+    // Обхід у глибину: гілки, для яких `overlaps` повертає false, пропускаються;
+    // для листків `overlaps` перевіряє точні межі
+    fn traverse(
+        &self,
+        overlaps: impl Fn(&NAabb) -> bool,
+        mut visit: impl FnMut(NEntityId, &NAabb),
+    ) {
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !overlaps(&node.bounds) {
+                continue;
+            }
+            match &node.kind {
+                NBvhNodeKind::Leaf { entity, bounds } => {
+                    if overlaps(bounds) {
+                        visit(*entity, bounds);
+                    }
+                }
+                NBvhNodeKind::Branch { children } => stack.extend(children),
+            }
+        }
+    }
+
+    fn allocate(&mut self, node: NBvhNode) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    // Вставляє листок: спускається від кореня туди, де новий батьківський
+    // вузол найменше збільшує сумарну площу поверхні дерева
+    fn insert_leaf(&mut self, leaf: usize) {
+        let Some(root) = self.root else {
+            self.nodes[leaf].parent = None;
+            self.root = Some(leaf);
+            return;
+        };
+
+        let bounds = self.nodes[leaf].bounds;
+        let mut sibling = root;
+        while let NBvhNodeKind::Branch { children } = self.nodes[sibling].kind {
+            let area = self.nodes[sibling].bounds.surface_area();
+            let combined = self.nodes[sibling].bounds.union(&bounds).surface_area();
+            // Вартість нового батька тут і приріст площі, який успадкують нащадки
+            let cost = 2.0 * combined;
+            let inheritance = 2.0 * (combined - area);
+            let descend_cost = |child: usize| {
+                let node = &self.nodes[child];
+                let union = node.bounds.union(&bounds).surface_area();
+                match node.kind {
+                    NBvhNodeKind::Leaf { .. } => union + inheritance,
+                    NBvhNodeKind::Branch { .. } => {
+                        union - node.bounds.surface_area() + inheritance
+                    }
+                }
+            };
+            let (cost0, cost1) = (descend_cost(children[0]), descend_cost(children[1]));
+            if cost < cost0 && cost < cost1 {
+                break;
+            }
+            sibling = if cost0 < cost1 { children[0] } else { children[1] };
+        }
+
+        let old_parent = self.nodes[sibling].parent;
+        let parent = self.allocate(NBvhNode {
+            bounds: self.nodes[sibling].bounds.union(&bounds),
+            parent: old_parent,
+            kind: NBvhNodeKind::Branch { children: [sibling, leaf] },
+        });
+        self.nodes[sibling].parent = Some(parent);
+        self.nodes[leaf].parent = Some(parent);
+        match old_parent {
+            Some(old_parent) => self.replace_child(old_parent, sibling, parent),
+            None => self.root = Some(parent),
+        }
+        self.refit(old_parent);
+    }
+
+    // Виймає листок з дерева (вузол листка лишається за викликачем);
+    // його батько звільняється, а місце батька займає сусід
+    fn remove_leaf(&mut self, leaf: usize) {
+        let Some(parent) = self.nodes[leaf].parent else {
+            self.root = None;
+            return;
+        };
+        let NBvhNodeKind::Branch { children } = self.nodes[parent].kind else {
+            unreachable!("bvh leaf parent must be a branch");
+        };
+        let sibling = if children[0] == leaf { children[1] } else { children[0] };
+        let grandparent = self.nodes[parent].parent;
+        self.nodes[sibling].parent = grandparent;
+        match grandparent {
+            Some(grandparent) => self.replace_child(grandparent, parent, sibling),
+            None => self.root = Some(sibling),
+        }
+        self.free.push(parent);
+        self.refit(grandparent);
+    }
+
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        if let NBvhNodeKind::Branch { children } = &mut self.nodes[parent].kind {
+            for child in children.iter_mut().filter(|child| **child == old) {
+                *child = new;
+            }
+        }
+    }
+
+    // Перераховує межі вузла та всіх його предків
+    fn refit(&mut self, mut node: Option<usize>) {
+        while let Some(index) = node {
+            if let NBvhNodeKind::Branch { children: [left, right] } = self.nodes[index].kind {
+                self.nodes[index].bounds = self.nodes[left].bounds.union(&self.nodes[right].bounds);
+            }
+            node = self.nodes[index].parent;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Matrix4, Point3, Vector3};
+
+    use super::*;
+
+    // Куб з ребром `size` з центром у (x, y, z)
+    fn cube(x: f32, y: f32, z: f32, size: f32) -> NAabb {
+        let half = size * 0.5;
+        NAabb::new(
+            Point3::new(x - half, y - half, z - half),
+            Point3::new(x + half, y + half, z + half),
+        )
+    }
+
+    fn sorted(mut entities: Vec<NEntityId>) -> Vec<NEntityId> {
+        entities.sort();
+        entities
+    }
+
+    // Інваріанти дерева: батьки й діти узгоджені, гілки містять межі дітей,
+    // листки — точні межі, кожна сутність досяжна з кореня рівно один раз
+    fn check(bvh: &NBvh) {
+        let mut found = Vec::new();
+        let mut stack: Vec<usize> = bvh.root.into_iter().collect();
+        if let Some(root) = bvh.root {
+            assert_eq!(bvh.nodes[root].parent, None);
+        }
+        while let Some(index) = stack.pop() {
+            let node = &bvh.nodes[index];
+            match node.kind {
+                NBvhNodeKind::Leaf { entity, bounds } => {
+                    assert!(node.bounds.contains(&bounds));
+                    assert_eq!(bvh.leaves[&entity], index);
+                    found.push(entity);
+                }
+                NBvhNodeKind::Branch { children } => {
+                    for child in children {
+                        assert_eq!(bvh.nodes[child].parent, Some(index));
+                        assert!(node.bounds.contains(&bvh.nodes[child].bounds));
+                        stack.push(child);
+                    }
+                }
+            }
+        }
+        assert_eq!(sorted(found), sorted(bvh.leaves.keys().copied().collect()));
+    }
+
+    #[test]
+    fn insert_keeps_exact_bounds() {
+        let mut bvh = NBvh::default();
+        assert!(bvh.is_empty());
+        for index in 0..16 {
+            bvh.update(NEntityId(index), cube(index as f32 * 2.0, 0.0, 0.0, 1.0));
+            check(&bvh);
+        }
+        assert_eq!(bvh.len(), 16);
+        assert_eq!(bvh.bounds(NEntityId(3)), Some(cube(6.0, 0.0, 0.0, 1.0)));
+        assert_eq!(bvh.bounds(NEntityId(16)), None);
+    }
+
+    #[test]
+    fn update_within_margin_keeps_tree() {
+        let mut bvh = NBvh::default();
+        bvh.update(NEntityId(0), cube(0.0, 0.0, 0.0, 1.0));
+        bvh.update(NEntityId(1), cube(5.0, 0.0, 0.0, 1.0));
+        let leaf = bvh.leaves[&NEntityId(0)];
+        let nodes: Vec<NAabb> = bvh.nodes.iter().map(|node| node.bounds).collect();
+
+        let moved = cube(BVH_MARGIN * 0.5, 0.0, 0.0, 1.0);
+        bvh.update(NEntityId(0), moved);
+        check(&bvh);
+        assert_eq!(bvh.leaves[&NEntityId(0)], leaf);
+        assert_eq!(bvh.nodes.iter().map(|node| node.bounds).collect::<Vec<_>>(), nodes);
+        assert_eq!(bvh.bounds(NEntityId(0)), Some(moved));
+    }
+
+    #[test]
+    fn update_out_of_bounds_reinserts() {
+        let mut bvh = NBvh::default();
+        for index in 0..4 {
+            bvh.update(NEntityId(index), cube(index as f32 * 3.0, 0.0, 0.0, 1.0));
+        }
+        let moved = cube(0.0, 20.0, 0.0, 1.0);
+        bvh.update(NEntityId(0), moved);
+        check(&bvh);
+        let leaf = bvh.leaves[&NEntityId(0)];
+        assert_eq!(bvh.nodes[leaf].bounds, moved.expanded(BVH_MARGIN));
+        assert!(bvh.query_box(&cube(0.0, 0.0, 0.0, 1.0)).is_empty());
+        assert_eq!(bvh.query_box(&moved), vec![NEntityId(0)]);
+
+        // Сильне зменшення теж перебудовує листок
+        let shrunk = cube(0.0, 20.0, 0.0, 0.01);
+        bvh.update(NEntityId(0), shrunk);
+        check(&bvh);
+        assert_eq!(bvh.nodes[bvh.leaves[&NEntityId(0)]].bounds, shrunk.expanded(BVH_MARGIN));
+    }
+
+    #[test]
+    fn remove_and_retain() {
+        let mut bvh = NBvh::default();
+        for index in 0..10 {
+            bvh.update(NEntityId(index), cube(index as f32 * 2.0, 0.0, 0.0, 1.0));
+        }
+        assert!(bvh.remove(NEntityId(4)));
+        assert!(!bvh.remove(NEntityId(4)));
+        check(&bvh);
+        assert_eq!(bvh.len(), 9);
+        assert_eq!(bvh.bounds(NEntityId(4)), None);
+
+        bvh.retain(|entity| entity.0 % 2 == 1);
+        check(&bvh);
+        let everything = cube(0.0, 0.0, 0.0, 100.0);
+        let odd = vec![NEntityId(1), NEntityId(3), NEntityId(5), NEntityId(7), NEntityId(9)];
+        assert_eq!(sorted(bvh.query_box(&everything)), odd);
+
+        bvh.retain(|_| false);
+        check(&bvh);
+        assert!(bvh.is_empty());
+        assert_eq!(bvh.root, None);
+
+        // Звільнені вузли використовуються повторно
+        let capacity = bvh.nodes.len();
+        for index in 0..10 {
+            bvh.update(NEntityId(index), cube(index as f32 * 2.0, 0.0, 0.0, 1.0));
+        }
+        check(&bvh);
+        assert_eq!(bvh.nodes.len(), capacity);
+    }
+
+    #[test]
+    fn query_frustum_culls_outside() {
+        let mut bvh = NBvh::default();
+        bvh.update(NEntityId(0), cube(0.0, 0.0, -5.0, 1.0));
+        bvh.update(NEntityId(1), cube(20.0, 0.0, -5.0, 1.0));
+        bvh.update(NEntityId(2), cube(-10.0, 0.0, -5.0, 1.0));
+        bvh.update(NEntityId(3), cube(0.0, 0.0, 5.0, 1.0));
+        // Ортографічна камера в початку координат дивиться вздовж -Z
+        let projection = Matrix4::from_nonuniform_scale(0.1, 0.1, -0.01);
+        let frustum = NFrustum::from_view_projection(projection);
+        assert_eq!(sorted(bvh.query_frustum(&frustum)), vec![NEntityId(0), NEntityId(2)]);
+    }
+
+    #[test]
+    fn query_ray_sorts_by_distance() {
+        let mut bvh = NBvh::default();
+        bvh.update(NEntityId(0), cube(10.0, 0.0, 0.0, 2.0));
+        bvh.update(NEntityId(1), cube(5.0, 0.0, 0.0, 2.0));
+        bvh.update(NEntityId(2), cube(20.0, 0.0, 0.0, 2.0));
+        bvh.update(NEntityId(3), cube(5.0, 5.0, 0.0, 2.0));
+        let ray = NRay::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+
+        let hits = bvh.query_ray(&ray, f32::INFINITY);
+        assert_eq!(hits, vec![(NEntityId(1), 4.0), (NEntityId(0), 9.0), (NEntityId(2), 19.0)]);
+        assert_eq!(bvh.query_ray(&ray, 10.0), vec![(NEntityId(1), 4.0), (NEntityId(0), 9.0)]);
+        let backwards = NRay::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(-1.0, 0.0, 0.0));
+        assert!(bvh.query_ray(&backwards, f32::INFINITY).is_empty());
+    }
+
+    #[test]
+    fn query_box_uses_exact_bounds() {
+        let mut bvh = NBvh::default();
+        for index in 0..8 {
+            bvh.update(NEntityId(index), cube(index as f32 * 2.0, 0.0, 0.0, 1.0));
+        }
+        assert_eq!(sorted(bvh.query_box(&cube(3.0, 0.0, 0.0, 2.5))), vec![
+            NEntityId(1),
+            NEntityId(2)
+        ]);
+        // Перетинає лише розширені межі листка, не точні
+        let gap = BVH_MARGIN * 0.5;
+        assert!(bvh.query_box(&cube(0.5 + gap, 0.0, 0.0, gap)).is_empty());
+    }
+}
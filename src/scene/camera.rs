@@ -0,0 +1,133 @@
+use cgmath::{perspective, Deg, Matrix4, Point3, SquareMatrix, Vector3, Vector4};
+
+use crate::{
+    graphics::post::settings::NPostProcessSettings,
+    scene::bounds::{NFrustum, NRay},
+};
+
+// cgmath будує проекцію для OpenGL (z у -1..1, y вгору).
+// Для Vulkan перевертаємо y та стискаємо z в 0..1.
+#[rustfmt::skip]
+const OPENGL_TO_VULKAN: Matrix4<f32> = Matrix4::new(
+    1.0,  0.0, 0.0, 0.0,
+    0.0, -1.0, 0.0, 0.0,
+    0.0,  0.0, 0.5, 0.0,
+    0.0,  0.0, 0.5, 1.0,
+);
+
+/// Процедурне небо: градієнт від зеніту до горизонту і від горизонту до землі
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NGradientSky {
+    pub zenith: [f32; 3],       // Цвет в зените (линейный RGB)
+    pub horizon: [f32; 3],      // Цвет у горизонта
+    pub ground: [f32; 3],       // Цвет земли (под горизонтом)
+}
+
+impl Default for NGradientSky {
+    fn default() -> Self {
+        NGradientSky {
+            zenith: [0.18, 0.36, 0.85],
+            horizon: [0.85, 0.9, 1.0],
+            ground: [0.22, 0.2, 0.18],
+        }
+    }
+}
+
+/// Чим камера заповнює пікселі, не закриті геометрією
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NBackground {
+    Color([f32; 4]),            // Очистка цветом (линейный RGBA)
+    #[default]
+    Skybox,                     // Кубмапа окружения сцены
+    Gradient(NGradientSky),     // Процедурное небо
+}
+
+impl NBackground {
+    // Колір очищення кадру; skybox і градієнт потім перекривають його
+    pub fn clear_color(&self) -> [f32; 4] {
+        match self {
+            NBackground::Color(color) => *color,
+            NBackground::Skybox | NBackground::Gradient(_) => [0.0, 0.0, 0.0, 1.0],
+        }
+    }
+}
+
+/// Камера сцени: положення, проекція, фон та власний стек пост-обробки
+#[derive(Clone, Debug)]
+pub struct NCamera {
+    pub position: Point3<f32>,      // Позиция камеры
+    pub target: Point3<f32>,        // Точка, на которую смотрит камера
+    pub up: Vector3<f32>,           // Направление "вверх"
+    pub fov_y: Deg<f32>,            // Вертикальный угол обзора
+    pub near: f32,                  // Ближняя плоскость отсечения
+    pub far: f32,                   // Дальняя плоскость отсечения
+    pub background: NBackground,    // Фон кадра
+    pub post_process: NPostProcessSettings, // Пост-обработка этой камеры
+}
+
+impl Default for NCamera {
+    fn default() -> Self {
+        NCamera {
+            position: Point3::new(0.0, 0.0, 2.0),
+            target: Point3::new(0.0, 0.0, 0.0),
+            up: Vector3::unit_y(),
+            fov_y: Deg(60.0),
+            near: 0.1,
+            far: 1000.0,
+            background: NBackground::Skybox,
+            post_process: NPostProcessSettings::default(),
+        }
+    }
+}
+
+impl NCamera {
+    pub fn view(&self) -> Matrix4<f32> {
+        Matrix4::look_at_rh(self.position, self.target, self.up)
+    }
+
+    pub fn projection(&self, aspect_ratio: f32) -> Matrix4<f32> {
+        OPENGL_TO_VULKAN * perspective(self.fov_y, aspect_ratio, self.near, self.far)
+    }
+
+    // Матриця світ -> clip space для кадру з розміром viewport_dimensions
+    pub fn view_projection(&self, viewport_dimensions: [u32; 2]) -> Matrix4<f32> {
+        let aspect_ratio = viewport_dimensions[0] as f32 / viewport_dimensions[1].max(1) as f32;
+        self.projection(aspect_ratio) * self.view()
+    }
+
+    // Промінь з камери через точку вьюпорта; uv — частки розміру вьюпорта
+    // від лівого верхнього кута (0..1)
+    pub fn screen_ray(&self, uv: [f32; 2], viewport_dimensions: [u32; 2]) -> NRay {
+        let inverse = self.view_projection(viewport_dimensions).invert().unwrap();
+        // Vulkan NDC: y вниз, як і в uv, глибина від 0 (near) до 1 (far)
+        let [x, y] = uv.map(|c| c * 2.0 - 1.0);
+        let unproject =
+            |depth: f32| Point3::from_homogeneous(inverse * Vector4::new(x, y, depth, 1.0));
+        let near = unproject(0.0);
+        NRay::new(near, unproject(1.0) - near)
+    }
+
+    // Frustum частини вьюпорта між кутами `min` і `max` (uv, як у screen_ray) —
+    // для виділення рамкою. Clip space перетворюється так, що прямокутник
+    // займає весь NDC, після чого площини знаходяться як для цілого кадру.
+    pub fn region_frustum(
+        &self,
+        min: [f32; 2],
+        max: [f32; 2],
+        viewport_dimensions: [u32; 2],
+    ) -> NFrustum {
+        let [min_x, min_y] = min.map(|c| c * 2.0 - 1.0);
+        let [max_x, max_y] = max.map(|c| c * 2.0 - 1.0);
+        let scale_x = 2.0 / (max_x - min_x).max(f32::EPSILON);
+        let scale_y = 2.0 / (max_y - min_y).max(f32::EPSILON);
+        let center = [(min_x + max_x) * 0.5, (min_y + max_y) * 0.5];
+        #[rustfmt::skip]
+        let region = Matrix4::new(
+            scale_x,               0.0,                   0.0, 0.0,
+            0.0,                   scale_y,               0.0, 0.0,
+            0.0,                   0.0,                   1.0, 0.0,
+            -scale_x * center[0], -scale_y * center[1], 0.0, 1.0,
+        );
+        NFrustum::from_view_projection(region * self.view_projection(viewport_dimensions))
+    }
+}
@@ -0,0 +1,257 @@
+// Бібліотека функцій пом'якшення (easing) для ключових кадрів і твінів.
+// Функція переводить нормований час 0..1 у прогрес 0..1 (Back та Elastic виходять
+// за межі). Кожне сімейство має три варіанти: In — повільний старт, Out — повільний
+// фініш (дзеркало In), InOut — In на першій половині та Out на другій.
+// Імена для JSON кліпів — snake_case: "linear", "step", "quad_in", "bounce_out",
+// "elastic_in_out" тощо.
+
+use std::f32::consts::PI;
+
+/// Функція пом'якшення
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NEasing {
+    #[default]
+    Linear,         // Равномерно
+    Step,           // Держит начало до конца отрезка
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    QuartIn,
+    QuartOut,
+    QuartInOut,
+    QuintIn,
+    QuintOut,
+    QuintInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    CircIn,
+    CircOut,
+    CircInOut,
+    BackIn,         // Отступ назад перед стартом
+    BackOut,
+    BackInOut,
+    ElasticIn,      // Затухающие колебания пружины
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,       // Отскоки мяча
+    BounceOut,
+    BounceInOut,
+}
+
+// Варіант сімейства
+#[derive(Clone, Copy)]
+enum NEasingMode {
+    In,
+    Out,
+    InOut,
+}
+
+const NAMES: [(&str, NEasing); 32] = [
+    ("linear", NEasing::Linear),
+    ("step", NEasing::Step),
+    ("quad_in", NEasing::QuadIn),
+    ("quad_out", NEasing::QuadOut),
+    ("quad_in_out", NEasing::QuadInOut),
+    ("cubic_in", NEasing::CubicIn),
+    ("cubic_out", NEasing::CubicOut),
+    ("cubic_in_out", NEasing::CubicInOut),
+    ("quart_in", NEasing::QuartIn),
+    ("quart_out", NEasing::QuartOut),
+    ("quart_in_out", NEasing::QuartInOut),
+    ("quint_in", NEasing::QuintIn),
+    ("quint_out", NEasing::QuintOut),
+    ("quint_in_out", NEasing::QuintInOut),
+    ("sine_in", NEasing::SineIn),
+    ("sine_out", NEasing::SineOut),
+    ("sine_in_out", NEasing::SineInOut),
+    ("expo_in", NEasing::ExpoIn),
+    ("expo_out", NEasing::ExpoOut),
+    ("expo_in_out", NEasing::ExpoInOut),
+    ("circ_in", NEasing::CircIn),
+    ("circ_out", NEasing::CircOut),
+    ("circ_in_out", NEasing::CircInOut),
+    ("back_in", NEasing::BackIn),
+    ("back_out", NEasing::BackOut),
+    ("back_in_out", NEasing::BackInOut),
+    ("elastic_in", NEasing::ElasticIn),
+    ("elastic_out", NEasing::ElasticOut),
+    ("elastic_in_out", NEasing::ElasticInOut),
+    ("bounce_in", NEasing::BounceIn),
+    ("bounce_out", NEasing::BounceOut),
+    ("bounce_in_out", NEasing::BounceInOut),
+];
+
+impl NEasing {
+    pub fn from_name(name: &str) -> Option<Self> {
+        NAMES.iter().find(|(key, _)| *key == name).map(|(_, easing)| *easing)
+    }
+
+    #[allow(dead_code)]
+    pub fn name(self) -> &'static str {
+        NAMES.iter().find(|(_, easing)| *easing == self).map(|(name, _)| *name).unwrap()
+    }
+
+    // Прогрес у момент `t` (обмежується 0..1)
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        let (ease_in, mode): (fn(f32) -> f32, NEasingMode) = match self {
+            NEasing::Linear => return t,
+            NEasing::Step => return if t < 1.0 { 0.0 } else { 1.0 },
+            NEasing::QuadIn => (quad_in, NEasingMode::In),
+            NEasing::QuadOut => (quad_in, NEasingMode::Out),
+            NEasing::QuadInOut => (quad_in, NEasingMode::InOut),
+            NEasing::CubicIn => (cubic_in, NEasingMode::In),
+            NEasing::CubicOut => (cubic_in, NEasingMode::Out),
+            NEasing::CubicInOut => (cubic_in, NEasingMode::InOut),
+            NEasing::QuartIn => (quart_in, NEasingMode::In),
+            NEasing::QuartOut => (quart_in, NEasingMode::Out),
+            NEasing::QuartInOut => (quart_in, NEasingMode::InOut),
+            NEasing::QuintIn => (quint_in, NEasingMode::In),
+            NEasing::QuintOut => (quint_in, NEasingMode::Out),
+            NEasing::QuintInOut => (quint_in, NEasingMode::InOut),
+            NEasing::SineIn => (sine_in, NEasingMode::In),
+            NEasing::SineOut => (sine_in, NEasingMode::Out),
+            NEasing::SineInOut => (sine_in, NEasingMode::InOut),
+            NEasing::ExpoIn => (expo_in, NEasingMode::In),
+            NEasing::ExpoOut => (expo_in, NEasingMode::Out),
+            NEasing::ExpoInOut => (expo_in, NEasingMode::InOut),
+            NEasing::CircIn => (circ_in, NEasingMode::In),
+            NEasing::CircOut => (circ_in, NEasingMode::Out),
+            NEasing::CircInOut => (circ_in, NEasingMode::InOut),
+            NEasing::BackIn => (back_in, NEasingMode::In),
+            NEasing::BackOut => (back_in, NEasingMode::Out),
+            NEasing::BackInOut => (back_in, NEasingMode::InOut),
+            NEasing::ElasticIn => (elastic_in, NEasingMode::In),
+            NEasing::ElasticOut => (elastic_in, NEasingMode::Out),
+            NEasing::ElasticInOut => (elastic_in, NEasingMode::InOut),
+            NEasing::BounceIn => (bounce_in, NEasingMode::In),
+            NEasing::BounceOut => (bounce_in, NEasingMode::Out),
+            NEasing::BounceInOut => (bounce_in, NEasingMode::InOut),
+        };
+        match mode {
+            NEasingMode::In => ease_in(t),
+            NEasingMode::Out => 1.0 - ease_in(1.0 - t),
+            NEasingMode::InOut if t < 0.5 => ease_in(t * 2.0) * 0.5,
+            NEasingMode::InOut => 1.0 - ease_in(2.0 - t * 2.0) * 0.5,
+        }
+    }
+}
+
+fn quad_in(t: f32) -> f32 {
+    t * t
+}
+
+fn cubic_in(t: f32) -> f32 {
+    t * t * t
+}
+
+fn quart_in(t: f32) -> f32 {
+    t.powi(4)
+}
+
+fn quint_in(t: f32) -> f32 {
+    t.powi(5)
+}
+
+fn sine_in(t: f32) -> f32 {
+    1.0 - (t * PI * 0.5).cos()
+}
+
+fn expo_in(t: f32) -> f32 {
+    if t <= 0.0 {
+        0.0
+    } else {
+        2.0f32.powf(10.0 * t - 10.0)
+    }
+}
+
+fn circ_in(t: f32) -> f32 {
+    1.0 - (1.0 - t * t).max(0.0).sqrt()
+}
+
+fn back_in(t: f32) -> f32 {
+    const OVERSHOOT: f32 = 1.70158;
+    (OVERSHOOT + 1.0) * t.powi(3) - OVERSHOOT * t * t
+}
+
+fn elastic_in(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    -(2.0f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * PI * 2.0 / 3.0).sin()
+}
+
+// Відскоки рахуються для Out (чотири параболи зі спадною висотою), In — дзеркало
+fn bounce_in(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    let t = 1.0 - t;
+    let out = if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    };
+    1.0 - out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1.0e-5;
+
+    #[test]
+    fn endpoints() {
+        for (name, easing) in NAMES {
+            assert!(easing.apply(0.0).abs() < EPSILON, "{}(0) = {}", name, easing.apply(0.0));
+            assert!((easing.apply(1.0) - 1.0).abs() < EPSILON, "{}(1)", name);
+            // Час за межами 0..1 обмежується
+            assert_eq!(easing.apply(-1.0), easing.apply(0.0), "{}", name);
+            assert_eq!(easing.apply(2.0), easing.apply(1.0), "{}", name);
+        }
+    }
+
+    #[test]
+    fn in_out_midpoint() {
+        for (name, easing) in NAMES.into_iter().filter(|(name, _)| name.ends_with("_in_out")) {
+            assert!((easing.apply(0.5) - 0.5).abs() < EPSILON, "{}(0.5)", name);
+        }
+    }
+
+    #[test]
+    fn out_mirrors_in() {
+        let pairs = NAMES.windows(3).filter(|names| names[0].0.ends_with("_in"));
+        for names in pairs {
+            let ((name, ease_in), (_, ease_out)) = (names[0], names[1]);
+            for t in [0.1, 0.25, 0.6, 0.9] {
+                let mirrored = 1.0 - ease_in.apply(1.0 - t);
+                assert!((ease_out.apply(t) - mirrored).abs() < EPSILON, "{} at {}", name, t);
+            }
+        }
+    }
+
+    #[test]
+    fn step_and_names() {
+        assert_eq!(NEasing::Step.apply(0.999), 0.0);
+        assert_eq!(NEasing::Linear.apply(0.3), 0.3);
+        for (name, easing) in NAMES {
+            assert_eq!(NEasing::from_name(name), Some(easing));
+            assert_eq!(easing.name(), name);
+        }
+        assert_eq!(NEasing::from_name("quad"), None);
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use cgmath::{Matrix4, One, Point3, Quaternion, Vector3};
+
+use crate::{
+    graphics::{material::NMaterial, mesh::NMesh, texture::NTexture},
+    scene::{
+        animator::NAnimationStateMachine,
+        bounds::NAabb,
+        particles::NParticleEmitter,
+        property_animation::NPropertyAnimator,
+        sprite_animation::NSpriteAnimator,
+        text::{NText, NTextSpace},
+        tilemap::NTilemap,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NEntityId(pub u64);
+
+/// Положення, поворот і масштаб сутності у світі
+#[derive(Clone, Copy, Debug)]
+pub struct NTransform {
+    pub position: Vector3<f32>,     // Позиция
+    pub rotation: Quaternion<f32>,  // Поворот
+    pub scale: Vector3<f32>,        // Масштаб по осям
+}
+
+impl Default for NTransform {
+    fn default() -> Self {
+        NTransform {
+            position: Vector3::new(0.0, 0.0, 0.0),
+            rotation: Quaternion::one(),
+            scale: Vector3::new(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl NTransform {
+    pub fn from_position(position: Vector3<f32>) -> Self {
+        NTransform { position, ..Default::default() }
+    }
+
+    // Матриця local -> world (масштаб, потім поворот, потім зсув)
+    pub fn matrix(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(self.position)
+            * Matrix4::from(self.rotation)
+            * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+}
+
+/// Меш сутності. Сутності з однаковими мешем і матеріалом
+/// малюються одним інстансованим draw call-ом.
+#[derive(Clone, Debug)]
+pub struct NMeshRenderer {
+    pub mesh: Arc<NMesh>,           // Меш
+    pub material: Arc<NMaterial>,   // Материал
+    pub color: [f32; 4],            // Цвет инстанса
+}
+
+/// Спрайт сутності: квадрат розміру `size` у площині XY. Спрайти з однаковою
+/// текстурою малюються одним інстансованим draw call-ом.
+#[derive(Clone, Debug)]
+pub struct NSprite {
+    pub texture: Option<Arc<NTexture>>, // Текстура (None -> белая)
+    pub color: [f32; 4],                // Цвет инстанса
+    pub size: [f32; 2],                 // Размер в единицах мира
+    pub uv_rect: [f32; 4],              // Область текстуры (x, y, ширина, высота) в UV
+}
+
+impl Default for NSprite {
+    fn default() -> Self {
+        NSprite { texture: None, color: [1.0; 4], size: [1.0, 1.0], uv_rect: [0.0, 0.0, 1.0, 1.0] }
+    }
+}
+
+/// Сутність сцени з необов'язковими компонентами
+#[derive(Clone, Debug)]
+pub struct NEntity {
+    id: NEntityId,                      // Идентификатор сущности
+    pub name: String,                   // Имя
+    pub transform: NTransform,          // Трансформ
+    pub mesh: Option<NMeshRenderer>,    // Меш (если есть)
+    pub sprite: Option<NSprite>,        // Спрайт (если есть)
+    pub animator: Option<NSpriteAnimator>, // Покадровая анимация спрайта (если есть)
+    pub particles: Option<NParticleEmitter>, // Эмиттер частиц (если есть)
+    pub text: Option<NText>,            // Текст (если есть)
+    pub tilemap: Option<NTilemap>,      // Тайловая карта (если есть)
+    pub animation: Option<NAnimationStateMachine>, // Скелетная анимация меша (если есть)
+    pub property_animator: Option<NPropertyAnimator>, // Анимация свойств ключами (если есть)
+}
+
+impl NEntity {
+    pub fn new(id: NEntityId, name: impl Into<String>) -> Self {
+        NEntity {
+            id,
+            name: name.into(),
+            transform: NTransform::default(),
+            mesh: None,
+            sprite: None,
+            animator: None,
+            particles: None,
+            text: None,
+            tilemap: None,
+            animation: None,
+            property_animator: None,
+        }
+    }
+
+    #[inline]
+    pub fn id(&self) -> NEntityId {
+        self.id
+    }
+
+    // Межі сутності у світі: об'єднання меж меша, спрайта, світового тексту і тайлової мапи.
+    // None — сутність нічого не малює геометрією (частинки живуть на GPU і не відсікаються,
+    // екранний текст не залежить від камери).
+    pub fn bounds(&self) -> Option<NAabb> {
+        let matrix = self.transform.matrix();
+        // Скінований меш — у поточній позі скелета, а не в позі прив'язки
+        let mesh = self.mesh.as_ref().map(|renderer| {
+            let bounds = match &self.animation {
+                Some(animation) if renderer.mesh.is_skinned() => {
+                    renderer.mesh.skinned_bounds(animation.palette())
+                }
+                _ => renderer.mesh.bounds(),
+            };
+            bounds.transformed(&matrix)
+        });
+        let sprite = self.sprite.as_ref().map(|sprite| {
+            let [width, height] = sprite.size.map(|size| size * 0.5);
+            NAabb::new(Point3::new(-width, -height, 0.0), Point3::new(width, height, 0.0))
+                .transformed(&matrix)
+        });
+        let text = self.text.as_ref().filter(|text| text.space == NTextSpace::World).map(|text| {
+            let (min, max) = text.local_rect();
+            NAabb::new(Point3::new(min[0], min[1], 0.0), Point3::new(max[0], max[1], 0.0))
+                .transformed(&matrix)
+        });
+        let tilemap = self.tilemap.as_ref().map(|tilemap| {
+            let (min, max) = tilemap.local_rect();
+            NAabb::new(Point3::new(min[0], min[1], 0.0), Point3::new(max[0], max[1], 0.0))
+                .transformed(&matrix)
+        });
+        [mesh, sprite, text, tilemap]
+            .into_iter()
+            .flatten()
+            .reduce(|bounds, other| bounds.union(&other))
+    }
+}
@@ -1,3 +1,19 @@
-mod scene;
-mod camera;
-mod entity;
\ No newline at end of file
+pub mod scene;
+pub mod camera;
+pub mod entity;
+pub mod particles;
+pub mod bounds;
+pub mod bvh;
+pub mod text;
+pub mod tilemap;
+pub mod tiled;
+pub mod sprite_animation;
+pub mod aseprite;
+pub mod skeleton;
+pub mod animation;
+pub mod animator;
+pub mod model;
+pub mod easing;
+pub mod property;
+pub mod property_animation;
+pub mod tween;
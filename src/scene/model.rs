@@ -0,0 +1,406 @@
+// Модель з glTF 2.0 (.gltf з зовнішніми або data: буферами, .glb).
+// Кожен трикутний примітив мешу вузла стає окремим NMesh з PBR матеріалом.
+// Меші без скіна запікаються у світові координати сцени glTF (NTransform сутності
+// не вміщає довільну матрицю вузла); скіновані лишаються в координатах моделі, як того
+// вимагає специфікація — їх розставляють суглоби скелета.
+// Кожен skin стає NSkeleton (суглоби в порядку skin.joints, предки поза скелетом
+// запікаються в `base` коренів), анімації — кліпами скінів, чиї суглоби вони анімують.
+// Морф-таргети не підтримуються. Текстури завантажуються лише з feature "image".
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use base64::Engine;
+use cgmath::{InnerSpace, Matrix, Matrix3, Matrix4, One, Quaternion, SquareMatrix, Vector3};
+
+use crate::{
+    graphics::{
+        material::{NMaterial, NMaterialValue},
+        mesh::{NMesh, NMeshVertex, NSkinVertex},
+        texture::NTexture,
+    },
+    scene::{
+        animation::{NAnimationChannel, NAnimationClip, NAnimationTarget, NInterpolation},
+        animator::NAnimationStateMachine,
+        entity::NTransform,
+        skeleton::{NJoint, NSkeleton},
+    },
+};
+
+/// Примітив моделі: меш, матеріал і скін
+#[derive(Clone, Debug)]
+pub struct NModelMesh {
+    pub mesh: Arc<NMesh>,           // Меш
+    pub material: Arc<NMaterial>,   // Материал
+    pub skin: Option<usize>,        // Скин в NModel::skins
+}
+
+/// Скін моделі: скелет і кліпи, що його анімують
+#[derive(Clone, Debug)]
+pub struct NModelSkin {
+    pub skeleton: Arc<NSkeleton>,           // Скелет
+    pub clips: Vec<Arc<NAnimationClip>>,    // Клипы анимаций
+}
+
+impl NModelSkin {
+    // Машина станів зі станом на кожен кліп без переходів (грає перший кліп)
+    #[allow(dead_code)]
+    pub fn state_machine(&self) -> NAnimationStateMachine {
+        let mut machine = NAnimationStateMachine::new(self.skeleton.clone());
+        for clip in &self.clips {
+            machine.add_state(clip.name.clone(), clip.clone());
+        }
+        machine
+    }
+}
+
+/// Модель, завантажена з glTF
+#[derive(Clone, Debug, Default)]
+pub struct NModel {
+    pub meshes: Vec<NModelMesh>,    // Примитивы
+    pub skins: Vec<NModelSkin>,     // Скины
+}
+
+impl NModel {
+    #[allow(dead_code)]
+    pub fn load_gltf(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        load(path).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+}
+
+fn load(path: &Path) -> Result<NModel, String> {
+    let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+    let gltf = gltf::Gltf::from_slice(&bytes).map_err(|err| err.to_string())?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let buffers = gltf
+        .buffers()
+        .map(|buffer| {
+            let data = match buffer.source() {
+                gltf::buffer::Source::Bin => gltf.blob.clone().ok_or("missing GLB chunk")?,
+                gltf::buffer::Source::Uri(uri) => read_uri(directory, uri)?,
+            };
+            if data.len() < buffer.length() {
+                return Err(format!("buffer {} is too short", buffer.index()));
+            }
+            Ok(data)
+        })
+        .collect::<Result<Vec<Vec<u8>>, String>>()?;
+    let document = &gltf.document;
+
+    // Батьки вузлів і світові матриці
+    let mut parents = vec![None; document.nodes().len()];
+    for node in document.nodes() {
+        for child in node.children() {
+            parents[child.index()] = Some(node.index());
+        }
+    }
+    let locals: Vec<Matrix4<f32>> =
+        document.nodes().map(|node| Matrix4::from(node.transform().matrix())).collect();
+    let world = |mut node: usize| {
+        let mut matrix = locals[node];
+        while let Some(parent) = parents[node] {
+            matrix = locals[parent] * matrix;
+            node = parent;
+        }
+        matrix
+    };
+
+    let mut skins = Vec::new();
+    for skin in document.skins() {
+        let joints: Vec<usize> = skin.joints().map(|joint| joint.index()).collect();
+        let reader = skin.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+        let inverse_binds: Vec<Matrix4<f32>> = match reader.read_inverse_bind_matrices() {
+            Some(matrices) => matrices.map(Matrix4::from).collect(),
+            None => vec![Matrix4::one(); joints.len()],
+        };
+        let nodes: Vec<_> = document.nodes().collect();
+        let skeleton_joints = joints
+            .iter()
+            .zip(&inverse_binds)
+            .map(|(&node, inverse_bind)| {
+                let parent = parents[node]
+                    .and_then(|parent| joints.iter().position(|&joint| joint == parent));
+                let (translation, rotation, scale) = nodes[node].transform().decomposed();
+                let rest = NTransform {
+                    position: translation.into(),
+                    rotation: Quaternion::new(rotation[3], rotation[0], rotation[1], rotation[2]),
+                    scale: scale.into(),
+                };
+                let name = nodes[node].name().map(str::to_owned);
+                let mut joint =
+                    NJoint::new(name.unwrap_or_else(|| format!("Joint {}", node)), parent, rest);
+                joint.inverse_bind = *inverse_bind;
+                if parent.is_none() {
+                    joint.base = parents[node].map_or(Matrix4::one(), world);
+                }
+                joint
+            })
+            .collect();
+        let name = skin.name().map(str::to_owned);
+        let name = name.unwrap_or_else(|| format!("Skin {}", skin.index()));
+        let skeleton = Arc::new(NSkeleton::new(name, skeleton_joints));
+        let clips = document
+            .animations()
+            .filter_map(|animation| animation_clip(&animation, &joints, &buffers).transpose())
+            .collect::<Result<Vec<_>, String>>()?;
+        skins.push(NModelSkin { skeleton, clips });
+    }
+
+    let mut textures = NTextureLoader { directory, buffers: &buffers, cache: HashMap::new() };
+    let materials = document
+        .materials()
+        .map(|material| Arc::new(pbr_material(&material, &mut textures)))
+        .collect::<Vec<_>>();
+    let default_material = Arc::new(NMaterial::pbr("glTF default"));
+
+    let mut meshes = Vec::new();
+    for node in document.nodes() {
+        let Some(mesh) = node.mesh() else { continue };
+        let skin = node.skin().map(|skin| skin.index());
+        let matrix = world(node.index());
+        for primitive in mesh.primitives() {
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                continue;
+            }
+            let name = match (mesh.name(), mesh.primitives().len()) {
+                (Some(name), 1) => name.to_owned(),
+                (Some(name), _) => format!("{} {}", name, primitive.index()),
+                (None, _) => format!("Mesh {} {}", mesh.index(), primitive.index()),
+            };
+            let skinned = skin.is_some();
+            let baked = (!skinned).then_some(matrix);
+            let primitive_mesh = primitive_mesh(&primitive, name, &buffers, baked)?;
+            let material = match primitive.material().index() {
+                Some(index) => materials[index].clone(),
+                None => default_material.clone(),
+            };
+            meshes.push(NModelMesh {
+                skin: skin.filter(|_| primitive_mesh.is_skinned()),
+                mesh: Arc::new(primitive_mesh),
+                material,
+            });
+        }
+    }
+    Ok(NModel { meshes, skins })
+}
+
+// Вміст буфера чи зображення за URI: data: base64 або файл відносно каталогу моделі
+fn read_uri(directory: &Path, uri: &str) -> Result<Vec<u8>, String> {
+    if let Some(data) = uri.strip_prefix("data:") {
+        let (_, payload) = data.split_once(";base64,").ok_or("unsupported data URI")?;
+        return base64::engine::general_purpose::STANDARD
+            .decode(payload)
+            .map_err(|err| err.to_string());
+    }
+    let path = directory.join(uri.replace("%20", " "));
+    std::fs::read(&path).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+// Трикутний примітив. `matrix` — світова матриця вузла для запікання (None — скінований).
+fn primitive_mesh(
+    primitive: &gltf::Primitive,
+    name: String,
+    buffers: &[Vec<u8>],
+    matrix: Option<Matrix4<f32>>,
+) -> Result<NMesh, String> {
+    let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+    let positions: Vec<[f32; 3]> =
+        reader.read_positions().ok_or(format!("{}: no positions", name))?.collect();
+    let indices: Vec<u32> = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
+    let normals: Vec<[f32; 3]> = match reader.read_normals() {
+        Some(normals) => normals.collect(),
+        None => smooth_normals(&positions, &indices),
+    };
+    let uvs: Vec<[f32; 2]> = match reader.read_tex_coords(0) {
+        Some(uvs) => uvs.into_f32().collect(),
+        None => vec![[0.0; 2]; positions.len()],
+    };
+
+    let normal_matrix = matrix.map(|matrix| {
+        let linear =
+            Matrix3::from_cols(matrix.x.truncate(), matrix.y.truncate(), matrix.z.truncate());
+        linear.invert().unwrap_or(Matrix3::one()).transpose()
+    });
+    let vertices = positions
+        .iter()
+        .zip(&normals)
+        .zip(&uvs)
+        .map(|((&position, &normal), &uv)| match (matrix, normal_matrix) {
+            (Some(matrix), Some(normal_matrix)) => NMeshVertex {
+                position: (matrix * Vector3::from(position).extend(1.0)).truncate().into(),
+                normal: (normal_matrix * Vector3::from(normal)).normalize().into(),
+                uv,
+            },
+            _ => NMeshVertex { position, normal, uv },
+        })
+        .collect();
+    let mesh = NMesh::new(name, vertices, indices);
+
+    if matrix.is_some() {
+        return Ok(mesh);
+    }
+    let (Some(joints), Some(weights)) = (reader.read_joints(0), reader.read_weights(0)) else {
+        return Ok(mesh);
+    };
+    let skin = joints
+        .into_u16()
+        .zip(weights.into_f32())
+        .map(|(joints, weights)| {
+            // Ваги нормалізуються: експортери не завжди дають суму рівно 1
+            let sum: f32 = weights.iter().sum();
+            let weights =
+                if sum > 0.0 { weights.map(|weight| weight / sum) } else { [1.0, 0.0, 0.0, 0.0] };
+            NSkinVertex { joints: joints.map(u32::from), weights }
+        })
+        .collect();
+    Ok(mesh.with_skin(skin))
+}
+
+// Нормалі вершин як середнє нормалей суміжних трикутників (для примітивів без NORMAL)
+fn smooth_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut normals = vec![Vector3::new(0.0, 0.0, 0.0); positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|corner| Vector3::from(positions[triangle[corner] as usize]));
+        let normal = (b - a).cross(c - a);
+        for &index in triangle {
+            normals[index as usize] += normal;
+        }
+    }
+    normals
+        .into_iter()
+        .map(|normal| {
+            if normal.magnitude2() > 0.0 { normal.normalize().into() } else { [0.0, 1.0, 0.0] }
+        })
+        .collect()
+}
+
+// Кліп із каналів анімації, що цілять у суглоби `joints`; None — анімація не для цього скіна
+fn animation_clip(
+    animation: &gltf::Animation,
+    joints: &[usize],
+    buffers: &[Vec<u8>],
+) -> Result<Option<Arc<NAnimationClip>>, String> {
+    let mut channels = Vec::new();
+    for channel in animation.channels() {
+        let node = channel.target().node().index();
+        let Some(joint) = joints.iter().position(|&joint| joint == node) else { continue };
+        let target = match channel.target().property() {
+            gltf::animation::Property::Translation => NAnimationTarget::Translation,
+            gltf::animation::Property::Rotation => NAnimationTarget::Rotation,
+            gltf::animation::Property::Scale => NAnimationTarget::Scale,
+            gltf::animation::Property::MorphTargetWeights => continue,
+        };
+        let interpolation = match channel.sampler().interpolation() {
+            gltf::animation::Interpolation::Linear => NInterpolation::Linear,
+            gltf::animation::Interpolation::Step => NInterpolation::Step,
+            gltf::animation::Interpolation::CubicSpline => NInterpolation::CubicSpline,
+        };
+        let reader = channel.reader(|buffer| buffers.get(buffer.index()).map(Vec::as_slice));
+        let times: Vec<f32> = reader.read_inputs().ok_or("animation without inputs")?.collect();
+        let outputs = reader.read_outputs().ok_or("animation without outputs")?;
+        let values: Vec<[f32; 4]> = match outputs {
+            gltf::animation::util::ReadOutputs::Translations(values)
+            | gltf::animation::util::ReadOutputs::Scales(values) => {
+                values.map(|[x, y, z]| [x, y, z, 0.0]).collect()
+            }
+            gltf::animation::util::ReadOutputs::Rotations(values) => values.into_f32().collect(),
+            gltf::animation::util::ReadOutputs::MorphTargetWeights(_) => continue,
+        };
+        let stride = if interpolation == NInterpolation::CubicSpline { 3 } else { 1 };
+        if times.is_empty() || values.len() != times.len() * stride {
+            return Err(format!("animation {}: malformed channel", animation.index()));
+        }
+        channels.push(NAnimationChannel::new(joint, target, interpolation, times, values));
+    }
+    if channels.is_empty() {
+        return Ok(None);
+    }
+    let name = animation.name().map(str::to_owned);
+    let name = name.unwrap_or_else(|| format!("Animation {}", animation.index()));
+    Ok(Some(Arc::new(NAnimationClip::new(name, channels))))
+}
+
+// Завантажувач текстур моделі з кешем за (зображення, sRGB)
+struct NTextureLoader<'a> {
+    directory: &'a Path,                            // Каталог модели
+    buffers: &'a [Vec<u8>],                         // Буферы модели
+    cache: HashMap<(usize, bool), Arc<NTexture>>,   // Загруженные текстуры
+}
+
+impl NTextureLoader<'_> {
+    // Текстура матеріалу; None — зображення не завантажилось (або немає feature "image")
+    #[cfg(feature = "image")]
+    fn load(&mut self, texture: gltf::Texture, srgb: bool) -> Option<Arc<NTexture>> {
+        let source = texture.source();
+        if let Some(texture) = self.cache.get(&(source.index(), srgb)) {
+            return Some(texture.clone());
+        }
+        let data = match source.source() {
+            gltf::image::Source::View { view, .. } => {
+                let buffer = self.buffers.get(view.buffer().index())?;
+                buffer.get(view.offset()..view.offset() + view.length())?.to_vec()
+            }
+            gltf::image::Source::Uri { uri, .. } => read_uri(self.directory, uri).ok()?,
+        };
+        let decoded = image::load_from_memory(&data).ok()?.into_rgba8();
+        let size = [decoded.width(), decoded.height()];
+        let name = source.name().map(str::to_owned);
+        let name = name.unwrap_or_else(|| format!("Image {}", source.index()));
+        let texture = if srgb {
+            NTexture::new(name, size, decoded.into_raw())
+        } else {
+            NTexture::linear(name, size, decoded.into_raw())
+        };
+        let texture = Arc::new(texture);
+        self.cache.insert((source.index(), srgb), texture.clone());
+        Some(texture)
+    }
+
+    #[cfg(not(feature = "image"))]
+    fn load(&mut self, _texture: gltf::Texture, _srgb: bool) -> Option<Arc<NTexture>> {
+        let _ = (self.directory, self.buffers, &self.cache);
+        None
+    }
+}
+
+// PBR матеріал з фактором і текстурами glTF
+fn pbr_material(material: &gltf::Material, textures: &mut NTextureLoader) -> NMaterial {
+    let pbr = material.pbr_metallic_roughness();
+    let name = material.name().map(str::to_owned);
+    let name = name.unwrap_or_else(|| format!("Material {}", material.index().unwrap_or(0)));
+    let [red, green, blue] = material.emissive_factor();
+    let mut result = NMaterial::pbr(name)
+        .with_param("base_color", NMaterialValue::Color(pbr.base_color_factor()))
+        .with_param("metallic", NMaterialValue::Float(pbr.metallic_factor()))
+        .with_param("roughness", NMaterialValue::Float(pbr.roughness_factor()))
+        .with_param("emissive", NMaterialValue::Color([red, green, blue, 1.0]));
+    let mut texture = |name: &str, texture: gltf::Texture, srgb: bool, result: NMaterial| {
+        match textures.load(texture, srgb) {
+            Some(texture) => result.with_param(name, NMaterialValue::Texture(texture)),
+            None => result,
+        }
+    };
+    if let Some(info) = pbr.base_color_texture() {
+        result = texture("base_color_texture", info.texture(), true, result);
+    }
+    if let Some(info) = pbr.metallic_roughness_texture() {
+        result = texture("metallic_roughness_texture", info.texture(), false, result);
+    }
+    if let Some(info) = material.emissive_texture() {
+        result = texture("emissive_texture", info.texture(), true, result);
+    }
+    if let Some(info) = material.occlusion_texture() {
+        result = texture("occlusion_texture", info.texture(), false, result)
+            .with_param("occlusion_strength", NMaterialValue::Float(info.strength()));
+    }
+    if let Some(info) = material.normal_texture() {
+        let loaded = texture("normal_texture", info.texture(), false, result);
+        // Карта нормалей вмикається, лише якщо текстура завантажилась
+        let scale = if loaded.texture("normal_texture").is_some() { info.scale() } else { 0.0 };
+        result = loaded.with_param("normal_scale", NMaterialValue::Float(scale));
+    }
+    result
+}
@@ -0,0 +1,157 @@
+// Компонент емітера частинок.
+// Емітер описує лише параметри ефекту; стан частинок живе на GPU
+// (симуляція в compute шейдері, див. graphics::systems::particles).
+// Криві та градієнти задаються ключами на відрізку життя частинки 0..1
+// і перед відправкою на GPU семплюються в таблиці фіксованого розміру.
+
+use std::sync::Arc;
+
+use cgmath::Vector3;
+
+use crate::graphics::texture::NTexture;
+
+/// Спосіб змішування частинок з кадром
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NParticleBlend {
+    Alpha,      // Обычная прозрачность, частицы сортируются от дальних к ближним
+    Additive,   // Сложение цвета (огонь, искры), порядок не важен
+}
+
+/// Одноразовий викид частинок у момент `time` циклу емітера
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NParticleBurst {
+    pub time: f32,      // Время от начала цикла в секундах
+    pub count: u32,     // Количество частиц
+}
+
+/// Крива значення за часом життя частинки (лінійна інтерполяція між ключами)
+#[derive(Clone, Debug, PartialEq)]
+pub struct NCurve {
+    pub keys: Vec<(f32, f32)>,  // Ключи (время 0..1, значение), по возрастанию времени
+}
+
+impl NCurve {
+    #[allow(dead_code)]
+    pub fn constant(value: f32) -> Self {
+        NCurve { keys: vec![(0.0, value)] }
+    }
+
+    pub fn linear(from: f32, to: f32) -> Self {
+        NCurve { keys: vec![(0.0, from), (1.0, to)] }
+    }
+
+    pub fn sample(&self, t: f32) -> f32 {
+        sample_keys(&self.keys, t, |a, b, f| a + (b - a) * f).unwrap_or(1.0)
+    }
+}
+
+/// Градієнт кольору (лінійний RGBA) за часом життя частинки
+#[derive(Clone, Debug, PartialEq)]
+pub struct NGradient {
+    pub keys: Vec<(f32, [f32; 4])>, // Ключи (время 0..1, цвет), по возрастанию времени
+}
+
+impl NGradient {
+    pub fn linear(from: [f32; 4], to: [f32; 4]) -> Self {
+        NGradient { keys: vec![(0.0, from), (1.0, to)] }
+    }
+
+    pub fn sample(&self, t: f32) -> [f32; 4] {
+        sample_keys(&self.keys, t, |a, b, f| std::array::from_fn(|i| a[i] + (b[i] - a[i]) * f))
+            .unwrap_or([1.0; 4])
+    }
+}
+
+// Значення між сусідніми ключами; до першого та після останнього ключа — крайні значення
+fn sample_keys<T: Copy>(keys: &[(f32, T)], t: f32, lerp: impl Fn(T, T, f32) -> T) -> Option<T> {
+    let first = keys.first()?;
+    if t <= first.0 {
+        return Some(first.1);
+    }
+    for pair in keys.windows(2) {
+        let ((t0, a), (t1, b)) = (pair[0], pair[1]);
+        if t <= t1 {
+            let f = if t1 > t0 { (t - t0) / (t1 - t0) } else { 1.0 };
+            return Some(lerp(a, b, f));
+        }
+    }
+    keys.last().map(|key| key.1)
+}
+
+/// Емітер частинок сутності. Частинки народжуються в сфері радіуса `radius`
+/// навколо сутності та летять у конусі навколо `direction` (локальні осі сутності).
+#[derive(Clone, Debug)]
+pub struct NParticleEmitter {
+    pub max_particles: u32,             // Максимум одновременно живущих частиц
+    pub rate: f32,                      // Частиц в секунду
+    pub bursts: Vec<NParticleBurst>,    // Разовые выбросы внутри цикла
+    pub duration: f32,                  // Длина цикла эмиттера в секундах
+    pub looping: bool,                  // Повторять цикл
+    pub lifetime: [f32; 2],             // Время жизни частицы (мин, макс) в секундах
+    pub speed: [f32; 2],                // Начальная скорость (мин, макс)
+    pub direction: Vector3<f32>,        // Направление вылета в локальных осях
+    pub spread: f32,                    // Половина угла конуса вылета в градусах
+    pub radius: f32,                    // Радиус сферы рождения
+    pub gravity: Vector3<f32>,          // Ускорение в мировых координатах
+    pub size: f32,                      // Начальный размер в единицах мира
+    pub size_over_lifetime: NCurve,     // Множитель размера за жизнь
+    pub color_over_lifetime: NGradient, // Цвет за жизнь
+    pub texture: Option<Arc<NTexture>>, // Текстура (None -> белая)
+    pub sheet: [u32; 2],                // Сетка кадров текстуры (столбцы, строки)
+    pub sheet_cycles: f32,              // Сколько раз анимация проигрывается за жизнь
+    pub blend: NParticleBlend,          // Смешивание
+    pub generation: u64,                // Поколение: смена сбрасывает частицы на GPU
+}
+
+impl Default for NParticleEmitter {
+    fn default() -> Self {
+        NParticleEmitter {
+            max_particles: 1024,
+            rate: 50.0,
+            bursts: Vec::new(),
+            duration: 5.0,
+            looping: true,
+            lifetime: [1.0, 2.0],
+            speed: [1.0, 2.0],
+            direction: Vector3::new(0.0, 1.0, 0.0),
+            spread: 25.0,
+            radius: 0.1,
+            gravity: Vector3::new(0.0, -1.0, 0.0),
+            size: 0.2,
+            size_over_lifetime: NCurve::linear(1.0, 0.2),
+            color_over_lifetime: NGradient::linear([1.0; 4], [1.0, 1.0, 1.0, 0.0]),
+            texture: None,
+            sheet: [1, 1],
+            sheet_cycles: 1.0,
+            blend: NParticleBlend::Alpha,
+            generation: 0,
+        }
+    }
+}
+
+impl NParticleEmitter {
+    // Скидає всі живі частинки та починає цикл спочатку
+    pub fn restart(&mut self) {
+        self.generation += 1;
+    }
+
+    // Кадр анімації текстури для частинки на частці життя t
+    pub fn sheet_frame(&self, t: f32) -> u32 {
+        let frames = (self.sheet[0] * self.sheet[1]).max(1);
+        ((t * self.sheet_cycles * frames as f32) as u32) % frames
+    }
+}
+
+// Процедурна текстура м'якої круглої плями для частинок
+pub fn soft_dot_texture(size: u32) -> NTexture {
+    let center = (size as f32 - 1.0) * 0.5;
+    let data = (0..size * size)
+        .flat_map(|index| {
+            let (x, y) = ((index % size) as f32 - center, (index / size) as f32 - center);
+            let distance = (x * x + y * y).sqrt() / center.max(1.0);
+            let alpha = (1.0 - distance).clamp(0.0, 1.0);
+            [255, 255, 255, (alpha * alpha * 255.0) as u8]
+        })
+        .collect();
+    NTexture::new("Soft dot", [size, size], data)
+}
@@ -0,0 +1,268 @@
+// Шляхи до числових властивостей компонентів сутності для ключових кадрів і твінів.
+// Шлях — "компонент.поле" з необов'язковою компонентою вектора через крапку:
+// "transform.position", "transform.position.y", "sprite.color.a", "text.size".
+// Компоненти вектора — x/y/z/w або r/g/b/a. Значення завжди [f32; 4], зайві
+// компоненти ігноруються; для одної компоненти значення лежить у [0].
+// Поворот анімується кутами Ейлера в градусах (x, y, z): так ключі читаються людьми
+// й обертання більше ніж на пів оберту між ключами не зрізається, поки кожен ключ
+// задає всі три кути. Окремі компоненти повороту ("transform.rotation.y") не
+// підтримуються: запис однієї компоненти мав би щокадру відновлювати інші кути з
+// кватерніона, а той за 90° дає інший набір кутів, і анімація перекидається.
+// Якщо в сутності немає компонента з шляху, читання дає None, а запис нічого не робить.
+// Інтенсивність освітлення анімувати не можна: світло дає лише NScene::environment —
+// спільний ассет (Arc<NEnvironment>) з HDR даними, а не компонент сутності, тож
+// ключі й твіни до нього не дістають; яскравість змінюється заміною оточення.
+
+use cgmath::{Deg, Euler, Quaternion, Rad, Vector3};
+
+use crate::scene::entity::NEntity;
+
+/// Поле компонента сутності, яке можна анімувати
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NPropertyField {
+    Position,       // transform.position
+    Rotation,       // transform.rotation (углы Эйлера в градусах)
+    Scale,          // transform.scale
+    MeshColor,      // mesh.color
+    SpriteColor,    // sprite.color
+    SpriteSize,     // sprite.size
+    SpriteUvRect,   // sprite.uv_rect
+    TextColor,      // text.color
+    TextSize,       // text.size
+    ParticleRate,   // particles.rate
+    ParticleSize,   // particles.size
+}
+
+const FIELDS: [(&str, NPropertyField, usize); 11] = [
+    ("transform.position", NPropertyField::Position, 3),
+    ("transform.rotation", NPropertyField::Rotation, 3),
+    ("transform.scale", NPropertyField::Scale, 3),
+    ("mesh.color", NPropertyField::MeshColor, 4),
+    ("sprite.color", NPropertyField::SpriteColor, 4),
+    ("sprite.size", NPropertyField::SpriteSize, 2),
+    ("sprite.uv_rect", NPropertyField::SpriteUvRect, 4),
+    ("text.color", NPropertyField::TextColor, 4),
+    ("text.size", NPropertyField::TextSize, 1),
+    ("particles.rate", NPropertyField::ParticleRate, 1),
+    ("particles.size", NPropertyField::ParticleSize, 1),
+];
+
+/// Шлях до властивості: поле і, можливо, одна його компонента
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NPropertyPath {
+    pub field: NPropertyField,      // Поле компонента
+    pub component: Option<usize>,   // Компонента вектора (None — всё поле)
+}
+
+impl NPropertyPath {
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let field = |name: &str| FIELDS.iter().find(|(key, _, _)| *key == name);
+        if let Some(&(_, field, _)) = field(path) {
+            return Ok(NPropertyPath { field, component: None });
+        }
+        let unknown = || format!("unknown property '{}'", path);
+        let (name, component) = path.rsplit_once('.').ok_or_else(unknown)?;
+        let &(_, field, width) = field(name).ok_or_else(unknown)?;
+        if field == NPropertyField::Rotation {
+            return Err(format!("property '{}' is animated only as a whole (x, y, z)", name));
+        }
+        let component = match component {
+            "x" | "r" => 0,
+            "y" | "g" => 1,
+            "z" | "b" => 2,
+            "w" | "a" => 3,
+            _ => return Err(unknown()),
+        };
+        if component >= width {
+            return Err(format!("property '{}' has no component {}", name, component));
+        }
+        Ok(NPropertyPath { field, component: Some(component) })
+    }
+
+    // Кількість значущих компонент значення
+    #[allow(dead_code)]
+    pub fn width(&self) -> usize {
+        match self.component {
+            Some(_) => 1,
+            None => FIELDS.iter().find(|(_, field, _)| *field == self.field).unwrap().2,
+        }
+    }
+
+    pub fn get(&self, entity: &NEntity) -> Option<[f32; 4]> {
+        let value = read(self.field, entity)?;
+        Some(match self.component {
+            Some(component) => [value[component], 0.0, 0.0, 0.0],
+            None => value,
+        })
+    }
+
+    pub fn set(&self, entity: &mut NEntity, value: [f32; 4]) {
+        let value = match self.component {
+            Some(component) => {
+                let Some(mut current) = read(self.field, entity) else { return };
+                current[component] = value[0];
+                current
+            }
+            None => value,
+        };
+        write(self.field, entity, value);
+    }
+}
+
+fn read(field: NPropertyField, entity: &NEntity) -> Option<[f32; 4]> {
+    let vector = |vector: Vector3<f32>| [vector.x, vector.y, vector.z, 0.0];
+    let transform = &entity.transform;
+    Some(match field {
+        NPropertyField::Position => vector(transform.position),
+        NPropertyField::Rotation => {
+            let euler = Euler::<Rad<f32>>::from(transform.rotation);
+            [Deg::from(euler.x).0, Deg::from(euler.y).0, Deg::from(euler.z).0, 0.0]
+        }
+        NPropertyField::Scale => vector(transform.scale),
+        NPropertyField::MeshColor => entity.mesh.as_ref()?.color,
+        NPropertyField::SpriteColor => entity.sprite.as_ref()?.color,
+        NPropertyField::SpriteSize => {
+            let [width, height] = entity.sprite.as_ref()?.size;
+            [width, height, 0.0, 0.0]
+        }
+        NPropertyField::SpriteUvRect => entity.sprite.as_ref()?.uv_rect,
+        NPropertyField::TextColor => entity.text.as_ref()?.color,
+        NPropertyField::TextSize => [entity.text.as_ref()?.size, 0.0, 0.0, 0.0],
+        NPropertyField::ParticleRate => [entity.particles.as_ref()?.rate, 0.0, 0.0, 0.0],
+        NPropertyField::ParticleSize => [entity.particles.as_ref()?.size, 0.0, 0.0, 0.0],
+    })
+}
+
+fn write(field: NPropertyField, entity: &mut NEntity, value: [f32; 4]) {
+    let vector = Vector3::new(value[0], value[1], value[2]);
+    match field {
+        NPropertyField::Position => entity.transform.position = vector,
+        NPropertyField::Rotation => {
+            let euler = Euler::new(Deg(value[0]), Deg(value[1]), Deg(value[2]));
+            entity.transform.rotation = Quaternion::from(euler);
+        }
+        NPropertyField::Scale => entity.transform.scale = vector,
+        NPropertyField::MeshColor => {
+            if let Some(mesh) = entity.mesh.as_mut() {
+                mesh.color = value;
+            }
+        }
+        NPropertyField::SpriteColor => {
+            if let Some(sprite) = entity.sprite.as_mut() {
+                sprite.color = value;
+            }
+        }
+        NPropertyField::SpriteSize => {
+            if let Some(sprite) = entity.sprite.as_mut() {
+                sprite.size = [value[0], value[1]];
+            }
+        }
+        NPropertyField::SpriteUvRect => {
+            if let Some(sprite) = entity.sprite.as_mut() {
+                sprite.uv_rect = value;
+            }
+        }
+        NPropertyField::TextColor => {
+            if let Some(text) = entity.text.as_mut() {
+                text.color = value;
+            }
+        }
+        NPropertyField::TextSize => {
+            if let Some(text) = entity.text.as_mut() {
+                text.size = value[0];
+            }
+        }
+        NPropertyField::ParticleRate => {
+            if let Some(particles) = entity.particles.as_mut() {
+                particles.rate = value[0];
+            }
+        }
+        NPropertyField::ParticleSize => {
+            if let Some(particles) = entity.particles.as_mut() {
+                particles.size = value[0];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+
+    use super::*;
+    use crate::scene::entity::{NEntityId, NSprite};
+
+    fn path(field: NPropertyField, component: Option<usize>) -> NPropertyPath {
+        NPropertyPath { field, component }
+    }
+
+    #[test]
+    fn parses_whole_fields() {
+        for (name, field, width) in FIELDS {
+            let parsed = NPropertyPath::parse(name).unwrap();
+            assert_eq!(parsed, path(field, None), "{}", name);
+            assert_eq!(parsed.width(), width, "{}", name);
+        }
+    }
+
+    #[test]
+    fn parses_components() {
+        let cases = [
+            ("transform.position.x", NPropertyField::Position, 0),
+            ("transform.scale.z", NPropertyField::Scale, 2),
+            ("sprite.color.a", NPropertyField::SpriteColor, 3),
+            ("mesh.color.g", NPropertyField::MeshColor, 1),
+            ("sprite.size.y", NPropertyField::SpriteSize, 1),
+            ("sprite.uv_rect.w", NPropertyField::SpriteUvRect, 3),
+            ("text.size.x", NPropertyField::TextSize, 0),
+        ];
+        for (name, field, component) in cases {
+            let parsed = NPropertyPath::parse(name).unwrap();
+            assert_eq!(parsed, path(field, Some(component)), "{}", name);
+            assert_eq!(parsed.width(), 1, "{}", name);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_paths() {
+        let error = |name: &str| NPropertyPath::parse(name).unwrap_err();
+        assert_eq!(error("transform"), "unknown property 'transform'");
+        assert_eq!(error("light.intensity"), "unknown property 'light.intensity'");
+        assert_eq!(error("transform.position.q"), "unknown property 'transform.position.q'");
+        assert_eq!(error("sprite.size.z"), "property 'sprite.size' has no component 2");
+        assert_eq!(error("text.size.y"), "property 'text.size' has no component 1");
+        // Поворот анімується лише цілим (кути Ейлера x, y, z)
+        for component in ["x", "y", "z", "w"] {
+            assert_eq!(
+                error(&format!("transform.rotation.{}", component)),
+                "property 'transform.rotation' is animated only as a whole (x, y, z)"
+            );
+        }
+    }
+
+    #[test]
+    fn get_and_set_components() {
+        let mut entity = NEntity::new(NEntityId(0), "entity");
+        let position = NPropertyPath::parse("transform.position.y").unwrap();
+        position.set(&mut entity, [5.0, 0.0, 0.0, 0.0]);
+        assert_eq!(entity.transform.position, Vector3::new(0.0, 5.0, 0.0));
+        assert_eq!(position.get(&entity), Some([5.0, 0.0, 0.0, 0.0]));
+
+        // Без спрайта читання дає None, а запис нічого не робить
+        let alpha = NPropertyPath::parse("sprite.color.a").unwrap();
+        assert_eq!(alpha.get(&entity), None);
+        alpha.set(&mut entity, [0.5; 4]);
+        assert!(entity.sprite.is_none());
+
+        entity.sprite = Some(NSprite::default());
+        alpha.set(&mut entity, [0.25, 0.0, 0.0, 0.0]);
+        assert_eq!(entity.sprite.as_ref().unwrap().color, [1.0, 1.0, 1.0, 0.25]);
+
+        let rotation = NPropertyPath::parse("transform.rotation").unwrap();
+        rotation.set(&mut entity, [30.0, 20.0, 10.0, 0.0]);
+        let angles = rotation.get(&entity).unwrap();
+        for (angle, expected) in angles.into_iter().zip([30.0, 20.0, 10.0]) {
+            assert!((angle - expected).abs() < 1.0e-3, "{} != {}", angle, expected);
+        }
+    }
+}
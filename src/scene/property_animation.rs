@@ -0,0 +1,198 @@
+// Кліпи анімації властивостей: криві ключових кадрів, що пишуть у поля компонентів
+// сутності за шляхами NPropertyPath (трансформ, колір спрайта, розмір тексту, ...).
+// Кожен ключ задає значення і пом'якшення відрізка до наступного ключа; до першого
+// ключа й після останнього крива тримає крайнє значення. Кліп — асет, спільний для
+// багатьох сутностей, а NPropertyAnimator — компонент сутності зі своїм часом.
+// JSON формат кліпа:
+//   { "name": "pulse", "curves": [ { "path": "sprite.color.a", "keys": [
+//       { "time": 0.0, "value": 1.0, "easing": "sine_in_out" },
+//       { "time": 0.5, "value": [0.2] } ] } ] }
+// "value" — число або масив до 4 чисел, "easing" за замовчуванням "linear".
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::{
+    graphics::asset::{next_asset_id, NAssetId},
+    scene::{easing::NEasing, entity::NEntity, property::NPropertyPath},
+};
+
+/// Ключовий кадр кривої
+#[derive(Clone, Copy, Debug)]
+pub struct NKeyframe {
+    pub time: f32,          // Время в секундах
+    pub value: [f32; 4],    // Значение
+    pub easing: NEasing,    // Смягчение отрезка до следующего ключа
+}
+
+/// Крива ключових кадрів однієї властивості
+#[derive(Clone, Debug)]
+pub struct NPropertyCurve {
+    pub path: NPropertyPath,    // Анимируемое свойство
+    pub keys: Vec<NKeyframe>,   // Ключи по возрастанию времени
+}
+
+impl NPropertyCurve {
+    pub fn new(path: NPropertyPath) -> Self {
+        NPropertyCurve { path, keys: Vec::new() }
+    }
+
+    // Додає ключ зі значенням `value` (до 4 компонент), зберігаючи порядок за часом
+    pub fn key(&mut self, time: f32, value: &[f32], easing: NEasing) -> &mut Self {
+        let mut padded = [0.0; 4];
+        for (target, value) in padded.iter_mut().zip(value) {
+            *target = *value;
+        }
+        let index = self.keys.partition_point(|key| key.time <= time);
+        self.keys.insert(index, NKeyframe { time, value: padded, easing });
+        self
+    }
+
+    // Значення кривої в момент `time`; None для кривої без ключів
+    pub fn sample(&self, time: f32) -> Option<[f32; 4]> {
+        let next = self.keys.partition_point(|key| key.time <= time);
+        if next == 0 || next == self.keys.len() {
+            return self.keys.get(next.saturating_sub(1)).map(|key| key.value);
+        }
+        let (from, to) = (&self.keys[next - 1], &self.keys[next]);
+        let t = from.easing.apply((time - from.time) / (to.time - from.time));
+        Some([0, 1, 2, 3].map(|axis| from.value[axis] + (to.value[axis] - from.value[axis]) * t))
+    }
+}
+
+/// Кліп анімації властивостей
+#[derive(Debug)]
+pub struct NPropertyClip {
+    id: NAssetId,                       // Идентификатор ассета
+    pub name: String,                   // Имя
+    pub curves: Vec<NPropertyCurve>,    // Кривые
+}
+
+impl NPropertyClip {
+    pub fn new(name: impl Into<String>) -> Self {
+        NPropertyClip { id: next_asset_id(), name: name.into(), curves: Vec::new() }
+    }
+
+    #[inline]
+    #[allow(dead_code)]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    // Додає криву для властивості за шляхом `path` (див. NPropertyPath::parse)
+    pub fn curve(&mut self, path: &str) -> Result<&mut NPropertyCurve, String> {
+        self.curves.push(NPropertyCurve::new(NPropertyPath::parse(path)?));
+        Ok(self.curves.last_mut().unwrap())
+    }
+
+    // Тривалість: час останнього ключа серед усіх кривих
+    pub fn duration(&self) -> f32 {
+        self.curves
+            .iter()
+            .filter_map(|curve| curve.keys.last())
+            .map(|key| key.time)
+            .fold(0.0, f32::max)
+    }
+
+    // Пише значення кривих у момент `time` у властивості сутності
+    pub fn sample(&self, time: f32, entity: &mut NEntity) {
+        for curve in &self.curves {
+            if let Some(value) = curve.sample(time) {
+                curve.path.set(entity, value);
+            }
+        }
+    }
+
+    // Розбирає JSON кліп; `name` — ім'я, якщо в JSON немає "name"
+    #[allow(dead_code)]
+    pub fn from_json(name: impl Into<String>, json: &str) -> Result<Self, String> {
+        let json: Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let name = json["name"].as_str().map_or_else(|| name.into(), str::to_owned);
+        let mut clip = NPropertyClip::new(name);
+        for curve in json["curves"].as_array().ok_or("missing curves")? {
+            let path = curve["path"].as_str().ok_or("curve without path")?;
+            let target = clip.curve(path)?;
+            for key in curve["keys"].as_array().ok_or(format!("{}: missing keys", path))? {
+                let time = key["time"].as_f64().ok_or(format!("{}: key without time", path))?;
+                let value: Vec<f32> = match &key["value"] {
+                    Value::Number(value) => vec![value.as_f64().unwrap_or_default() as f32],
+                    Value::Array(values) => values
+                        .iter()
+                        .map(|value| value.as_f64().map(|value| value as f32))
+                        .collect::<Option<_>>()
+                        .ok_or(format!("{}: bad key value", path))?,
+                    _ => return Err(format!("{}: key without value", path)),
+                };
+                if value.len() > 4 {
+                    return Err(format!("{}: key value has more than 4 components", path));
+                }
+                let easing = match key["easing"].as_str() {
+                    Some(easing) => NEasing::from_name(easing)
+                        .ok_or(format!("{}: unknown easing '{}'", path, easing))?,
+                    None => NEasing::Linear,
+                };
+                target.key(time as f32, &value, easing);
+            }
+        }
+        Ok(clip)
+    }
+
+    #[allow(dead_code)]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let result = (|| -> Result<Self, String> {
+            let json = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+            let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+            NPropertyClip::from_json(name.unwrap_or_default(), &json)
+        })();
+        result.map_err(|err| format!("{}: {}", path.display(), err))
+    }
+}
+
+/// Компонент сутності, що програє кліп анімації властивостей
+#[derive(Clone, Debug)]
+pub struct NPropertyAnimator {
+    pub clip: Arc<NPropertyClip>,   // Клип
+    pub speed: f32,                 // Скорость воспроизведения
+    pub looping: bool,              // Повтор клипа
+    pub playing: bool,              // Идёт воспроизведение
+    time: f32,                      // Время воспроизведения
+}
+
+impl NPropertyAnimator {
+    pub fn new(clip: Arc<NPropertyClip>) -> Self {
+        NPropertyAnimator { clip, speed: 1.0, looping: true, playing: true, time: 0.0 }
+    }
+
+    #[allow(dead_code)]
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    // Починає кліп спочатку
+    #[allow(dead_code)]
+    pub fn restart(&mut self) {
+        self.time = 0.0;
+        self.playing = true;
+    }
+
+    // Просуває час на `delta_time` секунд і пише значення кліпа в сутність.
+    // Кліп без повтору зупиняється на останньому кадрі.
+    pub fn update(&mut self, delta_time: f32, entity: &mut NEntity) {
+        if !self.playing {
+            return;
+        }
+        let duration = self.clip.duration();
+        self.time += delta_time * self.speed;
+        if duration <= 0.0 {
+            self.time = 0.0;
+        } else if self.looping {
+            self.time = self.time.rem_euclid(duration);
+        } else if self.time >= duration {
+            self.time = duration;
+            self.playing = false;
+        }
+        self.clip.sample(self.time, entity);
+    }
+}
@@ -0,0 +1,424 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use cgmath::{Point3, Vector3};
+
+use crate::{
+    graphics::{
+        environment::NEnvironment,
+        font::NFont,
+        material::{NMaterial, NMaterialValue},
+        mesh::{NMesh, NSkinVertex},
+    },
+    scene::{
+        animation::demo_clips,
+        animator::{NAnimationCondition, NAnimationStateMachine, NLayerBlend},
+        bounds::{NFrustum, NRay},
+        bvh::NBvh,
+        camera::NCamera,
+        easing::NEasing,
+        entity::{NEntity, NEntityId, NMeshRenderer, NSprite, NTransform},
+        particles::{
+            soft_dot_texture, NCurve, NGradient, NParticleBlend, NParticleBurst,
+            NParticleEmitter,
+        },
+        property::NPropertyPath,
+        property_animation::{NPropertyAnimator, NPropertyClip},
+        skeleton::NSkeleton,
+        sprite_animation::{demo_sprite_sheet, NPlayMode, NSpriteAnimator, NSpriteSheet},
+        text::{NText, NTextAlign, NTextOutline, NTextShadow},
+        tilemap::{demo_tileset, NTile, NTilemap},
+        tween::{NTween, REPEAT_FOREVER},
+    },
+};
+
+/// Сцена, яку рендерить NRenderPipeline
+#[derive(Clone, Debug, Default)]
+pub struct NScene {
+    pub camera: NCamera,            // Активная камера сцены
+    pub entities: Vec<NEntity>,     // Сущности сцены
+    pub environment: Option<Arc<NEnvironment>>, // Окружение (IBL, skybox); None — по умолчанию
+    next_entity_id: u64,            // Следующий свободный идентификатор
+    bvh: NBvh,                      // Иерархия границ сущностей (см. update_bounds)
+    entity_index: HashMap<NEntityId, usize>, // Индекс сущности в entities на момент update_bounds
+    tweens: Vec<NTween>,            // Идущие твины
+}
+
+impl NScene {
+    // Створює нову сутність і повертає її для заповнення компонентів
+    pub fn spawn(&mut self, name: impl Into<String>) -> &mut NEntity {
+        let id = NEntityId(self.next_entity_id);
+        self.next_entity_id += 1;
+        self.entities.push(NEntity::new(id, name));
+        self.entities.last_mut().unwrap()
+    }
+
+    // Імпортує файл ассета новою сутністю в початку координат:
+    // мапи Tiled (.tmx, .tmj, .json) стають тайловими мапами, а листи Aseprite
+    // (.json з "frames" і "meta") — спрайтами з аніматором
+    pub fn import(&mut self, path: &Path) -> Result<NEntityId, String> {
+        let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("json") if is_aseprite_sheet(path) => {
+                let sheet = Arc::new(NSpriteSheet::load_aseprite(path)?);
+                // Пропорції спрайта — як у першого кадру
+                let [_, _, width, height] = sheet.frames[0].uv_rect;
+                let [atlas_width, atlas_height] = sheet.texture.size.map(|size| size as f32);
+                let aspect = (height * atlas_height) / (width * atlas_width).max(f32::EPSILON);
+                let entity = self.spawn(name.unwrap_or_default());
+                entity.sprite = Some(NSprite { size: [1.0, aspect], ..NSprite::default() });
+                entity.animator = Some(NSpriteAnimator::new(sheet));
+                Ok(entity.id())
+            }
+            Some("tmx" | "tmj" | "json") => {
+                let tilemap = NTilemap::load_tiled(path)?;
+                let entity = self.spawn(name.unwrap_or_default());
+                entity.tilemap = Some(tilemap);
+                Ok(entity.id())
+            }
+            _ => Err(format!("{}: unsupported asset type", path.display())),
+        }
+    }
+
+    pub fn entity(&self, id: NEntityId) -> Option<&NEntity> {
+        self.entities.iter().find(|entity| entity.id() == id)
+    }
+
+    pub fn entity_mut(&mut self, id: NEntityId) -> Option<&mut NEntity> {
+        self.entities.iter_mut().find(|entity| entity.id() == id)
+    }
+
+    // Запускає твін властивості `path` сутності `entity` до значення `to` за `duration`
+    // секунд; затримка, пом'якшення й повтори налаштовуються на повернутому твіні
+    pub fn tween(
+        &mut self,
+        entity: NEntityId,
+        path: &str,
+        to: &[f32],
+        duration: f32,
+    ) -> Result<&mut NTween, String> {
+        let path = NPropertyPath::parse(path)?;
+        let mut value = [0.0; 4];
+        for (target, to) in value.iter_mut().zip(to) {
+            *target = *to;
+        }
+        self.tweens.push(NTween::new(entity, path, value, duration));
+        Ok(self.tweens.last_mut().unwrap())
+    }
+
+    // Зупиняє твіни сутності; властивості лишаються з поточними значеннями
+    #[allow(dead_code)]
+    pub fn stop_tweens(&mut self, entity: NEntityId) {
+        self.tweens.retain(|tween| tween.entity != entity);
+    }
+
+    #[allow(dead_code)]
+    pub fn is_tweening(&self, entity: NEntityId) -> bool {
+        self.tweens.iter().any(|tween| tween.entity == entity)
+    }
+
+    // Просуває анімації на `delta_time` секунд: машини станів скелетів перераховують
+    // палітри суглобів, покадрові переносять текстуру й область поточного кадру у спрайти.
+    // Сутність з аніматором без спрайта отримує спрайт за замовчуванням.
+    // Потім кліпи властивостей і твіни пишуть у поля компонентів (твіни — останніми).
+    pub fn update_animations(&mut self, delta_time: f32) {
+        for entity in &mut self.entities {
+            if let Some(animation) = entity.animation.as_mut() {
+                animation.update(delta_time);
+            }
+            if let Some(animator) = entity.animator.as_mut() {
+                animator.update(delta_time);
+                let sprite = entity.sprite.get_or_insert_with(NSprite::default);
+                let texture = &animator.sheet.texture;
+                if sprite.texture.as_ref().map(|current| current.id()) != Some(texture.id()) {
+                    sprite.texture = Some(texture.clone());
+                }
+                if let Some(uv_rect) = animator.uv_rect() {
+                    sprite.uv_rect = uv_rect;
+                }
+            }
+            // Аніматор пише в ту саму сутність, тому на час оновлення виймається з неї
+            if let Some(mut animator) = entity.property_animator.take() {
+                animator.update(delta_time, entity);
+                entity.property_animator = Some(animator);
+            }
+        }
+
+        let mut tweens = std::mem::take(&mut self.tweens);
+        tweens.retain_mut(|tween| match self.entity_mut(tween.entity) {
+            Some(entity) => !tween.update(delta_time, entity),
+            None => false,
+        });
+        self.tweens = tweens;
+    }
+
+    // Переносить межі сутностей у BVH. Викликається раз на кадр після змін сцени
+    // і перед рендерингом: сутності, що не вийшли за розширені межі свого листка,
+    // дерево не змінюють, видалені сутності прибираються з нього.
+    pub fn update_bounds(&mut self) {
+        self.entity_index.clear();
+        for (index, entity) in self.entities.iter().enumerate() {
+            self.entity_index.insert(entity.id(), index);
+            match entity.bounds() {
+                Some(bounds) => self.bvh.update(entity.id(), bounds),
+                None => {
+                    self.bvh.remove(entity.id());
+                }
+            }
+        }
+        let entity_index = &self.entity_index;
+        self.bvh.retain(|id| entity_index.contains_key(&id));
+    }
+
+    pub fn bvh(&self) -> &NBvh {
+        &self.bvh
+    }
+
+    // Індекси (в entities) сутностей, межі яких потрапляють у frustum,
+    // у порядку entities, щоб порядок відрисовки не залежав від форми дерева
+    pub fn visible_entities(&self, frustum: &NFrustum) -> Vec<usize> {
+        let mut visible: Vec<usize> = self
+            .bvh
+            .query_frustum(frustum)
+            .into_iter()
+            .filter_map(|id| self.entity_index.get(&id).copied())
+            .collect();
+        visible.sort_unstable();
+        visible
+    }
+
+    // Найближча сутність, межі якої перетинає промінь, і відстань до них
+    pub fn raycast(&self, ray: &NRay) -> Option<(NEntityId, f32)> {
+        self.bvh.query_ray(ray, f32::INFINITY).into_iter().next()
+    }
+
+    // Демонстраційна сцена: сітка кубів з одним мешем і PBR матеріалами
+    // (metallic росте вздовж z, roughness — вздовж x), ряд анімованих спрайтів
+    // та два емітери частинок (адитивний вогонь і дим з альфа-змішуванням),
+    // заголовок світовим текстом над спрайтами, підказка в HUD шрифтом `font`
+    // тайлова мапа з анімованою водою за сіткою кубів і три скіновані щупальця
+    // між вогнем і димом, якими керує машина станів анімації. Заголовок пульсує
+    // кліпом властивостей, спрайти підстрибують нескінченними твінами.
+    // Кожна пара меш + матеріал малюється одним draw call-ом.
+    pub fn demo(font: Arc<NFont>) -> Self {
+        let mut scene = NScene {
+            environment: Some(Arc::new(NEnvironment::sky("Sky"))),
+            ..Default::default()
+        };
+        scene.camera.position = Point3::new(0.0, 8.0, 14.0);
+
+        let cube = Arc::new(NMesh::cube());
+        let materials: Vec<Arc<NMaterial>> = (0..16)
+            .map(|index| {
+                let (metallic, roughness) = ((index / 4) as f32 / 3.0, (index % 4) as f32 / 3.0);
+                Arc::new(
+                    NMaterial::pbr(format!("PBR m{:.2} r{:.2}", metallic, roughness))
+                        .with_param("metallic", NMaterialValue::Float(metallic))
+                        .with_param("roughness", NMaterialValue::Float(roughness)),
+                )
+            })
+            .collect();
+        for x in -8..8 {
+            for z in -8..8 {
+                let entity = scene.spawn(format!("Cube {} {}", x, z));
+                entity.transform = NTransform::from_position(Vector3::new(
+                    x as f32 * 1.5 + 0.75,
+                    0.0,
+                    z as f32 * 1.5 + 0.75,
+                ));
+                entity.transform.scale = Vector3::new(0.8, 0.8, 0.8);
+                entity.mesh = Some(NMeshRenderer {
+                    mesh: cube.clone(),
+                    material: materials[((z + 8) / 4 * 4 + (x + 8) / 4) as usize].clone(),
+                    color: [(x + 8) as f32 / 16.0, 0.5, (z + 8) as f32 / 16.0, 1.0],
+                });
+            }
+        }
+
+        // Спрайти спільно використовують один лист: парні крутяться по колу з різною
+        // швидкістю, непарні гойдаються вперед-назад
+        let spinner = Arc::new(demo_sprite_sheet());
+        let mut sprites = Vec::new();
+        for i in -4..=4 {
+            let entity = scene.spawn(format!("Sprite {}", i));
+            sprites.push(entity.id());
+            entity.transform = NTransform::from_position(Vector3::new(i as f32 * 2.0, 2.0, 0.0));
+            entity.sprite = Some(NSprite { color: [1.0, 0.9, 0.6, 1.0], ..Default::default() });
+            let mut animator = NSpriteAnimator::new(spinner.clone());
+            if i % 2 == 0 {
+                animator.speed = 1.0 + i.abs() as f32 * 0.25;
+            } else {
+                animator.play_with("swing", NPlayMode::PingPong);
+            }
+            entity.animator = Some(animator);
+        }
+        // Підстрибування хвилею: кожен наступний спрайт стартує на 0.1 с пізніше
+        for (index, &sprite) in sprites.iter().enumerate() {
+            scene
+                .tween(sprite, "transform.position.y", &[2.5], 0.6)
+                .unwrap()
+                .ease(NEasing::QuadOut)
+                .delay(index as f32 * 0.1)
+                .repeat(REPEAT_FOREVER, true);
+        }
+
+        let dot = Arc::new(soft_dot_texture(64));
+        let fire = scene.spawn("Fire");
+        fire.transform = NTransform::from_position(Vector3::new(-3.0, 0.5, 4.0));
+        fire.particles = Some(NParticleEmitter {
+            rate: 120.0,
+            bursts: vec![NParticleBurst { time: 0.0, count: 200 }],
+            lifetime: [0.6, 1.2],
+            speed: [1.5, 3.0],
+            spread: 15.0,
+            radius: 0.3,
+            gravity: Vector3::new(0.0, 1.0, 0.0),
+            size: 0.5,
+            color_over_lifetime: NGradient {
+                keys: vec![
+                    (0.0, [1.0, 0.9, 0.5, 1.0]),
+                    (0.4, [1.0, 0.4, 0.1, 0.8]),
+                    (1.0, [0.4, 0.05, 0.0, 0.0]),
+                ],
+            },
+            texture: Some(dot.clone()),
+            blend: NParticleBlend::Additive,
+            ..Default::default()
+        });
+        let smoke = scene.spawn("Smoke");
+        smoke.transform = NTransform::from_position(Vector3::new(3.0, 0.5, 4.0));
+        smoke.particles = Some(NParticleEmitter {
+            max_particles: 512,
+            rate: 30.0,
+            lifetime: [3.0, 4.0],
+            speed: [0.5, 1.0],
+            gravity: Vector3::new(0.3, 0.2, 0.0),
+            size: 0.6,
+            size_over_lifetime: NCurve::linear(0.5, 2.5),
+            color_over_lifetime: NGradient::linear([0.5, 0.5, 0.5, 0.8], [0.2, 0.2, 0.2, 0.0]),
+            texture: Some(dot),
+            ..Default::default()
+        });
+
+        // Щупальця гойдаються ("sway"), після кожного кола б'ють ("whip") і повертаються;
+        // переходи — кросфейди, "breathe" — адитивний шар. Параметр "energy" задає
+        // швидкість гойдання, тож однакові машини станів швидко розходяться в часі.
+        const JOINTS: usize = 5;
+        const SEGMENT: f32 = 0.4;
+        let skeleton = Arc::new(NSkeleton::chain("Tentacle", JOINTS, SEGMENT));
+        let [sway, whip, breathe] = <[_; 3]>::try_from(demo_clips(JOINTS)).unwrap();
+        let height = SEGMENT * (JOINTS - 1) as f32;
+        let mut tentacle = NMesh::cylinder(0.15, height, 12, 16);
+        let skin = tentacle
+            .vertices
+            .iter()
+            .map(|vertex| {
+                let along = vertex.position[1] / SEGMENT;
+                let joint = (along.floor() as u32).min(JOINTS as u32 - 2);
+                let weight = (along - joint as f32).clamp(0.0, 1.0);
+                NSkinVertex {
+                    joints: [joint, joint + 1, 0, 0],
+                    weights: [1.0 - weight, weight, 0.0, 0.0],
+                }
+            })
+            .collect();
+        tentacle = tentacle.with_skin(skin);
+        tentacle.name = "Tentacle".to_owned();
+        let tentacle = Arc::new(tentacle);
+        let tentacle_material = Arc::new(
+            NMaterial::pbr("Tentacle")
+                .with_param("metallic", NMaterialValue::Float(0.0))
+                .with_param("roughness", NMaterialValue::Float(0.4)),
+        );
+        for i in 0..3 {
+            let mut animation = NAnimationStateMachine::new(skeleton.clone());
+            animation.add_state("sway", sway.clone()).speed_parameter = Some("energy".to_owned());
+            animation.add_state("whip", whip.clone()).looping = false;
+            animation.add_transition(Some("sway"), "whip", 0.3).when(NAnimationCondition::Finished);
+            animation.add_transition(Some("whip"), "sway", 0.4).when(NAnimationCondition::Finished);
+            animation.add_layer("breathe", breathe.clone(), NLayerBlend::Additive);
+            animation.set_float("energy", 1.0 + i as f32 * 0.35);
+
+            let entity = scene.spawn(format!("Tentacle {}", i));
+            entity.transform =
+                NTransform::from_position(Vector3::new(i as f32 * 1.2 - 1.2, 0.4, 4.0));
+            entity.mesh = Some(NMeshRenderer {
+                mesh: tentacle.clone(),
+                material: tentacle_material.clone(),
+                color: [0.6, 0.2 + i as f32 * 0.2, 0.7, 1.0],
+            });
+            entity.animation = Some(animation);
+        }
+
+        let title = scene.spawn("Title");
+        title.transform = NTransform::from_position(Vector3::new(0.0, 3.2, 0.0));
+        title.text = Some(NText {
+            size: 0.8,
+            pivot: [0.5, 1.0],
+            outline: Some(NTextOutline { width: 0.08, color: [0.1, 0.05, 0.0, 1.0] }),
+            ..NText::new(font.clone(), "Nova-Engine")
+        });
+        let mut pulse = NPropertyClip::new("Title Pulse");
+        pulse
+            .curve("transform.scale")
+            .unwrap()
+            .key(0.0, &[1.0, 1.0, 1.0], NEasing::SineInOut)
+            .key(1.0, &[1.1, 1.1, 1.1], NEasing::SineInOut)
+            .key(2.0, &[1.0, 1.0, 1.0], NEasing::Linear);
+        pulse
+            .curve("text.color")
+            .unwrap()
+            .key(0.0, &[1.0, 1.0, 1.0, 1.0], NEasing::CubicInOut)
+            .key(1.0, &[1.0, 0.8, 0.3, 1.0], NEasing::CubicInOut)
+            .key(2.0, &[1.0, 1.0, 1.0, 1.0], NEasing::Linear);
+        title.property_animator = Some(NPropertyAnimator::new(Arc::new(pulse)));
+        // Два шари: земля з ставком і цегляна платформа поверх
+        let mut tilemap = NTilemap::new([24, 10], [0.5, 0.5]);
+        let first = tilemap.add_tileset(Arc::new(demo_tileset()));
+        let [grass, dirt, brick, water] = [0, 1, 2, 3].map(|tile| NTile::new(first + tile));
+        let ground = tilemap.add_layer("Ground");
+        let platforms = tilemap.add_layer("Platforms");
+        for x in 0..24 {
+            let pond = (10..15).contains(&x);
+            tilemap.set_tile(ground, x, 7, if pond { water } else { grass });
+            for y in 8..10 {
+                tilemap.set_tile(ground, x, y, if pond && y == 8 { water } else { dirt });
+            }
+        }
+        for x in 3..9 {
+            tilemap.set_tile(platforms, x, 4, brick);
+            tilemap.set_tile(platforms, x + 12, 3, brick);
+        }
+        let level = scene.spawn("Tilemap");
+        level.transform = NTransform::from_position(Vector3::new(-6.0, 4.5, -13.0));
+        level.tilemap = Some(tilemap);
+
+        let hint = scene.spawn("HUD Hint");
+        let hint_text = "Metallic росте вздовж z, roughness — вздовж x.\n\
+                         Вогонь і дим — частинки на GPU.";
+        hint.text = Some(
+            NText {
+                size: 18.0,
+                color: [1.0, 1.0, 1.0, 0.9],
+                align: NTextAlign::Right,
+                pivot: [1.0, 0.0],
+                max_width: Some(360.0),
+                shadow: Some(NTextShadow {
+                    offset: [0.06, -0.06],
+                    softness: 0.05,
+                    color: [0.0, 0.0, 0.0, 0.7],
+                }),
+                ..NText::new(font, hint_text)
+            }
+            .on_screen([1.0, 0.0], [-16.0, 16.0]),
+        );
+        scene
+    }
+}
+
+// JSON експорт Aseprite: об'єкт з "frames" і "meta" (мапи Tiled мають "layers")
+fn is_aseprite_sheet(path: &Path) -> bool {
+    let Ok(source) = std::fs::read_to_string(path) else { return false };
+    serde_json::from_str::<serde_json::Value>(&source)
+        .is_ok_and(|json| json.get("frames").is_some() && json.get("meta").is_some())
+}
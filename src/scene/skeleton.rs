@@ -0,0 +1,191 @@
+// Скелет для скінінгу.
+// NSkeleton — суглоби з батьками, локальними трансформами пози спокою та inverse bind
+// матрицями; порядок суглобів — порядок палітри (як joints у glTF skin) і не мусить
+// іти від батьків до дітей: порядок обходу рахується при створенні.
+// NPose — локальні трансформи всіх суглобів. Кліпи пишуть у позу, пози змішуються
+// (кросфейди, шари з маскою) і додаються (адитивні шари), а palette перетворює позу
+// на матриці суглобів для вершинного шейдера: світ суглоба * inverse bind.
+
+use cgmath::{InnerSpace, Matrix4, One, Quaternion, Vector3};
+
+use crate::{
+    graphics::asset::{next_asset_id, NAssetId},
+    scene::entity::NTransform,
+};
+
+/// Суглоб скелета
+#[derive(Clone, Debug)]
+pub struct NJoint {
+    pub name: String,                   // Имя
+    pub parent: Option<usize>,          // Родительский сустав
+    pub rest: NTransform,               // Локальный трансформ в позе покоя
+    pub inverse_bind: Matrix4<f32>,     // Пространство модели -> пространство сустава при привязке
+    pub base: Matrix4<f32>,             // Для корней: матрица предков вне скелета
+}
+
+impl NJoint {
+    pub fn new(name: impl Into<String>, parent: Option<usize>, rest: NTransform) -> Self {
+        NJoint {
+            name: name.into(),
+            parent,
+            rest,
+            inverse_bind: Matrix4::one(),
+            base: Matrix4::one(),
+        }
+    }
+}
+
+/// Скелет: ієрархія суглобів
+#[derive(Debug)]
+pub struct NSkeleton {
+    id: NAssetId,               // Идентификатор ассета
+    pub name: String,           // Имя
+    joints: Vec<NJoint>,        // Суставы в порядке палитры
+    order: Vec<usize>,          // Порядок обхода: родители раньше детей
+}
+
+impl NSkeleton {
+    pub fn new(name: impl Into<String>, joints: Vec<NJoint>) -> Self {
+        // Глибина суглоба в ієрархії; цикл батьків — помилка даних
+        let depth = |mut joint: usize| {
+            let mut depth = 0;
+            while let Some(parent) = joints[joint].parent {
+                assert!(parent < joints.len(), "joint parent out of range");
+                depth += 1;
+                assert!(depth <= joints.len(), "joint hierarchy has a cycle");
+                joint = parent;
+            }
+            depth
+        };
+        let mut order: Vec<usize> = (0..joints.len()).collect();
+        order.sort_by_cached_key(|&joint| depth(joint));
+        NSkeleton { id: next_asset_id(), name: name.into(), joints, order }
+    }
+
+    // Скелет з одного ланцюжка суглобів `count` довжиною `length` кожен уздовж +Y.
+    // Inverse bind — обернені світові матриці пози спокою.
+    pub fn chain(name: impl Into<String>, count: usize, length: f32) -> Self {
+        let joints = (0..count)
+            .map(|index| {
+                let offset = if index == 0 { 0.0 } else { length };
+                let rest = NTransform::from_position(Vector3::new(0.0, offset, 0.0));
+                let mut joint = NJoint::new(format!("Joint {}", index), index.checked_sub(1), rest);
+                joint.inverse_bind =
+                    Matrix4::from_translation(Vector3::new(0.0, -(index as f32) * length, 0.0));
+                joint
+            })
+            .collect();
+        NSkeleton::new(name, joints)
+    }
+
+    #[inline]
+    #[allow(dead_code)]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    #[inline]
+    pub fn joints(&self) -> &[NJoint] {
+        &self.joints
+    }
+
+    #[allow(dead_code)]
+    pub fn joint_index(&self, name: &str) -> Option<usize> {
+        self.joints.iter().position(|joint| joint.name == name)
+    }
+
+    pub fn rest_pose(&self) -> NPose {
+        NPose { joints: self.joints.iter().map(|joint| joint.rest).collect() }
+    }
+
+    // Маска шару: 1 для суглоба `root` і всіх його нащадків, 0 для решти
+    #[allow(dead_code)]
+    pub fn mask(&self, root: usize) -> Vec<f32> {
+        let mut mask = vec![0.0; self.joints.len()];
+        for &joint in &self.order {
+            let inside = joint == root
+                || self.joints[joint].parent.is_some_and(|parent| mask[parent] > 0.0);
+            if inside {
+                mask[joint] = 1.0;
+            }
+        }
+        mask
+    }
+
+    // Матриці суглобів пози `pose` у просторі моделі
+    pub fn model_matrices(&self, pose: &NPose) -> Vec<Matrix4<f32>> {
+        let mut matrices = vec![Matrix4::one(); self.joints.len()];
+        for &joint in &self.order {
+            let local = pose.joints[joint].matrix();
+            matrices[joint] = match self.joints[joint].parent {
+                Some(parent) => matrices[parent] * local,
+                None => self.joints[joint].base * local,
+            };
+        }
+        matrices
+    }
+
+    // Палітра суглобів для скінінгу: модель суглоба * inverse bind
+    pub fn palette(&self, pose: &NPose) -> Vec<[[f32; 4]; 4]> {
+        self.model_matrices(pose)
+            .into_iter()
+            .zip(&self.joints)
+            .map(|(matrix, joint)| (matrix * joint.inverse_bind).into())
+            .collect()
+    }
+}
+
+/// Поза скелета: локальні трансформи суглобів у порядку NSkeleton::joints
+#[derive(Clone, Debug, Default)]
+pub struct NPose {
+    pub joints: Vec<NTransform>,    // Локальные трансформы суставов
+}
+
+impl NPose {
+    // Змішує позу з `other`: 0 — лишається поза, 1 — `other`.
+    // `mask` множить вагу окремо для кожного суглоба.
+    pub fn blend(&mut self, other: &NPose, weight: f32, mask: Option<&[f32]>) {
+        for (index, (joint, target)) in self.joints.iter_mut().zip(&other.joints).enumerate() {
+            let weight = weight * mask.map_or(1.0, |mask| mask[index]);
+            if weight <= 0.0 {
+                continue;
+            }
+            joint.position += (target.position - joint.position) * weight;
+            joint.rotation = blend_rotation(joint.rotation, target.rotation, weight);
+            joint.scale += (target.scale - joint.scale) * weight;
+        }
+    }
+
+    // Додає різницю `pose` відносно `reference` з вагою `weight` (адитивний шар)
+    pub fn add(&mut self, pose: &NPose, reference: &NPose, weight: f32, mask: Option<&[f32]>) {
+        let deltas = pose.joints.iter().zip(&reference.joints);
+        for (index, (joint, (target, base))) in self.joints.iter_mut().zip(deltas).enumerate() {
+            let weight = weight * mask.map_or(1.0, |mask| mask[index]);
+            if weight <= 0.0 {
+                continue;
+            }
+            joint.position += (target.position - base.position) * weight;
+            let delta = base.rotation.conjugate() * target.rotation;
+            joint.rotation = joint.rotation * blend_rotation(Quaternion::one(), delta, weight);
+            let ratio = |target: f32, base: f32| if base != 0.0 { target / base } else { 1.0 };
+            let scale = Vector3::new(
+                ratio(target.scale.x, base.scale.x),
+                ratio(target.scale.y, base.scale.y),
+                ratio(target.scale.z, base.scale.z),
+            );
+            let one = Vector3::new(1.0, 1.0, 1.0);
+            let scale = one + (scale - one) * weight;
+            joint.scale = Vector3::new(
+                joint.scale.x * scale.x,
+                joint.scale.y * scale.y,
+                joint.scale.z * scale.z,
+            );
+        }
+    }
+}
+
+// Нормалізована лінійна інтерполяція поворотів коротшим шляхом
+pub fn blend_rotation(from: Quaternion<f32>, to: Quaternion<f32>, weight: f32) -> Quaternion<f32> {
+    let to = if from.dot(to) < 0.0 { -to } else { to };
+    (from * (1.0 - weight) + to * weight).normalize()
+}
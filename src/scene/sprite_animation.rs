@@ -0,0 +1,336 @@
+// Покадрова анімація спрайтів.
+// NSpriteSheet — ассет: атлас, кадри (область атласу в UV і тривалість) та іменовані
+// кліпи — послідовності номерів кадрів із режимом програвання за замовчуванням і подіями
+// на окремих кадрах. Один лист спільний для багатьох сутностей через Arc.
+// NSpriteAnimator — компонент сутності: поточний кліп, позиція в ньому, режим і швидкість.
+// NScene::update_animations раз на кадр просуває аніматори і переносить текстуру та
+// область поточного кадру у спрайт сутності, тому рендер спрайтів про анімацію не знає.
+
+use std::sync::Arc;
+
+use crate::graphics::{
+    asset::{next_asset_id, NAssetId},
+    texture::NTexture,
+};
+
+/// Режим програвання кліпа
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NPlayMode {
+    #[default]
+    Loop,       // По кругу
+    PingPong,   // Вперёд-назад
+    Once,       // Один раз, остановка на последнем кадре
+}
+
+/// Кадр листа: область атласу і тривалість показу
+#[derive(Clone, Copy, Debug)]
+pub struct NSpriteFrame {
+    pub uv_rect: [f32; 4],  // Область атласа (x, y, ширина, высота) в UV
+    pub duration: f32,      // Длительность в секундах
+}
+
+/// Подія кліпа: спрацьовує щоразу, коли аніматор переходить на кадр `frame`
+#[derive(Clone, Debug)]
+pub struct NSpriteEvent {
+    pub frame: usize,       // Позиция кадра в клипе
+    pub name: String,       // Имя события
+}
+
+/// Іменований кліп: послідовність кадрів листа
+#[derive(Clone, Debug)]
+pub struct NSpriteClip {
+    pub name: String,               // Имя клипа
+    pub frames: Vec<usize>,         // Номера кадров листа по порядку
+    pub mode: NPlayMode,            // Режим по умолчанию
+    pub events: Vec<NSpriteEvent>,  // События на кадрах
+}
+
+impl NSpriteClip {
+    // Додає подію на кадр `frame` кліпа
+    pub fn add_event(&mut self, frame: usize, name: impl Into<String>) -> &mut Self {
+        self.events.push(NSpriteEvent { frame, name: name.into() });
+        self
+    }
+
+    // Тривалість одного проходу кліпа в секундах
+    #[allow(dead_code)]
+    pub fn duration(&self, sheet: &NSpriteSheet) -> f32 {
+        self.frames.iter().map(|&frame| sheet.frames[frame].duration).sum()
+    }
+}
+
+/// Лист анімацій: атлас, кадри і кліпи
+#[derive(Debug)]
+pub struct NSpriteSheet {
+    id: NAssetId,                   // Идентификатор ассета
+    pub name: String,               // Имя
+    pub texture: Arc<NTexture>,     // Атлас
+    pub frames: Vec<NSpriteFrame>,  // Кадры
+    pub clips: Vec<NSpriteClip>,    // Клипы
+}
+
+impl NSpriteSheet {
+    pub fn new(name: impl Into<String>, texture: Arc<NTexture>) -> Self {
+        NSpriteSheet {
+            id: next_asset_id(),
+            name: name.into(),
+            texture,
+            frames: Vec::new(),
+            clips: Vec::new(),
+        }
+    }
+
+    // Лист із рівномірної сітки `grid` (стовпці, рядки); кадри нумеруються по рядках
+    pub fn from_grid(
+        name: impl Into<String>,
+        texture: Arc<NTexture>,
+        grid: [u32; 2],
+        duration: f32,
+    ) -> Self {
+        let mut sheet = NSpriteSheet::new(name, texture);
+        let [width, height] = [1.0 / grid[0] as f32, 1.0 / grid[1] as f32];
+        for index in 0..grid[0] * grid[1] {
+            let (column, row) = (index % grid[0], index / grid[0]);
+            sheet.frames.push(NSpriteFrame {
+                uv_rect: [column as f32 * width, row as f32 * height, width, height],
+                duration,
+            });
+        }
+        sheet
+    }
+
+    #[inline]
+    #[allow(dead_code)]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    // Додає кліп і повертає його для налаштування подій
+    pub fn add_clip(
+        &mut self,
+        name: impl Into<String>,
+        frames: Vec<usize>,
+        mode: NPlayMode,
+    ) -> &mut NSpriteClip {
+        assert!(frames.iter().all(|&frame| frame < self.frames.len()), "clip frame out of range");
+        self.clips.push(NSpriteClip { name: name.into(), frames, mode, events: Vec::new() });
+        self.clips.last_mut().unwrap()
+    }
+
+    pub fn clip_index(&self, name: &str) -> Option<usize> {
+        self.clips.iter().position(|clip| clip.name == name)
+    }
+
+    #[allow(dead_code)]
+    pub fn clip(&self, name: &str) -> Option<&NSpriteClip> {
+        self.clip_index(name).map(|index| &self.clips[index])
+    }
+}
+
+/// Аніматор спрайта сутності
+#[derive(Clone, Debug)]
+pub struct NSpriteAnimator {
+    pub sheet: Arc<NSpriteSheet>,   // Лист анимаций
+    pub mode: NPlayMode,            // Режим текущего клипа
+    pub speed: f32,                 // Множитель скорости (1 — как в листе)
+    pub playing: bool,              // false — пауза на текущем кадре
+    clip: Option<usize>,            // Текущий клип
+    position: usize,                // Позиция кадра в клипе
+    elapsed: f32,                   // Время на текущем кадре в секундах
+    backwards: bool,                // Обратный ход в режиме PingPong
+    finished: bool,                 // Клип в режиме Once доигран
+    started: bool,                  // Клип запущен после последнего update
+    events: Vec<String>,            // События последнего update
+}
+
+impl NSpriteAnimator {
+    // Аніматор, що програє перший кліп листа (якщо він є)
+    pub fn new(sheet: Arc<NSpriteSheet>) -> Self {
+        let mut animator = NSpriteAnimator {
+            sheet,
+            mode: NPlayMode::Loop,
+            speed: 1.0,
+            playing: true,
+            clip: None,
+            position: 0,
+            elapsed: 0.0,
+            backwards: false,
+            finished: false,
+            started: false,
+            events: Vec::new(),
+        };
+        if let Some(clip) = animator.sheet.clips.first() {
+            let (name, mode) = (clip.name.clone(), clip.mode);
+            animator.play_with(&name, mode);
+        }
+        animator
+    }
+
+    // Перемикає на кліп у його режимі за замовчуванням. Кліп, що вже грає, не перезапускається,
+    // тому виклик можна повторювати щокадру. false — кліпа з таким ім'ям немає.
+    #[allow(dead_code)]
+    pub fn play(&mut self, name: &str) -> bool {
+        match self.sheet.clip_index(name) {
+            Some(index) if self.clip == Some(index) && !self.finished => true,
+            Some(index) => {
+                let mode = self.sheet.clips[index].mode;
+                self.start(index, mode);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Запускає кліп з початку в заданому режимі
+    pub fn play_with(&mut self, name: &str, mode: NPlayMode) -> bool {
+        match self.sheet.clip_index(name) {
+            Some(index) => {
+                self.start(index, mode);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn start(&mut self, clip: usize, mode: NPlayMode) {
+        self.clip = Some(clip);
+        self.mode = mode;
+        self.position = 0;
+        self.elapsed = 0.0;
+        self.backwards = false;
+        self.finished = false;
+        self.playing = true;
+        self.started = true;
+        self.events.clear();
+        self.fire_events();
+    }
+
+    #[allow(dead_code)]
+    pub fn clip_name(&self) -> Option<&str> {
+        self.clip.map(|clip| self.sheet.clips[clip].name.as_str())
+    }
+
+    // Номер поточного кадру листа
+    pub fn frame(&self) -> Option<usize> {
+        let clip = &self.sheet.clips[self.clip?];
+        clip.frames.get(self.position).copied()
+    }
+
+    // Область атласу поточного кадру
+    pub fn uv_rect(&self) -> Option<[f32; 4]> {
+        self.frame().map(|frame| self.sheet.frames[frame].uv_rect)
+    }
+
+    // Кліп у режимі Once дограв до останнього кадру
+    #[allow(dead_code)]
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    // Події, що спрацювали за останній update (або при запуску кліпа)
+    #[allow(dead_code)]
+    pub fn events(&self) -> &[String] {
+        &self.events
+    }
+
+    // Просуває анімацію на `delta_time` секунд. За великий крок може пройти кілька кадрів,
+    // події кожного з них потрапляють у events().
+    pub fn update(&mut self, delta_time: f32) {
+        // Події запуску кліпа доживають до кінця першого update
+        if !std::mem::take(&mut self.started) {
+            self.events.clear();
+        }
+        let Some(clip) = self.clip else { return };
+        if !self.playing || self.finished {
+            return;
+        }
+        let sheet = self.sheet.clone();
+        let frames = &sheet.clips[clip].frames;
+        if frames.is_empty() {
+            return;
+        }
+        self.elapsed += delta_time * self.speed.max(0.0);
+        // Кадри з нульовою тривалістю не повинні зациклити оновлення
+        for _ in 0..frames.len() * 2 {
+            let duration = sheet.frames[frames[self.position]].duration;
+            if self.elapsed < duration {
+                break;
+            }
+            self.elapsed -= duration;
+            if !self.advance(frames.len()) {
+                self.finished = true;
+                self.elapsed = 0.0;
+                break;
+            }
+            self.fire_events();
+        }
+        // Залишок часу після обрізаного циклу відкидається
+        let duration = sheet.frames[frames[self.position]].duration;
+        self.elapsed = self.elapsed.min(duration);
+    }
+
+    // Переходить на наступний кадр; false — кліп Once закінчився
+    fn advance(&mut self, length: usize) -> bool {
+        match self.mode {
+            NPlayMode::Loop => self.position = (self.position + 1) % length,
+            NPlayMode::Once if self.position + 1 < length => self.position += 1,
+            NPlayMode::Once => return false,
+            NPlayMode::PingPong if length == 1 => {}
+            NPlayMode::PingPong => {
+                if self.backwards && self.position == 0 {
+                    self.backwards = false;
+                } else if !self.backwards && self.position + 1 == length {
+                    self.backwards = true;
+                }
+                if self.backwards {
+                    self.position -= 1;
+                } else {
+                    self.position += 1;
+                }
+            }
+        }
+        true
+    }
+
+    fn fire_events(&mut self) {
+        let Some(clip) = self.clip else { return };
+        let clip = &self.sheet.clips[clip];
+        for event in clip.events.iter().filter(|event| event.frame == self.position) {
+            self.events.push(event.name.clone());
+        }
+    }
+}
+
+// Лист для демо-сцени: точка, що обертається по колу, 8 кадрів у рядку.
+// Кліпи: "spin" — повний оберт по колу, "swing" — півоберту вперед-назад із подією "tick".
+pub fn demo_sprite_sheet() -> NSpriteSheet {
+    const FRAME: u32 = 32;
+    const FRAMES: u32 = 8;
+    let data = (0..FRAME * FRAMES * FRAME)
+        .flat_map(|index| {
+            let (column, y) = (index % (FRAME * FRAMES), index / (FRAME * FRAMES));
+            let (frame, x) = (column / FRAME, column % FRAME);
+            let angle = frame as f32 / FRAMES as f32 * std::f32::consts::TAU;
+            let center = FRAME as f32 * 0.5;
+            let (dot_x, dot_y) = (center + angle.cos() * 10.0, center + angle.sin() * 10.0);
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let dot = ((px - dot_x).powi(2) + (py - dot_y).powi(2)).sqrt();
+            let ring = ((px - center).powi(2) + (py - center).powi(2)).sqrt();
+            let alpha = if dot < 5.0 {
+                255
+            } else if (ring - 10.0).abs() < 1.0 {
+                90
+            } else {
+                0
+            };
+            [255, 255, 255, alpha]
+        })
+        .collect();
+    let texture = Arc::new(NTexture::new("Demo spinner", [FRAME * FRAMES, FRAME], data));
+    let mut sheet = NSpriteSheet::from_grid("Demo spinner", texture, [FRAMES, 1], 0.08);
+    sheet.add_clip("spin", (0..FRAMES as usize).collect(), NPlayMode::Loop);
+    sheet
+        .add_clip("swing", (0..=FRAMES as usize / 2).collect(), NPlayMode::PingPong)
+        .add_event(0, "tick");
+    sheet
+}
@@ -0,0 +1,184 @@
+// Текстовий компонент сутності.
+// NText описує рядки, шрифт і оформлення (обводка, тінь); layout розкладає
+// текст у гліфи атласу шрифту в em просторі (y вгору): рядки переносяться
+// по словах за max_width, вирівнюються за align, а блок зсувається так, щоб
+// його точка pivot опинилась у початку координат. Малює текст NTextDrawSystem:
+// світовий — у площині XY сутності, екранний — поверх кадру (HUD).
+
+use std::sync::Arc;
+
+use crate::graphics::font::NFont;
+
+/// Горизонтальне вирівнювання рядків у блоці тексту
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NTextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Простір, у якому розміщується текст
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NTextSpace {
+    World,                                          // Плоскость XY сущности, size в единицах мира
+    Screen { anchor: [f32; 2], offset: [f32; 2] },  // Доля кадра (0..1) + сдвиг, size в пикселях
+}
+
+/// Обводка літер
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NTextOutline {
+    pub width: f32,         // Толщина (em), ограничена spread атласа шрифта
+    pub color: [f32; 4],    // Цвет
+}
+
+/// Тінь під текстом
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NTextShadow {
+    pub offset: [f32; 2],   // Сдвиг (em, y вверх), ограничен spread атласа шрифта
+    pub softness: f32,      // Размытие края (em)
+    pub color: [f32; 4],    // Цвет
+}
+
+/// Текст сутності
+#[derive(Clone, Debug)]
+pub struct NText {
+    pub text: String,                   // Текст ('\n' — новая строка)
+    pub font: Arc<NFont>,               // Шрифт с SDF атласом
+    pub size: f32,                      // Высота em: единицы мира или пиксели экрана
+    pub color: [f32; 4],                // Цвет заливки
+    pub align: NTextAlign,              // Выравнивание строк
+    pub pivot: [f32; 2],                // Точка блока в начале координат (0,0 — левый верх)
+    pub max_width: Option<f32>,         // Ширина переноса в единицах size (None — без переноса)
+    pub line_spacing: f32,              // Множитель межстрочного интервала
+    pub outline: Option<NTextOutline>,  // Обводка
+    pub shadow: Option<NTextShadow>,    // Тень
+    pub space: NTextSpace,              // Мир или экран
+}
+
+/// Гліф, розміщений розкладкою (em простір тексту, y вгору)
+#[derive(Clone, Copy, Debug)]
+pub struct NPlacedGlyph {
+    pub rect: [f32; 4],     // Квадрат глифа (x, y, ширина, высота)
+    pub uv_rect: [f32; 4],  // Область атласа (x, y, ширина, высота) в UV
+}
+
+impl NText {
+    pub fn new(font: Arc<NFont>, text: impl Into<String>) -> Self {
+        NText {
+            text: text.into(),
+            font,
+            size: 1.0,
+            color: [1.0; 4],
+            align: NTextAlign::Left,
+            pivot: [0.0, 0.0],
+            max_width: None,
+            line_spacing: 1.0,
+            outline: None,
+            shadow: None,
+            space: NTextSpace::World,
+        }
+    }
+
+    // Екранний текст: точка anchor у частках кадру (0,0 — лівий верхній кут)
+    // плюс зсув offset у пікселях
+    pub fn on_screen(mut self, anchor: [f32; 2], offset: [f32; 2]) -> Self {
+        self.space = NTextSpace::Screen { anchor, offset };
+        self
+    }
+
+    // Точка початку координат екранного тексту в пікселях кадру розміром viewport
+    // (None для світового тексту)
+    pub fn screen_position(&self, viewport: [f32; 2]) -> Option<[f32; 2]> {
+        let NTextSpace::Screen { anchor, offset } = self.space else { return None };
+        Some([anchor[0] * viewport[0] + offset[0], anchor[1] * viewport[1] + offset[1]])
+    }
+
+    // Прямокутник екранного тексту в пікселях кадру: (min, max), y вниз
+    pub fn screen_rect(&self, viewport: [f32; 2]) -> Option<([f32; 2], [f32; 2])> {
+        let position = self.screen_position(viewport)?;
+        let (min, max) = self.local_rect();
+        Some((
+            [position[0] + min[0], position[1] - max[1]],
+            [position[0] + max[0], position[1] - min[1]],
+        ))
+    }
+
+    // Рядки після переносу по словах: рядок ламається на пробілі перед словом,
+    // яке не вміщається в max_width. Довше за max_width слово лишається цілим.
+    fn lines(&self) -> Vec<&str> {
+        let max_width = self.max_width.map(|width| width / self.size);
+        let mut lines = Vec::new();
+        for paragraph in self.text.split('\n') {
+            let Some(max_width) = max_width else {
+                lines.push(paragraph);
+                continue;
+            };
+            let mut start = 0;  // Начало текущей строки
+            let mut fit = 0;    // Конец последнего поместившегося слова
+            let ends = paragraph.match_indices(' ').map(|(end, _)| end);
+            for end in ends.chain([paragraph.len()]) {
+                if fit > start && self.font.measure(&paragraph[start..end]) > max_width {
+                    lines.push(&paragraph[start..fit]);
+                    start = fit + 1;
+                }
+                fit = end;
+            }
+            lines.push(&paragraph[start..]);
+        }
+        lines
+    }
+
+    // Ширини рядків (без пробілів у кінці) і розмір блоку тексту в em
+    fn measure(&self, lines: &[&str]) -> (Vec<f32>, [f32; 2]) {
+        let font = &self.font;
+        let widths: Vec<f32> = lines.iter().map(|line| font.measure(line.trim_end())).collect();
+        let width = widths.iter().copied().fold(0.0, f32::max);
+        let line_height = font.line_height() * self.line_spacing;
+        let height = font.ascender - font.descender + line_height * (lines.len() - 1) as f32;
+        (widths, [width, height])
+    }
+
+    // Прямокутник блоку тексту в локальних координатах (em * size): (min, max)
+    pub fn local_rect(&self) -> ([f32; 2], [f32; 2]) {
+        let (_, size) = self.measure(&self.lines());
+        let [width, height] = size.map(|extent| extent * self.size);
+        let min = [-self.pivot[0] * width, (self.pivot[1] - 1.0) * height];
+        (min, [min[0] + width, min[1] + height])
+    }
+
+    // Гліфи тексту в em просторі (пробіли квадратів не мають)
+    pub fn layout(&self) -> Vec<NPlacedGlyph> {
+        let font = &self.font;
+        let lines = self.lines();
+        let (widths, [width, height]) = self.measure(&lines);
+        let line_height = font.line_height() * self.line_spacing;
+        let origin = [-self.pivot[0] * width, self.pivot[1] * height];
+
+        let mut glyphs = Vec::new();
+        for (index, (line, line_width)) in lines.iter().zip(widths).enumerate() {
+            let mut pen = origin[0]
+                + match self.align {
+                    NTextAlign::Left => 0.0,
+                    NTextAlign::Center => (width - line_width) * 0.5,
+                    NTextAlign::Right => width - line_width,
+                };
+            let baseline = origin[1] - font.ascender - line_height * index as f32;
+            let mut previous = None;
+            for c in line.chars() {
+                let Some(glyph) = font.glyph(c) else { continue };
+                pen += previous.map_or(0.0, |previous| font.kerning(previous, c));
+                let [x, y, glyph_width, glyph_height] = glyph.rect;
+                if glyph_width > 0.0 {
+                    glyphs.push(NPlacedGlyph {
+                        rect: [pen + x, baseline + y, glyph_width, glyph_height],
+                        uv_rect: glyph.uv_rect,
+                    });
+                }
+                pen += glyph.advance;
+                previous = Some(c);
+            }
+        }
+        glyphs
+    }
+}
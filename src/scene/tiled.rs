@@ -0,0 +1,703 @@
+// Імпорт мап Tiled (.tmx — XML, .tmj/.json — JSON).
+// Підтримуються ортогональні скінченні мапи: тайлові шари (також усередині груп),
+// вбудовані й зовнішні (.tsx/.tsj) тайлсети з одним атласом, дані шарів у CSV,
+// XML або base64 (без стиснення, zlib, gzip). Властивості тайлів collision/solid,
+// platform, hazard, water (bool) і flags (int), а також фігури колізії тайла
+// (objectgroup) стають NTileFlags; анімації тайлів — кадрами NTileFrame.
+// Клітинка мапи має ширину в 1 одиницю світу, висота — за пропорцією тайла Tiled.
+// Атласи завантажує load_texture за шляхом відносно файлу, що на них посилається;
+// load_tiled бере NTexture::load_or_placeholder (без feature "image" — заглушки).
+
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use base64::Engine;
+use roxmltree::Node;
+use serde_json::Value;
+
+use crate::{
+    graphics::texture::NTexture,
+    scene::tilemap::{NTile, NTileFlags, NTileFrame, NTilemap, NTileset, NTilesetRef},
+};
+
+// Тайлсет з файлу Tiled до завантаження атласу
+struct NTiledTileset {
+    first_gid: u32,                                 // GID первого тайла
+    name: String,                                   // Имя
+    image: PathBuf,                                 // Путь к атласу
+    image_size: [u32; 2],                           // Размер атласа в пикселях
+    tile_size: [u32; 2],                            // Размер тайла в пикселях
+    margin: u32,                                    // Отступ сетки от края атласа
+    spacing: u32,                                   // Промежуток между тайлами
+    tiles: Vec<(u32, NTileFlags, Vec<NTileFrame>)>, // Флаги и анимации отдельных тайлов
+}
+
+// Тайловий шар з файлу Tiled
+struct NTiledLayer {
+    name: String,           // Имя
+    visible: bool,          // Видимость (с учётом групп)
+    color: [f32; 4],        // Оттенок и прозрачность (с учётом групп)
+    data: Vec<u32>,         // GID клеток построчно (с битами отражения)
+}
+
+struct NTiledMap {
+    size: [u32; 2],                 // Размер в клетках
+    tile_size: [u32; 2],            // Размер клетки в пикселях
+    tilesets: Vec<NTiledTileset>,   // Тайлсеты
+    layers: Vec<NTiledLayer>,       // Тайловые слои снизу вверх
+}
+
+impl NTilemap {
+    // Завантажує мапу Tiled; атласи тайлсетів (шлях і розмір з тайлсету)
+    // повертає load_texture
+    pub fn from_tiled(
+        path: impl AsRef<Path>,
+        load_texture: &mut dyn FnMut(&Path, [u32; 2]) -> Result<Arc<NTexture>, String>,
+    ) -> Result<Self, String> {
+        let path = path.as_ref();
+        let map = match extension(path).as_str() {
+            "tmx" => parse_tmx(path),
+            "tmj" | "json" => parse_tmj(path),
+            other => Err(format!("unsupported Tiled map format '{}'", other)),
+        }
+        .map_err(|err| format!("{}: {}", path.display(), err))?;
+
+        let [tile_width, tile_height] = map.tile_size.map(|size| size.max(1) as f32);
+        let mut tilemap = NTilemap::new(map.size, [1.0, tile_height / tile_width]);
+        for tileset in map.tilesets {
+            let texture = load_texture(&tileset.image, tileset.image_size)?;
+            let mut result = NTileset::new(tileset.name, texture, tileset.tile_size)
+                .with_spacing(tileset.margin, tileset.spacing);
+            for (tile, flags, frames) in tileset.tiles {
+                result.set_flags(tile, flags);
+                result.set_animation(tile, frames);
+            }
+            tilemap.tilesets.push(NTilesetRef {
+                first_gid: tileset.first_gid,
+                tileset: Arc::new(result),
+            });
+        }
+        tilemap.tilesets.sort_by_key(|reference| reference.first_gid);
+
+        let width = map.size[0].max(1);
+        for layer in map.layers {
+            let index = tilemap.add_layer(layer.name);
+            tilemap.layers[index].visible = layer.visible;
+            tilemap.layers[index].color = layer.color;
+            for (cell, gid) in layer.data.into_iter().enumerate() {
+                let (x, y) = (cell as u32 % width, cell as u32 / width);
+                tilemap.set_tile(index, x, y, NTile(gid));
+            }
+        }
+        Ok(tilemap)
+    }
+
+    // Завантажує мапу Tiled разом з атласами; атлас, на який посилаються
+    // кілька тайлсетів, завантажується один раз
+    pub fn load_tiled(path: impl AsRef<Path>) -> Result<Self, String> {
+        let mut textures: std::collections::HashMap<PathBuf, Arc<NTexture>> = Default::default();
+        NTilemap::from_tiled(path, &mut |image: &Path, size: [u32; 2]| {
+            if let Some(texture) = textures.get(image) {
+                return Ok(texture.clone());
+            }
+            let texture = Arc::new(NTexture::load_or_placeholder(image, size)?);
+            textures.insert(image.to_path_buf(), texture.clone());
+            Ok(texture)
+        })
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default()
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+fn directory(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new(""))
+}
+
+// Зовнішній тайлсет (.tsx або .tsj/.json)
+fn external_tileset(path: &Path, first_gid: u32) -> Result<NTiledTileset, String> {
+    let result = match extension(path).as_str() {
+        "tsx" => {
+            let source = read(path)?;
+            let document = roxmltree::Document::parse(&source).map_err(|err| err.to_string())?;
+            tsx_tileset(document.root_element(), directory(path), first_gid)
+        }
+        _ => {
+            let source = read(path)?;
+            let json: Value = serde_json::from_str(&source).map_err(|err| err.to_string())?;
+            tsj_tileset(&json, directory(path), first_gid)
+        }
+    };
+    result.map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+// Прапорці колізії з властивості тайла
+fn property_flags(name: &str, value: &str) -> NTileFlags {
+    let enabled = value == "true";
+    match name.to_lowercase().as_str() {
+        "collision" | "solid" if enabled => NTileFlags::SOLID,
+        "platform" if enabled => NTileFlags::PLATFORM,
+        "hazard" if enabled => NTileFlags::HAZARD,
+        "water" if enabled => NTileFlags::WATER,
+        "flags" => NTileFlags(value.parse().unwrap_or(0)),
+        _ => NTileFlags::NONE,
+    }
+}
+
+// Колір шару: tintcolor (#RRGGBB або #AARRGGBB, sRGB) з непрозорістю opacity
+fn layer_color(tint: Option<&str>, opacity: f32) -> [f32; 4] {
+    let mut color = [1.0, 1.0, 1.0, opacity];
+    let Some(hex) = tint.map(|tint| tint.trim_start_matches('#')) else { return color };
+    let Ok(value) = u32::from_str_radix(hex, 16) else { return color };
+    let channel = |shift: u32| ((value >> shift) & 0xFF) as f32 / 255.0;
+    // Колір шейдер множить у лінійному просторі; 2.2 — наближення кривої sRGB
+    for (index, shift) in [16, 8, 0].into_iter().enumerate() {
+        color[index] = channel(shift).powf(2.2);
+    }
+    if hex.len() == 8 {
+        color[3] *= channel(24);
+    }
+    color
+}
+
+// Множить колір і видимість шару на батьківську групу
+fn inherit(layer: &mut NTiledLayer, color: [f32; 4], visible: bool) {
+    layer.visible &= visible;
+    for (channel, parent) in layer.color.iter_mut().zip(color) {
+        *channel *= parent;
+    }
+}
+
+// Розкодовує base64 дані шару (з можливим стисненням) у GID клітинок
+fn decode_base64(text: &str, compression: Option<&str>) -> Result<Vec<u32>, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(text.trim())
+        .map_err(|err| err.to_string())?;
+    let bytes = match compression.unwrap_or("") {
+        "" => bytes,
+        "zlib" => {
+            let mut data = Vec::new();
+            flate2::read::ZlibDecoder::new(&bytes[..])
+                .read_to_end(&mut data)
+                .map_err(|err| err.to_string())?;
+            data
+        }
+        "gzip" => {
+            let mut data = Vec::new();
+            flate2::read::GzDecoder::new(&bytes[..])
+                .read_to_end(&mut data)
+                .map_err(|err| err.to_string())?;
+            data
+        }
+        other => return Err(format!("unsupported layer compression '{}'", other)),
+    };
+    Ok(bytes.chunks_exact(4).map(|gid| u32::from_le_bytes(gid.try_into().unwrap())).collect())
+}
+
+// ---------------------------------------------------------------------------------------
+// TMX / TSX (XML)
+
+fn attribute<T: std::str::FromStr>(node: Node, name: &str) -> Result<T, String> {
+    let value = node
+        .attribute(name)
+        .ok_or_else(|| format!("<{}> without '{}'", node.tag_name().name(), name))?;
+    value.parse().map_err(|_| format!("<{}> has invalid '{}'", node.tag_name().name(), name))
+}
+
+fn attribute_or<T: std::str::FromStr>(node: Node, name: &str, default: T) -> T {
+    node.attribute(name).and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn parse_tmx(path: &Path) -> Result<NTiledMap, String> {
+    tmx_map(&read(path)?, directory(path))
+}
+
+// Мапа TMX; зовнішні тайлсети й атласи шукаються відносно `directory`
+fn tmx_map(source: &str, directory: &Path) -> Result<NTiledMap, String> {
+    let document = roxmltree::Document::parse(source).map_err(|err| err.to_string())?;
+    let map = document.root_element();
+    if !map.has_tag_name("map") {
+        return Err("root element is not <map>".into());
+    }
+    if map.attribute("orientation").unwrap_or("orthogonal") != "orthogonal" {
+        return Err("only orthogonal maps are supported".into());
+    }
+    if map.attribute("infinite") == Some("1") {
+        return Err("infinite maps are not supported".into());
+    }
+
+    let mut tilesets = Vec::new();
+    for tileset in map.children().filter(|node| node.has_tag_name("tileset")) {
+        let first_gid = attribute(tileset, "firstgid")?;
+        tilesets.push(match tileset.attribute("source") {
+            Some(source) => external_tileset(&directory.join(source), first_gid)?,
+            None => tsx_tileset(tileset, directory, first_gid)?,
+        });
+    }
+    let mut layers = Vec::new();
+    tmx_layers(map, &mut layers)?;
+
+    Ok(NTiledMap {
+        size: [attribute(map, "width")?, attribute(map, "height")?],
+        tile_size: [attribute(map, "tilewidth")?, attribute(map, "tileheight")?],
+        tilesets,
+        layers,
+    })
+}
+
+// Тайлові шари вузла <map> або <group> у порядку документа
+fn tmx_layers(node: Node, layers: &mut Vec<NTiledLayer>) -> Result<(), String> {
+    for element in node.children().filter(Node::is_element) {
+        let visible = element.attribute("visible") != Some("0");
+        let opacity = attribute_or(element, "opacity", 1.0);
+        let color = layer_color(element.attribute("tintcolor"), opacity);
+        match element.tag_name().name() {
+            "layer" => layers.push(NTiledLayer {
+                name: element.attribute("name").unwrap_or_default().to_string(),
+                visible,
+                color,
+                data: tmx_layer_data(element)?,
+            }),
+            "group" => {
+                let start = layers.len();
+                tmx_layers(element, layers)?;
+                for layer in &mut layers[start..] {
+                    inherit(layer, color, visible);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn tmx_layer_data(layer: Node) -> Result<Vec<u32>, String> {
+    let data = child(layer, "data").ok_or("<layer> without <data>")?;
+    let text = data.text().unwrap_or_default();
+    match data.attribute("encoding") {
+        None => data
+            .children()
+            .filter(|node| node.has_tag_name("tile"))
+            .map(|tile| Ok(attribute_or(tile, "gid", 0)))
+            .collect(),
+        Some("csv") => text
+            .split(',')
+            .map(|gid| gid.trim().parse().map_err(|_| format!("invalid gid '{}'", gid.trim())))
+            .collect(),
+        Some("base64") => decode_base64(text, data.attribute("compression")),
+        Some(other) => Err(format!("unsupported layer encoding '{}'", other)),
+    }
+}
+
+fn tsx_tileset(
+    tileset: Node,
+    directory: &Path,
+    first_gid: u32,
+) -> Result<NTiledTileset, String> {
+    let image = child(tileset, "image")
+        .ok_or("tilesets without a single atlas image are not supported")?;
+    let mut tiles = Vec::new();
+    for tile in tileset.children().filter(|node| node.has_tag_name("tile")) {
+        let mut flags = NTileFlags::NONE;
+        if let Some(properties) = child(tile, "properties") {
+            for property in properties.children().filter(|node| node.has_tag_name("property")) {
+                let name = property.attribute("name").unwrap_or_default();
+                let value = property.attribute("value").or(property.text()).unwrap_or_default();
+                flags = flags | property_flags(name, value);
+            }
+        }
+        if child(tile, "objectgroup").is_some() {
+            flags = flags | NTileFlags::SOLID;
+        }
+        let frames = child(tile, "animation")
+            .map(|animation| {
+                animation
+                    .children()
+                    .filter(|node| node.has_tag_name("frame"))
+                    .map(|frame| {
+                        Ok(NTileFrame {
+                            tile: attribute(frame, "tileid")?,
+                            duration: attribute::<f32>(frame, "duration")? / 1000.0,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, String>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        tiles.push((attribute(tile, "id")?, flags, frames));
+    }
+
+    Ok(NTiledTileset {
+        first_gid,
+        name: tileset.attribute("name").unwrap_or_default().to_string(),
+        image: directory.join(image.attribute("source").ok_or("<image> without 'source'")?),
+        image_size: [attribute_or(image, "width", 0), attribute_or(image, "height", 0)],
+        tile_size: [attribute(tileset, "tilewidth")?, attribute(tileset, "tileheight")?],
+        margin: attribute_or(tileset, "margin", 0),
+        spacing: attribute_or(tileset, "spacing", 0),
+        tiles,
+    })
+}
+
+// ---------------------------------------------------------------------------------------
+// TMJ / TSJ (JSON)
+
+fn json_u32(value: &Value, key: &str) -> Result<u32, String> {
+    value
+        .get(key)
+        .and_then(Value::as_u64)
+        .map(|number| number as u32)
+        .ok_or_else(|| format!("missing or invalid '{}'", key))
+}
+
+fn json_str<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn json_array<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value.get(key).and_then(Value::as_array).map_or(&[], Vec::as_slice)
+}
+
+fn parse_tmj(path: &Path) -> Result<NTiledMap, String> {
+    tmj_map(&read(path)?, directory(path))
+}
+
+// Мапа TMJ; зовнішні тайлсети й атласи шукаються відносно `directory`
+fn tmj_map(source: &str, directory: &Path) -> Result<NTiledMap, String> {
+    let json: Value = serde_json::from_str(source).map_err(|err| err.to_string())?;
+    if !matches!(json_str(&json, "orientation"), "" | "orthogonal") {
+        return Err("only orthogonal maps are supported".into());
+    }
+    if json.get("infinite").and_then(Value::as_bool) == Some(true) {
+        return Err("infinite maps are not supported".into());
+    }
+
+    let mut tilesets = Vec::new();
+    for tileset in json_array(&json, "tilesets") {
+        let first_gid = json_u32(tileset, "firstgid")?;
+        tilesets.push(match tileset.get("source").and_then(Value::as_str) {
+            Some(source) => external_tileset(&directory.join(source), first_gid)?,
+            None => tsj_tileset(tileset, directory, first_gid)?,
+        });
+    }
+    let mut layers = Vec::new();
+    tmj_layers(json_array(&json, "layers"), &mut layers)?;
+
+    Ok(NTiledMap {
+        size: [json_u32(&json, "width")?, json_u32(&json, "height")?],
+        tile_size: [json_u32(&json, "tilewidth")?, json_u32(&json, "tileheight")?],
+        tilesets,
+        layers,
+    })
+}
+
+fn tmj_layers(source: &[Value], layers: &mut Vec<NTiledLayer>) -> Result<(), String> {
+    for layer in source {
+        let visible = layer.get("visible").and_then(Value::as_bool).unwrap_or(true);
+        let opacity = layer.get("opacity").and_then(Value::as_f64).unwrap_or(1.0) as f32;
+        let color = layer_color(layer.get("tintcolor").and_then(Value::as_str), opacity);
+        match json_str(layer, "type") {
+            "tilelayer" => {
+                let data = match layer.get("data") {
+                    Some(Value::String(text)) => {
+                        decode_base64(text, layer.get("compression").and_then(Value::as_str))?
+                    }
+                    Some(Value::Array(gids)) => {
+                        gids.iter().map(|gid| gid.as_u64().unwrap_or(0) as u32).collect()
+                    }
+                    _ => return Err("tile layer without 'data'".into()),
+                };
+                layers.push(NTiledLayer {
+                    name: json_str(layer, "name").to_string(),
+                    visible,
+                    color,
+                    data,
+                });
+            }
+            "group" => {
+                let start = layers.len();
+                tmj_layers(json_array(layer, "layers"), layers)?;
+                for nested in &mut layers[start..] {
+                    inherit(nested, color, visible);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn tsj_tileset(
+    tileset: &Value,
+    directory: &Path,
+    first_gid: u32,
+) -> Result<NTiledTileset, String> {
+    let image = tileset
+        .get("image")
+        .and_then(Value::as_str)
+        .ok_or("tilesets without a single atlas image are not supported")?;
+    let mut tiles = Vec::new();
+    for tile in json_array(tileset, "tiles") {
+        let mut flags = NTileFlags::NONE;
+        for property in json_array(tile, "properties") {
+            let value = match property.get("value") {
+                Some(Value::String(value)) => value.clone(),
+                Some(value) => value.to_string(),
+                None => String::new(),
+            };
+            flags = flags | property_flags(json_str(property, "name"), &value);
+        }
+        if tile.get("objectgroup").is_some() {
+            flags = flags | NTileFlags::SOLID;
+        }
+        let frames = json_array(tile, "animation")
+            .iter()
+            .map(|frame| {
+                Ok(NTileFrame {
+                    tile: json_u32(frame, "tileid")?,
+                    duration: json_u32(frame, "duration")? as f32 / 1000.0,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        tiles.push((json_u32(tile, "id")?, flags, frames));
+    }
+
+    Ok(NTiledTileset {
+        first_gid,
+        name: json_str(tileset, "name").to_string(),
+        image: directory.join(image),
+        image_size: [
+            json_u32(tileset, "imagewidth").unwrap_or(0),
+            json_u32(tileset, "imageheight").unwrap_or(0),
+        ],
+        tile_size: [json_u32(tileset, "tilewidth")?, json_u32(tileset, "tileheight")?],
+        margin: json_u32(tileset, "margin").unwrap_or(0),
+        spacing: json_u32(tileset, "spacing").unwrap_or(0),
+        tiles,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    // Вбудований тайлсет з властивостями й анімацією тайлів
+    const TSX_TILESET: &str = r##"
+        <tileset firstgid="1" name="terrain" tilewidth="16" tileheight="8" spacing="1">
+            <image source="terrain.png" width="64" height="32"/>
+            <tile id="0">
+                <properties>
+                    <property name="collision" type="bool" value="true"/>
+                    <property name="water" type="bool" value="false"/>
+                </properties>
+            </tile>
+            <tile id="1">
+                <properties>
+                    <property name="Platform" type="bool" value="true"/>
+                    <property name="flags" type="int" value="12"/>
+                </properties>
+            </tile>
+            <tile id="2">
+                <objectgroup><object x="0" y="0" width="16" height="8"/></objectgroup>
+                <animation>
+                    <frame tileid="2" duration="100"/>
+                    <frame tileid="3" duration="250"/>
+                </animation>
+            </tile>
+        </tileset>"##;
+
+    fn tmx(layers: &str) -> NTiledMap {
+        let source = format!(
+            r#"<map orientation="orthogonal" width="2" height="2" tilewidth="16" tileheight="8">
+                {}{}
+            </map>"#,
+            TSX_TILESET, layers
+        );
+        tmx_map(&source, Path::new("maps")).unwrap()
+    }
+
+    // GID клітинок у base64 зі стисненням Tiled
+    fn encode(gids: &[u32], compression: &str) -> String {
+        let bytes: Vec<u8> = gids.iter().flat_map(|gid| gid.to_le_bytes()).collect();
+        let bytes = match compression {
+            "zlib" => {
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&bytes).unwrap();
+                encoder.finish().unwrap()
+            }
+            "gzip" => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&bytes).unwrap();
+                encoder.finish().unwrap()
+            }
+            _ => bytes,
+        };
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    #[test]
+    fn tmx_csv_and_xml_layers() {
+        let map = tmx(r#"
+            <layer name="ground"><data encoding="csv">
+                1,2,
+                0,3
+            </data></layer>
+            <layer name="props"><data>
+                <tile gid="4"/><tile/><tile gid="1"/><tile gid="2"/>
+            </data></layer>"#);
+        assert_eq!(map.size, [2, 2]);
+        assert_eq!(map.tile_size, [16, 8]);
+        let names: Vec<&str> = map.layers.iter().map(|layer| layer.name.as_str()).collect();
+        assert_eq!(names, ["ground", "props"]);
+        assert_eq!(map.layers[0].data, [1, 2, 0, 3]);
+        assert_eq!(map.layers[1].data, [4, 0, 1, 2]);
+        assert!(map.layers.iter().all(|layer| layer.visible && layer.color == [1.0; 4]));
+    }
+
+    #[test]
+    fn tmx_base64_layers() {
+        let gids = [1, 0, 3, 4];
+        for compression in ["", "zlib", "gzip"] {
+            let attribute = if compression.is_empty() {
+                String::new()
+            } else {
+                format!(r#" compression="{}""#, compression)
+            };
+            let map = tmx(&format!(
+                r#"<layer name="ground"><data encoding="base64"{}>
+                    {}
+                </data></layer>"#,
+                attribute,
+                encode(&gids, compression)
+            ));
+            assert_eq!(map.layers[0].data, gids, "compression '{}'", compression);
+        }
+        assert!(decode_base64(&encode(&gids, ""), Some("zstd")).is_err());
+        assert!(decode_base64("not base64!", None).is_err());
+    }
+
+    #[test]
+    fn tmj_array_and_base64_layers() {
+        let source = format!(
+            r#"{{
+                "orientation": "orthogonal", "width": 2, "height": 2,
+                "tilewidth": 16, "tileheight": 16,
+                "tilesets": [{{
+                    "firstgid": 1, "name": "terrain", "image": "terrain.png",
+                    "imagewidth": 64, "imageheight": 64, "tilewidth": 16, "tileheight": 16
+                }}],
+                "layers": [
+                    {{ "type": "tilelayer", "name": "array", "data": [1, 0, 2, 3] }},
+                    {{ "type": "tilelayer", "name": "zlib", "encoding": "base64",
+                       "compression": "zlib", "data": "{}" }},
+                    {{ "type": "objectgroup", "name": "objects" }}
+                ]
+            }}"#,
+            encode(&[4, 3, 2, 1], "zlib")
+        );
+        let map = tmj_map(&source, Path::new("maps")).unwrap();
+        assert_eq!(map.layers.len(), 2);
+        assert_eq!(map.layers[0].data, [1, 0, 2, 3]);
+        assert_eq!(map.layers[1].data, [4, 3, 2, 1]);
+        assert_eq!(map.tilesets[0].image, Path::new("maps").join("terrain.png"));
+        assert_eq!(map.tilesets[0].image_size, [64, 64]);
+    }
+
+    #[test]
+    fn group_tint_and_visibility_are_inherited() {
+        let map = tmx(r##"
+            <group name="outer" opacity="0.5" tintcolor="#ff0000">
+                <group name="inner" visible="0">
+                    <layer name="hidden"><data encoding="csv">0,0,0,0</data></layer>
+                </group>
+                <layer name="tinted" opacity="0.5" tintcolor="#80ffffff">
+                    <data encoding="csv">0,0,0,0</data>
+                </layer>
+            </group>
+            <layer name="plain"><data encoding="csv">0,0,0,0</data></layer>"##);
+        let [hidden, tinted, plain] = &map.layers[..] else { panic!("expected three layers") };
+        assert!(!hidden.visible);
+        assert_eq!(hidden.color, [1.0, 0.0, 0.0, 0.5]);
+        assert!(tinted.visible);
+        let alpha = 0.5 * (128.0 / 255.0) * 0.5;
+        assert_eq!(tinted.color[..3], [1.0, 0.0, 0.0]);
+        assert!((tinted.color[3] - alpha).abs() < 1.0e-6);
+        assert_eq!(plain.color, [1.0; 4]);
+
+        // Те саме для JSON: групи всередині груп
+        let source = r##"{
+            "width": 1, "height": 1, "tilewidth": 8, "tileheight": 8, "tilesets": [],
+            "layers": [{ "type": "group", "visible": false, "opacity": 0.5, "layers": [
+                { "type": "group", "tintcolor": "#00ff00", "layers": [
+                    { "type": "tilelayer", "name": "nested", "data": [0] }
+                ]}
+            ]}]
+        }"##;
+        let map = tmj_map(source, Path::new("")).unwrap();
+        assert!(!map.layers[0].visible);
+        assert_eq!(map.layers[0].color, [0.0, 1.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn tileset_flags_and_animations() {
+        let map = tmx("");
+        let tileset = &map.tilesets[0];
+        assert_eq!(tileset.name, "terrain");
+        assert_eq!(tileset.image, Path::new("maps").join("terrain.png"));
+        assert_eq!(tileset.image_size, [64, 32]);
+        assert_eq!((tileset.tile_size, tileset.margin, tileset.spacing), ([16, 8], 0, 1));
+
+        let tile = |id: u32| tileset.tiles.iter().find(|tile| tile.0 == id).unwrap();
+        assert_eq!(tile(0).1, NTileFlags::SOLID);
+        assert_eq!(tile(1).1, NTileFlags::PLATFORM | NTileFlags(12));
+        assert_eq!(tile(2).1, NTileFlags::SOLID);
+        let frames =
+            [NTileFrame { tile: 2, duration: 0.1 }, NTileFrame { tile: 3, duration: 0.25 }];
+        assert_eq!(tile(2).2, frames);
+        assert!(tile(0).2.is_empty());
+    }
+
+    #[test]
+    fn flip_bits_survive_parsing() {
+        let flipped = 0x8000_0000u32 | 0x2000_0000 | 3;
+        let map = tmx(&format!(
+            r#"<layer name="ground"><data encoding="csv">{},0,0,{}</data></layer>"#,
+            flipped,
+            0x4000_0000u32 | 1
+        ));
+        let tile = NTile(map.layers[0].data[0]);
+        assert_eq!((tile.gid(), tile.flips()), (3, (true, false, true)));
+        let tile = NTile(map.layers[0].data[3]);
+        assert_eq!((tile.gid(), tile.flips()), (1, (false, true, false)));
+    }
+
+    #[test]
+    fn rejects_unsupported_maps() {
+        let map = |attributes: &str| {
+            let source = format!("<map {}/>", attributes);
+            match tmx_map(&source, Path::new("")) {
+                Err(error) => error,
+                Ok(_) => panic!("map with {} should be rejected", attributes),
+            }
+        };
+        assert_eq!(map(r#"orientation="isometric""#), "only orthogonal maps are supported");
+        assert_eq!(map(r#"infinite="1""#), "infinite maps are not supported");
+        assert!(tmx_map("<tileset/>", Path::new("")).is_err());
+    }
+}
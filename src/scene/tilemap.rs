@@ -0,0 +1,407 @@
+// Тайлова мапа сутності.
+// NTileset — текстура-атлас із сіткою тайлів однакового розміру, анімаціями тайлів
+// і прапорцями колізії. NTilemap — шари сітки однакового розміру; клітинка — NTile,
+// глобальний номер тайла з бітами віддзеркалення (як GID у Tiled): номери тайлсетів
+// ідуть підряд починаючи з first_gid, 0 — порожня клітинка.
+// Мапа лежить у площині XY сутності: клітинка (0,0) — лівий верхній кут,
+// стовпці йдуть уздовж +X, рядки — уздовж −Y.
+// Шари поділені на чанки TILE_CHUNK_SIZE x TILE_CHUNK_SIZE клітинок; set_tile дає чанку
+// нову ревізію, і NTilemapDrawSystem перебудовує GPU меш лише змінених чанків.
+// Анімації тайлів програє шейдер за часом кадру, тому вони меші не змінюють.
+
+use std::{collections::BTreeMap, ops::BitOr, sync::Arc};
+
+use crate::graphics::{
+    asset::{next_asset_id, NAssetId},
+    texture::NTexture,
+};
+
+// Сторона чанка в клітинках
+pub const TILE_CHUNK_SIZE: u32 = 16;
+
+/// Клітинка мапи: глобальний номер тайла і біти віддзеркалення у старших бітах
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NTile(pub u32);
+
+impl NTile {
+    pub const EMPTY: NTile = NTile(0);
+
+    const FLIP_HORIZONTAL: u32 = 0x8000_0000;
+    const FLIP_VERTICAL: u32 = 0x4000_0000;
+    const FLIP_DIAGONAL: u32 = 0x2000_0000;
+    // Старші 4 біти — прапорці (четвертий Tiled використовує для гексагональних мап)
+    const FLAGS: u32 = 0xF000_0000;
+
+    pub fn new(gid: u32) -> Self {
+        NTile(gid & !Self::FLAGS)
+    }
+
+    // Віддзеркалення: діагональне (транспонування) застосовується першим
+    #[allow(dead_code)]
+    pub fn flipped(self, horizontal: bool, vertical: bool, diagonal: bool) -> Self {
+        let mut bits = self.gid();
+        if horizontal {
+            bits |= Self::FLIP_HORIZONTAL;
+        }
+        if vertical {
+            bits |= Self::FLIP_VERTICAL;
+        }
+        if diagonal {
+            bits |= Self::FLIP_DIAGONAL;
+        }
+        NTile(bits)
+    }
+
+    #[inline]
+    pub fn gid(self) -> u32 {
+        self.0 & !Self::FLAGS
+    }
+
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.gid() == 0
+    }
+
+    // Біти віддзеркалення (горизонтальне, вертикальне, діагональне)
+    pub fn flips(self) -> (bool, bool, bool) {
+        (
+            self.0 & Self::FLIP_HORIZONTAL != 0,
+            self.0 & Self::FLIP_VERTICAL != 0,
+            self.0 & Self::FLIP_DIAGONAL != 0,
+        )
+    }
+}
+
+/// Прапорці колізії тайла (бітова маска)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NTileFlags(pub u32);
+
+#[allow(dead_code)]
+impl NTileFlags {
+    pub const NONE: NTileFlags = NTileFlags(0);
+    pub const SOLID: NTileFlags = NTileFlags(1);        // Непроходимый
+    pub const PLATFORM: NTileFlags = NTileFlags(2);     // Проходимый снизу (односторонний)
+    pub const HAZARD: NTileFlags = NTileFlags(4);       // Наносит урон
+    pub const WATER: NTileFlags = NTileFlags(8);        // Вода
+
+    #[inline]
+    pub fn contains(self, other: NTileFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for NTileFlags {
+    type Output = NTileFlags;
+
+    fn bitor(self, other: NTileFlags) -> NTileFlags {
+        NTileFlags(self.0 | other.0)
+    }
+}
+
+/// Кадр анімації тайла
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NTileFrame {
+    pub tile: u32,          // Локальный номер тайла в тайлсете
+    pub duration: f32,      // Длительность в секундах
+}
+
+/// Атлас тайлів: сітка tile_size з відступом margin від краю і spacing між тайлами
+#[derive(Debug)]
+pub struct NTileset {
+    id: NAssetId,                                   // Идентификатор ассета
+    pub name: String,                               // Имя тайлсета
+    pub texture: Arc<NTexture>,                     // Атлас
+    pub tile_size: [u32; 2],                        // Размер тайла в пикселях
+    pub margin: u32,                                // Отступ сетки от края атласа
+    pub spacing: u32,                               // Промежуток между тайлами
+    pub columns: u32,                               // Тайлов в строке атласа
+    pub tile_count: u32,                            // Всего тайлов
+    flags: Vec<NTileFlags>,                         // Флаги коллизии по тайлам
+    animations: BTreeMap<u32, Vec<NTileFrame>>,     // Анимации по тайлам
+}
+
+impl NTileset {
+    // Тайлсет на весь атлас без відступів
+    pub fn new(name: impl Into<String>, texture: Arc<NTexture>, tile_size: [u32; 2]) -> Self {
+        NTileset {
+            id: next_asset_id(),
+            name: name.into(),
+            texture,
+            tile_size,
+            margin: 0,
+            spacing: 0,
+            columns: 0,
+            tile_count: 0,
+            flags: Vec::new(),
+            animations: BTreeMap::new(),
+        }
+        .with_spacing(0, 0)
+    }
+
+    // Відступ сітки від краю та між тайлами; кількість тайлів рахується заново
+    pub fn with_spacing(mut self, margin: u32, spacing: u32) -> Self {
+        self.margin = margin;
+        self.spacing = spacing;
+        let [columns, rows] = [0, 1].map(|axis| {
+            let free = (self.texture.size[axis] + spacing).saturating_sub(margin * 2);
+            free / (self.tile_size[axis] + spacing).max(1)
+        });
+        self.columns = columns.max(1);
+        self.tile_count = columns * rows;
+        self.flags.resize(self.tile_count as usize, NTileFlags::NONE);
+        self
+    }
+
+    #[inline]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    pub fn flags(&self, tile: u32) -> NTileFlags {
+        self.flags.get(tile as usize).copied().unwrap_or_default()
+    }
+
+    pub fn set_flags(&mut self, tile: u32, flags: NTileFlags) {
+        if let Some(slot) = self.flags.get_mut(tile as usize) {
+            *slot = flags;
+        }
+    }
+
+    // Анімація тайла; порожній список кадрів прибирає анімацію
+    pub fn set_animation(&mut self, tile: u32, frames: Vec<NTileFrame>) {
+        if frames.is_empty() {
+            self.animations.remove(&tile);
+        } else {
+            self.animations.insert(tile, frames);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn animation(&self, tile: u32) -> Option<&[NTileFrame]> {
+        self.animations.get(&tile).map(Vec::as_slice)
+    }
+
+    // Таблиця анімацій для шейдера: перші tile_count записів — (початок, кількість кадрів)
+    // для кожного тайла (0 кадрів — без анімації), далі кадри (тайл, кінець кадру в мс
+    // від початку анімації)
+    pub fn animation_table(&self) -> Vec<[u32; 2]> {
+        let mut table = vec![[0, 0]; self.tile_count.max(1) as usize];
+        for (&tile, frames) in &self.animations {
+            if tile >= self.tile_count {
+                continue;
+            }
+            let start = table.len() as u32;
+            let mut end = 0;
+            for frame in frames {
+                end += ((frame.duration * 1000.0).round() as u32).max(1);
+                table.push([frame.tile.min(self.tile_count - 1), end]);
+            }
+            table[tile as usize] = [start, frames.len() as u32];
+        }
+        table
+    }
+}
+
+/// Тайлсет мапи з першим глобальним номером його тайлів
+#[derive(Clone, Debug)]
+pub struct NTilesetRef {
+    pub first_gid: u32,             // GID первого тайла
+    pub tileset: Arc<NTileset>,     // Тайлсет
+}
+
+/// Шар мапи
+#[derive(Clone, Debug)]
+pub struct NTileLayer {
+    pub name: String,           // Имя слоя
+    pub visible: bool,          // Рисуется ли слой
+    pub color: [f32; 4],        // Цвет (оттенок и прозрачность) слоя
+    tiles: Vec<NTile>,          // Клетки построчно
+    revisions: Vec<u64>,        // Ревизии чанков (меняются при изменении клеток)
+}
+
+impl NTileLayer {
+    // Ревізія чанка; унікальна серед усіх мап, тож клон мапи не плутає кеш мешів
+    #[inline]
+    pub fn revision(&self, chunk: usize) -> u64 {
+        self.revisions[chunk]
+    }
+}
+
+/// Тайлова мапа сутності
+#[derive(Clone, Debug)]
+pub struct NTilemap {
+    id: NAssetId,                       // Идентификатор (ключ кэша мешей чанков)
+    pub size: [u32; 2],                 // Размер в клетках
+    pub tile_size: [f32; 2],            // Размер клетки в единицах мира
+    pub color: [f32; 4],                // Цвет всей карты
+    pub tilesets: Vec<NTilesetRef>,     // Тайлсеты по возрастанию first_gid
+    pub layers: Vec<NTileLayer>,        // Слои снизу вверх
+}
+
+impl NTilemap {
+    pub fn new(size: [u32; 2], tile_size: [f32; 2]) -> Self {
+        NTilemap {
+            id: next_asset_id(),
+            size,
+            tile_size,
+            color: [1.0; 4],
+            tilesets: Vec::new(),
+            layers: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn id(&self) -> NAssetId {
+        self.id
+    }
+
+    // Додає тайлсет після вже доданих; повертає його first_gid
+    pub fn add_tileset(&mut self, tileset: Arc<NTileset>) -> u32 {
+        let first_gid =
+            self.tilesets.last().map_or(1, |last| last.first_gid + last.tileset.tile_count);
+        self.tilesets.push(NTilesetRef { first_gid, tileset });
+        first_gid
+    }
+
+    // Додає порожній шар поверх наявних; повертає його індекс
+    pub fn add_layer(&mut self, name: impl Into<String>) -> usize {
+        let chunks = self.chunk_count();
+        self.layers.push(NTileLayer {
+            name: name.into(),
+            visible: true,
+            color: [1.0; 4],
+            tiles: vec![NTile::EMPTY; (self.size[0] * self.size[1]) as usize],
+            revisions: (0..chunks[0] * chunks[1]).map(|_| next_asset_id()).collect(),
+        });
+        self.layers.len() - 1
+    }
+
+    // Кількість чанків по X і Y
+    pub fn chunk_count(&self) -> [u32; 2] {
+        self.size.map(|cells| cells.div_ceil(TILE_CHUNK_SIZE))
+    }
+
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        (x < self.size[0] && y < self.size[1]).then_some((y * self.size[0] + x) as usize)
+    }
+
+    fn chunk_index(&self, x: u32, y: u32) -> usize {
+        let columns = self.chunk_count()[0];
+        ((y / TILE_CHUNK_SIZE) * columns + x / TILE_CHUNK_SIZE) as usize
+    }
+
+    // Клітинка шару (поза мапою — порожня)
+    pub fn tile(&self, layer: usize, x: u32, y: u32) -> NTile {
+        self.index(x, y).map_or(NTile::EMPTY, |index| self.layers[layer].tiles[index])
+    }
+
+    // Змінює клітинку шару; чанк отримує нову ревізію, лише якщо клітинка змінилась
+    pub fn set_tile(&mut self, layer: usize, x: u32, y: u32, tile: NTile) {
+        let Some(index) = self.index(x, y) else { return };
+        let chunk = self.chunk_index(x, y);
+        let layer = &mut self.layers[layer];
+        if layer.tiles[index] != tile {
+            layer.tiles[index] = tile;
+            layer.revisions[chunk] = next_asset_id();
+        }
+    }
+
+    // Непорожні клітинки чанка шару: (x, y, клітинка)
+    pub fn chunk_tiles(
+        &self,
+        layer: usize,
+        chunk: usize,
+    ) -> impl Iterator<Item = (u32, u32, NTile)> + '_ {
+        let columns = self.chunk_count()[0];
+        let origin =
+            [chunk as u32 % columns, chunk as u32 / columns].map(|cell| cell * TILE_CHUNK_SIZE);
+        let [end_x, end_y] =
+            [0, 1].map(|axis| (origin[axis] + TILE_CHUNK_SIZE).min(self.size[axis]));
+        (origin[1]..end_y)
+            .flat_map(move |y| (origin[0]..end_x).map(move |x| (x, y)))
+            .map(move |(x, y)| (x, y, self.tile(layer, x, y)))
+            .filter(|(_, _, tile)| !tile.is_empty())
+    }
+
+    // Тайлсет і локальний номер тайла за глобальним номером
+    pub fn resolve(&self, gid: u32) -> Option<(usize, u32)> {
+        let index = self.tilesets.iter().rposition(|reference| reference.first_gid <= gid)?;
+        let local = gid - self.tilesets[index].first_gid;
+        (local < self.tilesets[index].tileset.tile_count).then_some((index, local))
+    }
+
+    // Прапорці колізії клітинки: об'єднання прапорців тайлів усіх шарів
+    #[allow(dead_code)]
+    pub fn flags(&self, x: u32, y: u32) -> NTileFlags {
+        (0..self.layers.len())
+            .filter_map(|layer| self.resolve(self.tile(layer, x, y).gid()))
+            .map(|(tileset, local)| self.tilesets[tileset].tileset.flags(local))
+            .fold(NTileFlags::NONE, BitOr::bitor)
+    }
+
+    // Клітинка під точкою в локальних координатах мапи (площина XY сутності)
+    #[allow(dead_code)]
+    pub fn cell_at(&self, point: [f32; 2]) -> Option<[u32; 2]> {
+        let x = (point[0] / self.tile_size[0]).floor();
+        let y = (-point[1] / self.tile_size[1]).floor();
+        let inside = x >= 0.0 && y >= 0.0 && x < self.size[0] as f32 && y < self.size[1] as f32;
+        inside.then_some([x as u32, y as u32])
+    }
+
+    // Прямокутник клітинки в локальних координатах: (min, max)
+    pub fn cell_rect(&self, x: u32, y: u32) -> ([f32; 2], [f32; 2]) {
+        let [width, height] = self.tile_size;
+        let min = [x as f32 * width, -((y + 1) as f32) * height];
+        (min, [min[0] + width, min[1] + height])
+    }
+
+    // Прямокутник усієї мапи в локальних координатах: (min, max)
+    pub fn local_rect(&self) -> ([f32; 2], [f32; 2]) {
+        let height = self.size[1] as f32 * self.tile_size[1];
+        ([0.0, -height], [self.size[0] as f32 * self.tile_size[0], 0.0])
+    }
+}
+
+// Процедурний тайлсет 16x16 пікселів: трава, земля, цегла і два кадри води.
+// Трава, земля і цегла тверді, вода анімована.
+pub fn demo_tileset() -> NTileset {
+    const TILE: u32 = 16;
+    const TILES: u32 = 5;
+    let data = (0..TILE * TILES * TILE)
+        .flat_map(|index| {
+            let (column, y) = (index % (TILE * TILES), index / (TILE * TILES));
+            let (tile, x) = (column / TILE, column % TILE);
+            // Псевдовипадковий шум для плям на землі й траві
+            let noise = (x * 7 + y * 13 + tile * 5) % 6 == 0;
+            match tile {
+                0 if y < 4 => if noise { [70, 150, 50, 255] } else { [90, 180, 60, 255] },
+                0 | 1 => if noise { [95, 60, 35, 255] } else { [125, 85, 50, 255] },
+                2 => {
+                    let offset = if (y / 4) % 2 == 0 { 0 } else { 4 };
+                    let mortar = y % 4 == 0 || (x + offset) % 8 == 0;
+                    if mortar { [170, 165, 155, 255] } else { [160, 70, 50, 255] }
+                }
+                _ => {
+                    let wave = (x + y * 2 + (tile - 3) * 4) % 8 < 2;
+                    if wave { [120, 180, 240, 210] } else { [50, 110, 200, 190] }
+                }
+            }
+        })
+        .collect();
+    let texture = NTexture::new("Demo tiles", [TILE * TILES, TILE], data);
+    let mut tileset = NTileset::new("Demo tiles", Arc::new(texture), [TILE, TILE]);
+    for tile in 0..3 {
+        tileset.set_flags(tile, NTileFlags::SOLID);
+    }
+    tileset.set_flags(3, NTileFlags::WATER);
+    tileset.set_animation(3, vec![
+        NTileFrame { tile: 3, duration: 0.4 },
+        NTileFrame { tile: 4, duration: 0.4 },
+    ]);
+    tileset
+}
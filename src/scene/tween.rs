@@ -0,0 +1,103 @@
+// Твіни — разові анімації властивостей з коду: "довести sprite.color сутності до
+// червоного за 0.3 с з quad_out". Твін створюється через NScene::tween і живе в сцені,
+// доки не добіжить до кінця; NScene::update_animations просуває його разом з іншими
+// анімаціями, уже після кліпів властивостей, тож твін перекриває їхні значення.
+// Початкове значення без `from` читається із сутності в момент старту (після затримки).
+// Повтори з yoyo йдуть туди-назад; сутність або компонент, що зникли, завершують твін.
+
+use crate::scene::{
+    easing::NEasing,
+    entity::{NEntity, NEntityId},
+    property::NPropertyPath,
+};
+
+/// Число повторів для нескінченного твіна
+pub const REPEAT_FOREVER: u32 = u32::MAX;
+
+/// Анімація однієї властивості сутності від початкового до кінцевого значення
+#[derive(Clone, Debug)]
+pub struct NTween {
+    pub entity: NEntityId,          // Анимируемая сущность
+    pub path: NPropertyPath,        // Свойство
+    pub from: Option<[f32; 4]>,     // Начальное значение (None — текущее на старте)
+    pub to: [f32; 4],               // Конечное значение
+    pub duration: f32,              // Длительность одного прохода в секундах
+    pub delay: f32,                 // Задержка перед стартом в секундах
+    pub easing: NEasing,            // Смягчение
+    pub repeat: u32,                // Дополнительные проходы (REPEAT_FOREVER — бесконечно)
+    pub yoyo: bool,                 // Каждый второй проход идёт обратно
+    elapsed: f32,                   // Прошло с создания
+    start: Option<[f32; 4]>,        // Начальное значение, зафиксированное на старте
+}
+
+impl NTween {
+    pub fn new(entity: NEntityId, path: NPropertyPath, to: [f32; 4], duration: f32) -> Self {
+        NTween {
+            entity,
+            path,
+            from: None,
+            to,
+            duration,
+            delay: 0.0,
+            easing: NEasing::Linear,
+            repeat: 0,
+            yoyo: false,
+            elapsed: 0.0,
+            start: None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn from(&mut self, value: &[f32]) -> &mut Self {
+        let mut from = [0.0; 4];
+        for (target, value) in from.iter_mut().zip(value) {
+            *target = *value;
+        }
+        self.from = Some(from);
+        self
+    }
+
+    pub fn ease(&mut self, easing: NEasing) -> &mut Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn delay(&mut self, delay: f32) -> &mut Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn repeat(&mut self, repeat: u32, yoyo: bool) -> &mut Self {
+        self.repeat = repeat;
+        self.yoyo = yoyo;
+        self
+    }
+
+    // Просуває твін на `delta_time` секунд і пише значення в сутність.
+    // true — твін завершено і його треба прибрати.
+    pub fn update(&mut self, delta_time: f32, entity: &mut NEntity) -> bool {
+        self.elapsed += delta_time;
+        let time = self.elapsed - self.delay;
+        if time < 0.0 {
+            return false;
+        }
+        let start = match self.start.or(self.from).or_else(|| self.path.get(entity)) {
+            Some(start) => *self.start.insert(start),
+            None => return true,
+        };
+
+        let passes = time / self.duration.max(f32::EPSILON);
+        let finished = self.repeat != REPEAT_FOREVER && passes >= self.repeat as f32 + 1.0;
+        let (pass, t) = if finished {
+            (self.repeat, 1.0)
+        } else {
+            let pass = passes.floor();
+            (pass as u32, passes - pass)
+        };
+        let t = if self.yoyo && pass % 2 == 1 { 1.0 - t } else { t };
+        let t = self.easing.apply(t);
+        let value = [0, 1, 2, 3].map(|axis| start[axis] + (self.to[axis] - start[axis]) * t);
+        self.path.set(entity, value);
+        finished
+    }
+}
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 
 use egui_dock::tree;
@@ -10,27 +12,46 @@ use egui_tiles::Tile;
 use egui_winit_vulkano::Gui;
 use vulkano::{
     format::Format,
-    image::view::ImageView,
+    image::{sampler::SamplerCreateInfo, view::ImageView},
 };
 
 use crate::ui::tiles::PaneTrait;
 use crate::ui::tiles::{TileUI, show_tiles_ui};
+use crate::ui::selection::NSharedSelection;
+use crate::ui::windows::details::NSharedDetails;
+use crate::ui::windows::hierarchy::NSharedHierarchy;
+use crate::ui::windows::viewport::{NSharedViewport, NViewportState};
 use egui_winit::winit::event_loop::ActiveEventLoop;
 use egui_winit_vulkano::{GuiConfig};
 use crate::core::App;
+use crate::graphics::settings::{
+    NDepthFormat, NRenderSettings, NViewMode, MAX_OUTLINE_THICKNESS, MSAA_SAMPLE_OPTIONS,
+};
+use crate::graphics::stats::NRenderStats;
+use crate::scene::camera::NBackground;
 
 
 // Структура GuiState управляет состоянием пользовательского интерфейса
 pub struct GuiSystem {
     pub tile_ui: TileUI,
-    pub gui: Gui
+    pub gui: Gui,
+    pub render_settings: NRenderSettings,
+    pub dump_render_graph: bool,    // Запрос дампа графа кадра
+    pub render_graph: Option<String>, // Дамп графа кадра для окна Render graph
+    pub render_stats: NRenderStats, // Статистика последнего кадра рендерера
+    pub viewport: NSharedViewport,  // Состояние вьюпорта (текстура сцены, размер, подписи)
+    pub shader_errors: Vec<String>, // Ошибки компиляции шейдеров и пайплайнов
+    pub import_errors: Vec<String>, // Ошибки импорта перетащенных файлов
+    pub details: NSharedDetails,    // Компоненты сущности в панели Details
+    pub hierarchy: NSharedHierarchy, // Список сущностей для панели Hierarchy
+    pub selection: NSharedSelection, // Выделенные сущности (вьюпорт, Hierarchy, Details)
 }
 
 impl GuiSystem {
     // Створює GUI-ідентифікатори для зображень та налаштовує тайлову систему
     pub fn new( event_loop:& ActiveEventLoop , app: &mut App) -> GuiSystem {
 
-        let gui = {
+        let mut gui = {
             let renderer = app.windows.get_primary_renderer_mut().unwrap();
             Gui::new(
                 event_loop,
@@ -41,11 +62,45 @@ impl GuiSystem {
             )
         };
 
-        let tile_ui = TileUI::new();
+        // Зображення сцени показується у вьюпорті як текстура egui
+        let scene_texture = gui.register_user_image_view(
+            app.scene_image.clone(),
+            SamplerCreateInfo::default(),
+        );
+        let extent = app.scene_image.image().extent();
+        let viewport = Rc::new(RefCell::new(NViewportState {
+            texture: Some(scene_texture),
+            size: [extent[0], extent[1]],
+            pick: None,
+            hover: None,
+            view_mode: NViewMode::Lit,
+            background: NBackground::default(),
+        }));
+
+        let details = NSharedDetails::default();
+        let hierarchy = NSharedHierarchy::default();
+        let selection = NSharedSelection::default();
+        let tile_ui = TileUI::new(
+            viewport.clone(),
+            app.renderer.render_pipeline.timings().clone(),
+            details.clone(),
+            hierarchy.clone(),
+            selection.clone(),
+        );
 
         GuiSystem {
             tile_ui,
-            gui
+            gui,
+            render_settings: NRenderSettings::default(),
+            dump_render_graph: false,
+            render_graph: None,
+            render_stats: NRenderStats::default(),
+            viewport,
+            shader_errors: Vec::new(),
+            import_errors: Vec::new(),
+            details,
+            hierarchy,
+            selection,
         }
     }
 
@@ -82,6 +137,68 @@ impl GuiSystem {
                         ui.checkbox(visible, name.clone());
                     }
                 });
+
+                ui.menu_button("Settings", |ui| {
+                    // Кількість семплів обмежується можливостями пристрою вже в рендерері
+                    let msaa = &mut self.render_settings.msaa_samples;
+                    egui::ComboBox::from_label("MSAA")
+                        .selected_text(msaa_label(*msaa))
+                        .show_ui(ui, |ui| {
+                            for samples in MSAA_SAMPLE_OPTIONS {
+                                ui.selectable_value(msaa, samples, msaa_label(samples));
+                            }
+                        });
+                    ui.checkbox(&mut self.render_settings.debug_draw, "Debug draw");
+                    ui.checkbox(&mut self.render_settings.frustum_culling, "Frustum culling");
+                    // Непідтримуваний пристроєм формат рендерер замінить найближчим
+                    let depth = &mut self.render_settings.depth_format;
+                    egui::ComboBox::from_label("Depth format")
+                        .selected_text(depth.label())
+                        .show_ui(ui, |ui| {
+                            for format in NDepthFormat::ALL {
+                                ui.selectable_value(depth, format, format.label());
+                            }
+                        });
+                    ui.separator();
+                    let outline = &mut self.render_settings.outline;
+                    ui.horizontal(|ui| {
+                        ui.color_edit_button_rgba_unmultiplied(&mut outline.selected_color);
+                        ui.label("Selection outline");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.color_edit_button_rgba_unmultiplied(&mut outline.hovered_color);
+                        ui.label("Hover outline");
+                    });
+                    ui.add(
+                        egui::Slider::new(&mut outline.thickness, 1..=MAX_OUTLINE_THICKNESS)
+                            .text("Outline thickness"),
+                    );
+                    ui.separator();
+                    if ui.button("Dump render graph").clicked() {
+                        self.dump_render_graph = true;
+                        ui.close_menu();
+                    }
+                });
+
+                ui.menu_button("Stats", |ui| {
+                    let stats = &self.render_stats;
+                    ui.label(format!("Draw calls: {}", stats.draw_calls()));
+                    ui.label(format!("Instances: {}", stats.instances()));
+                    ui.label(format!("Visible: {} (culled {})", stats.visible, stats.culled));
+                    ui.separator();
+                    egui::Grid::new("render_batches").striped(true).show(ui, |ui| {
+                        ui.strong("System");
+                        ui.strong("Batch");
+                        ui.strong("Instances");
+                        ui.end_row();
+                        for batch in &stats.batches {
+                            ui.label(batch.system);
+                            ui.label(&batch.name);
+                            ui.label(batch.instances.to_string());
+                            ui.end_row();
+                        }
+                    });
+                });
             });
         });
 
@@ -98,11 +215,60 @@ impl GuiSystem {
             }
         }
 
+        // Помилки шейдерів видно, поки їх не виправлять; працює останній вдалий пайплайн
+        if !self.shader_errors.is_empty() {
+            egui::Window::new("Shader errors").show(&egui_context, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for error in &self.shader_errors {
+                        let text = egui::RichText::new(error).monospace();
+                        ui.colored_label(egui::Color32::LIGHT_RED, text);
+                        ui.separator();
+                    }
+                });
+            });
+        }
+
+        // Помилки імпорту перетягнутих файлів — до закриття вікна
+        if !self.import_errors.is_empty() {
+            let mut open = true;
+            egui::Window::new("Import errors").open(&mut open).show(&egui_context, |ui| {
+                for error in &self.import_errors {
+                    ui.colored_label(egui::Color32::LIGHT_RED, error);
+                }
+            });
+            if !open {
+                self.import_errors.clear();
+            }
+        }
+
+        // Дамп графа кадра лишається у вікні до закриття; текст можна скопіювати
+        if let Some(graph) = &self.render_graph {
+            let mut open = true;
+            egui::Window::new("Render graph").open(&mut open).show(&egui_context, |ui| {
+                if ui.button("Copy to clipboard").clicked() {
+                    ui.ctx().copy_text(graph.clone());
+                }
+                ui.separator();
+                egui::ScrollArea::both().show(ui, |ui| {
+                    ui.label(egui::RichText::new(graph).monospace());
+                });
+            });
+            if !open {
+                self.render_graph = None;
+            }
+        }
+
         update_tiles_visibility( &mut self.tile_ui, &egui_context);
         show_tiles_ui(&egui_context, &mut self.tile_ui);
+        // Режим перегляду обирається у вьюпорті, а до рендерера йде з налаштуваннями
+        self.render_settings.view_mode = self.viewport.borrow().view_mode;
     }
 }
 
+fn msaa_label(samples: u32) -> String {
+    if samples <= 1 { "Off".to_owned() } else { format!("{}x", samples) }
+}
+
 fn update_tiles_visibility( tile_ui: &mut TileUI, ui : &Context) {
     let pane_ids: Vec<egui_tiles::TileId> = tile_ui.tree.tiles
         .iter()
@@ -2,4 +2,5 @@ pub mod gui;
 pub mod widgets;
 pub mod tiles;
 pub mod dock;
-pub mod windows;
\ No newline at end of file
+pub mod windows;
+pub mod selection;
\ No newline at end of file
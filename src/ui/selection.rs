@@ -0,0 +1,86 @@
+// Виділення сутностей редактора — спільний ресурс панелей.
+// Вьюпорт (клік і рамка) та Hierarchy змінюють виділення, Hierarchy і Details
+// його показують. Останню додану сутність вважаємо основною: її компоненти
+// відкриваються в Details.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use egui_winit::egui::Modifiers;
+
+use crate::scene::entity::NEntityId;
+
+/// Як клік або рамка змінюють поточне виділення
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NSelectMode {
+    Replace,    // Заменить выделение
+    Add,        // Добавить к выделению (Shift)
+    Toggle,     // Переключить выделение (Ctrl / Cmd)
+}
+
+impl NSelectMode {
+    pub fn from_modifiers(modifiers: Modifiers) -> Self {
+        if modifiers.command {
+            NSelectMode::Toggle
+        } else if modifiers.shift {
+            NSelectMode::Add
+        } else {
+            NSelectMode::Replace
+        }
+    }
+}
+
+/// Виділені сутності в порядку виділення
+#[derive(Clone, Debug, Default)]
+pub struct NSelection {
+    entities: Vec<NEntityId>,   // Выделенные сущности, последняя — основная
+}
+
+pub type NSharedSelection = Rc<RefCell<NSelection>>;
+
+impl NSelection {
+    pub fn entities(&self) -> &[NEntityId] {
+        &self.entities
+    }
+
+    // Основна сутність (остання виділена)
+    pub fn primary(&self) -> Option<NEntityId> {
+        self.entities.last().copied()
+    }
+
+    pub fn contains(&self, entity: NEntityId) -> bool {
+        self.entities.contains(&entity)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    // Застосовує результат кліку або рамки. Replace з порожнім набором
+    // (клік повз сутності) знімає виділення.
+    pub fn select(&mut self, entities: impl IntoIterator<Item = NEntityId>, mode: NSelectMode) {
+        if mode == NSelectMode::Replace {
+            self.entities.clear();
+        }
+        for entity in entities {
+            let position = self.entities.iter().position(|&selected| selected == entity);
+            match (mode, position) {
+                (NSelectMode::Toggle, Some(position)) => {
+                    self.entities.remove(position);
+                }
+                (_, Some(_)) => {}
+                (_, None) => self.entities.push(entity),
+            }
+        }
+    }
+
+    // Прибирає сутності, яких більше немає в сцені
+    pub fn retain(&mut self, keep: impl Fn(NEntityId) -> bool) {
+        self.entities.retain(|&entity| keep(entity));
+    }
+}
@@ -7,9 +7,12 @@ use std::sync::Arc;
 use egui::{CursorIcon};
 
 use crate::ui::windows::{content_browser::ContentBrowser,
-                            details::Details, 
-                            hierarchy::Hierarchy, 
-                            viewport::Viewport};
+                            details::{Details, NSharedDetails}, 
+                            hierarchy::{Hierarchy, NSharedHierarchy}, 
+                            profiler::Profiler,
+                            viewport::{NSharedViewport, Viewport}};
+use crate::graphics::profiler::NSharedTimings;
+use crate::ui::selection::NSharedSelection;
 
 #[derive(Debug, Clone)]
 pub struct BasePane {
@@ -85,7 +88,13 @@ pub struct TileUI {
 }
 
 impl TileUI {
-    pub fn new() -> Self {
+    pub fn new(
+        viewport: NSharedViewport,
+        timings: NSharedTimings,
+        details: NSharedDetails,
+        hierarchy: NSharedHierarchy,
+        selection: NSharedSelection,
+    ) -> Self {
         let mut tiles: Tiles<Pane> = Tiles::default();
         let mut next_pane_nr = 0;
 
@@ -97,16 +106,24 @@ impl TileUI {
         next_pane_nr += 1;
         tabs.push(tiles.insert_pane(horizontal_panes));
 
-        let horizontal_panes = Box::new(Details::new(next_pane_nr, "Details".into()));
+        let horizontal_panes = Box::new(
+            Details::new(next_pane_nr, "Details".into(), details, selection.clone()),
+        );
         next_pane_nr += 1;
         tabs.push(tiles.insert_pane(horizontal_panes));
 
         // Add a single pane as a tab
-        let horizontal_panes = Box::new(Viewport::new(next_pane_nr, "Viewport".into()));
+        let horizontal_panes = Box::new(Viewport::new(next_pane_nr, "Viewport".into(), viewport));
+        next_pane_nr += 1;
+        tabs.push(tiles.insert_pane(horizontal_panes));
+
+        let horizontal_panes = Box::new(
+            Hierarchy::new(next_pane_nr, "Hierarchy".into(), hierarchy, selection),
+        );
         next_pane_nr += 1;
         tabs.push(tiles.insert_pane(horizontal_panes));
 
-        let horizontal_panes = Box::new(Hierarchy::new(next_pane_nr, "Hierarchy".into()));
+        let horizontal_panes = Box::new(Profiler::new(next_pane_nr, "Profiler".into(), timings));
         next_pane_nr += 1;
         tabs.push(tiles.insert_pane(horizontal_panes));
 
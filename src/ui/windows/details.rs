@@ -1,28 +1,76 @@
-use egui_winit::egui::{Ui};
+use std::cell::RefCell;
+use std::rc::Rc;
 
+use egui_winit::egui::{self, Color32, Pos2, Rgba, Stroke, Ui};
+
+use crate::scene::entity::NEntityId;
+use crate::scene::particles::{NParticleBlend, NParticleBurst, NParticleEmitter};
+use crate::ui::selection::NSharedSelection;
 use crate::ui::tiles::*;
 
-#[derive(Clone, Debug)] 
+/// Стан панелі Details, спільний для панелі та App:
+/// App кладе сюди копію компонентів основної виділеної сутності, панель редагує її,
+/// а App після малювання GUI повертає змінені компоненти в сцену.
+#[derive(Debug, Default)]
+pub struct NDetailsState {
+    pub entity: Option<NEntityId>,              // Сущность, показанная в панели
+    pub name: String,                           // Имя сущности
+    pub particles: Option<NParticleEmitter>,    // Копия эмиттера частиц
+    pub changed: bool,                          // Компоненты изменены в панели
+}
+
+pub type NSharedDetails = Rc<RefCell<NDetailsState>>;
+
+#[derive(Clone, Debug)]
 pub struct Details {
     pub base: BasePane,
-
+    state: NSharedDetails,
+    selection: NSharedSelection,
 }
 
 impl Details{
-    pub fn new(id: usize, name: String) -> Self {
+    pub fn new(
+        id: usize,
+        name: String,
+        state: NSharedDetails,
+        selection: NSharedSelection,
+    ) -> Self {
         Details {
             base: BasePane {
                 id,
                 name,
                 visible: true,
             },
+            state,
+            selection,
         }
-    }    
+    }
 }
 
 impl PaneTrait  for  Details {
     fn render(&mut self, ui: &mut Ui) {
-        ui.label(format!("BasePane: {}", self.base.name));
+        let mut state = self.state.borrow_mut();
+        if state.entity.is_none() {
+            ui.label("No entity selected");
+            return;
+        }
+        ui.heading(&state.name);
+        let selected = self.selection.borrow().len();
+        if selected > 1 {
+            ui.weak(format!("{} entities selected, showing the last one", selected));
+        }
+
+        let NDetailsState { particles, changed, .. } = &mut *state;
+        if let Some(emitter) = particles {
+            egui::CollapsingHeader::new("Particle emitter").default_open(true).show(ui, |ui| {
+                emitter_preview(ui, emitter);
+                if ui.button("Restart").clicked() {
+                    emitter.restart();
+                    *changed = true;
+                }
+                *changed |= emitter_ui(ui, emitter);
+            });
+        }
     }
 
     fn get_base_mut(&mut self) -> &mut BasePane {
@@ -38,4 +86,264 @@ impl CloneablePane for Details {
     fn clone_box(&self) -> Box<dyn PaneTrait> {
         Box::new(self.clone())
     }
-}
\ No newline at end of file
+}
+
+// Лінійний RGBA емітера -> колір egui
+fn linear_color(color: [f32; 4]) -> Color32 {
+    Rgba::from_rgba_unmultiplied(color[0], color[1], color[2], color[3].clamp(0.0, 1.0)).into()
+}
+
+// Прев'ю емітера: градієнт кольору, крива розміру та одна частинка,
+// що програє своє життя по колу (колір, розмір і кадр анімації)
+fn emitter_preview(ui: &mut Ui, emitter: &NParticleEmitter) {
+    let (rect, _) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), 96.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, Color32::from_gray(16));
+
+    let curves =
+        egui::Rect::from_min_max(rect.min, egui::pos2(rect.max.x - rect.height(), rect.max.y));
+    let steps = 32;
+    let gradient_height = 16.0;
+    for step in 0..steps {
+        let t = step as f32 / (steps - 1) as f32;
+        let x0 = curves.min.x + curves.width() * step as f32 / steps as f32;
+        let x1 = curves.min.x + curves.width() * (step + 1) as f32 / steps as f32;
+        painter.rect_filled(
+            egui::Rect::from_min_max(
+                egui::pos2(x0, curves.max.y - gradient_height),
+                egui::pos2(x1, curves.max.y),
+            ),
+            0.0,
+            linear_color(emitter.color_over_lifetime.sample(t)),
+        );
+    }
+
+    // Крива розміру, нормована на максимум
+    let sizes: Vec<f32> = (0..steps)
+        .map(|step| emitter.size_over_lifetime.sample(step as f32 / (steps - 1) as f32))
+        .collect();
+    let max_size = sizes.iter().copied().fold(1.0e-3, f32::max);
+    let plot_height = curves.height() - gradient_height - 8.0;
+    let points: Vec<Pos2> = sizes
+        .iter()
+        .enumerate()
+        .map(|(step, size)| {
+            egui::pos2(
+                curves.min.x + curves.width() * step as f32 / (steps - 1) as f32,
+                curves.min.y + 4.0 + plot_height * (1.0 - size / max_size),
+            )
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, Stroke::new(1.5, Color32::LIGHT_BLUE)));
+
+    // Частинка з середнім часом життя
+    let lifetime = ((emitter.lifetime[0] + emitter.lifetime[1]) * 0.5).max(0.05);
+    let t = (ui.input(|input| input.time) as f32 % lifetime) / lifetime;
+    let marker = curves.min.x + curves.width() * t;
+    painter.line_segment(
+        [egui::pos2(marker, curves.min.y), egui::pos2(marker, curves.max.y)],
+        Stroke::new(1.0, Color32::WHITE),
+    );
+    let particle = egui::Rect::from_min_max(egui::pos2(curves.max.x, rect.min.y), rect.max);
+    let radius = particle.height() * 0.45 * emitter.size_over_lifetime.sample(t) / max_size;
+    painter.circle_filled(
+        particle.center(),
+        radius.max(1.0),
+        linear_color(emitter.color_over_lifetime.sample(t)),
+    );
+    if emitter.sheet[0] * emitter.sheet[1] > 1 {
+        painter.text(
+            particle.left_top() + egui::vec2(4.0, 4.0),
+            egui::Align2::LEFT_TOP,
+            format!("frame {}", emitter.sheet_frame(t)),
+            egui::FontId::monospace(10.0),
+            Color32::GRAY,
+        );
+    }
+    ui.ctx().request_repaint();
+}
+
+// Редактор параметрів емітера; повертає true, якщо щось змінилося
+fn emitter_ui(ui: &mut Ui, emitter: &mut NParticleEmitter) -> bool {
+    let mut changed = false;
+    egui::Grid::new("particle_emitter").num_columns(2).striped(true).show(ui, |ui| {
+        ui.label("Max particles");
+        changed |= ui
+            .add(egui::DragValue::new(&mut emitter.max_particles).range(1..=65536))
+            .changed();
+        ui.end_row();
+
+        ui.label("Rate");
+        changed |= ui
+            .add(egui::DragValue::new(&mut emitter.rate).speed(1.0).range(0.0..=10000.0))
+            .changed();
+        ui.end_row();
+
+        ui.label("Duration");
+        ui.horizontal(|ui| {
+            changed |= ui
+                .add(egui::DragValue::new(&mut emitter.duration).speed(0.1).range(0.01..=600.0))
+                .changed();
+            changed |= ui.checkbox(&mut emitter.looping, "Looping").changed();
+        });
+        ui.end_row();
+
+        ui.label("Lifetime");
+        changed |= range_ui(ui, &mut emitter.lifetime, 0.01);
+        ui.end_row();
+
+        ui.label("Speed");
+        changed |= range_ui(ui, &mut emitter.speed, 0.0);
+        ui.end_row();
+
+        ui.label("Direction");
+        changed |= vector_ui(ui, &mut emitter.direction);
+        ui.end_row();
+
+        ui.label("Spread");
+        changed |=
+            ui.add(egui::Slider::new(&mut emitter.spread, 0.0..=180.0).suffix("°")).changed();
+        ui.end_row();
+
+        ui.label("Radius");
+        changed |= ui
+            .add(egui::DragValue::new(&mut emitter.radius).speed(0.01).range(0.0..=100.0))
+            .changed();
+        ui.end_row();
+
+        ui.label("Gravity");
+        changed |= vector_ui(ui, &mut emitter.gravity);
+        ui.end_row();
+
+        ui.label("Size");
+        changed |= ui
+            .add(egui::DragValue::new(&mut emitter.size).speed(0.01).range(0.0..=100.0))
+            .changed();
+        ui.end_row();
+
+        ui.label("Texture sheet");
+        ui.horizontal(|ui| {
+            let sheet = &mut emitter.sheet;
+            changed |= ui.add(egui::DragValue::new(&mut sheet[0]).range(1..=64)).changed();
+            ui.label("x");
+            changed |= ui.add(egui::DragValue::new(&mut sheet[1]).range(1..=64)).changed();
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut emitter.sheet_cycles)
+                        .speed(0.1)
+                        .range(0.0..=100.0)
+                        .prefix("cycles "),
+                )
+                .changed();
+        });
+        ui.end_row();
+
+        ui.label("Blending");
+        egui::ComboBox::from_id_salt("particle_blend")
+            .selected_text(format!("{:?}", emitter.blend))
+            .show_ui(ui, |ui| {
+                for blend in [NParticleBlend::Alpha, NParticleBlend::Additive] {
+                    changed |= ui
+                        .selectable_value(&mut emitter.blend, blend, format!("{:?}", blend))
+                        .changed();
+                }
+            });
+        ui.end_row();
+    });
+
+    egui::CollapsingHeader::new("Bursts").show(ui, |ui| {
+        let mut remove = None;
+        for (index, burst) in emitter.bursts.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                let time = egui::DragValue::new(&mut burst.time).speed(0.05).range(0.0..=600.0);
+                changed |= ui.add(time.prefix("t ")).changed();
+                let count = egui::DragValue::new(&mut burst.count).range(0..=65536);
+                changed |= ui.add(count).changed();
+                if ui.small_button("❌").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove {
+            emitter.bursts.remove(index);
+            changed = true;
+        }
+        if ui.button("Add burst").clicked() {
+            emitter.bursts.push(NParticleBurst { time: 0.0, count: 50 });
+            changed = true;
+        }
+    });
+
+    egui::CollapsingHeader::new("Color over lifetime").show(ui, |ui| {
+        let keys = &mut emitter.color_over_lifetime.keys;
+        let mut remove = None;
+        for (index, (time, color)) in keys.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                changed |= ui.add(egui::Slider::new(time, 0.0..=1.0)).changed();
+                changed |= ui.color_edit_button_rgba_unmultiplied(color).changed();
+                if ui.small_button("❌").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        changed |= edit_keys(ui, keys, remove, [1.0; 4]);
+    });
+
+    egui::CollapsingHeader::new("Size over lifetime").show(ui, |ui| {
+        let keys = &mut emitter.size_over_lifetime.keys;
+        let mut remove = None;
+        for (index, (time, size)) in keys.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                changed |= ui.add(egui::Slider::new(time, 0.0..=1.0)).changed();
+                changed |=
+                    ui.add(egui::DragValue::new(size).speed(0.01).range(0.0..=100.0)).changed();
+                if ui.small_button("❌").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        changed |= edit_keys(ui, keys, remove, 1.0);
+    });
+    changed
+}
+
+// Видалення та додавання ключа кривої; ключі лишаються відсортованими за часом
+fn edit_keys<T: Copy>(
+    ui: &mut Ui,
+    keys: &mut Vec<(f32, T)>,
+    remove: Option<usize>,
+    value: T,
+) -> bool {
+    let mut changed = false;
+    if let Some(index) = remove {
+        keys.remove(index);
+        changed = true;
+    }
+    if ui.button("Add key").clicked() {
+        keys.push((1.0, keys.last().map_or(value, |key| key.1)));
+        changed = true;
+    }
+    keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+    changed
+}
+
+fn range_ui(ui: &mut Ui, range: &mut [f32; 2], min: f32) -> bool {
+    let [low, high] = *range;
+    ui.horizontal(|ui| {
+        let low = ui.add(egui::DragValue::new(&mut range[0]).speed(0.05).range(min..=high));
+        let high = ui.add(egui::DragValue::new(&mut range[1]).speed(0.05).range(low..=1000.0));
+        low.changed() || high.changed()
+    })
+    .inner
+}
+
+fn vector_ui(ui: &mut Ui, vector: &mut cgmath::Vector3<f32>) -> bool {
+    ui.horizontal(|ui| {
+        let x = ui.add(egui::DragValue::new(&mut vector.x).speed(0.05).prefix("x "));
+        let y = ui.add(egui::DragValue::new(&mut vector.y).speed(0.05).prefix("y "));
+        let z = ui.add(egui::DragValue::new(&mut vector.z).speed(0.05).prefix("z "));
+        x.changed() || y.changed() || z.changed()
+    })
+    .inner
+}
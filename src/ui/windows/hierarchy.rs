@@ -1,28 +1,67 @@
-use egui_winit::egui::{Ui};
+use std::cell::RefCell;
+use std::rc::Rc;
 
+use egui_winit::egui::{self, Ui};
+
+use crate::scene::entity::NEntityId;
+use crate::ui::selection::{NSelectMode, NSharedSelection};
 use crate::ui::tiles::*;
 
-#[derive(Clone, Debug)] 
+/// Стан панелі Hierarchy, спільний для панелі та App:
+/// App щокадру кладе сюди список сутностей сцени, панель показує його
+/// та змінює спільне виділення.
+#[derive(Debug, Default)]
+pub struct NHierarchyState {
+    pub entities: Vec<(NEntityId, String)>,     // Сущности сцены и их имена
+}
+
+pub type NSharedHierarchy = Rc<RefCell<NHierarchyState>>;
+
+#[derive(Clone, Debug)]
 pub struct Hierarchy {
     pub base: BasePane,
-
+    state: NSharedHierarchy,
+    selection: NSharedSelection,
 }
 
 impl Hierarchy{
-    pub fn new(id: usize, name: String) -> Self {
+    pub fn new(
+        id: usize,
+        name: String,
+        state: NSharedHierarchy,
+        selection: NSharedSelection,
+    ) -> Self {
         Hierarchy {
             base: BasePane {
                 id,
                 name,
                 visible: true,
             },
+            state,
+            selection,
         }
-    }    
+    }
 }
 
 impl PaneTrait  for  Hierarchy {
     fn render(&mut self, ui: &mut Ui) {
-        ui.label(format!("BasePane: {}", self.base.name));
+        let state = self.state.borrow();
+        let mut selection = self.selection.borrow_mut();
+        let mode = NSelectMode::from_modifiers(ui.input(|input| input.modifiers));
+        // Рядки однакової висоти, тож малюються лише видимі
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::vertical().auto_shrink(false).show_rows(
+            ui,
+            row_height,
+            state.entities.len(),
+            |ui, rows| {
+                for (id, name) in &state.entities[rows] {
+                    if ui.selectable_label(selection.contains(*id), name).clicked() {
+                        selection.select([*id], mode);
+                    }
+                }
+            },
+        );
     }
 
     fn get_base_mut(&mut self) -> &mut BasePane {
@@ -39,4 +78,4 @@ impl CloneablePane for Hierarchy {
     fn clone_box(&self) -> Box<dyn PaneTrait> {
         Box::new(self.clone())
     }
-}
\ No newline at end of file
+}
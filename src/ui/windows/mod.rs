@@ -1,4 +1,5 @@
 pub mod content_browser;
 pub mod details;
 pub mod hierarchy;
-pub mod viewport;
\ No newline at end of file
+pub mod viewport;
+pub mod profiler;
\ No newline at end of file
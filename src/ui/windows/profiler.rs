@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use egui_winit::egui::{self, Color32, Pos2, Stroke, Ui};
+
+use crate::graphics::profiler::{NFrameTimings, NSharedTimings};
+use crate::ui::tiles::*;
+
+// Скільки останніх кадрів усереднюється в таблиці
+const AVERAGE_FRAMES: usize = 60;
+const CPU_COLOR: Color32 = Color32::from_rgb(90, 170, 255);
+const GPU_COLOR: Color32 = Color32::from_rgb(255, 150, 60);
+
+#[derive(Clone, Debug)] 
+pub struct Profiler {
+    pub base: BasePane,
+    timings: NSharedTimings,
+
+}
+
+impl Profiler{
+    pub fn new(id: usize, name: String, timings: NSharedTimings) -> Self {
+        Profiler {
+            base: BasePane {
+                id,
+                name,
+                visible: true,
+            },
+            timings,
+        }
+    }    
+}
+
+impl PaneTrait  for  Profiler {
+    fn render(&mut self, ui: &mut Ui) {
+        let history = self.timings.lock().unwrap();
+        let Some(last) = history.frames.back() else {
+            ui.label("No frames recorded yet");
+            return;
+        };
+        ui.label(format!(
+            "Frame {:.2} ms | CPU record {:.2} ms | GPU {}",
+            last.frame_ms,
+            last.cpu_ms(),
+            format_ms(last.gpu_ms()),
+        ));
+
+        // Графіки CPU та GPU часу кадру поруч
+        let cpu: Vec<f32> = history.frames.iter().map(NFrameTimings::cpu_ms).collect();
+        let gpu: Vec<f32> =
+            history.frames.iter().map(|frame| frame.gpu_ms().unwrap_or(0.0)).collect();
+        let frame: Vec<f32> = history.frames.iter().map(|frame| frame.frame_ms).collect();
+        ui.columns(2, |columns| {
+            draw_graph(&mut columns[0], "CPU", &[(&frame, Color32::GRAY), (&cpu, CPU_COLOR)]);
+            draw_graph(&mut columns[1], "GPU", &[(&gpu, GPU_COLOR)]);
+        });
+
+        // Середні значення по scope-ах за останні кадри
+        let mut averages: BTreeMap<(usize, String), (u32, f32, f32, u32, u32)> = BTreeMap::new();
+        let recent = history.frames.iter().rev().take(AVERAGE_FRAMES);
+        for frame in recent {
+            for (order, scope) in frame.scopes.iter().enumerate() {
+                let entry = averages
+                    .entry((order, scope.name.clone()))
+                    .or_insert((scope.depth, 0.0, 0.0, 0, 0));
+                entry.1 += scope.cpu_ms;
+                entry.3 += 1;
+                if let Some(gpu_ms) = scope.gpu_ms {
+                    entry.2 += gpu_ms;
+                    entry.4 += 1;
+                }
+            }
+        }
+
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("profiler_scopes").striped(true).show(ui, |ui| {
+                ui.strong("Scope");
+                ui.colored_label(CPU_COLOR, "CPU ms");
+                ui.colored_label(GPU_COLOR, "GPU ms");
+                ui.end_row();
+                for ((_, name), (depth, cpu_sum, gpu_sum, cpu_count, gpu_count)) in averages {
+                    ui.label(format!("{}{}", "  ".repeat(depth as usize), name));
+                    ui.label(format!("{:.3}", cpu_sum / cpu_count as f32));
+                    let gpu = (gpu_count > 0).then(|| gpu_sum / gpu_count as f32);
+                    ui.label(format_ms(gpu));
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
+    fn get_base_mut(&mut self) -> &mut BasePane {
+        &mut self.base
+    }
+
+    fn get_base(& self) -> & BasePane {
+        & self.base
+    }
+}
+
+impl CloneablePane for Profiler {
+    fn clone_box(&self) -> Box<dyn PaneTrait> {
+        Box::new(self.clone())
+    }
+}
+
+fn format_ms(ms: Option<f32>) -> String {
+    ms.map_or("-".to_owned(), |ms| format!("{:.3}", ms))
+}
+
+// Лінійний графік історії; масштаб по Y — максимум серед усіх серій
+fn draw_graph(ui: &mut Ui, title: &str, series: &[(&Vec<f32>, Color32)]) {
+    let max = series
+        .iter()
+        .flat_map(|(values, _)| values.iter().copied())
+        .fold(1.0f32, f32::max);
+    ui.label(format!("{} (max {:.2} ms)", title, max));
+
+    let (rect, _) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), 80.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, Color32::from_gray(20));
+
+    for (values, color) in series {
+        if values.len() < 2 {
+            continue;
+        }
+        let step = rect.width() / (values.len() - 1) as f32;
+        let points: Vec<Pos2> = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                Pos2::new(rect.left() + i as f32 * step, rect.bottom() - value / max * rect.height())
+            })
+            .collect();
+        painter.add(egui::Shape::line(points, Stroke::new(1.0, *color)));
+    }
+}
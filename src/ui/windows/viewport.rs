@@ -1,28 +1,127 @@
-use egui_winit::egui::{Ui};
+use std::cell::RefCell;
+use std::rc::Rc;
 
+use egui_winit::egui::{
+    self, load::SizedTexture, Color32, ImageSource, Pos2, Rect, Sense, Stroke, TextureId, Ui,
+};
+
+use crate::graphics::settings::NViewMode;
+use crate::scene::camera::{NBackground, NGradientSky};
+use crate::ui::selection::NSelectMode;
 use crate::ui::tiles::*;
 
+// Рамка коротша за цю відстань (в точках egui) вважається кліком
+const MARQUEE_MIN_SIZE: f32 = 4.0;
+
+/// Запит на виділення з вьюпорта: клік у точці або рамка (uv у частках розміру, 0..1)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NViewportPick {
+    Point { uv: [f32; 2], mode: NSelectMode },                  // Клик
+    Region { min: [f32; 2], max: [f32; 2], mode: NSelectMode }, // Рамка
+}
+
+/// Стан вьюпорта, спільний для панелі та App:
+/// App рендерить сцену в текстуру `texture`, а панель повідомляє,
+/// якого розміру зображення їй потрібне, де користувач виділяв сутності,
+/// де зараз курсор (для підсвічування сутності під ним), в якому режимі
+/// показувати сцену і який фон у камери.
+#[derive(Debug, Default)]
+pub struct NViewportState {
+    pub texture: Option<TextureId>,     // Текстура сцены, зарегистрированная в egui
+    pub size: [u32; 2],                 // Желаемый размер изображения сцены в пикселях
+    pub pick: Option<NViewportPick>,    // Необработанный запрос выделения
+    pub hover: Option<[f32; 2]>,        // Курсор над сценой (uv), если не тянут рамку
+    pub view_mode: NViewMode,           // Режим просмотра (lit, wireframe, каналы G-буфера...)
+    pub background: NBackground,        // Фон камеры сцены
+}
+
+pub type NSharedViewport = Rc<RefCell<NViewportState>>;
+
 #[derive(Clone, Debug)] 
 pub struct Viewport {
     pub base: BasePane,
-
+    state: NSharedViewport,
+    marquee: Option<Pos2>,  // Начало рамки выделения, пока её тянут
 }
 
 impl Viewport{
-    pub fn new(id: usize, name: String) -> Self {
+    pub fn new(id: usize, name: String, state: NSharedViewport) -> Self {
         Viewport {
             base: BasePane {
                 id,
                 name,
                 visible: true,
             },
+            state,
+            marquee: None,
         }
     }    
 }
 
 impl PaneTrait  for  Viewport {
     fn render(&mut self, ui: &mut Ui) {
-        ui.label(format!("BasePane: {}", self.base.name));
+        let mut state = self.state.borrow_mut();
+        // Режим перегляду перемикається наживо: рендерер збирає варіанти пайплайнів
+        ui.horizontal(|ui| {
+            let view_mode = &mut state.view_mode;
+            egui::ComboBox::from_id_salt("viewport_view_mode")
+                .selected_text(view_mode.label())
+                .show_ui(ui, |ui| {
+                    for mode in NViewMode::ALL {
+                        ui.selectable_value(view_mode, mode, mode.label());
+                    }
+                });
+            ui.menu_button("Background", |ui| background_ui(ui, &mut state.background));
+        });
+        let available = ui.available_size();
+        let pixels_per_point = ui.ctx().pixels_per_point();
+        state.size = [
+            (available.x * pixels_per_point).max(1.0) as u32,
+            (available.y * pixels_per_point).max(1.0) as u32,
+        ];
+
+        let Some(texture) = state.texture else {
+            state.hover = None;
+            ui.label(format!("BasePane: {}", self.base.name));
+            return;
+        };
+        let rect = ui.image(ImageSource::Texture(SizedTexture::new(texture, available))).rect;
+        let response = ui.interact(rect, ui.id().with("viewport_pick"), Sense::click_and_drag());
+        let mode = NSelectMode::from_modifiers(ui.input(|input| input.modifiers));
+        let uv = |pos: Pos2| {
+            let pos = (pos - rect.min) / rect.size();
+            [pos.x.clamp(0.0, 1.0), pos.y.clamp(0.0, 1.0)]
+        };
+
+        let painter = ui.painter_at(rect);
+        if response.drag_started_by(egui::PointerButton::Primary) {
+            self.marquee = response.interact_pointer_pos();
+        }
+        if let (Some(start), Some(current)) = (self.marquee, response.interact_pointer_pos()) {
+            let marquee = Rect::from_two_pos(start, current);
+            painter.rect_filled(marquee, 0.0, Color32::from_rgba_unmultiplied(90, 150, 255, 30));
+            painter.rect_stroke(
+                marquee,
+                0.0,
+                Stroke::new(1.0, Color32::from_rgb(90, 150, 255)),
+                egui::StrokeKind::Inside,
+            );
+            if response.drag_stopped() {
+                state.pick = Some(if marquee.size().max_elem() < MARQUEE_MIN_SIZE {
+                    NViewportPick::Point { uv: uv(current), mode }
+                } else {
+                    NViewportPick::Region { min: uv(marquee.min), max: uv(marquee.max), mode }
+                });
+            }
+        } else if response.clicked() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                state.pick = Some(NViewportPick::Point { uv: uv(pos), mode });
+            }
+        }
+        if !response.dragged() {
+            self.marquee = None;
+        }
+        state.hover = response.hover_pos().filter(|_| self.marquee.is_none()).map(uv);
     }
 
     fn get_base_mut(&mut self) -> &mut BasePane {
@@ -34,6 +133,46 @@ impl PaneTrait  for  Viewport {
     }
 }
 
+// Вибір фону камери та його параметрів
+fn background_ui(ui: &mut Ui, background: &mut NBackground) {
+    // Варіанти зберігають поточні параметри, щоб перемикання їх не скидало
+    let color = match background {
+        NBackground::Color(color) => *color,
+        _ => [0.05, 0.05, 0.05, 1.0],
+    };
+    let sky = match background {
+        NBackground::Gradient(sky) => *sky,
+        _ => NGradientSky::default(),
+    };
+    ui.radio_value(background, NBackground::Color(color), "Color");
+    ui.radio_value(background, NBackground::Skybox, "Skybox");
+    ui.radio_value(background, NBackground::Gradient(sky), "Gradient sky");
+    ui.separator();
+    match background {
+        NBackground::Color(color) => {
+            ui.horizontal(|ui| {
+                ui.color_edit_button_rgba_unmultiplied(color);
+                ui.label("Clear color");
+            });
+        }
+        NBackground::Skybox => {
+            ui.weak("Scene environment cubemap");
+        }
+        NBackground::Gradient(sky) => {
+            for (label, color) in [
+                ("Zenith", &mut sky.zenith),
+                ("Horizon", &mut sky.horizon),
+                ("Ground", &mut sky.ground),
+            ] {
+                ui.horizontal(|ui| {
+                    ui.color_edit_button_rgb(color);
+                    ui.label(label);
+                });
+            }
+        }
+    }
+}
+
 impl CloneablePane for Viewport {
     fn clone_box(&self) -> Box<dyn PaneTrait> {
         Box::new(self.clone())